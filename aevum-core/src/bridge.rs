@@ -0,0 +1,547 @@
+//! Bridge entre Bond e Aevum (wBND)
+//!
+//! BND travado no Bond vira wBND no Aevum. O usuario envia ao Bond uma
+//! transacao com um output para `bridge_lock_script(recipient)` e depois
+//! apresenta ao Aevum uma `BondSpvProof`: o cabecalho do bloco, a
+//! transacao e a prova merkle de que ela esta no bloco. So entao o valor
+//! travado e creditado em wBND ao destinatario, uma unica vez por
+//! transacao. Queimar wBND emite um evento de saque para o Bond.
+//!
+//! O cabecalho da prova so vale se estiver na `BondHeaderChain`, a cadeia
+//! de cabecalhos do Bond repassada ao Aevum a partir de um checkpoint
+//! confiavel, com confirmacoes e trabalho suficientes por cima. A prova de
+//! trabalho de um cabecalho isolado nao basta: o `bits` e escolhido por
+//! quem monta o cabecalho.
+
+use crate::placeholder::AevumState;
+use crate::transaction::TxEvent;
+use bond_core::{verify_merkle_proof, BlockHeader, Transaction};
+use serde::{Deserialize, Serialize};
+use shared::{target_work, BlockchainError, Hash256, Result};
+use std::collections::HashMap;
+
+/// Prefixo do script de lock da bridge no Bond
+pub const BRIDGE_LOCK_PREFIX: &[u8] = b"AEVUM-BRIDGE";
+
+/// Script de output do Bond que trava BND para `recipient` no Aevum
+#[must_use]
+pub fn bridge_lock_script(recipient: &Hash256) -> Vec<u8> {
+    let mut script = BRIDGE_LOCK_PREFIX.to_vec();
+    script.extend_from_slice(recipient.as_bytes());
+    script
+}
+
+/// Confirmacoes exigidas por padrao antes de emitir wBND
+pub const DEFAULT_REQUIRED_CONFIRMATIONS: u64 = 6;
+
+/// Cabecalho do Bond aceito pelo relay
+#[derive(Debug, Clone)]
+struct RelayedHeader {
+    previous_hash: Hash256,
+    height: u64,
+    /// Trabalho do proprio cabecalho
+    work: u128,
+    /// Trabalho acumulado desde o checkpoint, inclusive este cabecalho
+    chain_work: u128,
+}
+
+/// Cadeia de cabecalhos do Bond conhecida pelo Aevum
+///
+/// Comeca em um checkpoint confiavel (hash e altura conhecidos) e so aceita
+/// cabecalhos que estendem um cabecalho ja aceito e cumprem o proprio alvo.
+/// A ponta e a do ramo com mais trabalho acumulado. Um lock so e aceito
+/// depois de `required_confirmations` cabecalhos no ramo da ponta e com
+/// pelo menos `min_work` de trabalho acumulado por cima dele, o que um
+/// cabecalho forjado com `bits` facil nao alcanca.
+#[derive(Debug, Clone)]
+pub struct BondHeaderChain {
+    headers: HashMap<Hash256, RelayedHeader>,
+    tip: Hash256,
+    required_confirmations: u64,
+    min_work: u128,
+}
+
+impl BondHeaderChain {
+    /// Cria a cadeia a partir do checkpoint `checkpoint` na altura `height`
+    #[must_use]
+    pub fn new(
+        checkpoint: Hash256,
+        height: u64,
+        required_confirmations: u64,
+        min_work: u128,
+    ) -> Self {
+        let mut headers = HashMap::new();
+        headers.insert(
+            checkpoint,
+            RelayedHeader {
+                previous_hash: Hash256::zero(),
+                height,
+                work: 0,
+                chain_work: 0,
+            },
+        );
+        Self {
+            headers,
+            tip: checkpoint,
+            required_confirmations,
+            min_work,
+        }
+    }
+
+    /// Hash do cabecalho na ponta do ramo mais pesado
+    #[must_use]
+    pub const fn tip(&self) -> Hash256 {
+        self.tip
+    }
+
+    /// Aceita um cabecalho repassado do Bond e retorna o seu hash
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o pai do cabecalho for desconhecido ou se o
+    /// cabecalho nao cumprir o proprio alvo
+    pub fn add_header(&mut self, header: &BlockHeader) -> Result<Hash256> {
+        let hash = header.hash()?;
+        if self.headers.contains_key(&hash) {
+            return Ok(hash);
+        }
+        let parent = self.headers.get(&header.previous_hash).ok_or_else(|| {
+            BlockchainError::InvalidBlock("Cabecalho do Bond sem pai conhecido".to_string())
+        })?;
+        if !header.meets_target()? {
+            return Err(BlockchainError::InsufficientDifficulty);
+        }
+
+        let work = target_work(header.bits);
+        let relayed = RelayedHeader {
+            previous_hash: header.previous_hash,
+            height: parent.height + 1,
+            work,
+            chain_work: parent.chain_work.saturating_add(work),
+        };
+        if relayed.chain_work > self.headers[&self.tip].chain_work {
+            self.tip = hash;
+        }
+        self.headers.insert(hash, relayed);
+        Ok(hash)
+    }
+
+    /// Numero de confirmacoes de `hash` no ramo da ponta (a ponta tem uma),
+    /// ou zero se o cabecalho for desconhecido ou estiver em outro ramo
+    #[must_use]
+    pub fn confirmations(&self, hash: &Hash256) -> u64 {
+        let Some(target) = self.headers.get(hash) else {
+            return 0;
+        };
+        let mut cursor = self.tip;
+        let mut current = &self.headers[&cursor];
+        while current.height > target.height {
+            cursor = current.previous_hash;
+            current = &self.headers[&cursor];
+        }
+        if cursor == *hash {
+            self.headers[&self.tip].height - target.height + 1
+        } else {
+            0
+        }
+    }
+
+    /// Verifica se o cabecalho `hash` pode sustentar uma emissao de wBND
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o cabecalho nao estiver no ramo da ponta, se tiver
+    /// menos confirmacoes que as exigidas ou se o trabalho acumulado dele
+    /// ate a ponta for menor que o minimo
+    pub fn check_confirmed(&self, hash: &Hash256) -> Result<()> {
+        let confirmations = self.confirmations(hash);
+        if confirmations == 0 {
+            return Err(BlockchainError::InvalidBlock(
+                "Cabecalho fora da cadeia do Bond".to_string(),
+            ));
+        }
+        if confirmations < self.required_confirmations {
+            return Err(BlockchainError::InvalidBlock(format!(
+                "Lock com {confirmations} de {} confirmacoes exigidas",
+                self.required_confirmations
+            )));
+        }
+
+        let header = &self.headers[hash];
+        let work = self.headers[&self.tip].chain_work - header.chain_work + header.work;
+        if work < self.min_work {
+            return Err(BlockchainError::InsufficientDifficulty);
+        }
+        Ok(())
+    }
+}
+
+/// Pedido de emissao de wBND por um lock no Bond
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeRequest {
+    /// Transacao do Bond que travou o BND
+    pub bond_txid: Hash256,
+    /// Valor travado, em Elos
+    pub amount: u128,
+    /// Conta do Aevum que recebe o wBND
+    pub recipient: Hash256,
+}
+
+/// Prova SPV de que um lock foi incluido em um bloco do Bond
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BondSpvProof {
+    /// Pedido que a prova sustenta
+    pub request: BridgeRequest,
+    /// Cabecalho do bloco do Bond que inclui o lock
+    pub header: BlockHeader,
+    /// Transacao de lock completa
+    pub lock_tx: Transaction,
+    /// Caminho merkle da transacao ate `header.merkle_root`
+    pub merkle_proof: Vec<(Hash256, bool)>,
+}
+
+impl BondSpvProof {
+    /// Verifica a prova contra o pedido e a cadeia de cabecalhos do Bond
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a transacao nao for a do pedido, se a prova merkle
+    /// for invalida, se o cabecalho nao estiver confirmado em `headers`
+    /// (ver `BondHeaderChain::check_confirmed`) ou se nenhum output travar
+    /// exatamente `amount` para `recipient`
+    pub fn verify(&self, headers: &BondHeaderChain) -> Result<()> {
+        let txid = self.lock_tx.hash()?;
+        if txid != self.request.bond_txid {
+            return Err(BlockchainError::InvalidTransaction(
+                "Transacao de lock nao corresponde ao pedido".to_string(),
+            ));
+        }
+
+        if !verify_merkle_proof(&txid, &self.merkle_proof, &self.header.merkle_root) {
+            return Err(BlockchainError::InvalidTransaction(
+                "Prova merkle invalida".to_string(),
+            ));
+        }
+
+        headers.check_confirmed(&self.header.hash()?)?;
+
+        let lock_script = bridge_lock_script(&self.request.recipient);
+        let locked = self.lock_tx.outputs.iter().any(|output| {
+            output.script_pubkey == lock_script && u128::from(output.value) == self.request.amount
+        });
+        if !locked {
+            return Err(BlockchainError::InvalidTransaction(
+                "Lock nao cobre o valor pedido".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Emite wBND a partir de um lock comprovado no Bond
+///
+/// # Errors
+///
+/// Retorna erro se a prova for invalida contra `headers`, se o lock ja
+/// tiver sido usado ou se o saldo de wBND do destinatario transbordar
+pub fn mint_wrapped(
+    state: &mut AevumState,
+    headers: &BondHeaderChain,
+    proof: &BondSpvProof,
+) -> Result<()> {
+    proof.verify(headers)?;
+
+    let request = &proof.request;
+    if state.bridge_claims.contains(&request.bond_txid) {
+        return Err(BlockchainError::InvalidTransaction(
+            "Lock ja resgatado".to_string(),
+        ));
+    }
+    let balance = state
+        .wrapped_balances
+        .get(&request.recipient)
+        .copied()
+        .unwrap_or(0)
+        .checked_add(request.amount)
+        .ok_or_else(|| {
+            BlockchainError::InvalidTransaction("Saldo de wBND excede o limite".to_string())
+        })?;
+
+    state.bridge_claims.insert(request.bond_txid);
+    state.wrapped_balances.insert(request.recipient, balance);
+    state.events.push(TxEvent::WrappedMinted {
+        bond_txid: request.bond_txid,
+        recipient: request.recipient,
+        amount: request.amount,
+    });
+    Ok(())
+}
+
+/// Queima wBND para sacar BND no Bond
+///
+/// Retorna o evento de saque, que tambem vai para o log de eventos do
+/// estado para ser processado do lado do Bond.
+///
+/// # Errors
+///
+/// Retorna erro se `from` nao tiver wBND suficiente
+pub fn burn_wrapped(
+    state: &mut AevumState,
+    from: Hash256,
+    amount: u128,
+    bond_script: Vec<u8>,
+) -> Result<TxEvent> {
+    let balance = state.wrapped_balances.get(&from).copied().unwrap_or(0);
+    if amount == 0 || balance < amount {
+        return Err(BlockchainError::InsufficientFunds);
+    }
+
+    if balance == amount {
+        state.wrapped_balances.remove(&from);
+    } else {
+        state.wrapped_balances.insert(from, balance - amount);
+    }
+
+    let event = TxEvent::WrappedBurned {
+        from,
+        amount,
+        bond_script,
+    };
+    state.events.push(event.clone());
+    Ok(event)
+}
+
+/// Representa uma transação de bridge entre Bond e Aevum
+#[derive(Debug, Clone)]
+pub struct BridgeTransaction {
+    /// Hash da transação no Bond
+    pub bond_tx_hash: Hash256,
+    /// Endereço de destino no Aevum
+    pub aevum_recipient: Hash256,
+    /// Valor a ser transferido
+    pub amount: u128,
+    /// Status da bridge
+    pub status: BridgeStatus,
+}
+
+/// Status possíveis de uma operação de bridge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BridgeStatus {
+    /// Transação iniciada no Bond
+    Initiated,
+    /// Validada por validadores
+    Validated,
+    /// Executada no Aevum
+    Executed,
+    /// Falhou por algum motivo
+    Failed(String),
+}
+
+/// Valida uma transação de bridge
+///
+/// # Errors
+///
+/// Retorna erro se a transação de bridge for inválida
+pub const fn validate_bridge_transaction(_tx: &BridgeTransaction) -> Result<()> {
+    // Implementação placeholder para Sprint 8
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bond_core::block::calculate_merkle_root;
+    use bond_core::{Block, OutPoint, TxInput, TxOutput};
+    use chrono::Utc;
+    use shared::MAX_TARGET_BITS;
+
+    fn checkpoint() -> Hash256 {
+        Hash256::keccak256(b"checkpoint")
+    }
+
+    /// Cabecalho minerado sobre `previous_hash` no alvo mais facil
+    fn mined_header(previous_hash: Hash256, merkle_root: Hash256) -> BlockHeader {
+        let mut header = BlockHeader::new(
+            1,
+            previous_hash,
+            merkle_root,
+            Utc::now(),
+            MAX_TARGET_BITS,
+            0,
+        );
+        while !header.meets_target().unwrap() {
+            header.nonce += 1;
+        }
+        header
+    }
+
+    /// Relay que exige 3 confirmacoes e o trabalho de 3 cabecalhos, com o
+    /// bloco de `proof` e mais `confirmations - 1` cabecalhos por cima
+    fn relay(proof: &BondSpvProof, confirmations: u64) -> BondHeaderChain {
+        let mut headers =
+            BondHeaderChain::new(checkpoint(), 100, 3, 3 * target_work(MAX_TARGET_BITS));
+        let mut tip = headers.add_header(&proof.header).unwrap();
+        for _ in 1..confirmations {
+            tip = headers
+                .add_header(&mined_header(tip, Hash256::zero()))
+                .unwrap();
+        }
+        headers
+    }
+
+    /// Bloco do Bond, sobre o checkpoint, com um lock de `amount` para
+    /// `recipient`
+    fn lock_proof(recipient: Hash256, amount: u64) -> BondSpvProof {
+        let lock_tx = Transaction::new(
+            1,
+            vec![TxInput::new(
+                OutPoint {
+                    txid: Hash256::keccak256(b"funding"),
+                    vout: 0,
+                },
+                vec![],
+                0,
+            )],
+            vec![
+                TxOutput::new(amount, bridge_lock_script(&recipient)),
+                TxOutput::new(10, vec![1]),
+            ],
+            0,
+        );
+        let transactions = vec![Transaction::coinbase(7, 5_000, vec![1]), lock_tx.clone()];
+        let header = mined_header(checkpoint(), calculate_merkle_root(&transactions).unwrap());
+        let block = Block::new(header.clone(), transactions);
+
+        BondSpvProof {
+            request: BridgeRequest {
+                bond_txid: lock_tx.hash().unwrap(),
+                amount: u128::from(amount),
+                recipient,
+            },
+            header,
+            lock_tx,
+            merkle_proof: block.merkle_proof(1).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_mint_from_verified_proof() {
+        let mut state = AevumState::new();
+        let recipient = Hash256::keccak256(b"recipient");
+        let proof = lock_proof(recipient, 2_500);
+        let headers = relay(&proof, 3);
+
+        mint_wrapped(&mut state, &headers, &proof).unwrap();
+        assert_eq!(state.wrapped_balances[&recipient], 2_500);
+        assert_eq!(
+            state.events,
+            vec![TxEvent::WrappedMinted {
+                bond_txid: proof.request.bond_txid,
+                recipient,
+                amount: 2_500,
+            }]
+        );
+
+        // O mesmo lock nao emite duas vezes
+        assert!(mint_wrapped(&mut state, &headers, &proof).is_err());
+        assert_eq!(state.wrapped_balances[&recipient], 2_500);
+
+        let event = burn_wrapped(&mut state, recipient, 1_000, vec![9]).unwrap();
+        assert_eq!(
+            event,
+            TxEvent::WrappedBurned {
+                from: recipient,
+                amount: 1_000,
+                bond_script: vec![9],
+            }
+        );
+        assert_eq!(state.events.last(), Some(&event));
+        assert_eq!(state.wrapped_balances[&recipient], 1_500);
+        assert!(burn_wrapped(&mut state, recipient, 1_501, vec![9]).is_err());
+    }
+
+    #[test]
+    fn test_mint_rejects_balance_overflow() {
+        let mut state = AevumState::new();
+        let recipient = Hash256::keccak256(b"recipient");
+        state.wrapped_balances.insert(recipient, u128::MAX - 1_000);
+        let proof = lock_proof(recipient, 2_500);
+        let headers = relay(&proof, 3);
+
+        assert!(mint_wrapped(&mut state, &headers, &proof).is_err());
+        assert_eq!(state.wrapped_balances[&recipient], u128::MAX - 1_000);
+        // O lock continua disponivel para um resgate futuro
+        assert!(state.bridge_claims.is_empty());
+        assert!(state.events.is_empty());
+    }
+
+    #[test]
+    fn test_mint_rejects_mismatched_proof() {
+        let mut state = AevumState::new();
+        let recipient = Hash256::keccak256(b"recipient");
+
+        // Pedido alega mais do que foi travado
+        let mut inflated = lock_proof(recipient, 2_500);
+        let headers = relay(&inflated, 3);
+        inflated.request.amount = 25_000;
+        assert!(mint_wrapped(&mut state, &headers, &inflated).is_err());
+
+        // Lock de outro destinatario
+        let mut redirected = lock_proof(recipient, 2_500);
+        let headers = relay(&redirected, 3);
+        redirected.request.recipient = Hash256::keccak256(b"thief");
+        assert!(mint_wrapped(&mut state, &headers, &redirected).is_err());
+
+        // Prova merkle adulterada
+        let mut forged = lock_proof(recipient, 2_500);
+        let headers = relay(&forged, 3);
+        forged.merkle_proof[0].0 = Hash256::keccak256(b"other");
+        assert!(mint_wrapped(&mut state, &headers, &forged).is_err());
+
+        assert!(state.wrapped_balances.is_empty());
+        assert!(state.bridge_claims.is_empty());
+        assert!(state.events.is_empty());
+    }
+
+    #[test]
+    fn test_mint_rejects_forged_or_unconfirmed_header() {
+        let mut state = AevumState::new();
+        let recipient = Hash256::keccak256(b"recipient");
+        let proof = lock_proof(recipient, 2_500);
+
+        // Cabecalho forjado e coerente: merkle e prova de trabalho validas
+        // no proprio `bits`, mas fora da cadeia de cabecalhos do Bond
+        let mut forged = proof.clone();
+        forged.header = mined_header(Hash256::keccak256(b"forjado"), proof.header.merkle_root);
+        assert!(forged.header.meets_target().unwrap());
+        let mut headers = relay(&proof, 3);
+        assert!(headers.add_header(&forged.header).is_err());
+        assert!(mint_wrapped(&mut state, &headers, &forged).is_err());
+
+        // Cabecalho conhecido, mas em um ramo com menos trabalho
+        let mut side = proof.clone();
+        side.header = mined_header(checkpoint(), proof.header.merkle_root);
+        headers.add_header(&side.header).unwrap();
+        assert_eq!(headers.confirmations(&side.header.hash().unwrap()), 0);
+        assert!(mint_wrapped(&mut state, &headers, &side).is_err());
+
+        // Confirmacoes insuficientes
+        assert!(mint_wrapped(&mut state, &relay(&proof, 2), &proof).is_err());
+
+        // Confirmacoes suficientes, mas trabalho abaixo do minimo
+        let mut weak = BondHeaderChain::new(checkpoint(), 100, 3, u128::MAX);
+        let mut tip = weak.add_header(&proof.header).unwrap();
+        for _ in 0..2 {
+            tip = weak
+                .add_header(&mined_header(tip, Hash256::zero()))
+                .unwrap();
+        }
+        assert_eq!(weak.confirmations(&proof.header.hash().unwrap()), 3);
+        assert!(mint_wrapped(&mut state, &weak, &proof).is_err());
+        assert!(state.wrapped_balances.is_empty());
+
+        mint_wrapped(&mut state, &relay(&proof, 3), &proof).unwrap();
+        assert_eq!(state.wrapped_balances[&recipient], 2_500);
+    }
+}
@@ -0,0 +1,430 @@
+//! Cadeia de blocos do Aevum sobre o consenso `DPoS`
+//!
+//! Cada bloco e produzido pelo lider do slot da sua altura (ou, antes de
+//! existir uma escala, por qualquer validador registrado) e aplica suas
+//! transacoes ao `AevumState` com `AevumState::apply_block`.
+//!
+//! O produtor assina o bloco com a mesma mensagem de
+//! `SlashingEvidence::sign_block`, e o validador e identificado pelo
+//! endereco da chave publica da assinatura (`utils::address_from_key`), o
+//! mesmo com que ele se registra ao fazer stake e recebe as taxas; um
+//! bloco que so declara o `producer` sem a assinatura correspondente e
+//! rejeitado.
+
+use crate::consensus::{DposEngine, SlashingEvidence};
+use crate::placeholder::{utils, AevumState};
+use crate::transaction::AevumTransaction;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use shared::{BlockchainError, ConsensusEngine, Hash256, KeyPair, Result, Signature};
+
+/// Bloco do Aevum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AevumBlock {
+    /// Altura do bloco
+    pub height: u64,
+    /// Hash do bloco anterior
+    pub previous_hash: Hash256,
+    /// Validador que produziu o bloco e recebe as taxas
+    pub producer: Hash256,
+    /// Momento da producao (segundos desde a epoca Unix)
+    pub timestamp: i64,
+    /// Transacoes do bloco, na ordem de aplicacao
+    pub transactions: Vec<AevumTransaction>,
+    /// Assinatura do produtor sobre a altura e o hash do bloco
+    #[serde(default)]
+    pub signature: Option<Signature>,
+}
+
+impl AevumBlock {
+    /// Hash do bloco, sem a assinatura do produtor
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a serializacao falhar
+    pub fn hash(&self) -> Result<Hash256> {
+        let serialized = serde_json::to_vec(&(
+            self.height,
+            &self.previous_hash,
+            &self.producer,
+            self.timestamp,
+            &self.transactions,
+        ))
+        .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+        Ok(Hash256::keccak256(&serialized))
+    }
+
+    /// Assina o bloco com a chave do produtor
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a chave nao corresponder a `producer` ou se a
+    /// assinatura falhar
+    pub fn sign(&mut self, keypair: &KeyPair) -> Result<()> {
+        if utils::address_from_key(&keypair.public_key) != self.producer {
+            return Err(BlockchainError::InvalidBlock(
+                "Chave nao corresponde ao produtor do bloco".to_string(),
+            ));
+        }
+        self.signature = Some(SlashingEvidence::sign_block(
+            keypair,
+            self.height,
+            &self.hash()?,
+        )?);
+        Ok(())
+    }
+
+    /// Verifica que o bloco foi assinado pela chave de `producer`
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o bloco nao estiver assinado, se a chave da
+    /// assinatura nao corresponder a `producer` ou se a assinatura for
+    /// invalida
+    pub fn verify_producer(&self) -> Result<()> {
+        let signature = self.signature.as_ref().ok_or_else(|| {
+            BlockchainError::InvalidBlock("Bloco nao assinado pelo produtor".to_string())
+        })?;
+        if utils::address_from_key(signature.public_key()) != self.producer {
+            return Err(BlockchainError::InvalidSignature);
+        }
+        let message = SlashingEvidence::signing_message(self.height, &self.hash()?);
+        if !signature.verify(&message)? {
+            return Err(BlockchainError::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+/// Cadeia do Aevum: blocos aceitos, estado das contas e motor `DPoS`
+#[derive(Debug, Clone)]
+pub struct AevumChain {
+    blocks: Vec<AevumBlock>,
+    state: AevumState,
+    engine: DposEngine,
+}
+
+impl AevumChain {
+    /// Cria a cadeia a partir do estado inicial, com um bloco genese vazio
+    #[must_use]
+    pub fn new(state: AevumState, engine: DposEngine) -> Self {
+        let genesis = AevumBlock {
+            height: 0,
+            previous_hash: Hash256::zero(),
+            producer: Hash256::zero(),
+            timestamp: 0,
+            transactions: Vec::new(),
+            signature: None,
+        };
+        Self {
+            blocks: vec![genesis],
+            state,
+            engine,
+        }
+    }
+
+    /// Estado atual das contas
+    #[must_use]
+    pub const fn state(&self) -> &AevumState {
+        &self.state
+    }
+
+    /// Motor de consenso
+    #[must_use]
+    pub const fn engine(&self) -> &DposEngine {
+        &self.engine
+    }
+
+    /// Enfileira uma evidencia de slashing recebida pela rede
+    ///
+    /// A evidencia e aplicada no fim da epoca, quando `add_block` chega a
+    /// sua ultima altura, para que todos os nos a apliquem no mesmo ponto da
+    /// cadeia independente de quando a mensagem chegou.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a evidencia for invalida
+    pub fn queue_slashing_evidence(
+        &mut self,
+        evidence: SlashingEvidence,
+        reporter: Hash256,
+    ) -> Result<()> {
+        self.engine.queue_slashing_evidence(evidence, reporter)
+    }
+
+    /// Ultimo bloco aceito
+    #[must_use]
+    pub fn tip(&self) -> &AevumBlock {
+        // A cadeia sempre tem ao menos o genese
+        &self.blocks[self.blocks.len() - 1]
+    }
+
+    /// Verifica se `producer` pode produzir o bloco na altura `height`
+    fn check_producer(&self, producer: &Hash256, height: u64) -> Result<()> {
+        let allowed = match self.engine.slot_leader(height) {
+            Some(leader) => leader == *producer,
+            None => self.state.validators.contains_key(producer),
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(BlockchainError::InvalidBlock(format!(
+                "{producer} nao e o produtor do slot {height}"
+            )))
+        }
+    }
+}
+
+/// Consenso `DPoS` do Aevum, para o no P2P
+///
+/// O produtor e a chave do validador, que assina o bloco produzido.
+impl ConsensusEngine for AevumChain {
+    type Block = AevumBlock;
+    type Transaction = AevumTransaction;
+    type Producer = KeyPair;
+
+    fn validate_block(&self, block: &AevumBlock) -> Result<()> {
+        let tip = self.tip();
+        if block.height != tip.height + 1 {
+            return Err(BlockchainError::InvalidBlock(
+                "Altura do bloco invalida".to_string(),
+            ));
+        }
+        if block.previous_hash != tip.hash()? {
+            return Err(BlockchainError::InvalidBlock(
+                "Hash anterior invalido".to_string(),
+            ));
+        }
+        self.check_producer(&block.producer, block.height)?;
+        block.verify_producer()?;
+
+        // As transacoes precisam ser aplicaveis sobre o estado atual, o que
+        // inclui a assinatura de cada remetente (`verify_sender`)
+        self.state
+            .clone()
+            .apply_block(&block.transactions, &block.producer)
+            .map(drop)
+    }
+
+    fn add_block(&mut self, block: AevumBlock) -> Result<()> {
+        self.validate_block(&block)?;
+        self.state
+            .apply_block(&block.transactions, &block.producer)?;
+        self.engine.record_slot(block.producer, true);
+        // No fim da epoca as evidencias enfileiradas sao aplicadas e a
+        // nova escala e eleita
+        self.state.block_height = block.height;
+        self.engine.advance_epoch(&mut self.state);
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    fn produce_block(
+        &self,
+        producer: &KeyPair,
+        transactions: Vec<AevumTransaction>,
+    ) -> Result<AevumBlock> {
+        let tip = self.tip();
+        let height = tip.height + 1;
+        let validator = utils::address_from_key(&producer.public_key);
+        self.check_producer(&validator, height)?;
+        let mut block = AevumBlock {
+            height,
+            previous_hash: tip.hash()?,
+            producer: validator,
+            timestamp: Utc::now().timestamp(),
+            transactions,
+            signature: None,
+        };
+        block.sign(producer)?;
+        Ok(block)
+    }
+
+    fn tip_height(&self) -> u64 {
+        self.tip().height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::placeholder::{DposConfig, ValidatorInfo};
+    use crate::transaction::{AevumTransactionType, GAS_STAKING, GAS_TRANSFER};
+    use shared::SignatureAlgorithm;
+
+    /// Chave de validador e o identificador derivado dela
+    fn validator_keypair() -> (KeyPair, Hash256) {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let validator = utils::address_from_key(&keypair.public_key);
+        (keypair, validator)
+    }
+
+    /// Conta de usuario: chave e endereco derivado dela
+    fn user_key() -> (KeyPair, Hash256) {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let address = utils::address_from_key(&keypair.public_key);
+        (keypair, address)
+    }
+
+    fn chain_with_validator(validator: Hash256) -> AevumChain {
+        let mut state = AevumState::new();
+        state
+            .validators
+            .insert(validator, ValidatorInfo::new(validator, 1_000));
+        AevumChain::new(state, DposEngine::new(DposConfig::default()).unwrap())
+    }
+
+    fn transfer(from: Hash256, to: Hash256, value: u128) -> AevumTransaction {
+        AevumTransaction::new(
+            from,
+            to,
+            value,
+            0,
+            GAS_TRANSFER,
+            1,
+            AevumTransactionType::Transfer,
+        )
+    }
+
+    #[test]
+    fn test_validator_produces_and_adds_blocks() {
+        let (validator_key, validator) = validator_keypair();
+        let (alice_key, alice) = user_key();
+        let bob = Hash256::keccak256(b"bob");
+        let mut chain = chain_with_validator(validator);
+        chain.state.create_account(alice, 1_000_000);
+
+        let mut tx = transfer(alice, bob, 1_000);
+        tx.sign(&alice_key).unwrap();
+        let block = chain.produce_block(&validator_key, vec![tx]).unwrap();
+        assert_eq!(block.producer, validator);
+        chain.add_block(block).unwrap();
+
+        assert_eq!(chain.tip_height(), 1);
+        assert_eq!(chain.state().get_account(&bob).unwrap().balance, 1_000);
+        // As taxas vao para a conta controlada pela chave do produtor
+        let producer_account = utils::address_from_key(&validator_key.public_key);
+        assert_eq!(
+            chain
+                .state()
+                .get_account(&producer_account)
+                .unwrap()
+                .balance,
+            u128::from(GAS_TRANSFER) - chain.state().burned_supply
+        );
+    }
+
+    #[test]
+    fn test_validator_registered_by_staking_produces_blocks() {
+        let (validator_key, validator) = validator_keypair();
+        let (staker_key, staker) = user_key();
+        let mut chain = chain_with_validator(validator);
+        chain.state.create_account(staker, 1_000_000);
+
+        // A conta entra no conjunto de validadores por uma transacao de stake
+        let mut stake = AevumTransaction::new(
+            staker,
+            Hash256::zero(),
+            10_000,
+            0,
+            GAS_STAKING,
+            1,
+            AevumTransactionType::Stake,
+        );
+        stake.sign(&staker_key).unwrap();
+        let block = chain.produce_block(&validator_key, vec![stake]).unwrap();
+        chain.add_block(block).unwrap();
+        assert!(chain.state().validators.contains_key(&staker));
+
+        // E passa a produzir blocos com a mesma chave
+        let block = chain.produce_block(&staker_key, vec![]).unwrap();
+        assert_eq!(block.producer, staker);
+        chain.add_block(block).unwrap();
+        assert_eq!(chain.tip_height(), 2);
+    }
+
+    #[test]
+    fn test_invalid_blocks_are_rejected() {
+        let (validator_key, validator) = validator_keypair();
+        let (outsider_key, outsider) = user_key();
+        let mut chain = chain_with_validator(validator);
+
+        // Quem nao e validador nao produz blocos
+        assert!(chain.produce_block(&outsider_key, vec![]).is_err());
+        let mut block = chain.produce_block(&validator_key, vec![]).unwrap();
+        block.producer = outsider;
+        assert!(chain.add_block(block).is_err());
+
+        // Bloco que nao estende a ponta
+        let mut block = chain.produce_block(&validator_key, vec![]).unwrap();
+        block.previous_hash = Hash256::keccak256(b"other");
+        block.sign(&validator_key).unwrap();
+        assert!(chain.validate_block(&block).is_err());
+
+        // Transacao sem saldo invalida o bloco e nao altera o estado
+        let mut tx = transfer(outsider, validator, 1);
+        tx.sign(&outsider_key).unwrap();
+        let block = chain.produce_block(&validator_key, vec![tx]).unwrap();
+        assert!(chain.add_block(block).is_err());
+        assert_eq!(chain.tip_height(), 0);
+    }
+
+    #[test]
+    fn test_forged_producer_is_rejected() {
+        let (validator_key, validator) = validator_keypair();
+        let (forger_key, _) = validator_keypair();
+        let mut chain = chain_with_validator(validator);
+
+        // Bloco que so declara o lider, sem assinatura
+        let mut block = chain.produce_block(&validator_key, vec![]).unwrap();
+        block.signature = None;
+        assert!(chain.validate_block(&block).is_err());
+
+        // Assinado por outra chave em nome do lider
+        block.signature = Some(
+            SlashingEvidence::sign_block(&forger_key, block.height, &block.hash().unwrap())
+                .unwrap(),
+        );
+        assert!(chain.validate_block(&block).is_err());
+        assert!(block.sign(&forger_key).is_err());
+
+        // Conteudo alterado depois da assinatura do produtor
+        let mut block = chain.produce_block(&validator_key, vec![]).unwrap();
+        block.timestamp += 1;
+        assert!(chain.add_block(block).is_err());
+        assert_eq!(chain.tip_height(), 0);
+
+        let block = chain.produce_block(&validator_key, vec![]).unwrap();
+        chain.add_block(block).unwrap();
+        assert_eq!(chain.tip_height(), 1);
+    }
+
+    #[test]
+    fn test_unsigned_or_spoofed_transactions_invalidate_block() {
+        let (validator_key, validator) = validator_keypair();
+        let (_, alice) = user_key();
+        let (mallory_key, mallory) = user_key();
+        let mut chain = chain_with_validator(validator);
+        chain.state.create_account(alice, 1_000_000);
+
+        // O produtor legitimo inclui uma transacao nao assinada
+        let unsigned = transfer(alice, mallory, 900_000);
+        let block = chain
+            .produce_block(&validator_key, vec![unsigned.clone()])
+            .unwrap();
+        assert!(chain.add_block(block).is_err());
+
+        // Ou assinada por outra chave declarando a conta de alice
+        let mut spoofed = unsigned;
+        spoofed.sign(&mallory_key).unwrap();
+        let block = chain.produce_block(&validator_key, vec![spoofed]).unwrap();
+        assert!(chain.add_block(block).is_err());
+
+        assert_eq!(chain.tip_height(), 0);
+        assert_eq!(
+            chain.state().get_account(&alice).unwrap().balance,
+            1_000_000
+        );
+        assert!(chain.state().get_account(&mallory).is_none());
+    }
+}
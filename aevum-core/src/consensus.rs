@@ -0,0 +1,1126 @@
+//! Consenso `DPoS` do Aevum
+//!
+//! Este modulo concentra as regras de consenso que precisam ser
+//! verificaveis por qualquer no da rede, como a punicao (slashing)
+//! de validadores que assinam dois blocos diferentes na mesma altura.
+
+use crate::placeholder::{utils, AevumState, DposConfig};
+use serde::{Deserialize, Serialize};
+use shared::{BlockchainError, Hash256, KeyPair, Result, Signature};
+use std::collections::{HashMap, HashSet};
+
+/// Percentual do stake removido de um validador punido
+pub const SLASH_PERCENTAGE: u128 = 10;
+
+/// Escala de pontos-base (100% = 10.000)
+pub const BPS_SCALE: u128 = 10_000;
+
+/// Desempenho de um validador na producao de blocos
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidatorPerformance {
+    /// Blocos produzidos desde o registro
+    pub blocks_produced: u64,
+    /// Slots perdidos desde o registro
+    pub blocks_missed: u64,
+    /// Blocos produzidos na epoca atual
+    pub epoch_blocks_produced: u64,
+    /// Slots perdidos na epoca atual
+    pub epoch_blocks_missed: u64,
+    /// Epocas seguidas sem nenhum slot perdido
+    pub consecutive_good_epochs: u64,
+    /// Media movel exponencial da taxa de aprovacao das epocas fechadas
+    /// (pontos-base); `None` antes da primeira epoca com slots
+    #[serde(default)]
+    pub recent_approval_bps: Option<u128>,
+}
+
+impl ValidatorPerformance {
+    /// Registra o resultado de um slot atribuido ao validador
+    pub const fn record_slot(&mut self, produced: bool) {
+        if produced {
+            self.blocks_produced += 1;
+            self.epoch_blocks_produced += 1;
+        } else {
+            self.blocks_missed += 1;
+            self.epoch_blocks_missed += 1;
+        }
+    }
+
+    /// Fecha a epoca atual, estendendo ou zerando a sequencia de epocas boas
+    ///
+    /// A taxa da epoca entra na media recente com peso
+    /// `approval_ema_weight_bps`; epocas sem slots nao a alteram.
+    pub const fn close_epoch(&mut self, config: &DposConfig) {
+        if self.epoch_blocks_missed == 0 && self.epoch_blocks_produced > 0 {
+            self.consecutive_good_epochs += 1;
+        } else if self.epoch_blocks_missed > 0 {
+            self.consecutive_good_epochs = 0;
+        }
+
+        let slots = self.epoch_blocks_produced + self.epoch_blocks_missed;
+        if slots > 0 {
+            let epoch_rate = self.epoch_blocks_produced as u128 * BPS_SCALE / slots as u128;
+            self.recent_approval_bps = Some(match self.recent_approval_bps {
+                Some(previous) => {
+                    let weight = config.approval_ema_weight_bps;
+                    (epoch_rate * weight + previous * (BPS_SCALE - weight)) / BPS_SCALE
+                }
+                None => epoch_rate,
+            });
+        }
+        self.epoch_blocks_produced = 0;
+        self.epoch_blocks_missed = 0;
+    }
+
+    /// Taxa de aprovacao em pontos-base (100% sem historico)
+    #[must_use]
+    pub fn approval_rate_bps(&self) -> u128 {
+        let total = self.blocks_produced + self.blocks_missed;
+        if total == 0 {
+            return BPS_SCALE;
+        }
+        u128::from(self.blocks_produced) * BPS_SCALE / u128::from(total)
+    }
+
+    /// Taxa de aprovacao das epocas recentes em pontos-base, com mais peso
+    /// para as ultimas (100% sem historico)
+    #[must_use]
+    pub fn recent_approval_rate_bps(&self) -> u128 {
+        self.recent_approval_bps.unwrap_or(BPS_SCALE)
+    }
+
+    /// Indica se a taxa de aprovacao recente caiu abaixo de
+    /// `min_approval_rate_bps`
+    ///
+    /// Usa a media recente, e nao a taxa de toda a vida, para que um bom
+    /// historico nao esconda uma epoca inteira sem produzir blocos.
+    #[must_use]
+    pub fn should_be_slashed(&self, config: &DposConfig) -> bool {
+        self.recent_approval_rate_bps() < config.min_approval_rate_bps
+    }
+
+    /// Bonus de uptime em pontos-base segundo a configuracao
+    #[must_use]
+    pub fn uptime_bonus_bps(&self, config: &DposConfig) -> u128 {
+        (u128::from(self.consecutive_good_epochs) * config.uptime_bonus_bps_per_epoch)
+            .min(config.max_uptime_bonus_bps)
+    }
+}
+
+/// Prova de equivocacao: o mesmo validador assinou dois blocos
+/// diferentes na mesma altura
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashingEvidence {
+    /// Identificador do validador (endereco da chave publica,
+    /// `utils::address_from_key`)
+    pub validator: Hash256,
+    /// Altura em que ocorreu a dupla assinatura
+    pub height: u64,
+    /// Hash do primeiro bloco assinado
+    pub block_hash_a: Hash256,
+    /// Hash do segundo bloco assinado
+    pub block_hash_b: Hash256,
+    /// Assinatura do primeiro bloco
+    pub sig_a: Signature,
+    /// Assinatura do segundo bloco
+    pub sig_b: Signature,
+}
+
+impl SlashingEvidence {
+    /// Mensagem assinada por um validador ao produzir um bloco
+    #[must_use]
+    pub fn signing_message(height: u64, block_hash: &Hash256) -> Vec<u8> {
+        let mut message = height.to_le_bytes().to_vec();
+        message.extend_from_slice(block_hash.as_bytes());
+        message
+    }
+
+    /// Assina um bloco na altura indicada com a chave do validador
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a assinatura falhar
+    pub fn sign_block(keypair: &KeyPair, height: u64, block_hash: &Hash256) -> Result<Signature> {
+        keypair.sign(&Self::signing_message(height, block_hash))
+    }
+
+    /// Verifica a evidencia de forma independente
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se os blocos forem iguais, se as assinaturas nao
+    /// pertencerem ao validador ou se alguma delas for invalida
+    pub fn verify(&self) -> Result<()> {
+        if self.block_hash_a == self.block_hash_b {
+            return Err(BlockchainError::InvalidTransaction(
+                "Evidencia de slashing com blocos identicos".to_string(),
+            ));
+        }
+
+        for (signature, block_hash) in [
+            (&self.sig_a, &self.block_hash_a),
+            (&self.sig_b, &self.block_hash_b),
+        ] {
+            let signer = utils::address_from_key(signature.public_key());
+            if signer != self.validator {
+                return Err(BlockchainError::InvalidSignature);
+            }
+
+            if !signature.verify(&Self::signing_message(self.height, block_hash))? {
+                return Err(BlockchainError::InvalidSignature);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Motor de consenso `DPoS`
+#[derive(Debug, Clone)]
+pub struct DposEngine {
+    /// Configuracao do consenso
+    pub config: DposConfig,
+    /// Blocos assinados observados por (validador, altura)
+    observed_blocks: HashMap<(Hash256, u64), (Hash256, Signature)>,
+    /// Equivocacoes ja punidas, para nao punir duas vezes a mesma evidencia
+    slashed_offenses: HashSet<(Hash256, u64)>,
+    /// Desempenho de cada validador
+    pub performance: HashMap<Hash256, ValidatorPerformance>,
+    /// Evidencias aguardando o fim da epoca, com quem as reportou
+    pending_slashing: Vec<(SlashingEvidence, Hash256)>,
+    /// Ordem de producao de blocos da epoca atual
+    schedule: Vec<Hash256>,
+}
+
+impl DposEngine {
+    /// Cria um novo motor de consenso
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se `config` nao passar em [`DposConfig::validate`]
+    pub fn new(config: DposConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            observed_blocks: HashMap::new(),
+            slashed_offenses: HashSet::new(),
+            performance: HashMap::new(),
+            pending_slashing: Vec::new(),
+            schedule: Vec::new(),
+        })
+    }
+
+    /// Registra se um validador produziu o bloco do seu slot
+    pub fn record_slot(&mut self, validator: Hash256, produced: bool) {
+        self.performance
+            .entry(validator)
+            .or_default()
+            .record_slot(produced);
+    }
+
+    /// Fecha a epoca no registro de desempenho de todos os validadores
+    pub fn close_epoch_performance(&mut self) {
+        for performance in self.performance.values_mut() {
+            performance.close_epoch(&self.config);
+        }
+    }
+
+    /// Validadores cuja taxa de aprovacao recente justifica punicao,
+    /// ordenados pelo endereco
+    #[must_use]
+    pub fn underperforming_validators(&self) -> Vec<Hash256> {
+        let mut validators: Vec<Hash256> = self
+            .performance
+            .iter()
+            .filter(|(_, performance)| performance.should_be_slashed(&self.config))
+            .map(|(validator, _)| *validator)
+            .collect();
+        validators.sort_by_key(|validator| *validator.as_bytes());
+        validators
+    }
+
+    /// Peso de cada validador ativo, limitado por `max_voting_power_bps`
+    ///
+    /// O peso parte do stake efetivo, mas nenhum validador passa da fracao
+    /// maxima do stake efetivo total dos ativos; o excedente nao da
+    /// influencia extra, o que aumenta a fatia relativa dos menores.
+    #[must_use]
+    pub fn voting_weights(&self, state: &AevumState) -> HashMap<Hash256, u128> {
+        let active: Vec<(Hash256, u128)> = state
+            .validators
+            .iter()
+            .filter(|(_, info)| info.is_active)
+            .map(|(address, info)| (*address, info.effective_stake()))
+            .collect();
+        let total: u128 = active.iter().map(|(_, stake)| stake).sum();
+        let cap = total * self.config.max_voting_power_bps.min(BPS_SCALE) / BPS_SCALE;
+
+        active
+            .into_iter()
+            .map(|(address, stake)| (address, stake.min(cap)))
+            .collect()
+    }
+
+    /// Divide a recompensa da epoca entre os validadores ativos
+    ///
+    /// O peso de cada validador e o de `voting_weights` ponderado pela taxa
+    /// de aprovacao e acrescido do bonus de uptime. Restos da divisao
+    /// inteira nao sao distribuidos.
+    #[must_use]
+    pub fn calculate_rewards(
+        &self,
+        state: &AevumState,
+        total_reward: u128,
+    ) -> HashMap<Hash256, u128> {
+        let weights: Vec<(Hash256, u128)> = self
+            .voting_weights(state)
+            .into_iter()
+            .map(|(address, stake)| {
+                let performance = self.performance.get(&address).cloned().unwrap_or_default();
+                let weight = stake * performance.approval_rate_bps() / BPS_SCALE
+                    * (BPS_SCALE + performance.uptime_bonus_bps(&self.config))
+                    / BPS_SCALE;
+                (address, weight)
+            })
+            .collect();
+
+        let total_weight: u128 = weights.iter().map(|(_, weight)| weight).sum();
+        if total_weight == 0 {
+            return HashMap::new();
+        }
+
+        weights
+            .into_iter()
+            .map(|(address, weight)| (address, total_reward * weight / total_weight))
+            .collect()
+    }
+
+    /// Elege os validadores da proxima epoca
+    ///
+    /// Candidatos sao ordenados pelo stake efetivo (proprio mais delegado),
+    /// com o endereco como desempate para que todos os nos cheguem ao mesmo
+    /// resultado. Validadores ainda no atraso de ativacao ficam de fora. Os
+    /// `max_validators` primeiros acima do stake minimo sao ativados; os
+    /// demais sao desativados.
+    ///
+    /// A eleicao usa o stake sem limite; a influencia dos eleitos depois e
+    /// limitada por `voting_weights`.
+    pub fn elect_validators(&self, state: &mut AevumState) -> Vec<Hash256> {
+        let mut candidates: Vec<(Hash256, u128)> = state
+            .validators_by_stake()
+            .into_iter()
+            .filter(|(address, stake)| {
+                *stake >= self.config.min_validator_stake
+                    && state.validators[address].activation_epoch <= state.current_epoch
+            })
+            .collect();
+        candidates.truncate(self.config.max_validators as usize);
+
+        let elected: Vec<Hash256> = candidates.into_iter().map(|(address, _)| address).collect();
+        for (address, info) in &mut state.validators {
+            info.is_active = elected.contains(address);
+        }
+
+        elected
+    }
+
+    /// Registra um bloco assinado por um validador
+    ///
+    /// Retorna uma evidencia de slashing caso o validador ja tenha
+    /// assinado outro bloco na mesma altura.
+    pub fn record_signed_block(
+        &mut self,
+        validator: Hash256,
+        height: u64,
+        block_hash: Hash256,
+        signature: Signature,
+    ) -> Option<SlashingEvidence> {
+        match self.observed_blocks.get(&(validator, height)) {
+            Some((previous_hash, previous_sig)) if *previous_hash != block_hash => {
+                Some(SlashingEvidence {
+                    validator,
+                    height,
+                    block_hash_a: *previous_hash,
+                    block_hash_b: block_hash,
+                    sig_a: previous_sig.clone(),
+                    sig_b: signature,
+                })
+            }
+            Some(_) => None,
+            None => {
+                self.observed_blocks
+                    .insert((validator, height), (block_hash, signature));
+                None
+            }
+        }
+    }
+
+    /// Verifica se o bloco atual do estado encerra a epoca corrente
+    #[must_use]
+    pub const fn should_advance_epoch(&self, state: &AevumState) -> bool {
+        self.config.epoch_length > 0
+            && state.block_height
+                >= (state.current_epoch + 1).saturating_mul(self.config.epoch_length)
+    }
+
+    /// Ordem de producao de blocos para os validadores eleitos
+    ///
+    /// Os `epoch_length` slots da epoca sao repartidos em proporcao ao peso
+    /// de `voting_weights`, com as sobras indo para os maiores restos, e
+    /// embaralhados com uma semente derivada da epoca e do conjunto de
+    /// validadores, para que todos os nos obtenham a mesma ordem. A ordem e
+    /// reduzida ao menor ciclo com as mesmas proporcoes, que `slot_leader`
+    /// percorre. Sem peso conhecido, os eleitos recebem um slot cada.
+    #[must_use]
+    pub fn generate_schedule(
+        &self,
+        state: &AevumState,
+        elected: &[Hash256],
+        epoch: u64,
+    ) -> Vec<Hash256> {
+        let weights = self.voting_weights(state);
+        let mut set: Vec<(Hash256, u128)> = elected
+            .iter()
+            .map(|address| (*address, weights.get(address).copied().unwrap_or(0)))
+            .collect();
+        set.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+        set.dedup_by_key(|(address, _)| *address);
+        if set.iter().all(|(_, weight)| *weight == 0) {
+            for (_, weight) in &mut set {
+                *weight = 1;
+            }
+        }
+        let total: u128 = set.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return Vec::new();
+        }
+
+        // Quota inteira de cada validador; as sobras vao para os maiores restos
+        let slots = u128::from(self.config.epoch_length).max(set.len() as u128);
+        let mut counts: Vec<u128> = set
+            .iter()
+            .map(|(_, weight)| slots * weight / total)
+            .collect();
+        let mut by_remainder: Vec<usize> = (0..set.len()).collect();
+        by_remainder.sort_by_key(|&i| std::cmp::Reverse(slots * set[i].1 % total));
+        let assigned: u128 = counts.iter().sum();
+        for &i in by_remainder
+            .iter()
+            .take(usize::try_from(slots - assigned).unwrap_or(0))
+        {
+            counts[i] += 1;
+        }
+
+        let divisor = counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .fold(0, |acc, &count| gcd(acc, count))
+            .max(1);
+        let mut schedule: Vec<Hash256> = set
+            .iter()
+            .zip(&counts)
+            .flat_map(|((address, _), count)| {
+                std::iter::repeat_n(*address, usize::try_from(count / divisor).unwrap_or(0))
+            })
+            .collect();
+
+        // Fisher-Yates com aleatoriedade derivada da semente
+        let mut seed_bytes = epoch.to_le_bytes().to_vec();
+        for (address, weight) in &set {
+            seed_bytes.extend_from_slice(address.as_bytes());
+            seed_bytes.extend_from_slice(&weight.to_le_bytes());
+        }
+        let seed = Hash256::keccak256(&seed_bytes);
+        for i in (1..schedule.len()).rev() {
+            let mut draw = seed.as_bytes().to_vec();
+            draw.extend_from_slice(&(i as u64).to_le_bytes());
+            let random = Hash256::keccak256(&draw);
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&random.as_bytes()[..8]);
+            let j = u64::from_le_bytes(word) % (i as u64 + 1);
+            schedule.swap(i, usize::try_from(j).unwrap_or(0));
+        }
+        schedule
+    }
+
+    /// Ordem de producao de blocos da epoca atual
+    #[must_use]
+    pub fn schedule(&self) -> &[Hash256] {
+        &self.schedule
+    }
+
+    /// Validador responsavel pelo bloco na altura indicada
+    #[must_use]
+    pub fn slot_leader(&self, height: u64) -> Option<Hash256> {
+        if self.schedule.is_empty() {
+            return None;
+        }
+        let slot = height % self.schedule.len() as u64;
+        self.schedule
+            .get(usize::try_from(slot).unwrap_or(0))
+            .copied()
+    }
+
+    /// Enfileira uma evidencia para ser aplicada no fim da epoca
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a evidencia for invalida
+    pub fn queue_slashing_evidence(
+        &mut self,
+        evidence: SlashingEvidence,
+        reporter: Hash256,
+    ) -> Result<()> {
+        evidence.verify()?;
+        self.pending_slashing.push((evidence, reporter));
+        Ok(())
+    }
+
+    /// Encerra a epoca se o bloco atual do estado chegou ao seu fim
+    ///
+    /// Aplica as evidencias de slashing pendentes, divide `epoch_reward`
+    /// entre os validadores ativos, avanca `state.current_epoch`, elege os
+    /// validadores da nova epoca e gera sua ordem de producao. O historico
+    /// de desempenho continua valendo para quem segue registrado. Retorna
+    /// `false` sem alterar nada se a epoca ainda nao terminou.
+    pub fn advance_epoch(&mut self, state: &mut AevumState) -> bool {
+        if !self.should_advance_epoch(state) {
+            return false;
+        }
+
+        for (evidence, reporter) in std::mem::take(&mut self.pending_slashing) {
+            // Validador que saiu do registro nao tem mais o que punir
+            let _ = self.process_slashing_evidence(state, &evidence, &reporter);
+        }
+
+        self.close_epoch_performance();
+        for (validator, reward) in self.calculate_rewards(state, self.config.epoch_reward) {
+            state.credit(validator, reward);
+        }
+
+        state.advance_epoch();
+        let elected = self.elect_validators(state);
+        self.schedule = self.generate_schedule(state, &elected, state.current_epoch);
+        self.performance
+            .retain(|validator, _| state.validators.contains_key(validator));
+
+        true
+    }
+
+    /// Remove `SLASH_PERCENTAGE` do stake de um validador (inclusive o que
+    /// aguarda liberacao) e de cada delegacao feita a ele
+    ///
+    /// Retorna o valor removido por endereco: o validador pelo stake
+    /// proprio e cada delegador pela sua delegacao. O validador e
+    /// desativado se o stake efetivo restante ficar abaixo do minimo.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o validador nao estiver registrado
+    pub fn apply_slashing(
+        &self,
+        state: &mut AevumState,
+        validator: &Hash256,
+    ) -> Result<HashMap<Hash256, u128>> {
+        let info = state.validators.get_mut(validator).ok_or_else(|| {
+            BlockchainError::InvalidTransaction("Validador nao encontrado".to_string())
+        })?;
+
+        let mut breakdown = HashMap::new();
+        let mut own_slash = info.stake_amount * SLASH_PERCENTAGE / 100;
+        info.remove_stake(own_slash)?;
+        // Stake ainda na fila de liberacao tambem responde pela falta
+        for entry in &mut info.unbonding {
+            let slash = entry.amount * SLASH_PERCENTAGE / 100;
+            entry.amount -= slash;
+            own_slash += slash;
+        }
+        breakdown.insert(*validator, own_slash);
+
+        let mut delegated_slash = 0;
+        for ((delegator, delegate), amount) in &mut state.delegations {
+            if delegate != validator {
+                continue;
+            }
+            let slash = *amount * SLASH_PERCENTAGE / 100;
+            *amount -= slash;
+            delegated_slash += slash;
+            *breakdown.entry(*delegator).or_insert(0) += slash;
+        }
+
+        if let Some(info) = state.validators.get_mut(validator) {
+            info.delegated_stake = info.delegated_stake.saturating_sub(delegated_slash);
+            if info.effective_stake() < self.config.min_validator_stake {
+                info.is_active = false;
+            }
+        }
+
+        Ok(breakdown)
+    }
+
+    /// Processa uma evidencia recebida pela rede
+    ///
+    /// `reporter` recebe `slashing_bounty_bps` do valor punido como
+    /// recompensa; o restante e queimado. Retorna o valor punido, ou zero
+    /// se a evidencia ja foi aplicada.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a evidencia for invalida ou o validador desconhecido
+    pub fn process_slashing_evidence(
+        &mut self,
+        state: &mut AevumState,
+        evidence: &SlashingEvidence,
+        reporter: &Hash256,
+    ) -> Result<u128> {
+        evidence.verify()?;
+
+        if !self
+            .slashed_offenses
+            .insert((evidence.validator, evidence.height))
+        {
+            return Ok(0);
+        }
+
+        let slashed: u128 = self
+            .apply_slashing(state, &evidence.validator)?
+            .values()
+            .sum();
+        let bounty = slashed * self.config.slashing_bounty_bps / BPS_SCALE;
+        state.credit(*reporter, bounty);
+        state.burned_supply += slashed - bounty;
+
+        Ok(slashed)
+    }
+}
+
+/// Maximo divisor comum
+const fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let rest = a % b;
+        a = b;
+        b = rest;
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::SignatureAlgorithm;
+
+    fn setup_validator(state: &mut AevumState, keypair: &KeyPair) -> Hash256 {
+        let validator = utils::address_from_key(&keypair.public_key);
+        state.register_validator(validator, 10_000).unwrap();
+        validator
+    }
+
+    #[test]
+    fn test_valid_evidence_slashes_on_receiving_node() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let block_a = Hash256::keccak256(b"bloco a");
+        let block_b = Hash256::keccak256(b"bloco b");
+
+        // No que detecta a equivocacao
+        let mut detector = DposEngine::new(DposConfig::default()).unwrap();
+        let validator = utils::address_from_key(&keypair.public_key);
+        let sig_a = SlashingEvidence::sign_block(&keypair, 7, &block_a).unwrap();
+        let sig_b = SlashingEvidence::sign_block(&keypair, 7, &block_b).unwrap();
+        assert!(detector
+            .record_signed_block(validator, 7, block_a, sig_a)
+            .is_none());
+        let evidence = detector
+            .record_signed_block(validator, 7, block_b, sig_b)
+            .unwrap();
+
+        // Evidencia trafega serializada pela rede
+        let gossip = serde_json::to_vec(&evidence).unwrap();
+        let received: SlashingEvidence = serde_json::from_slice(&gossip).unwrap();
+
+        // No receptor verifica e aplica de forma independente
+        let mut state = AevumState::new();
+        setup_validator(&mut state, &keypair);
+        let mut receiver = DposEngine::new(DposConfig::default()).unwrap();
+        let reporter = Hash256::keccak256(b"reporter");
+
+        let slashed = receiver
+            .process_slashing_evidence(&mut state, &received, &reporter)
+            .unwrap();
+        assert_eq!(slashed, 1_000);
+        assert_eq!(state.validators[&validator].stake_amount, 9_000);
+
+        // Denunciante recebe 10% do valor punido; o restante e queimado
+        assert_eq!(state.get_account(&reporter).unwrap().balance, 100);
+        assert_eq!(state.burned_supply, 900);
+
+        // Reenvio da mesma evidencia nao pune novamente
+        assert_eq!(
+            receiver
+                .process_slashing_evidence(&mut state, &received, &reporter)
+                .unwrap(),
+            0
+        );
+        assert_eq!(state.validators[&validator].stake_amount, 9_000);
+        assert_eq!(state.get_account(&reporter).unwrap().balance, 100);
+    }
+
+    #[test]
+    fn test_delegation_raises_election_weight() {
+        use crate::transaction::{AevumTransaction, AevumTransactionType};
+
+        let config = DposConfig {
+            max_validators: 1,
+            ..DposConfig::default()
+        };
+        let engine = DposEngine::new(config.clone()).unwrap();
+        let mut state = AevumState::with_config(config);
+
+        let strong = Hash256::keccak256(b"strong");
+        let weak = Hash256::keccak256(b"weak");
+        let delegator_key = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let delegator = utils::address_from_key(&delegator_key.public_key);
+        state.register_validator(strong, 5_000).unwrap();
+        state.register_validator(weak, 3_000).unwrap();
+        state.create_account(delegator, 1_000_000);
+        state.advance_epoch();
+
+        assert_eq!(engine.elect_validators(&mut state), vec![strong]);
+
+        let mut delegate = AevumTransaction::new(
+            delegator,
+            Hash256::zero(),
+            3_000,
+            0,
+            50_000,
+            1,
+            AevumTransactionType::Delegate { validator: weak },
+        );
+        delegate.sign(&delegator_key).unwrap();
+        assert!(state.apply_transaction(&delegate).unwrap().is_success());
+
+        assert_eq!(engine.elect_validators(&mut state), vec![weak]);
+        assert!(state.validators[&weak].is_active);
+        assert!(!state.validators[&strong].is_active);
+    }
+
+    #[test]
+    fn test_activation_delay_before_election() {
+        let config = DposConfig {
+            activation_delay_epochs: 2,
+            ..DposConfig::default()
+        };
+        let engine = DposEngine::new(config.clone()).unwrap();
+        let mut state = AevumState::with_config(config);
+        let veteran = Hash256::keccak256(b"veteran");
+        let newcomer = Hash256::keccak256(b"newcomer");
+
+        state.register_validator(veteran, 5_000).unwrap();
+        state.advance_epoch();
+        state.advance_epoch();
+
+        // Registrado nesta epoca com stake maior, mas ainda nao elegivel
+        state.register_validator(newcomer, 50_000).unwrap();
+        assert_eq!(state.validators[&newcomer].activation_epoch, 4);
+        assert_eq!(engine.elect_validators(&mut state), vec![veteran]);
+        assert!(!state.validators[&newcomer].is_active);
+
+        state.advance_epoch();
+        assert_eq!(engine.elect_validators(&mut state), vec![veteran]);
+
+        state.advance_epoch();
+        assert_eq!(engine.elect_validators(&mut state), vec![newcomer, veteran]);
+    }
+
+    #[test]
+    fn test_validators_by_stake_matches_election() {
+        let config = DposConfig {
+            max_validators: 3,
+            ..DposConfig::default()
+        };
+        let engine = DposEngine::new(config.clone()).unwrap();
+        let mut state = AevumState::with_config(config);
+
+        let stakes = [
+            (b"a".as_slice(), 2_000),
+            (b"b", 7_000),
+            (b"c", 4_000),
+            (b"d", 4_000),
+            (b"e", 500),
+        ];
+        for (seed, stake) in stakes {
+            state
+                .register_validator(Hash256::keccak256(seed), stake)
+                .unwrap();
+        }
+        // Stake delegado conta para a ordenacao
+        let a = Hash256::keccak256(b"a");
+        state.validators.get_mut(&a).unwrap().delegated_stake = 3_000;
+        state.advance_epoch();
+
+        let ranking = state.validators_by_stake();
+        assert_eq!(ranking.len(), 5);
+        assert!(ranking.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert_eq!(ranking[0], (Hash256::keccak256(b"b"), 7_000));
+        assert_eq!(ranking[1].1, 5_000);
+
+        let elected = engine.elect_validators(&mut state);
+        let expected: Vec<Hash256> = ranking
+            .iter()
+            .take(3)
+            .map(|(address, _)| *address)
+            .collect();
+        assert_eq!(elected, expected);
+    }
+
+    #[test]
+    fn test_uptime_streak_earns_bonus() {
+        let mut engine = DposEngine::new(DposConfig::default()).unwrap();
+        let mut state = AevumState::new();
+        let steady = Hash256::keccak256(b"steady");
+        let recent_miss = Hash256::keccak256(b"recent miss");
+        for validator in [steady, recent_miss] {
+            state.register_validator(validator, 10_000).unwrap();
+            state.validators.get_mut(&validator).unwrap().is_active = true;
+        }
+
+        // Cinco epocas de 10 slots; cada validador perde exatamente um slot
+        for epoch in 0..5 {
+            for slot in 0..10 {
+                engine.record_slot(steady, !(epoch == 0 && slot == 0));
+                engine.record_slot(recent_miss, !(epoch == 4 && slot == 0));
+            }
+            engine.close_epoch_performance();
+        }
+
+        let steady_perf = &engine.performance[&steady];
+        let recent_perf = &engine.performance[&recent_miss];
+        assert_eq!(
+            steady_perf.approval_rate_bps(),
+            recent_perf.approval_rate_bps()
+        );
+        assert_eq!(steady_perf.consecutive_good_epochs, 4);
+        assert_eq!(recent_perf.consecutive_good_epochs, 0);
+
+        let rewards = engine.calculate_rewards(&state, 1_000_000);
+        assert!(rewards[&steady] > rewards[&recent_miss]);
+    }
+
+    #[test]
+    fn test_missed_recent_epoch_crosses_slash_threshold() {
+        let mut engine = DposEngine::new(DposConfig::default()).unwrap();
+        let veteran = Hash256::keccak256(b"veteran");
+
+        // Dez epocas perfeitas de 10 slots
+        for _ in 0..10 {
+            for _ in 0..10 {
+                engine.record_slot(veteran, true);
+            }
+            engine.close_epoch_performance();
+        }
+        assert!(engine.underperforming_validators().is_empty());
+
+        // Um slot perdido nao basta
+        engine.record_slot(veteran, false);
+        for _ in 0..9 {
+            engine.record_slot(veteran, true);
+        }
+        engine.close_epoch_performance();
+        assert!(engine.underperforming_validators().is_empty());
+
+        // Uma epoca inteira sem produzir
+        for _ in 0..10 {
+            engine.record_slot(veteran, false);
+        }
+        engine.close_epoch_performance();
+
+        let performance = &engine.performance[&veteran];
+        // A taxa de toda a vida quase nao muda; a recente despenca
+        assert!(performance.approval_rate_bps() > 9_000);
+        assert_eq!(performance.recent_approval_rate_bps(), 4_750);
+        assert!(performance.should_be_slashed(&engine.config));
+        assert_eq!(engine.underperforming_validators(), vec![veteran]);
+    }
+
+    #[test]
+    fn test_approval_decay_is_configurable() {
+        let config = DposConfig::builder()
+            .approval_rate_bps(9_000, 2_000)
+            .build()
+            .unwrap();
+        let mut engine = DposEngine::new(config).unwrap();
+        let veteran = Hash256::keccak256(b"veteran");
+        for produced in [true, false] {
+            for _ in 0..10 {
+                engine.record_slot(veteran, produced);
+            }
+            engine.close_epoch_performance();
+        }
+
+        // Com 90% de peso na ultima epoca, uma falha completa derruba a
+        // taxa abaixo de 20%
+        assert_eq!(
+            engine.performance[&veteran].recent_approval_rate_bps(),
+            1_000
+        );
+        assert_eq!(engine.underperforming_validators(), vec![veteran]);
+        assert!(DposConfig::builder()
+            .approval_rate_bps(0, 5_000)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_advance_epoch_reelects_validators() {
+        let config = DposConfig {
+            max_validators: 1,
+            epoch_length: 10,
+            epoch_reward: 1_000,
+            ..DposConfig::default()
+        };
+        let mut engine = DposEngine::new(config.clone()).unwrap();
+        let mut state = AevumState::with_config(config);
+        let incumbent = Hash256::keccak256(b"incumbent");
+        let challenger = Hash256::keccak256(b"challenger");
+        state.register_validator(incumbent, 5_000).unwrap();
+
+        state.block_height = 9;
+        assert!(!engine.advance_epoch(&mut state));
+        assert_eq!(state.current_epoch, 0);
+
+        // Primeira epoca: so o incumbente esta elegivel
+        state.block_height = 10;
+        assert!(engine.advance_epoch(&mut state));
+        assert_eq!(state.current_epoch, 1);
+        assert_eq!(engine.schedule(), &[incumbent]);
+        assert_eq!(engine.slot_leader(11), Some(incumbent));
+
+        for height in 10..20 {
+            engine.record_slot(engine.slot_leader(height).unwrap(), true);
+        }
+        state.register_validator(challenger, 8_000).unwrap();
+
+        // Segunda epoca: o desafiante com mais stake assume a vaga
+        state.block_height = 20;
+        assert!(engine.advance_epoch(&mut state));
+        assert_eq!(state.current_epoch, 2);
+        assert_eq!(engine.schedule(), &[challenger]);
+        assert!(state.validators[&challenger].is_active);
+        assert!(!state.validators[&incumbent].is_active);
+
+        // O incumbente recebeu a recompensa da epoca que produziu
+        assert_eq!(state.get_account(&incumbent).unwrap().balance, 1_000);
+        let performance = &engine.performance[&incumbent];
+        assert_eq!(performance.blocks_produced, 10);
+        assert_eq!(performance.consecutive_good_epochs, 1);
+    }
+
+    #[test]
+    fn test_pending_slashing_applied_at_epoch_end() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let config = DposConfig {
+            epoch_length: 10,
+            ..DposConfig::default()
+        };
+        let mut engine = DposEngine::new(config.clone()).unwrap();
+        let mut state = AevumState::with_config(config);
+        let validator = setup_validator(&mut state, &keypair);
+        let block_a = Hash256::keccak256(b"bloco a");
+        let block_b = Hash256::keccak256(b"bloco b");
+        let evidence = SlashingEvidence {
+            validator,
+            height: 3,
+            block_hash_a: block_a,
+            block_hash_b: block_b,
+            sig_a: SlashingEvidence::sign_block(&keypair, 3, &block_a).unwrap(),
+            sig_b: SlashingEvidence::sign_block(&keypair, 3, &block_b).unwrap(),
+        };
+        let reporter = Hash256::keccak256(b"reporter");
+
+        engine.queue_slashing_evidence(evidence, reporter).unwrap();
+        assert_eq!(state.validators[&validator].stake_amount, 10_000);
+
+        state.block_height = 10;
+        assert!(engine.advance_epoch(&mut state));
+        assert_eq!(state.validators[&validator].stake_amount, 9_000);
+        assert_eq!(state.get_account(&reporter).unwrap().balance, 100);
+    }
+
+    #[test]
+    fn test_voting_power_cap_limits_large_validator() {
+        let config = DposConfig {
+            max_voting_power_bps: 3_333,
+            ..DposConfig::default()
+        };
+        let mut engine = DposEngine::new(config.clone()).unwrap();
+        let mut state = AevumState::with_config(config);
+        let whale = Hash256::keccak256(b"whale");
+        let small_a = Hash256::keccak256(b"small a");
+        let small_b = Hash256::keccak256(b"small b");
+        state.register_validator(whale, 10_000).unwrap();
+        state.register_validator(small_a, 1_000).unwrap();
+        state.register_validator(small_b, 1_000).unwrap();
+        state.advance_epoch();
+        assert_eq!(engine.elect_validators(&mut state).len(), 3);
+
+        // Limite de 33,33% de 12.000
+        let weights = engine.voting_weights(&state);
+        assert_eq!(weights[&whale], 3_999);
+        assert_eq!(weights[&small_a], 1_000);
+
+        let capped = engine.calculate_rewards(&state, 1_000_000);
+        engine.config.max_voting_power_bps = BPS_SCALE;
+        let uncapped = engine.calculate_rewards(&state, 1_000_000);
+        assert_eq!(uncapped[&whale], 833_333);
+        assert_eq!(capped[&whale], 666_611);
+        assert!(capped[&small_a] > uncapped[&small_a]);
+        assert_eq!(capped[&small_a], capped[&small_b]);
+    }
+
+    #[test]
+    fn test_slashing_reduces_delegations_proportionally() {
+        use crate::transaction::{AevumTransaction, AevumTransactionType};
+
+        let config = DposConfig {
+            min_validator_stake: 15_000,
+            ..DposConfig::default()
+        };
+        let engine = DposEngine::new(config.clone()).unwrap();
+        let mut state = AevumState::with_config(config);
+        let validator = Hash256::keccak256(b"validator");
+        let alice_key = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let bob_key = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let alice = utils::address_from_key(&alice_key.public_key);
+        let bob = utils::address_from_key(&bob_key.public_key);
+        state.register_validator(validator, 10_000).unwrap();
+        for (delegator_key, amount) in [(&alice_key, 4_000), (&bob_key, 2_500)] {
+            let delegator = utils::address_from_key(&delegator_key.public_key);
+            state.create_account(delegator, 1_000_000);
+            let mut delegate = AevumTransaction::new(
+                delegator,
+                Hash256::zero(),
+                amount,
+                0,
+                50_000,
+                1,
+                AevumTransactionType::Delegate { validator },
+            );
+            delegate.sign(delegator_key).unwrap();
+            assert!(state.apply_transaction(&delegate).unwrap().is_success());
+        }
+        state.advance_epoch();
+        engine.elect_validators(&mut state);
+        assert!(state.validators[&validator].is_active);
+
+        let breakdown = engine.apply_slashing(&mut state, &validator).unwrap();
+        assert_eq!(breakdown.len(), 3);
+        assert_eq!(breakdown[&validator], 1_000);
+        assert_eq!(breakdown[&alice], 400);
+        assert_eq!(breakdown[&bob], 250);
+
+        assert_eq!(state.delegations[&(alice, validator)], 3_600);
+        assert_eq!(state.delegations[&(bob, validator)], 2_250);
+        let info = &state.validators[&validator];
+        assert_eq!(info.stake_amount, 9_000);
+        assert_eq!(info.delegated_stake, 5_850);
+
+        // 14.850 de stake efetivo fica abaixo do minimo de 15.000
+        assert!(!info.is_active);
+        assert_eq!(state.voting_stake(&alice), 3_600);
+    }
+
+    fn scheduling_state(stakes: &[(&[u8], u128)]) -> (DposEngine, AevumState, Vec<Hash256>) {
+        let config = DposConfig {
+            epoch_length: 3_000,
+            max_voting_power_bps: BPS_SCALE,
+            activation_delay_epochs: 0,
+            ..DposConfig::default()
+        };
+        let engine = DposEngine::new(config.clone()).unwrap();
+        let mut state = AevumState::with_config(config);
+        for (seed, stake) in stakes {
+            state
+                .register_validator(Hash256::keccak256(seed), *stake)
+                .unwrap();
+        }
+        let elected = engine.elect_validators(&mut state);
+        (engine, state, elected)
+    }
+
+    #[test]
+    fn test_schedule_slots_follow_stake() {
+        let (engine, state, elected) =
+            scheduling_state(&[(b"grande", 3_003), (b"pequeno", 1_001), (b"medio", 1_700)]);
+        let large = Hash256::keccak256(b"grande");
+        let small = Hash256::keccak256(b"pequeno");
+
+        let schedule = engine.generate_schedule(&state, &elected, 1);
+        let slots = |validator: &Hash256| {
+            (0..engine.config.epoch_length)
+                .filter(|height| {
+                    schedule[usize::try_from(height % schedule.len() as u64).unwrap()] == *validator
+                })
+                .count()
+        };
+        let (large_slots, small_slots) = (slots(&large), slots(&small));
+        assert!(large_slots * 100 >= small_slots * 290);
+        assert!(large_slots * 100 <= small_slots * 310);
+
+        // Rodizio simples daria a cada um um terco dos slots
+        assert!(large_slots > 1_000);
+    }
+
+    #[test]
+    fn test_schedule_is_reproducible() {
+        let stakes: [(&[u8], u128); 4] =
+            [(b"a", 2_000), (b"b", 1_500), (b"c", 4_100), (b"d", 1_000)];
+        let (engine, state, elected) = scheduling_state(&stakes);
+        let (other_engine, other_state, mut other_elected) = scheduling_state(&stakes);
+        other_elected.reverse();
+
+        let schedule = engine.generate_schedule(&state, &elected, 5);
+        assert_eq!(
+            schedule,
+            other_engine.generate_schedule(&other_state, &other_elected, 5)
+        );
+        assert_ne!(schedule, engine.generate_schedule(&state, &elected, 6));
+    }
+
+    #[test]
+    fn test_forged_evidence_is_rejected() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let other = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let block_a = Hash256::keccak256(b"bloco a");
+        let block_b = Hash256::keccak256(b"bloco b");
+
+        let mut state = AevumState::new();
+        let validator = setup_validator(&mut state, &keypair);
+
+        // Segunda assinatura feita por outra chave
+        let forged = SlashingEvidence {
+            validator,
+            height: 7,
+            block_hash_a: block_a,
+            block_hash_b: block_b,
+            sig_a: SlashingEvidence::sign_block(&keypair, 7, &block_a).unwrap(),
+            sig_b: SlashingEvidence::sign_block(&other, 7, &block_b).unwrap(),
+        };
+
+        // Assinatura valida, mas sobre outro bloco
+        let mismatched = SlashingEvidence {
+            sig_b: SlashingEvidence::sign_block(&keypair, 7, &block_a).unwrap(),
+            ..forged.clone()
+        };
+
+        let mut engine = DposEngine::new(DposConfig::default()).unwrap();
+        let reporter = Hash256::keccak256(b"reporter");
+        assert!(engine
+            .process_slashing_evidence(&mut state, &forged, &reporter)
+            .is_err());
+        assert!(engine
+            .process_slashing_evidence(&mut state, &mismatched, &reporter)
+            .is_err());
+        assert_eq!(state.validators[&validator].stake_amount, 10_000);
+        assert!(state.get_account(&reporter).is_none());
+        assert_eq!(state.burned_supply, 0);
+    }
+}
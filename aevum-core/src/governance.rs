@@ -0,0 +1,394 @@
+//! Governanca on-chain do Aevum
+//!
+//! Propostas sao votadas com peso proporcional ao stake durante uma
+//! janela de blocos e finalizadas ao final dessa janela. Uma proposta
+//! aprovada pode carregar uma `ProposalAction`, aplicada aos parametros do
+//! consenso por `AevumState::execute_proposal`.
+
+use crate::placeholder::DposConfig;
+use serde::{Deserialize, Serialize};
+use shared::{BlockchainError, Hash256, Result};
+use std::collections::{HashMap, HashSet};
+
+/// Estado de uma proposta de governanca
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProposalStatus {
+    /// Votacao em andamento
+    Active,
+    /// Aprovada pela maioria do stake votante
+    Passed,
+    /// Rejeitada
+    Rejected,
+    /// Aprovada e aplicada
+    Executed,
+}
+
+/// Mudanca de parametro aplicada quando uma proposta aprovada e executada
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProposalAction {
+    /// Altera `DposConfig::min_validator_stake`
+    SetMinValidatorStake(u128),
+    /// Altera `DposConfig::epoch_reward`, a recompensa dividida por epoca
+    SetEpochReward(u128),
+    /// Altera `DposConfig::max_validators`
+    SetMaxValidators(u32),
+    /// Altera `DposConfig::fee_burn_bps`
+    SetFeeBurnBps(u128),
+}
+
+impl ProposalAction {
+    /// Aplica a acao a uma copia de `config` e valida o resultado
+    ///
+    /// # Errors
+    ///
+    /// Retorna o erro de [`DposConfig::validate`] se o novo valor for
+    /// invalido
+    pub fn apply(&self, config: &DposConfig) -> Result<DposConfig> {
+        let mut updated = config.clone();
+        match *self {
+            Self::SetMinValidatorStake(stake) => updated.min_validator_stake = stake,
+            Self::SetEpochReward(reward) => updated.epoch_reward = reward,
+            Self::SetMaxValidators(max) => updated.max_validators = max,
+            Self::SetFeeBurnBps(bps) => updated.fee_burn_bps = bps,
+        }
+        updated.validate()?;
+        Ok(updated)
+    }
+
+    /// Codificacao binaria deterministica: um byte de tag seguido do valor
+    pub(crate) fn write_canonical(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::SetMinValidatorStake(stake) => {
+                out.push(0);
+                out.extend_from_slice(&stake.to_le_bytes());
+            }
+            Self::SetEpochReward(reward) => {
+                out.push(1);
+                out.extend_from_slice(&reward.to_le_bytes());
+            }
+            Self::SetMaxValidators(max) => {
+                out.push(2);
+                out.extend_from_slice(&max.to_le_bytes());
+            }
+            Self::SetFeeBurnBps(bps) => {
+                out.push(3);
+                out.extend_from_slice(&bps.to_le_bytes());
+            }
+        }
+    }
+}
+
+/// Proposta de governanca
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovernanceProposal {
+    /// Identificador unico da proposta
+    pub id: u64,
+    /// Endereco de quem criou a proposta
+    pub proposer: Hash256,
+    /// Titulo curto
+    pub title: String,
+    /// Descricao detalhada
+    pub description: String,
+    /// Altura de inicio da votacao
+    pub voting_start: u64,
+    /// Ultima altura em que votos sao aceitos
+    pub voting_end: u64,
+    /// Peso total a favor
+    pub votes_for: u128,
+    /// Peso total contra
+    pub votes_against: u128,
+    /// Enderecos que ja votaram (descartados apos o periodo de retencao)
+    pub voters: HashSet<Hash256>,
+    /// Numero de votantes (preservado apos a poda)
+    pub voter_count: u64,
+    /// Compromisso sobre o conjunto de votantes, calculado na poda
+    pub voters_commitment: Option<Hash256>,
+    /// Estado atual
+    pub status: ProposalStatus,
+    /// Altura em que a proposta foi finalizada
+    pub finalized_at: Option<u64>,
+    /// Indica se a lista de votantes ja foi podada
+    pub pruned: bool,
+    /// Mudanca aplicada na execucao; `None` para propostas apenas
+    /// consultivas
+    #[serde(default)]
+    pub action: Option<ProposalAction>,
+}
+
+impl GovernanceProposal {
+    /// Cria uma nova proposta com votacao aberta entre `voting_start` e `voting_end`
+    #[must_use]
+    pub fn new(
+        id: u64,
+        proposer: Hash256,
+        title: String,
+        description: String,
+        voting_start: u64,
+        voting_end: u64,
+    ) -> Self {
+        Self {
+            id,
+            proposer,
+            title,
+            description,
+            voting_start,
+            voting_end,
+            votes_for: 0,
+            votes_against: 0,
+            voters: HashSet::new(),
+            voter_count: 0,
+            voters_commitment: None,
+            status: ProposalStatus::Active,
+            finalized_at: None,
+            pruned: false,
+            action: None,
+        }
+    }
+
+    /// Associa uma acao a ser aplicada se a proposta for aprovada
+    #[must_use]
+    pub const fn with_action(mut self, action: ProposalAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    /// Verifica se o periodo de votacao ja terminou
+    #[must_use]
+    pub const fn is_expired(&self, current_height: u64) -> bool {
+        current_height > self.voting_end
+    }
+
+    /// Verifica se a proposta ja foi finalizada
+    #[must_use]
+    pub const fn is_finalized(&self) -> bool {
+        self.finalized_at.is_some()
+    }
+
+    /// Registra um voto ponderado
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a votacao nao estiver aberta ou se o endereco ja votou
+    pub fn add_vote(
+        &mut self,
+        voter: Hash256,
+        support: bool,
+        weight: u128,
+        current_height: u64,
+    ) -> Result<()> {
+        if self.status != ProposalStatus::Active
+            || current_height < self.voting_start
+            || self.is_expired(current_height)
+        {
+            return Err(BlockchainError::InvalidTransaction(
+                "Votacao nao esta aberta".to_string(),
+            ));
+        }
+
+        if !self.voters.insert(voter) {
+            return Err(BlockchainError::InvalidTransaction(
+                "Endereco ja votou nesta proposta".to_string(),
+            ));
+        }
+
+        if support {
+            self.votes_for += weight;
+        } else {
+            self.votes_against += weight;
+        }
+        self.voter_count += 1;
+
+        Ok(())
+    }
+
+    /// Finaliza a proposta apos o fim da votacao
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a votacao ainda estiver aberta ou ja tiver sido finalizada
+    pub fn finalize(&mut self, current_height: u64) -> Result<ProposalStatus> {
+        if self.is_finalized() {
+            return Err(BlockchainError::InvalidTransaction(
+                "Proposta ja finalizada".to_string(),
+            ));
+        }
+
+        if !self.is_expired(current_height) {
+            return Err(BlockchainError::InvalidTransaction(
+                "Votacao ainda em andamento".to_string(),
+            ));
+        }
+
+        self.status = if self.votes_for > self.votes_against {
+            ProposalStatus::Passed
+        } else {
+            ProposalStatus::Rejected
+        };
+        self.finalized_at = Some(current_height);
+
+        Ok(self.status)
+    }
+
+    /// Marca como executada uma proposta aprovada
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a proposta ja tiver sido executada ou nao tiver sido
+    /// aprovada
+    pub fn mark_executed(&mut self) -> Result<()> {
+        match self.status {
+            ProposalStatus::Passed => {
+                self.status = ProposalStatus::Executed;
+                Ok(())
+            }
+            ProposalStatus::Executed => Err(BlockchainError::InvalidTransaction(
+                "Proposta ja executada".to_string(),
+            )),
+            ProposalStatus::Active | ProposalStatus::Rejected => Err(
+                BlockchainError::InvalidTransaction("Proposta nao foi aprovada".to_string()),
+            ),
+        }
+    }
+
+    /// Hash do conjunto de votantes, independente da ordem dos votos
+    #[must_use]
+    pub fn compute_voters_commitment(&self) -> Hash256 {
+        let mut voters: Vec<&Hash256> = self.voters.iter().collect();
+        voters.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+
+        let mut data = Vec::with_capacity(voters.len() * 32);
+        for voter in voters {
+            data.extend_from_slice(voter.as_bytes());
+        }
+        Hash256::keccak256(&data)
+    }
+
+    /// Descarta a lista de votantes, mantendo o resultado, a contagem e
+    /// um compromisso sobre quem votou
+    pub fn prune_voters(&mut self) {
+        self.voters_commitment = Some(self.compute_voters_commitment());
+        self.voters = HashSet::new();
+        self.pruned = true;
+    }
+}
+
+/// Poda propostas finalizadas ha mais de `proposal_retention_blocks`
+///
+/// Retorna o numero de propostas podadas.
+pub fn prune_finalized_proposals(
+    proposals: &mut HashMap<u64, GovernanceProposal>,
+    current_height: u64,
+    config: &DposConfig,
+) -> usize {
+    let mut pruned = 0;
+
+    for proposal in proposals.values_mut() {
+        let Some(finalized_at) = proposal.finalized_at else {
+            continue;
+        };
+
+        if !proposal.pruned
+            && current_height.saturating_sub(finalized_at) >= config.proposal_retention_blocks
+        {
+            proposal.prune_voters();
+            pruned += 1;
+        }
+    }
+
+    pruned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proposal(id: u64) -> GovernanceProposal {
+        GovernanceProposal::new(
+            id,
+            Hash256::keccak256(b"proposer"),
+            "Aumentar validadores".to_string(),
+            "Aumenta o numero maximo de validadores".to_string(),
+            0,
+            100,
+        )
+    }
+
+    #[test]
+    fn test_vote_and_finalize() {
+        let mut proposal = proposal(1);
+        let voter = Hash256::keccak256(b"voter");
+
+        proposal.add_vote(voter, true, 500, 10).unwrap();
+        assert!(proposal.add_vote(voter, false, 500, 11).is_err());
+        assert!(proposal.finalize(50).is_err());
+
+        assert_eq!(proposal.finalize(101).unwrap(), ProposalStatus::Passed);
+        assert!(proposal
+            .add_vote(Hash256::keccak256(b"late"), true, 1, 102)
+            .is_err());
+    }
+
+    #[test]
+    fn test_many_voters_are_counted_once() {
+        let mut proposal = proposal(1);
+        for i in 0..1_000u32 {
+            let voter = Hash256::keccak256(&i.to_le_bytes());
+            proposal.add_vote(voter, i % 4 != 0, 10, 5).unwrap();
+        }
+
+        // Nenhum votante consegue votar de novo, em qualquer sentido
+        for i in (0..1_000u32).step_by(97) {
+            let voter = Hash256::keccak256(&i.to_le_bytes());
+            assert!(proposal.add_vote(voter, true, 10, 6).is_err());
+            assert!(proposal.add_vote(voter, false, 10, 6).is_err());
+        }
+
+        assert_eq!(proposal.voter_count, 1_000);
+        assert_eq!(proposal.voters.len(), 1_000);
+        assert_eq!(proposal.votes_for, 7_500);
+        assert_eq!(proposal.votes_against, 2_500);
+    }
+
+    #[test]
+    fn test_prune_finalized_proposals() {
+        let config = DposConfig {
+            proposal_retention_blocks: 1_000,
+            ..DposConfig::default()
+        };
+        let mut proposals = HashMap::new();
+
+        let mut old = proposal(1);
+        old.add_vote(Hash256::keccak256(b"a"), true, 300, 10)
+            .unwrap();
+        old.add_vote(Hash256::keccak256(b"b"), false, 100, 20)
+            .unwrap();
+        old.finalize(101).unwrap();
+        proposals.insert(1, old);
+
+        let mut recent = proposal(2);
+        recent.voting_end = 1_500;
+        recent
+            .add_vote(Hash256::keccak256(b"a"), true, 300, 10)
+            .unwrap();
+        recent.finalize(1_501).unwrap();
+        proposals.insert(2, recent);
+
+        assert_eq!(prune_finalized_proposals(&mut proposals, 2_000, &config), 1);
+
+        // Proposta antiga perdeu os votantes, mas o resultado continua disponivel
+        let old = &proposals[&1];
+        assert!(old.pruned);
+        assert!(old.voters.is_empty());
+        assert!(old.voters_commitment.is_some());
+        assert_eq!(old.status, ProposalStatus::Passed);
+        assert_eq!(old.votes_for, 300);
+        assert_eq!(old.votes_against, 100);
+        assert_eq!(old.voter_count, 2);
+
+        // Proposta recente ainda dentro da janela de retencao
+        assert_eq!(proposals[&2].voters.len(), 1);
+
+        // Poda e idempotente
+        assert_eq!(prune_finalized_proposals(&mut proposals, 2_000, &config), 0);
+    }
+}
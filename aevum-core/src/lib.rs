@@ -22,10 +22,25 @@
 //! **Atual**: Estrutura básica implementada (Sprint 3)\
 //! **Próximo**: Implementação completa `DPoS` (Sprint 6)
 
+pub mod bridge;
+pub mod chain;
+pub mod consensus;
+pub mod governance;
+pub mod mempool;
 pub mod placeholder;
+pub mod transaction;
 
 // Re-exports para facilitar o uso da biblioteca
-pub use placeholder::{utils, AccountState, AevumState, DposConfig, ValidatorInfo};
+pub use bridge::{BondHeaderChain, BondSpvProof, BridgeRequest};
+pub use chain::{AevumBlock, AevumChain};
+pub use consensus::{DposEngine, SlashingEvidence, ValidatorPerformance};
+pub use governance::{GovernanceProposal, ProposalAction, ProposalStatus};
+pub use mempool::{AevumMempool, AevumMempoolConfig, DEFAULT_MEMPOOL_TTL_SECS};
+pub use placeholder::{
+    utils, AccountProof, AccountState, AevumState, DposConfig, DposConfigBuilder, UnbondingEntry,
+    ValidatorInfo,
+};
+pub use transaction::{AevumTransaction, AevumTransactionType, TxEvent, TxReceipt, TxStatus};
 
 /// Função placeholder para demonstração do módulo Aevum
 ///
@@ -62,47 +77,6 @@ pub mod constants {
     pub const BASE_GAS_PRICE: u128 = 1_000_000_000; // 1 Gwei
 }
 
-/// Funções utilitárias para integração Aevum-Bond
-pub mod bridge {
-    use shared::{Hash256, Result};
-
-    /// Representa uma transação de bridge entre Bond e Aevum
-    #[derive(Debug, Clone)]
-    pub struct BridgeTransaction {
-        /// Hash da transação no Bond
-        pub bond_tx_hash: Hash256,
-        /// Endereço de destino no Aevum
-        pub aevum_recipient: Hash256,
-        /// Valor a ser transferido
-        pub amount: u128,
-        /// Status da bridge
-        pub status: BridgeStatus,
-    }
-
-    /// Status possíveis de uma operação de bridge
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum BridgeStatus {
-        /// Transação iniciada no Bond
-        Initiated,
-        /// Validada por validadores
-        Validated,
-        /// Executada no Aevum
-        Executed,
-        /// Falhou por algum motivo
-        Failed(String),
-    }
-
-    /// Valida uma transação de bridge
-    ///
-    /// # Errors
-    ///
-    /// Retorna erro se a transação de bridge for inválida
-    pub const fn validate_bridge_transaction(_tx: &BridgeTransaction) -> Result<()> {
-        // Implementação placeholder para Sprint 8
-        Ok(())
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use crate::{bridge, constants, AEVUM_VERSION};
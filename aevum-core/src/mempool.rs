@@ -0,0 +1,603 @@
+//! Mempool de transacoes Aevum
+//!
+//! Transacoes pendentes sao agrupadas por remetente e ordenadas por nonce.
+//! Cada remetente tem um limite de transacoes e de gas na fila, para que
+//! uma unica conta nao consiga ocupar a mempool inteira.
+//!
+//! Em relacao ao nonce atual da conta, as transacoes de um remetente se
+//! dividem em *pendentes* (a sequencia sem lacunas a partir do nonce da
+//! conta, executavel em ordem) e *enfileiradas* (as que vem depois de uma
+//! lacuna e aguardam o nonce que falta). Quando a lacuna e preenchida, as
+//! enfileiradas passam a pendentes sem nenhuma acao extra.
+//!
+//! Transacoes que ficam mais de `ttl_secs` na mempool, ou cujo nonce ja foi
+//! consumido por um bloco, sao descartadas por `expire`.
+//!
+//! O preco minimo do gas aceito sobe quando a mempool se aproxima da
+//! capacidade e volta a `min_gas_price` quando ela esvazia
+//! (`current_min_fee`).
+
+use crate::placeholder::AevumState;
+use crate::transaction::AevumTransaction;
+use shared::{dynamic_fee_floor, BlockchainError, Hash256, Result};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Configuracao da mempool Aevum
+#[derive(Debug, Clone)]
+pub struct AevumMempoolConfig {
+    /// Numero maximo de transacoes na mempool
+    pub max_transactions: usize,
+    /// Numero maximo de transacoes pendentes por remetente
+    pub max_per_sender: usize,
+    /// Soma maxima de `gas_limit` pendente por remetente
+    pub max_gas_per_sender: u64,
+    /// Preco minimo do gas aceito com a mempool pouco ocupada
+    pub min_gas_price: u128,
+    /// Segundos que uma transacao pode ficar na mempool
+    pub ttl_secs: u64,
+}
+
+/// Tempo padrao que uma transacao pode ficar na mempool (3 horas)
+pub const DEFAULT_MEMPOOL_TTL_SECS: u64 = 3 * 60 * 60;
+
+impl Default for AevumMempoolConfig {
+    fn default() -> Self {
+        Self {
+            max_transactions: 10_000,
+            max_per_sender: 16,
+            max_gas_per_sender: 16 * 1_000_000,
+            min_gas_price: 1,
+            ttl_secs: DEFAULT_MEMPOOL_TTL_SECS,
+        }
+    }
+}
+
+/// Pool de transacoes Aevum aguardando inclusao em bloco
+#[derive(Debug, Clone, Default)]
+pub struct AevumMempool {
+    /// Configuracao de admissao
+    pub config: AevumMempoolConfig,
+    /// Transacoes por remetente, ordenadas por nonce
+    transactions: HashMap<Hash256, BTreeMap<u64, AevumTransaction>>,
+    /// Momento de entrada de cada transacao, por (remetente, nonce)
+    received_at: HashMap<(Hash256, u64), u64>,
+}
+
+impl AevumMempool {
+    /// Cria uma mempool vazia
+    #[must_use]
+    pub fn new(config: AevumMempoolConfig) -> Self {
+        Self {
+            config,
+            transactions: HashMap::new(),
+            received_at: HashMap::new(),
+        }
+    }
+
+    /// Adiciona uma transacao a mempool, registrando o horario atual
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o preco do gas estiver abaixo do minimo, se ja
+    /// existir transacao com o mesmo nonce, se a mempool estiver cheia, se
+    /// o remetente tiver atingido o limite de transacoes ou de gas pendentes
+    /// ou se a transacao nao estiver assinada pela chave dona de `from`
+    pub fn add_transaction(&mut self, tx: AevumTransaction) -> Result<Hash256> {
+        let now = u64::try_from(chrono::Utc::now().timestamp()).unwrap_or(0);
+        self.add_transaction_at(tx, now)
+    }
+
+    /// Adiciona uma transacao recebida em `now` (segundos desde a epoca Unix)
+    ///
+    /// # Errors
+    ///
+    /// Os mesmos de `add_transaction`
+    pub fn add_transaction_at(&mut self, tx: AevumTransaction, now: u64) -> Result<Hash256> {
+        if tx.gas_price < self.current_min_fee() {
+            return Err(BlockchainError::InvalidTransaction(
+                "Preco do gas abaixo do minimo".to_string(),
+            ));
+        }
+
+        if tx.gas_limit < tx.tx_type.intrinsic_gas() {
+            return Err(BlockchainError::InvalidTransaction(
+                "Gas limit insuficiente".to_string(),
+            ));
+        }
+
+        if self.len() >= self.config.max_transactions {
+            return Err(BlockchainError::InvalidTransaction(
+                "Mempool cheia".to_string(),
+            ));
+        }
+
+        let queued = self.transactions.get(&tx.from);
+        if queued.is_some_and(|txs| txs.contains_key(&tx.nonce)) {
+            return Err(BlockchainError::InvalidTransaction(
+                "Transacao com este nonce ja esta na mempool".to_string(),
+            ));
+        }
+
+        if self.sender_count(&tx.from) >= self.config.max_per_sender {
+            return Err(BlockchainError::InvalidTransaction(
+                "Remetente atingiu o limite de transacoes pendentes".to_string(),
+            ));
+        }
+
+        if self.sender_gas(&tx.from).saturating_add(tx.gas_limit) > self.config.max_gas_per_sender {
+            return Err(BlockchainError::InvalidTransaction(
+                "Remetente atingiu o limite de gas pendente".to_string(),
+            ));
+        }
+
+        // Por ultimo, a verificacao mais cara
+        tx.verify_sender()?;
+
+        let tx_hash = tx.hash()?;
+        self.received_at.insert((tx.from, tx.nonce), now);
+        self.transactions
+            .entry(tx.from)
+            .or_default()
+            .insert(tx.nonce, tx);
+
+        Ok(tx_hash)
+    }
+
+    /// Transacoes prontas para execucao: para cada remetente, a sequencia
+    /// de nonces sem lacunas a partir do nonce atual da conta
+    ///
+    /// As transacoes de remetentes diferentes sao intercaladas por
+    /// `effective_gas_price` decrescente, mas as de um mesmo remetente
+    /// sempre aparecem em ordem de nonce.
+    #[must_use]
+    pub fn get_executable_transactions(&self, state: &AevumState) -> Vec<AevumTransaction> {
+        let mut runs: Vec<Vec<&AevumTransaction>> = self
+            .transactions
+            .iter()
+            .map(|(from, txs)| {
+                let (pending, _) = split_at_gap(txs, account_nonce(state, from));
+                let mut run = pending;
+                run.reverse();
+                run
+            })
+            .filter(|run| !run.is_empty())
+            .collect();
+
+        let mut executable = Vec::new();
+        while let Some(best) = runs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, run)| run.last().map(|tx| (i, tx.effective_gas_price())))
+            .max_by_key(|&(i, price)| (price, std::cmp::Reverse(i)))
+            .map(|(i, _)| i)
+        {
+            if let Some(tx) = runs[best].pop() {
+                executable.push(tx.clone());
+            }
+        }
+        executable
+    }
+
+    /// Transacoes enfileiradas: as que estao depois de uma lacuna de nonce e
+    /// so podem ser executadas quando o nonce que falta chegar
+    #[must_use]
+    pub fn get_queued_transactions(&self, state: &AevumState) -> Vec<AevumTransaction> {
+        self.transactions
+            .iter()
+            .flat_map(|(from, txs)| split_at_gap(txs, account_nonce(state, from)).1)
+            .cloned()
+            .collect()
+    }
+
+    /// Remove as transacoes de `from` com nonce abaixo de `account_nonce`,
+    /// que ja nao podem ser executadas
+    ///
+    /// Retorna quantas transacoes foram descartadas.
+    pub fn prune_below_nonce(&mut self, from: Hash256, account_nonce: u64) -> usize {
+        let Some(txs) = self.transactions.get_mut(&from) else {
+            return 0;
+        };
+        let kept = txs.split_off(&account_nonce);
+        let pruned = std::mem::replace(txs, kept);
+        for nonce in pruned.keys() {
+            self.received_at.remove(&(from, *nonce));
+        }
+        if txs.is_empty() {
+            self.transactions.remove(&from);
+        }
+        pruned.len()
+    }
+
+    /// Descarta as transacoes expiradas
+    ///
+    /// Sao removidas as que estao na mempool ha `ttl_secs` ou mais e as
+    /// cujo nonce ja foi consumido por um bloco minerado. Retorna quantas
+    /// transacoes foram removidas.
+    pub fn expire(&mut self, now: u64, state: &AevumState) -> usize {
+        let senders: Vec<Hash256> = self.transactions.keys().copied().collect();
+        let mut removed = 0;
+        for from in senders {
+            removed += self.prune_below_nonce(from, account_nonce(state, &from));
+        }
+
+        let ttl = self.config.ttl_secs;
+        let stale: Vec<(Hash256, u64)> = self
+            .received_at
+            .iter()
+            .filter(|(_, received)| now.saturating_sub(**received) >= ttl)
+            .map(|(key, _)| *key)
+            .collect();
+        for (from, nonce) in stale {
+            self.remove(&from, nonce);
+            removed += 1;
+        }
+        removed
+    }
+
+    /// Seleciona as transacoes de um novo bloco ate `gas_limit`
+    ///
+    /// Antes da selecao, descarta as transacoes com nonce ja consumido por
+    /// cada remetente. Se a transacao de um remetente nao couber no bloco,
+    /// as seguintes dele tambem ficam de fora, para nao abrir lacuna.
+    pub fn select_block_transactions(
+        &mut self,
+        state: &AevumState,
+        gas_limit: u64,
+    ) -> Vec<AevumTransaction> {
+        let senders: Vec<Hash256> = self.transactions.keys().copied().collect();
+        for from in senders {
+            self.prune_below_nonce(from, account_nonce(state, &from));
+        }
+
+        let mut gas_used = 0u64;
+        let mut skipped = HashSet::new();
+        let mut selected = Vec::new();
+        for tx in self.get_executable_transactions(state) {
+            if skipped.contains(&tx.from) {
+                continue;
+            }
+            match gas_used.checked_add(tx.gas_limit) {
+                Some(total) if total <= gas_limit => {
+                    gas_used = total;
+                    selected.push(tx);
+                }
+                _ => {
+                    skipped.insert(tx.from);
+                }
+            }
+        }
+        selected
+    }
+
+    /// Remove transacoes confirmadas em um bloco
+    pub fn remove_transactions(&mut self, transactions: &[AevumTransaction]) {
+        for tx in transactions {
+            self.remove(&tx.from, tx.nonce);
+        }
+    }
+
+    fn remove(&mut self, from: &Hash256, nonce: u64) {
+        self.received_at.remove(&(*from, nonce));
+        if let Some(txs) = self.transactions.get_mut(from) {
+            txs.remove(&nonce);
+            if txs.is_empty() {
+                self.transactions.remove(from);
+            }
+        }
+    }
+
+    /// Preco minimo do gas aceito agora, conforme a ocupacao da mempool
+    #[must_use]
+    pub fn current_min_fee(&self) -> u128 {
+        dynamic_fee_floor(
+            self.config.min_gas_price,
+            self.len(),
+            self.config.max_transactions,
+        )
+    }
+
+    /// Numero de transacoes pendentes de um remetente
+    #[must_use]
+    pub fn sender_count(&self, from: &Hash256) -> usize {
+        self.transactions.get(from).map_or(0, BTreeMap::len)
+    }
+
+    /// Soma do `gas_limit` pendente de um remetente
+    #[must_use]
+    pub fn sender_gas(&self, from: &Hash256) -> u64 {
+        self.transactions
+            .get(from)
+            .map_or(0, |txs| txs.values().map(|tx| tx.gas_limit).sum())
+    }
+
+    /// Numero total de transacoes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.transactions.values().map(BTreeMap::len).sum()
+    }
+
+    /// Verifica se a mempool esta vazia
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+}
+
+/// Nonce atual da conta, ou zero se ela ainda nao existir
+fn account_nonce(state: &AevumState, from: &Hash256) -> u64 {
+    state.get_account(from).map_or(0, |a| a.nonce)
+}
+
+/// Separa as transacoes de um remetente em pendentes (sequencia contigua a
+/// partir de `next_nonce`) e enfileiradas (o restante acima dela)
+fn split_at_gap(
+    txs: &BTreeMap<u64, AevumTransaction>,
+    mut next_nonce: u64,
+) -> (Vec<&AevumTransaction>, Vec<&AevumTransaction>) {
+    let mut pending = Vec::new();
+    let mut queued = Vec::new();
+    for (&nonce, tx) in txs.range(next_nonce..) {
+        if queued.is_empty() && nonce == next_nonce {
+            pending.push(tx);
+            next_nonce = next_nonce.saturating_add(1);
+        } else {
+            queued.push(tx);
+        }
+    }
+    (pending, queued)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::placeholder::utils;
+    use crate::transaction::{AevumTransactionType, GAS_TRANSFER};
+    use shared::{KeyPair, SignatureAlgorithm};
+
+    /// Par de chaves ML-DSA-44 e o endereco derivado dele
+    fn keyed_account() -> (KeyPair, Hash256) {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let address = utils::address_from_key(&keypair.public_key);
+        (keypair, address)
+    }
+
+    fn priced_transfer(from: &KeyPair, nonce: u64, gas_price: u128) -> AevumTransaction {
+        let mut tx = AevumTransaction::new(
+            utils::address_from_key(&from.public_key),
+            Hash256::keccak256(b"to"),
+            1,
+            nonce,
+            GAS_TRANSFER,
+            gas_price,
+            AevumTransactionType::Transfer,
+        );
+        tx.sign(from).unwrap();
+        tx
+    }
+
+    fn transfer(from: &KeyPair, nonce: u64) -> AevumTransaction {
+        priced_transfer(from, nonce, 1)
+    }
+
+    #[test]
+    fn test_sender_transaction_cap() {
+        let mut mempool = AevumMempool::new(AevumMempoolConfig {
+            max_per_sender: 3,
+            ..AevumMempoolConfig::default()
+        });
+        let (spammer_key, spammer) = keyed_account();
+        let (honest_key, _) = keyed_account();
+
+        for nonce in 0..3 {
+            mempool
+                .add_transaction(transfer(&spammer_key, nonce))
+                .unwrap();
+        }
+        assert!(mempool.add_transaction(transfer(&spammer_key, 3)).is_err());
+        assert_eq!(mempool.sender_count(&spammer), 3);
+
+        // Outros remetentes continuam sendo aceitos
+        mempool.add_transaction(transfer(&honest_key, 0)).unwrap();
+        assert_eq!(mempool.len(), 4);
+
+        // Apos a confirmacao, o remetente volta a ter espaco
+        mempool.remove_transactions(&[transfer(&spammer_key, 0)]);
+        mempool.add_transaction(transfer(&spammer_key, 3)).unwrap();
+        assert_eq!(mempool.sender_count(&spammer), 3);
+    }
+
+    #[test]
+    fn test_fee_floor_rises_under_load_and_relaxes() {
+        let mut mempool = AevumMempool::new(AevumMempoolConfig {
+            max_transactions: 10,
+            ..AevumMempoolConfig::default()
+        });
+        assert_eq!(mempool.current_min_fee(), 1);
+
+        // Ate a metade da capacidade o piso continua no minimo base
+        let mut added = Vec::new();
+        for _ in 0..5 {
+            let tx = transfer(&keyed_account().0, 0);
+            mempool.add_transaction(tx.clone()).unwrap();
+            added.push(tx);
+        }
+        assert_eq!(mempool.current_min_fee(), 2);
+
+        // Transacoes pagando o minimo base deixam de ser aceitas
+        let cheap = transfer(&keyed_account().0, 0);
+        assert!(mempool.add_transaction(cheap.clone()).is_err());
+        let generous = priced_transfer(&keyed_account().0, 0, 2);
+        mempool.add_transaction(generous.clone()).unwrap();
+        added.push(generous);
+        assert_eq!(mempool.current_min_fee(), 4);
+
+        // Com a mempool esvaziando, o piso volta ao minimo base
+        mempool.remove_transactions(&added);
+        assert_eq!(mempool.current_min_fee(), 1);
+        mempool.add_transaction(cheap).unwrap();
+    }
+
+    #[test]
+    fn test_unsigned_or_spoofed_transactions_are_rejected() {
+        let mut mempool = AevumMempool::default();
+        let (alice_key, alice) = keyed_account();
+        let (mallory_key, _) = keyed_account();
+
+        let mut spoofed = transfer(&alice_key, 0);
+        spoofed.signature = None;
+        assert!(mempool.add_transaction(spoofed.clone()).is_err());
+        spoofed.sign(&mallory_key).unwrap();
+        assert!(mempool.add_transaction(spoofed).is_err());
+        assert_eq!(mempool.sender_count(&alice), 0);
+
+        mempool.add_transaction(transfer(&alice_key, 0)).unwrap();
+        assert_eq!(mempool.sender_count(&alice), 1);
+    }
+
+    #[test]
+    fn test_sender_gas_cap() {
+        let mut mempool = AevumMempool::new(AevumMempoolConfig {
+            max_gas_per_sender: GAS_TRANSFER * 2,
+            ..AevumMempoolConfig::default()
+        });
+        let (sender_key, sender) = keyed_account();
+
+        mempool.add_transaction(transfer(&sender_key, 0)).unwrap();
+        mempool.add_transaction(transfer(&sender_key, 1)).unwrap();
+        assert!(mempool.add_transaction(transfer(&sender_key, 2)).is_err());
+        assert_eq!(mempool.sender_gas(&sender), GAS_TRANSFER * 2);
+    }
+
+    #[test]
+    fn test_executable_transactions_follow_account_nonce() {
+        let mut mempool = AevumMempool::default();
+        let (sender_key, sender) = keyed_account();
+        let mut state = AevumState::new();
+        state.create_account(sender, 1_000_000);
+
+        mempool.add_transaction(transfer(&sender_key, 0)).unwrap();
+        mempool.add_transaction(transfer(&sender_key, 1)).unwrap();
+        assert!(mempool.add_transaction(transfer(&sender_key, 1)).is_err());
+
+        let nonces: Vec<u64> = mempool
+            .get_executable_transactions(&state)
+            .iter()
+            .map(|tx| tx.nonce)
+            .collect();
+        assert_eq!(nonces, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_nonce_gap_queues_until_filled() {
+        let mut mempool = AevumMempool::default();
+        let (sender_key, sender) = keyed_account();
+        let mut state = AevumState::new();
+        state.create_account(sender, 1_000_000);
+        state.get_account_mut(&sender).unwrap().nonce = 1;
+
+        for nonce in [0, 1, 2, 4] {
+            mempool
+                .add_transaction(transfer(&sender_key, nonce))
+                .unwrap();
+        }
+        let nonces = |txs: Vec<AevumTransaction>| txs.iter().map(|tx| tx.nonce).collect::<Vec<_>>();
+        assert_eq!(
+            nonces(mempool.get_executable_transactions(&state)),
+            vec![1, 2]
+        );
+        assert_eq!(nonces(mempool.get_queued_transactions(&state)), vec![4]);
+
+        // O nonce 0 ja foi consumido e e descartado ao montar o bloco
+        assert_eq!(mempool.prune_below_nonce(sender, 1), 1);
+        assert_eq!(mempool.len(), 3);
+
+        // Preencher a lacuna promove o nonce 4
+        mempool.add_transaction(transfer(&sender_key, 3)).unwrap();
+        assert_eq!(
+            nonces(mempool.get_executable_transactions(&state)),
+            vec![1, 2, 3, 4]
+        );
+        assert!(mempool.get_queued_transactions(&state).is_empty());
+    }
+
+    #[test]
+    fn test_block_selection_prunes_stale_nonces() {
+        let mut mempool = AevumMempool::default();
+        let (sender_key, sender) = keyed_account();
+        let mut state = AevumState::new();
+        state.create_account(sender, 1_000_000);
+
+        for nonce in 0..3 {
+            mempool
+                .add_transaction(transfer(&sender_key, nonce))
+                .unwrap();
+        }
+        state.get_account_mut(&sender).unwrap().nonce = 2;
+
+        let selected = mempool.select_block_transactions(&state, GAS_TRANSFER * 10);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].nonce, 2);
+        assert_eq!(mempool.len(), 1);
+    }
+
+    #[test]
+    fn test_executable_transactions_ordered_by_effective_price() {
+        let mut mempool = AevumMempool::default();
+        let mut state = AevumState::new();
+        for gas_price in [5, 50, 20] {
+            let (sender_key, sender) = keyed_account();
+            state.create_account(sender, 1_000_000);
+            mempool
+                .add_transaction(priced_transfer(&sender_key, 0, gas_price))
+                .unwrap();
+        }
+
+        let prices: Vec<u128> = mempool
+            .get_executable_transactions(&state)
+            .iter()
+            .map(|tx| tx.gas_price)
+            .collect();
+        assert_eq!(prices, vec![50, 20, 5]);
+    }
+
+    #[test]
+    fn test_expire_drops_old_and_superseded_transactions() {
+        let mut mempool = AevumMempool::new(AevumMempoolConfig {
+            ttl_secs: 600,
+            ..AevumMempoolConfig::default()
+        });
+        let (old_key, old) = keyed_account();
+        let (fresh_key, fresh) = keyed_account();
+        let (mined_key, mined) = keyed_account();
+        let mut state = AevumState::new();
+        state.create_account(mined, 1_000_000);
+        let start = 1_700_000_000;
+
+        mempool
+            .add_transaction_at(transfer(&old_key, 0), start)
+            .unwrap();
+        mempool
+            .add_transaction_at(transfer(&fresh_key, 0), start + 300)
+            .unwrap();
+        mempool
+            .add_transaction_at(transfer(&mined_key, 0), start + 300)
+            .unwrap();
+        mempool
+            .add_transaction_at(transfer(&mined_key, 1), start + 300)
+            .unwrap();
+
+        // Um bloco consumiu o nonce 0 de `mined`
+        state.get_account_mut(&mined).unwrap().nonce = 1;
+        assert_eq!(mempool.expire(start + 599, &state), 1);
+        assert_eq!(mempool.sender_count(&mined), 1);
+        assert_eq!(mempool.sender_count(&old), 1);
+
+        assert_eq!(mempool.expire(start + 600, &state), 1);
+        assert_eq!(mempool.sender_count(&old), 0);
+        assert_eq!(mempool.sender_count(&fresh), 1);
+        assert_eq!(mempool.len(), 2);
+
+        assert_eq!(mempool.expire(start + 900, &state), 2);
+        assert!(mempool.is_empty());
+    }
+}
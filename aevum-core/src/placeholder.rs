@@ -4,12 +4,16 @@
 //! que sera a segunda blockchain do ecosistema Aevum & Bond.
 //! Planejado para implementacao completa no Sprint 6.
 
+use crate::consensus::BPS_SCALE;
+use crate::governance::{GovernanceProposal, ProposalStatus};
+use crate::transaction::{AevumTransaction, AevumTransactionType, TxEvent, TxReceipt, TxStatus};
+use bond_core::{merkle_path, merkle_root_of, verify_merkle_proof};
 use serde::{Deserialize, Serialize};
-use shared::{BlockchainError, Hash256, Result};
-use std::collections::HashMap;
+use shared::{BlockchainError, Hash256, NetworkType, Result};
+use std::collections::{HashMap, HashSet};
 
 /// Estado de uma conta no Aevum
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AccountState {
     /// Numero sequencial para prevenir replay attacks
     pub nonce: u64,
@@ -57,6 +61,76 @@ impl AccountState {
     pub const fn receive(&mut self, amount: u128) {
         self.balance += amount;
     }
+
+    /// Verifica se a conta nunca foi usada: sem saldo, nonce, codigo ou
+    /// storage
+    ///
+    /// Contas com nonce maior que zero nao sao vazias mesmo sem saldo,
+    /// porque o nonce retido impede o replay de transacoes antigas.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.balance == 0
+            && self.nonce == 0
+            && self.code_hash.is_none()
+            && self.storage_root.is_none()
+    }
+
+    /// Folha da conta na arvore de `AevumState::state_root`
+    ///
+    /// Compromete o endereco e todos os campos da conta, em bytes
+    /// little-endian; campos opcionais ausentes sao um byte zero.
+    #[must_use]
+    pub fn leaf_hash(&self, address: &Hash256) -> Hash256 {
+        let mut data = Vec::with_capacity(32 + 8 + 16 + 2 * 33);
+        data.extend_from_slice(address.as_bytes());
+        data.extend_from_slice(&self.nonce.to_le_bytes());
+        data.extend_from_slice(&self.balance.to_le_bytes());
+        for hash in [&self.code_hash, &self.storage_root] {
+            match hash {
+                Some(hash) => {
+                    data.push(1);
+                    data.extend_from_slice(hash.as_bytes());
+                }
+                None => data.push(0),
+            }
+        }
+        Hash256::keccak256(&data)
+    }
+}
+
+/// Prova de que uma conta esta na `state_root` do Aevum
+///
+/// Um cliente leve que conhece apenas a root verifica o saldo e o nonce da
+/// conta sem baixar o estado.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountProof {
+    /// Endereco da conta
+    pub address: Hash256,
+    /// Estado provado da conta
+    pub account: AccountState,
+    /// Caminho merkle da folha da conta ate a root
+    pub merkle_proof: Vec<(Hash256, bool)>,
+}
+
+impl AccountProof {
+    /// Verifica a prova contra `state_root`
+    #[must_use]
+    pub fn verify(&self, state_root: &Hash256) -> bool {
+        verify_merkle_proof(
+            &self.account.leaf_hash(&self.address),
+            &self.merkle_proof,
+            state_root,
+        )
+    }
+}
+
+/// Stake retirado que aguarda o fim do `unstake_delay`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnbondingEntry {
+    /// Valor retirado
+    pub amount: u128,
+    /// Epoca em que o valor volta para a conta do validador
+    pub release_epoch: u64,
 }
 
 /// Informacoes de um validador `DPoS`
@@ -66,12 +140,21 @@ pub struct ValidatorInfo {
     pub public_key: Hash256,
     /// Total de tokens em stake
     pub stake_amount: u128,
+    /// Total de tokens delegados por terceiros
+    #[serde(default)]
+    pub delegated_stake: u128,
     /// Numero de delegadores
     pub delegator_count: u32,
     /// Status ativo/inativo
     pub is_active: bool,
     /// Epoca de ativacao
     pub activation_epoch: u64,
+    /// Stake retirado ainda bloqueado, em ordem de retirada
+    ///
+    /// Nao conta para a eleicao nem pode ser gasto, mas continua sujeito a
+    /// slashing ate ser liberado.
+    #[serde(default)]
+    pub unbonding: Vec<UnbondingEntry>,
 }
 
 impl ValidatorInfo {
@@ -81,12 +164,20 @@ impl ValidatorInfo {
         Self {
             public_key,
             stake_amount,
+            delegated_stake: 0,
             delegator_count: 0,
             is_active: false,
             activation_epoch: 0,
+            unbonding: Vec::new(),
         }
     }
 
+    /// Stake efetivo (proprio mais delegado)
+    #[must_use]
+    pub const fn effective_stake(&self) -> u128 {
+        self.stake_amount + self.delegated_stake
+    }
+
     /// Adiciona stake ao validador
     pub const fn add_stake(&mut self, amount: u128) {
         self.stake_amount += amount;
@@ -104,6 +195,40 @@ impl ValidatorInfo {
         self.stake_amount -= amount;
         Ok(())
     }
+
+    /// Move stake proprio para a fila de liberacao ate `release_epoch`
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o validador não possui stake suficiente
+    pub fn begin_unbonding(&mut self, amount: u128, release_epoch: u64) -> Result<()> {
+        self.remove_stake(amount)?;
+        self.unbonding.push(UnbondingEntry {
+            amount,
+            release_epoch,
+        });
+        Ok(())
+    }
+
+    /// Remove da fila e retorna o total liberado ate a epoca `epoch`
+    pub fn release_unbonded(&mut self, epoch: u64) -> u128 {
+        let mut released = 0;
+        self.unbonding.retain(|entry| {
+            if entry.release_epoch <= epoch {
+                released += entry.amount;
+                false
+            } else {
+                true
+            }
+        });
+        released
+    }
+
+    /// Total ainda bloqueado na fila de liberacao
+    #[must_use]
+    pub fn unbonding_amount(&self) -> u128 {
+        self.unbonding.iter().map(|entry| entry.amount).sum()
+    }
 }
 
 /// Estado global do Aevum (`WorldState`)
@@ -113,21 +238,55 @@ pub struct AevumState {
     pub accounts: HashMap<Hash256, AccountState>,
     /// Mapeamento de validadores ativos
     pub validators: HashMap<Hash256, ValidatorInfo>,
+    /// Valor delegado por (delegador, validador)
+    pub delegations: HashMap<(Hash256, Hash256), u128>,
+    /// Epoca da ultima delegacao de cada par (delegador, validador)
+    pub delegation_epochs: HashMap<(Hash256, Hash256), u64>,
+    /// Propostas de governanca por identificador
+    pub proposals: HashMap<u64, GovernanceProposal>,
+    /// Identificador da proxima proposta criada
+    pub next_proposal_id: u64,
     /// Numero da epoca atual
     pub current_epoch: u64,
     /// Altura do bloco atual
     pub block_height: u64,
+    /// Total queimado (parcela do slashing que nao vai para o denunciante e
+    /// parcela queimada das taxas de gas)
+    pub burned_supply: u128,
+    /// Configuracao do consenso usada nas regras de stake
+    pub config: DposConfig,
+    /// Eventos emitidos pelas transacoes aplicadas, em ordem
+    pub events: Vec<TxEvent>,
+    /// Saldos de wBND (BND travado no Bond)
+    pub wrapped_balances: HashMap<Hash256, u128>,
+    /// Locks do Bond ja resgatados pela bridge
+    pub bridge_claims: HashSet<Hash256>,
 }
 
 impl AevumState {
     /// Cria um novo estado inicial
     #[must_use]
     pub fn new() -> Self {
+        Self::with_config(DposConfig::default())
+    }
+
+    /// Cria um novo estado inicial com a configuracao indicada
+    #[must_use]
+    pub fn with_config(config: DposConfig) -> Self {
         Self {
             accounts: HashMap::new(),
             validators: HashMap::new(),
+            delegations: HashMap::new(),
+            delegation_epochs: HashMap::new(),
+            proposals: HashMap::new(),
+            next_proposal_id: 0,
             current_epoch: 0,
             block_height: 0,
+            burned_supply: 0,
+            config,
+            events: Vec::new(),
+            wrapped_balances: HashMap::new(),
+            bridge_claims: HashSet::new(),
         }
     }
 
@@ -189,21 +348,503 @@ impl AevumState {
             ));
         }
 
-        let validator = ValidatorInfo::new(validator_key, stake_amount);
+        let validator = self.new_validator(validator_key, stake_amount);
         self.validators.insert(validator_key, validator);
         Ok(())
     }
 
+    /// Cria um validador que so pode ser eleito apos `activation_delay_epochs`
+    const fn new_validator(&self, validator_key: Hash256, stake_amount: u128) -> ValidatorInfo {
+        let mut validator = ValidatorInfo::new(validator_key, stake_amount);
+        validator.activation_epoch = self.current_epoch + self.config.activation_delay_epochs;
+        validator
+    }
+
     /// Obtem lista de validadores ativos
     #[must_use]
     pub fn get_active_validators(&self) -> Vec<&ValidatorInfo> {
         self.validators.values().filter(|v| v.is_active).collect()
     }
 
+    /// Aplica uma transacao ao estado
+    ///
+    /// Verifica a assinatura do remetente e o nonce e cobra o gas antes de
+    /// executar. Uma vez cobrada, a transacao e considerada incluida: o
+    /// nonce avanca mesmo que a execucao falhe, e a falha e reportada no
+    /// recibo. Sem produtor de bloco para receber a recompensa, a taxa
+    /// inteira e queimada.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a transacao nao estiver assinada pela chave dona de
+    /// `from`, se a conta de origem nao existir, se o nonce nao coincidir,
+    /// se o gas limit for insuficiente ou se o saldo nao cobrir o gas
+    pub fn apply_transaction(&mut self, tx: &AevumTransaction) -> Result<TxReceipt> {
+        self.apply_transaction_for(tx, None)
+    }
+
+    /// Aplica as transacoes de um bloco produzido por `producer`
+    ///
+    /// A taxa de cada transacao e dividida conforme `fee_burn_bps`: a
+    /// parcela queimada sai do suprimento e o restante e creditado ao
+    /// produtor. Se alguma transacao for rejeitada, o estado volta a ser o
+    /// de antes do bloco.
+    ///
+    /// # Errors
+    ///
+    /// Retorna o erro da primeira transacao que nao puder ser aplicada
+    pub fn apply_block(
+        &mut self,
+        transactions: &[AevumTransaction],
+        producer: &Hash256,
+    ) -> Result<Vec<TxReceipt>> {
+        let snapshot = self.clone();
+        let receipts = transactions
+            .iter()
+            .map(|tx| self.apply_transaction_for(tx, Some(producer)))
+            .collect::<Result<Vec<_>>>();
+        if receipts.is_err() {
+            *self = snapshot;
+        } else {
+            self.prune_empty_accounts();
+        }
+        receipts
+    }
+
+    /// Remove as contas vazias que nao tem nenhum papel no consenso
+    ///
+    /// Uma conta e removida se `AccountState::is_empty` e se o endereco nao
+    /// for validador, nao participar de delegacoes nem tiver saldo de wBND.
+    /// Chamado ao fim de cada bloco; retorna o numero de contas removidas.
+    pub fn prune_empty_accounts(&mut self) -> usize {
+        let prunable: Vec<Hash256> = self
+            .accounts
+            .iter()
+            .filter(|(address, account)| self.is_prunable(address, account))
+            .map(|(address, _)| *address)
+            .collect();
+        for address in &prunable {
+            self.accounts.remove(address);
+        }
+        prunable.len()
+    }
+
+    /// Verifica se `account` pode ser removida por `prune_empty_accounts`
+    fn is_prunable(&self, address: &Hash256, account: &AccountState) -> bool {
+        account.is_empty()
+            && !self.validators.contains_key(address)
+            && !self
+                .delegations
+                .keys()
+                .any(|(delegator, validator)| delegator == address || validator == address)
+            && self
+                .wrapped_balances
+                .get(address)
+                .is_none_or(|&balance| balance == 0)
+    }
+
+    /// Divide uma taxa entre a parcela queimada e a recompensa do produtor
+    #[must_use]
+    pub fn split_fee(&self, fee: u128) -> (u128, u128) {
+        let burned = fee * self.config.fee_burn_bps.min(BPS_SCALE) / BPS_SCALE;
+        (burned, fee - burned)
+    }
+
+    /// Suprimento em circulacao: saldos das contas mais o stake proprio e
+    /// delegado dos validadores
+    #[must_use]
+    pub fn total_supply(&self) -> u128 {
+        let balances: u128 = self.accounts.values().map(|a| a.balance).sum();
+        let staked: u128 = self
+            .validators
+            .values()
+            .map(|info| info.effective_stake() + info.unbonding_amount())
+            .sum();
+        balances + staked
+    }
+
+    fn apply_transaction_for(
+        &mut self,
+        tx: &AevumTransaction,
+        producer: Option<&Hash256>,
+    ) -> Result<TxReceipt> {
+        // So o dono da conta de origem pode mover seus fundos
+        tx.verify_sender()?;
+        let tx_hash = tx.hash()?;
+
+        let gas_used = tx.tx_type.intrinsic_gas();
+        if gas_used > tx.gas_limit {
+            return Err(BlockchainError::InvalidTransaction(
+                "Gas limit insuficiente".to_string(),
+            ));
+        }
+        let fee = u128::from(gas_used)
+            .checked_mul(tx.gas_price)
+            .ok_or_else(|| {
+                BlockchainError::InvalidTransaction("Taxa de gas excede o limite".to_string())
+            })?;
+
+        let sender = self.accounts.get_mut(&tx.from).ok_or_else(|| {
+            BlockchainError::InvalidTransaction("Conta de origem nao encontrada".to_string())
+        })?;
+
+        if tx.nonce != sender.nonce {
+            return Err(BlockchainError::InvalidTransaction(format!(
+                "Nonce invalido: esperado {}, recebido {}",
+                sender.nonce, tx.nonce
+            )));
+        }
+
+        if !sender.has_sufficient_balance(fee) {
+            return Err(BlockchainError::InsufficientFunds);
+        }
+
+        sender.balance -= fee;
+        sender.nonce += 1;
+
+        let (burned, reward) = match producer {
+            Some(_) => self.split_fee(fee),
+            None => (fee, 0),
+        };
+        self.burned_supply += burned;
+        if let Some(producer) = producer {
+            self.credit(*producer, reward);
+        }
+
+        let (status, events) = match self.execute_transaction(tx) {
+            Ok(events) => (TxStatus::Success, events),
+            Err(e) => (TxStatus::Failed(e.to_string()), Vec::new()),
+        };
+        self.events.extend(events.iter().cloned());
+
+        Ok(TxReceipt {
+            tx_hash,
+            status,
+            gas_used,
+            fee,
+            events,
+        })
+    }
+
+    /// Executa o efeito de uma transacao ja cobrada
+    ///
+    /// Todas as verificacoes sao feitas antes de qualquer alteracao, para
+    /// que uma falha nao deixe o estado parcialmente modificado.
+    fn execute_transaction(&mut self, tx: &AevumTransaction) -> Result<Vec<TxEvent>> {
+        let balance = self.accounts.get(&tx.from).map_or(0, |a| a.balance);
+        let mut events = Vec::new();
+
+        match &tx.tx_type {
+            AevumTransactionType::Transfer => {
+                if balance < tx.value {
+                    return Err(BlockchainError::InsufficientFunds);
+                }
+                self.debit(&tx.from, tx.value);
+                self.accounts
+                    .entry(tx.to)
+                    .or_insert_with(|| AccountState::new(0))
+                    .receive(tx.value);
+            }
+            AevumTransactionType::Stake => {
+                if balance < tx.value {
+                    return Err(BlockchainError::InsufficientFunds);
+                }
+                self.debit(&tx.from, tx.value);
+                if !self.validators.contains_key(&tx.from) {
+                    let validator = self.new_validator(tx.from, 0);
+                    self.validators.insert(tx.from, validator);
+                }
+                if let Some(validator) = self.validators.get_mut(&tx.from) {
+                    validator.add_stake(tx.value);
+                }
+                events.push(TxEvent::Staked {
+                    validator: tx.from,
+                    amount: tx.value,
+                });
+            }
+            AevumTransactionType::Unstake => {
+                let validator = self.validators.get_mut(&tx.from).ok_or_else(|| {
+                    BlockchainError::InvalidTransaction("Validador nao encontrado".to_string())
+                })?;
+                // O stake fica bloqueado (e punivel) ate a epoca de liberacao
+                let unlock_epoch = self.current_epoch + self.config.unstake_delay;
+                validator.begin_unbonding(tx.value, unlock_epoch)?;
+                let released = validator.release_unbonded(self.current_epoch);
+                if released > 0 {
+                    self.credit(tx.from, released);
+                }
+                events.push(TxEvent::Unstaked {
+                    validator: tx.from,
+                    amount: tx.value,
+                    unlock_epoch,
+                });
+            }
+            AevumTransactionType::Delegate { validator } => {
+                if tx.value == 0 {
+                    return Err(BlockchainError::InvalidTransaction(
+                        "Delegacao de valor zero".to_string(),
+                    ));
+                }
+                if !self.validators.contains_key(validator) {
+                    return Err(BlockchainError::InvalidTransaction(
+                        "Validador nao encontrado".to_string(),
+                    ));
+                }
+                if balance < tx.value {
+                    return Err(BlockchainError::InsufficientFunds);
+                }
+                self.debit(&tx.from, tx.value);
+
+                let key = (tx.from, *validator);
+                let delegated = self.delegations.entry(key).or_insert(0);
+                let is_new = *delegated == 0;
+                *delegated += tx.value;
+                self.delegation_epochs.insert(key, self.current_epoch);
+
+                if let Some(info) = self.validators.get_mut(validator) {
+                    info.delegated_stake += tx.value;
+                    if is_new {
+                        info.delegator_count += 1;
+                    }
+                }
+                events.push(TxEvent::Delegated {
+                    delegator: tx.from,
+                    validator: *validator,
+                    amount: tx.value,
+                });
+            }
+            AevumTransactionType::Undelegate { validator } => {
+                if tx.value == 0 {
+                    return Err(BlockchainError::InvalidTransaction(
+                        "Retirada de delegacao de valor zero".to_string(),
+                    ));
+                }
+                let key = (tx.from, *validator);
+                let delegated = self.delegations.get(&key).copied().unwrap_or(0);
+                if delegated < tx.value {
+                    return Err(BlockchainError::InsufficientFunds);
+                }
+
+                let delegated_at = self.delegation_epochs.get(&key).copied().unwrap_or(0);
+                if self.current_epoch < delegated_at + self.config.unstake_delay {
+                    return Err(BlockchainError::InvalidTransaction(format!(
+                        "Delegacao bloqueada ate a epoca {}",
+                        delegated_at + self.config.unstake_delay
+                    )));
+                }
+
+                let remaining = delegated - tx.value;
+                if remaining == 0 {
+                    self.delegations.remove(&key);
+                    self.delegation_epochs.remove(&key);
+                } else {
+                    self.delegations.insert(key, remaining);
+                }
+
+                if let Some(info) = self.validators.get_mut(validator) {
+                    info.delegated_stake -= tx.value;
+                    if remaining == 0 {
+                        info.delegator_count -= 1;
+                    }
+                }
+                self.accounts
+                    .entry(tx.from)
+                    .or_insert_with(|| AccountState::new(0))
+                    .receive(tx.value);
+                events.push(TxEvent::Undelegated {
+                    delegator: tx.from,
+                    validator: *validator,
+                    amount: tx.value,
+                });
+            }
+            AevumTransactionType::CreateProposal {
+                title,
+                description,
+                voting_period,
+                action,
+            } => {
+                let id = self.next_proposal_id;
+                let mut proposal = GovernanceProposal::new(
+                    id,
+                    tx.from,
+                    title.clone(),
+                    description.clone(),
+                    self.block_height,
+                    self.block_height.saturating_add(*voting_period),
+                );
+                proposal.action = *action;
+                self.proposals.insert(id, proposal);
+                self.next_proposal_id += 1;
+            }
+            AevumTransactionType::Vote {
+                proposal_id,
+                support,
+                weight,
+            } => {
+                if *weight > self.voting_stake(&tx.from) {
+                    return Err(BlockchainError::InvalidTransaction(
+                        "Peso do voto excede o stake do votante".to_string(),
+                    ));
+                }
+                let proposal = self.proposals.get_mut(proposal_id).ok_or_else(|| {
+                    BlockchainError::InvalidTransaction("Proposta nao encontrada".to_string())
+                })?;
+                proposal.add_vote(tx.from, *support, *weight, self.block_height)?;
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Executa uma proposta aprovada, aplicando sua acao a `config`
+    ///
+    /// A nova configuracao e validada antes de substituir a atual; se for
+    /// invalida, nada muda e a proposta continua aprovada. Um `DposEngine`
+    /// que mantenha a propria copia da configuracao deve recebe-la de novo.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a proposta nao existir, nao tiver sido aprovada, ja
+    /// tiver sido executada ou se a acao gerar uma configuracao invalida
+    pub fn execute_proposal(&mut self, proposal_id: u64) -> Result<()> {
+        let proposal = self.proposals.get_mut(&proposal_id).ok_or_else(|| {
+            BlockchainError::InvalidTransaction("Proposta nao encontrada".to_string())
+        })?;
+        let updated = match proposal.action {
+            Some(action) if proposal.status == ProposalStatus::Passed => {
+                Some(action.apply(&self.config)?)
+            }
+            _ => None,
+        };
+
+        proposal.mark_executed()?;
+        if let Some(config) = updated {
+            self.config = config;
+        }
+        Ok(())
+    }
+
+    /// Stake de um endereco para fins de votacao: stake proprio como
+    /// validador mais o total delegado por ele
+    #[must_use]
+    pub fn voting_stake(&self, address: &Hash256) -> u128 {
+        let own = self.validators.get(address).map_or(0, |v| v.stake_amount);
+        let delegated: u128 = self
+            .delegations
+            .iter()
+            .filter(|((delegator, _), _)| delegator == address)
+            .map(|(_, amount)| amount)
+            .sum();
+        own + delegated
+    }
+
+    /// Credita um valor a uma conta, criando-a se necessario
+    pub fn credit(&mut self, address: Hash256, amount: u128) {
+        self.accounts
+            .entry(address)
+            .or_insert_with(|| AccountState::new(0))
+            .balance += amount;
+    }
+
+    /// Debita um valor ja verificado da conta
+    fn debit(&mut self, address: &Hash256, amount: u128) {
+        if let Some(account) = self.accounts.get_mut(address) {
+            account.balance -= amount;
+        }
+    }
+
+    /// Validadores ordenados por stake efetivo (proprio mais delegado)
+    ///
+    /// Empates sao desfeitos pelos bytes do endereco, para que todos os nos
+    /// obtenham a mesma ordem.
+    #[must_use]
+    pub fn validators_by_stake(&self) -> Vec<(Hash256, u128)> {
+        let mut ranking: Vec<(Hash256, u128)> = self
+            .validators
+            .iter()
+            .map(|(address, info)| (*address, info.effective_stake()))
+            .collect();
+        ranking.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.as_bytes().cmp(b.0.as_bytes()))
+        });
+        ranking
+    }
+
+    /// Root merkle das contas, para provas a clientes leves
+    ///
+    /// As folhas (`AccountState::leaf_hash`) sao ordenadas pelos bytes do
+    /// endereco, para que todos os nos obtenham a mesma root. Contas que
+    /// `prune_empty_accounts` removeria ficam de fora, entao a root nao
+    /// depende de a poda ja ter ocorrido. Sem contas a root e zero.
+    #[must_use]
+    pub fn state_root(&self) -> Hash256 {
+        merkle_root_of(
+            self.sorted_account_leaves()
+                .into_iter()
+                .map(|(_, leaf)| leaf)
+                .collect(),
+        )
+    }
+
+    /// Prova de inclusao da conta `address` na `state_root` atual
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a conta nao existir ou for vazia e podavel
+    pub fn account_proof(&self, address: &Hash256) -> Result<AccountProof> {
+        let account = self.accounts.get(address).ok_or_else(|| {
+            BlockchainError::InvalidTransaction("Conta nao encontrada".to_string())
+        })?;
+        let leaves = self.sorted_account_leaves();
+        let index = leaves
+            .iter()
+            .position(|(leaf_address, _)| leaf_address == address)
+            .ok_or_else(|| {
+                BlockchainError::InvalidTransaction("Conta nao encontrada".to_string())
+            })?;
+
+        Ok(AccountProof {
+            address: *address,
+            account: account.clone(),
+            merkle_proof: merkle_path(leaves.into_iter().map(|(_, leaf)| leaf).collect(), index),
+        })
+    }
+
+    /// Folhas das contas ordenadas pelos bytes do endereco
+    fn sorted_account_leaves(&self) -> Vec<(Hash256, Hash256)> {
+        let mut leaves: Vec<(Hash256, Hash256)> = self
+            .accounts
+            .iter()
+            .filter(|(address, account)| !self.is_prunable(address, account))
+            .map(|(address, account)| (*address, account.leaf_hash(address)))
+            .collect();
+        leaves.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+        leaves
+    }
+
+    /// Remove e retorna os eventos emitidos desde a ultima chamada
+    pub fn drain_events(&mut self) -> Vec<TxEvent> {
+        std::mem::take(&mut self.events)
+    }
+
     /// Avança para a próxima epoca
-    pub const fn advance_epoch(&mut self) {
+    ///
+    /// Stake retirado cuja epoca de liberacao chegou volta para a conta do
+    /// validador.
+    pub fn advance_epoch(&mut self) {
         self.current_epoch += 1;
-        // TODO: Implementar eleicao de validadores
+
+        let epoch = self.current_epoch;
+        let released: Vec<(Hash256, u128)> = self
+            .validators
+            .iter_mut()
+            .map(|(address, info)| (*address, info.release_unbonded(epoch)))
+            .filter(|(_, amount)| *amount > 0)
+            .collect();
+        for (address, amount) in released {
+            self.credit(address, amount);
+        }
     }
 }
 
@@ -224,22 +865,283 @@ pub struct DposConfig {
     pub epoch_length: u64,
     /// Tempo de bloqueio do unstake (em epocas)
     pub unstake_delay: u64,
+    /// Blocos apos a finalizacao em que a lista de votantes de uma proposta e mantida
+    pub proposal_retention_blocks: u64,
+    /// Bonus de recompensa por epoca consecutiva sem falhas (pontos-base)
+    pub uptime_bonus_bps_per_epoch: u128,
+    /// Bonus maximo de recompensa por uptime (pontos-base)
+    pub max_uptime_bonus_bps: u128,
+    /// Parcela do valor punido paga a quem reporta a evidencia (pontos-base)
+    pub slashing_bounty_bps: u128,
+    /// Epocas apos o registro antes que um validador possa ser eleito
+    pub activation_delay_epochs: u64,
+    /// Recompensa dividida entre os validadores ativos ao fim de cada epoca
+    #[serde(default)]
+    pub epoch_reward: u128,
+    /// Fracao maxima do peso total que um validador ativo pode ter (pontos-base)
+    #[serde(default = "default_max_voting_power_bps")]
+    pub max_voting_power_bps: u128,
+    /// Parcela das taxas de gas queimada; o restante vai para o produtor do
+    /// bloco (pontos-base)
+    #[serde(default = "default_fee_burn_bps")]
+    pub fee_burn_bps: u128,
+    /// Peso da epoca mais recente na media movel da taxa de aprovacao
+    /// (pontos-base); o restante fica com o historico
+    #[serde(default = "default_approval_ema_weight_bps")]
+    pub approval_ema_weight_bps: u128,
+    /// Taxa de aprovacao recente abaixo da qual o validador deve ser
+    /// punido (pontos-base)
+    #[serde(default = "default_min_approval_rate_bps")]
+    pub min_approval_rate_bps: u128,
+}
+
+const fn default_max_voting_power_bps() -> u128 {
+    3_333
+}
+
+const fn default_fee_burn_bps() -> u128 {
+    5_000
+}
+
+const fn default_approval_ema_weight_bps() -> u128 {
+    5_000
+}
+
+const fn default_min_approval_rate_bps() -> u128 {
+    6_000
 }
 
 impl Default for DposConfig {
     fn default() -> Self {
         Self {
-            max_validators: 21,        // Inspirado em EOS
-            min_validator_stake: 1000, // 1000 tokens minimos
-            epoch_length: 2160,        // ~6 horas com 10s/bloco
-            unstake_delay: 7,          // 7 epocas (~2 dias)
+            max_validators: 21,                                         // Inspirado em EOS
+            min_validator_stake: 1000,                                  // 1000 tokens minimos
+            epoch_length: 2160,                                         // ~6 horas com 10s/bloco
+            unstake_delay: 7,                                           // 7 epocas (~2 dias)
+            proposal_retention_blocks: 15_120,                          // 7 epocas
+            uptime_bonus_bps_per_epoch: 50,                             // +0,5% por epoca limpa
+            max_uptime_bonus_bps: 1_000,                                // ate +10%
+            slashing_bounty_bps: 1_000,                                 // 10% para o denunciante
+            activation_delay_epochs: 1, // elegivel a partir da proxima epoca
+            epoch_reward: 1_000_000,    // dividido pelo peso de cada validador
+            max_voting_power_bps: default_max_voting_power_bps(), // ~1/3 do peso total
+            fee_burn_bps: default_fee_burn_bps(), // metade queimada
+            approval_ema_weight_bps: default_approval_ema_weight_bps(), // meia-vida de uma epoca
+            min_approval_rate_bps: default_min_approval_rate_bps(), // punido abaixo de 60%
         }
     }
 }
 
+impl DposConfig {
+    /// Configuracao da rede `network`
+    ///
+    /// A Mainnet usa os valores padrao; Testnet e Devnet encurtam epocas e
+    /// bloqueios, e a Regtest permite um unico validador com stake simbolico
+    /// e epocas de poucos blocos.
+    #[must_use]
+    pub fn for_network(network: NetworkType) -> Self {
+        let defaults = Self::default();
+        match network {
+            NetworkType::Mainnet => defaults,
+            NetworkType::Testnet => Self {
+                epoch_length: 720, // ~2 horas com 10s/bloco
+                unstake_delay: 2,
+                proposal_retention_blocks: 1_440,
+                ..defaults
+            },
+            NetworkType::Devnet => Self {
+                max_validators: 5,
+                min_validator_stake: 100,
+                epoch_length: 100,
+                unstake_delay: 1,
+                proposal_retention_blocks: 100,
+                ..defaults
+            },
+            NetworkType::Regtest => Self {
+                max_validators: 1,
+                min_validator_stake: 1,
+                epoch_length: 10,
+                unstake_delay: 1,
+                proposal_retention_blocks: 10,
+                activation_delay_epochs: 0,
+                ..defaults
+            },
+        }
+    }
+
+    /// Builder partindo dos valores padrao
+    #[must_use]
+    pub fn builder() -> DposConfigBuilder {
+        DposConfigBuilder::default()
+    }
+
+    /// Verifica se os parametros fazem sentido para o consenso
+    ///
+    /// # Errors
+    ///
+    /// Retorna `InvalidBlock` descrevendo o primeiro campo zerado ou fora
+    /// do intervalo aceito
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| {
+            Err(BlockchainError::InvalidBlock(format!(
+                "Configuracao DPoS invalida: {reason}"
+            )))
+        };
+
+        if self.max_validators == 0 {
+            return invalid("max_validators deve ser maior que zero".to_string());
+        }
+        if self.min_validator_stake == 0 {
+            return invalid("min_validator_stake deve ser maior que zero".to_string());
+        }
+        if self.epoch_length == 0 {
+            return invalid("epoch_length deve ser maior que zero".to_string());
+        }
+        if self.max_voting_power_bps == 0 {
+            return invalid("max_voting_power_bps deve ser maior que zero".to_string());
+        }
+        if self.approval_ema_weight_bps == 0 {
+            return invalid("approval_ema_weight_bps deve ser maior que zero".to_string());
+        }
+        for (field, value) in [
+            ("max_voting_power_bps", self.max_voting_power_bps),
+            ("max_uptime_bonus_bps", self.max_uptime_bonus_bps),
+            ("slashing_bounty_bps", self.slashing_bounty_bps),
+            ("fee_burn_bps", self.fee_burn_bps),
+            ("approval_ema_weight_bps", self.approval_ema_weight_bps),
+            ("min_approval_rate_bps", self.min_approval_rate_bps),
+        ] {
+            if value > BPS_SCALE {
+                return invalid(format!("{field} = {value} excede {BPS_SCALE} pontos-base"));
+            }
+        }
+        if self.uptime_bonus_bps_per_epoch > self.max_uptime_bonus_bps {
+            return invalid(format!(
+                "uptime_bonus_bps_per_epoch = {} excede max_uptime_bonus_bps = {}",
+                self.uptime_bonus_bps_per_epoch, self.max_uptime_bonus_bps
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Monta um `DposConfig` validado a partir dos valores padrao
+#[derive(Debug, Clone, Default)]
+pub struct DposConfigBuilder {
+    config: DposConfig,
+}
+
+impl DposConfigBuilder {
+    /// Define o numero maximo de validadores ativos
+    #[must_use]
+    pub const fn max_validators(mut self, max_validators: u32) -> Self {
+        self.config.max_validators = max_validators;
+        self
+    }
+
+    /// Define o stake minimo para ser validador
+    #[must_use]
+    pub const fn min_validator_stake(mut self, min_validator_stake: u128) -> Self {
+        self.config.min_validator_stake = min_validator_stake;
+        self
+    }
+
+    /// Define a duracao da epoca em blocos
+    #[must_use]
+    pub const fn epoch_length(mut self, epoch_length: u64) -> Self {
+        self.config.epoch_length = epoch_length;
+        self
+    }
+
+    /// Define o tempo de bloqueio do unstake (em epocas)
+    #[must_use]
+    pub const fn unstake_delay(mut self, unstake_delay: u64) -> Self {
+        self.config.unstake_delay = unstake_delay;
+        self
+    }
+
+    /// Define por quantos blocos a lista de votantes de uma proposta e mantida
+    #[must_use]
+    pub const fn proposal_retention_blocks(mut self, blocks: u64) -> Self {
+        self.config.proposal_retention_blocks = blocks;
+        self
+    }
+
+    /// Define o bonus por epoca sem falhas e o bonus maximo (pontos-base)
+    #[must_use]
+    pub const fn uptime_bonus_bps(mut self, per_epoch: u128, max: u128) -> Self {
+        self.config.uptime_bonus_bps_per_epoch = per_epoch;
+        self.config.max_uptime_bonus_bps = max;
+        self
+    }
+
+    /// Define a parcela do valor punido paga ao denunciante (pontos-base)
+    #[must_use]
+    pub const fn slashing_bounty_bps(mut self, bps: u128) -> Self {
+        self.config.slashing_bounty_bps = bps;
+        self
+    }
+
+    /// Define as epocas de espera antes que um validador possa ser eleito
+    #[must_use]
+    pub const fn activation_delay_epochs(mut self, epochs: u64) -> Self {
+        self.config.activation_delay_epochs = epochs;
+        self
+    }
+
+    /// Define a recompensa distribuida ao fim de cada epoca
+    #[must_use]
+    pub const fn epoch_reward(mut self, epoch_reward: u128) -> Self {
+        self.config.epoch_reward = epoch_reward;
+        self
+    }
+
+    /// Define a fracao maxima do peso total de um validador (pontos-base)
+    #[must_use]
+    pub const fn max_voting_power_bps(mut self, bps: u128) -> Self {
+        self.config.max_voting_power_bps = bps;
+        self
+    }
+
+    /// Define a parcela das taxas de gas queimada (pontos-base)
+    #[must_use]
+    pub const fn fee_burn_bps(mut self, bps: u128) -> Self {
+        self.config.fee_burn_bps = bps;
+        self
+    }
+
+    /// Define o peso da epoca mais recente na taxa de aprovacao e a taxa
+    /// minima antes da punicao (pontos-base)
+    #[must_use]
+    pub const fn approval_rate_bps(mut self, ema_weight: u128, min_rate: u128) -> Self {
+        self.config.approval_ema_weight_bps = ema_weight;
+        self.config.min_approval_rate_bps = min_rate;
+        self
+    }
+
+    /// Valida e devolve a configuracao
+    ///
+    /// # Errors
+    ///
+    /// Retorna o erro de [`DposConfig::validate`]
+    pub fn build(self) -> Result<DposConfig> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
 /// Funcoes de utilidade para o Aevum
 pub mod utils {
-    use shared::Hash256;
+    use shared::{BlockchainError, Hash256, Result};
+
+    /// Prefixo legivel (HRP) dos enderecos Aevum em Bech32
+    pub const ADDRESS_HRP: &str = "aev";
+
+    /// Alfabeto Bech32 (BIP-173)
+    const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+    /// Numero de caracteres do checksum Bech32
+    const CHECKSUM_LEN: usize = 6;
 
     /// Calcula o poder de voto de um validador baseado no stake
     #[must_use]
@@ -261,18 +1163,200 @@ pub mod utils {
         !address.as_bytes().iter().all(|&b| b == 0)
     }
 
+    /// Verifica se um endereco textual tem checksum valido e nao e nulo
+    #[must_use]
+    pub fn is_valid_address_str(address: &str) -> bool {
+        decode_address(address).is_ok_and(|address| is_valid_address(&address))
+    }
+
+    /// Codifica um endereco em Bech32 com o prefixo `aev`
+    ///
+    /// O checksum detecta qualquer erro de ate quatro caracteres, entao um
+    /// endereco digitado errado nao decodifica para outra conta.
+    #[must_use]
+    pub fn format_address(address: &Hash256) -> String {
+        let data = convert_bits(address.as_bytes(), 8, 5, true).unwrap_or_default();
+        let checksum = bech32_checksum(ADDRESS_HRP, &data);
+
+        let mut encoded = String::with_capacity(ADDRESS_HRP.len() + 1 + data.len() + CHECKSUM_LEN);
+        encoded.push_str(ADDRESS_HRP);
+        encoded.push('1');
+        encoded.extend(
+            data.iter()
+                .chain(&checksum)
+                .map(|&value| char::from(BECH32_CHARSET[usize::from(value)])),
+        );
+        encoded
+    }
+
+    /// Decodifica um endereco gerado por `format_address`
+    ///
+    /// Aceita o texto todo em minusculas ou todo em maiusculas.
+    ///
+    /// # Errors
+    ///
+    /// Retorna `SerializationError` se o prefixo, os caracteres, o checksum
+    /// ou o tamanho do endereco forem invalidos
+    pub fn decode_address(encoded: &str) -> Result<Hash256> {
+        let invalid = |reason: &str| {
+            BlockchainError::SerializationError(format!("Endereco invalido: {reason}"))
+        };
+
+        if encoded.chars().any(|c| c.is_ascii_lowercase())
+            && encoded.chars().any(|c| c.is_ascii_uppercase())
+        {
+            return Err(invalid("mistura maiusculas e minusculas"));
+        }
+        let encoded = encoded.to_ascii_lowercase();
+        let (hrp, payload) = encoded
+            .rsplit_once('1')
+            .ok_or_else(|| invalid("separador ausente"))?;
+        if hrp != ADDRESS_HRP {
+            return Err(invalid("prefixo desconhecido"));
+        }
+        if payload.len() < CHECKSUM_LEN {
+            return Err(invalid("checksum ausente"));
+        }
+
+        let values = payload
+            .bytes()
+            .map(|c| {
+                BECH32_CHARSET
+                    .iter()
+                    .position(|&symbol| symbol == c)
+                    .and_then(|index| u8::try_from(index).ok())
+                    .ok_or_else(|| invalid("caractere fora do alfabeto"))
+            })
+            .collect::<Result<Vec<u8>>>()?;
+        if bech32_polymod(&[hrp_expand(hrp), values.clone()].concat()) != 1 {
+            return Err(invalid("checksum incorreto"));
+        }
+
+        let data = &values[..values.len() - CHECKSUM_LEN];
+        let bytes = convert_bits(data, 5, 8, false).ok_or_else(|| invalid("padding invalido"))?;
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| invalid("tamanho incorreto"))?;
+        Ok(Hash256::from_bytes(bytes))
+    }
+
+    fn bech32_polymod(values: &[u8]) -> u32 {
+        const GENERATOR: [u32; 5] = [
+            0x3b6a_57b2,
+            0x2650_8e6d,
+            0x1ea1_19fa,
+            0x3d42_33dd,
+            0x2a14_62b3,
+        ];
+        values.iter().fold(1, |checksum, &value| {
+            let top = checksum >> 25;
+            let checksum = ((checksum & 0x01ff_ffff) << 5) ^ u32::from(value);
+            GENERATOR
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| (top >> bit) & 1 == 1)
+                .fold(checksum, |checksum, (_, generator)| checksum ^ generator)
+        })
+    }
+
+    fn hrp_expand(hrp: &str) -> Vec<u8> {
+        hrp.bytes()
+            .map(|c| c >> 5)
+            .chain(std::iter::once(0))
+            .chain(hrp.bytes().map(|c| c & 31))
+            .collect()
+    }
+
+    fn bech32_checksum(hrp: &str, data: &[u8]) -> [u8; CHECKSUM_LEN] {
+        let values = [hrp_expand(hrp), data.to_vec(), vec![0; CHECKSUM_LEN]].concat();
+        let polymod = bech32_polymod(&values) ^ 1;
+        let mut checksum = [0; CHECKSUM_LEN];
+        for (i, value) in checksum.iter_mut().enumerate() {
+            // Cada caractere carrega 5 bits do polymod
+            *value = ((polymod >> (5 * (CHECKSUM_LEN - 1 - i))) & 31) as u8;
+        }
+        checksum
+    }
+
+    /// Reagrupa `data` de palavras de `from` bits para palavras de `to` bits
+    ///
+    /// Sem `pad`, bits restantes precisam ser zero e caber em menos de uma
+    /// palavra de `from` bits; caso contrario retorna `None`.
+    fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+        let max_value = (1u32 << to) - 1;
+        let mut accumulator = 0u32;
+        let mut bits = 0u32;
+        let mut converted = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+        for &value in data {
+            if u32::from(value) >> from != 0 {
+                return None;
+            }
+            accumulator = (accumulator << from) | u32::from(value);
+            bits += from;
+            while bits >= to {
+                bits -= to;
+                converted.push(((accumulator >> bits) & max_value) as u8);
+            }
+        }
+
+        if pad {
+            if bits > 0 {
+                converted.push(((accumulator << (to - bits)) & max_value) as u8);
+            }
+        } else if bits >= from || (accumulator << (to - bits)) & max_value != 0 {
+            return None;
+        }
+        Some(converted)
+    }
+
     /// Gera endereco a partir de chave publica
     #[must_use]
     pub fn address_from_public_key(public_key: &Hash256) -> Hash256 {
         // Implementacao simplificada - usar os ultimos 20 bytes do hash
         Hash256::keccak256(public_key.as_bytes())
     }
+
+    /// Gera endereco a partir de uma chave publica ML-DSA
+    ///
+    /// A chave completa nao cabe em um `Hash256`, entao o endereco e
+    /// derivado do hash dos seus bytes.
+    #[must_use]
+    pub fn address_from_key(public_key: &shared::PublicKey) -> Hash256 {
+        address_from_public_key(&Hash256::keccak256(public_key.as_bytes()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{utils, AccountState, AevumState, DposConfig, ValidatorInfo};
-    use shared::Hash256;
+    use super::{utils, AccountState, AevumState, DposConfig, UnbondingEntry, ValidatorInfo};
+    use crate::governance::{ProposalAction, ProposalStatus};
+    use crate::transaction::{
+        AevumTransaction, AevumTransactionType, TxEvent, TxStatus, GAS_TRANSFER,
+    };
+    use shared::{BlockchainError, Hash256, KeyPair, NetworkType, SignatureAlgorithm};
+
+    /// Par de chaves ML-DSA-44 e o endereco derivado dele
+    fn keyed_account() -> (KeyPair, Hash256) {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let address = utils::address_from_key(&keypair.public_key);
+        (keypair, address)
+    }
+
+    fn signed(mut tx: AevumTransaction, keypair: &KeyPair) -> AevumTransaction {
+        tx.sign(keypair).unwrap();
+        tx
+    }
+
+    fn transfer_tx(from: &KeyPair, to: Hash256, value: u128, nonce: u64) -> AevumTransaction {
+        let tx = AevumTransaction::new(
+            utils::address_from_key(&from.public_key),
+            to,
+            value,
+            nonce,
+            GAS_TRANSFER,
+            1,
+            AevumTransactionType::Transfer,
+        );
+        signed(tx, from)
+    }
 
     #[test]
     fn test_account_creation() {
@@ -322,6 +1406,53 @@ mod tests {
         assert_eq!(state.get_account(&addr2).unwrap().balance, 300);
     }
 
+    #[test]
+    fn test_state_root_changes_with_balance() {
+        let mut state = AevumState::new();
+        assert_eq!(state.state_root(), Hash256::zero());
+
+        let addr1 = Hash256::keccak256(b"addr1");
+        let addr2 = Hash256::keccak256(b"addr2");
+        state.create_account(addr1, 1000);
+        state.create_account(addr2, 500);
+        let root = state.state_root();
+        assert_eq!(state.state_root(), root);
+
+        state.credit(addr2, 1);
+        assert_ne!(state.state_root(), root);
+    }
+
+    #[test]
+    fn test_account_proof_verifies_against_state_root() {
+        let mut state = AevumState::new();
+        let addresses: Vec<Hash256> = (0u8..5).map(|i| Hash256::keccak256(&[i])).collect();
+        for (i, address) in (0u128..).zip(&addresses) {
+            state.create_account(*address, 100 * (i + 1));
+        }
+        let root = state.state_root();
+
+        for address in &addresses {
+            let proof = state.account_proof(address).unwrap();
+            assert!(proof.verify(&root));
+        }
+
+        // Saldo adulterado ou conta que nao esta no estado nao verificam
+        let mut forged = state.account_proof(&addresses[2]).unwrap();
+        forged.account.balance += 1;
+        assert!(!forged.verify(&root));
+
+        let outsider = Hash256::keccak256(b"outsider");
+        assert!(state.account_proof(&outsider).is_err());
+        let mut foreign = state.account_proof(&addresses[0]).unwrap();
+        foreign.address = outsider;
+        assert!(!foreign.verify(&root));
+
+        // Prova antiga nao verifica contra a root depois de uma mudanca
+        let proof = state.account_proof(&addresses[1]).unwrap();
+        state.credit(addresses[1], 1);
+        assert!(!proof.verify(&state.state_root()));
+    }
+
     #[test]
     fn test_dpos_config() {
         let config = DposConfig::default();
@@ -335,4 +1466,736 @@ mod tests {
         let power = utils::calculate_voting_power(5000, 20000);
         assert!((power - 0.25).abs() < f64::EPSILON); // 25% do stake total
     }
+
+    #[test]
+    fn test_dpos_config_rejects_invalid_fields() {
+        assert!(DposConfig::default().validate().is_ok());
+
+        let invalid = [
+            DposConfig::builder().max_validators(0),
+            DposConfig::builder().min_validator_stake(0),
+            DposConfig::builder().epoch_length(0),
+            DposConfig::builder().max_voting_power_bps(0),
+            DposConfig::builder().max_voting_power_bps(10_001),
+            DposConfig::builder().uptime_bonus_bps(50, 10_001),
+            DposConfig::builder().uptime_bonus_bps(200, 100),
+            DposConfig::builder().slashing_bounty_bps(10_001),
+            DposConfig::builder().fee_burn_bps(10_001),
+        ];
+        for builder in invalid {
+            let config = format!("{builder:?}");
+            assert!(
+                matches!(builder.build(), Err(BlockchainError::InvalidBlock(_))),
+                "{config}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dpos_config_differs_per_network() {
+        let networks = [
+            NetworkType::Mainnet,
+            NetworkType::Testnet,
+            NetworkType::Devnet,
+            NetworkType::Regtest,
+        ];
+        let configs: Vec<String> = networks
+            .iter()
+            .map(|network| {
+                let config = DposConfig::for_network(network.clone());
+                assert!(config.validate().is_ok(), "{network:?}");
+                format!("{config:?}")
+            })
+            .collect();
+
+        for (i, config) in configs.iter().enumerate() {
+            assert!(!configs[i + 1..].contains(config), "{:?}", networks[i]);
+        }
+        assert_eq!(
+            DposConfig::for_network(NetworkType::Mainnet).epoch_length,
+            DposConfig::default().epoch_length
+        );
+    }
+
+    #[test]
+    fn test_dpos_config_builder() {
+        let config = DposConfig::builder()
+            .max_validators(5)
+            .epoch_length(100)
+            .fee_burn_bps(10_000)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.max_validators, 5);
+        assert_eq!(config.epoch_length, 100);
+        assert_eq!(config.fee_burn_bps, 10_000);
+        assert_eq!(
+            config.min_validator_stake,
+            DposConfig::default().min_validator_stake
+        );
+    }
+
+    #[test]
+    fn test_address_bech32_round_trip_and_checksum() {
+        let address = utils::address_from_public_key(&Hash256::keccak256(b"alice"));
+        let encoded = utils::format_address(&address);
+        assert!(encoded.starts_with("aev1"));
+        assert_eq!(utils::decode_address(&encoded).unwrap(), address);
+        assert_eq!(
+            utils::decode_address(&encoded.to_ascii_uppercase()).unwrap(),
+            address
+        );
+        assert!(utils::is_valid_address_str(&encoded));
+
+        // Trocar qualquer caractere do payload invalida o checksum
+        for index in utils::ADDRESS_HRP.len() + 1..encoded.len() {
+            let mut mutated = encoded.clone().into_bytes();
+            mutated[index] = if mutated[index] == b'q' { b'p' } else { b'q' };
+            let mutated = String::from_utf8(mutated).unwrap();
+            assert!(utils::decode_address(&mutated).is_err(), "{mutated}");
+            assert!(!utils::is_valid_address_str(&mutated));
+        }
+        assert!(utils::decode_address(&encoded.replacen("aev", "bnd", 1)).is_err());
+    }
+
+    #[test]
+    fn test_apply_transfer_increments_nonce_once() {
+        let mut state = AevumState::new();
+        let (alice_key, alice) = keyed_account();
+        let bob = Hash256::keccak256(b"bob");
+        state.create_account(alice, 100_000);
+
+        let receipt = state
+            .apply_transaction(&transfer_tx(&alice_key, bob, 1_000, 0))
+            .unwrap();
+
+        assert_eq!(receipt.status, TxStatus::Success);
+        assert_eq!(receipt.gas_used, GAS_TRANSFER);
+        assert_eq!(receipt.fee, u128::from(GAS_TRANSFER));
+        assert_eq!(state.get_account(&alice).unwrap().nonce, 1);
+        assert_eq!(
+            state.get_account(&alice).unwrap().balance,
+            100_000 - 1_000 - u128::from(GAS_TRANSFER)
+        );
+        assert_eq!(state.get_account(&bob).unwrap().balance, 1_000);
+        assert_eq!(state.get_account(&bob).unwrap().nonce, 0);
+    }
+
+    #[test]
+    fn test_apply_rejects_unsigned_or_spoofed_sender() {
+        let mut state = AevumState::new();
+        let (_, alice) = keyed_account();
+        let (mallory_key, _) = keyed_account();
+        let bob = Hash256::keccak256(b"bob");
+        state.create_account(alice, 100_000);
+
+        // Sem assinatura
+        let unsigned = AevumTransaction::new(
+            alice,
+            bob,
+            1_000,
+            0,
+            GAS_TRANSFER,
+            1,
+            AevumTransactionType::Transfer,
+        );
+        assert!(state.apply_transaction(&unsigned).is_err());
+
+        // Assinada por outra chave declarando a conta de alice
+        let spoofed = signed(unsigned, &mallory_key);
+        assert!(state.apply_transaction(&spoofed).is_err());
+        assert!(state
+            .apply_block(&[spoofed], &Hash256::keccak256(b"producer"))
+            .is_err());
+
+        assert_eq!(state.get_account(&alice).unwrap().balance, 100_000);
+        assert_eq!(state.get_account(&alice).unwrap().nonce, 0);
+        assert!(state.get_account(&bob).is_none());
+    }
+
+    #[test]
+    fn test_apply_rejects_nonce_mismatch() {
+        let mut state = AevumState::new();
+        let (alice_key, alice) = keyed_account();
+        state.create_account(alice, 100_000);
+
+        let result = state.apply_transaction(&transfer_tx(&alice_key, Hash256::zero(), 1, 5));
+        assert!(matches!(
+            result,
+            Err(BlockchainError::InvalidTransaction(_))
+        ));
+        assert_eq!(state.get_account(&alice).unwrap().nonce, 0);
+        assert_eq!(state.get_account(&alice).unwrap().balance, 100_000);
+    }
+
+    #[test]
+    fn test_apply_rejects_insufficient_balance_for_gas() {
+        let mut state = AevumState::new();
+        let (alice_key, alice) = keyed_account();
+        state.create_account(alice, u128::from(GAS_TRANSFER) - 1);
+
+        let result = state.apply_transaction(&transfer_tx(&alice_key, Hash256::zero(), 0, 0));
+        assert!(matches!(result, Err(BlockchainError::InsufficientFunds)));
+        assert_eq!(state.get_account(&alice).unwrap().nonce, 0);
+
+        // Saldo cobre o gas mas nao o valor: cobrada, porem sem efeito
+        state.create_account(alice, u128::from(GAS_TRANSFER));
+        let receipt = state
+            .apply_transaction(&transfer_tx(&alice_key, Hash256::zero(), 1, 0))
+            .unwrap();
+        assert!(!receipt.is_success());
+        assert_eq!(state.get_account(&alice).unwrap().nonce, 1);
+        assert_eq!(state.get_account(&alice).unwrap().balance, 0);
+    }
+
+    #[test]
+    fn test_apply_rejects_overflowing_fee() {
+        let mut state = AevumState::new();
+        let (alice_key, alice) = keyed_account();
+        state.create_account(alice, u128::MAX);
+
+        let tx = AevumTransaction::new(
+            alice,
+            Hash256::zero(),
+            1,
+            0,
+            GAS_TRANSFER,
+            u128::MAX,
+            AevumTransactionType::Transfer,
+        );
+        let result = state.apply_transaction(&signed(tx, &alice_key));
+        assert!(matches!(
+            result,
+            Err(BlockchainError::InvalidTransaction(_))
+        ));
+        assert_eq!(state.get_account(&alice).unwrap().nonce, 0);
+        assert_eq!(state.get_account(&alice).unwrap().balance, u128::MAX);
+    }
+
+    #[test]
+    fn test_apply_stake_and_delegate() {
+        let mut state = AevumState::new();
+        let (validator_key, validator) = keyed_account();
+        let (delegator_key, delegator) = keyed_account();
+        state.create_account(validator, 1_000_000);
+        state.create_account(delegator, 1_000_000);
+
+        let stake = staking_tx(&validator_key, 5_000, 0, AevumTransactionType::Stake);
+        assert!(state.apply_transaction(&stake).unwrap().is_success());
+
+        let delegate = staking_tx(
+            &delegator_key,
+            2_000,
+            0,
+            AevumTransactionType::Delegate { validator },
+        );
+        assert!(state.apply_transaction(&delegate).unwrap().is_success());
+
+        let info = &state.validators[&validator];
+        assert_eq!(info.stake_amount, 5_000);
+        assert_eq!(info.delegated_stake, 2_000);
+        assert_eq!(info.effective_stake(), 7_000);
+        assert_eq!(info.delegator_count, 1);
+        assert_eq!(state.delegations[&(delegator, validator)], 2_000);
+    }
+
+    #[test]
+    fn test_zero_value_delegation_is_rejected() {
+        let mut state = AevumState::new();
+        let (validator_key, validator) = keyed_account();
+        let (delegator_key, delegator) = keyed_account();
+        state.create_account(validator, 1_000_000);
+        state.create_account(delegator, 1_000_000);
+        let stake = staking_tx(&validator_key, 5_000, 0, AevumTransactionType::Stake);
+        assert!(state.apply_transaction(&stake).unwrap().is_success());
+
+        // Cobrada, mas sem criar delegacao nem contar delegador
+        for nonce in 0..2 {
+            let delegate = staking_tx(
+                &delegator_key,
+                0,
+                nonce,
+                AevumTransactionType::Delegate { validator },
+            );
+            assert!(!state.apply_transaction(&delegate).unwrap().is_success());
+        }
+        let undelegate = staking_tx(
+            &delegator_key,
+            0,
+            2,
+            AevumTransactionType::Undelegate { validator },
+        );
+        assert!(!state.apply_transaction(&undelegate).unwrap().is_success());
+
+        assert_eq!(state.validators[&validator].delegator_count, 0);
+        assert!(!state.delegations.contains_key(&(delegator, validator)));
+        assert_eq!(state.get_account(&delegator).unwrap().nonce, 3);
+    }
+
+    fn staking_tx(
+        from: &KeyPair,
+        value: u128,
+        nonce: u64,
+        tx_type: AevumTransactionType,
+    ) -> AevumTransaction {
+        let address = utils::address_from_key(&from.public_key);
+        let tx = AevumTransaction::new(address, Hash256::zero(), value, nonce, 50_000, 1, tx_type);
+        signed(tx, from)
+    }
+
+    #[test]
+    fn test_undelegate_respects_unstake_delay() {
+        let config = DposConfig {
+            unstake_delay: 2,
+            ..DposConfig::default()
+        };
+        let mut state = AevumState::with_config(config);
+        let validator = Hash256::keccak256(b"validator");
+        let (delegator_key, delegator) = keyed_account();
+        state.create_account(delegator, 1_000_000);
+        state.register_validator(validator, 5_000).unwrap();
+
+        let delegate = AevumTransactionType::Delegate { validator };
+        let undelegate = AevumTransactionType::Undelegate { validator };
+
+        state
+            .apply_transaction(&staking_tx(&delegator_key, 2_000, 0, delegate))
+            .unwrap();
+        state.advance_epoch();
+
+        // Uma epoca depois ainda esta bloqueada
+        let receipt = state
+            .apply_transaction(&staking_tx(&delegator_key, 2_000, 1, undelegate.clone()))
+            .unwrap();
+        assert!(!receipt.is_success());
+        assert_eq!(state.validators[&validator].delegated_stake, 2_000);
+
+        state.advance_epoch();
+        let balance_before = state.get_account(&delegator).unwrap().balance;
+        let receipt = state
+            .apply_transaction(&staking_tx(&delegator_key, 2_000, 2, undelegate))
+            .unwrap();
+        assert!(receipt.is_success());
+
+        let info = &state.validators[&validator];
+        assert_eq!(info.delegated_stake, 0);
+        assert_eq!(info.delegator_count, 0);
+        assert!(!state.delegations.contains_key(&(delegator, validator)));
+        assert_eq!(
+            state.get_account(&delegator).unwrap().balance,
+            balance_before + 2_000 - receipt.fee
+        );
+    }
+
+    #[test]
+    fn test_unstaked_funds_release_after_delay() {
+        let config = DposConfig {
+            unstake_delay: 2,
+            ..DposConfig::default()
+        };
+        let mut state = AevumState::with_config(config);
+        let (validator_key, validator) = keyed_account();
+        state.create_account(validator, 1_000_000);
+        state.advance_epoch();
+        let stake = staking_tx(&validator_key, 5_000, 0, AevumTransactionType::Stake);
+        let unstake = staking_tx(&validator_key, 2_000, 1, AevumTransactionType::Unstake);
+        assert!(state.apply_transaction(&stake).unwrap().is_success());
+        assert!(state.apply_transaction(&unstake).unwrap().is_success());
+        let balance = state.get_account(&validator).unwrap().balance;
+
+        // Bloqueado e fora do peso de eleicao ate a epoca 3
+        let info = &state.validators[&validator];
+        assert_eq!(info.stake_amount, 3_000);
+        assert_eq!(info.effective_stake(), 3_000);
+        assert_eq!(
+            info.unbonding,
+            vec![UnbondingEntry {
+                amount: 2_000,
+                release_epoch: 3
+            }]
+        );
+        let supply = state.total_supply();
+        state.advance_epoch();
+        assert_eq!(state.get_account(&validator).unwrap().balance, balance);
+        assert_eq!(state.total_supply(), supply);
+
+        state.advance_epoch();
+        assert_eq!(state.current_epoch, 3);
+        assert_eq!(
+            state.get_account(&validator).unwrap().balance,
+            balance + 2_000
+        );
+        assert!(state.validators[&validator].unbonding.is_empty());
+        assert_eq!(state.total_supply(), supply);
+    }
+
+    #[test]
+    fn test_staking_transactions_emit_events() {
+        let config = DposConfig {
+            unstake_delay: 0,
+            ..DposConfig::default()
+        };
+        let mut state = AevumState::with_config(config);
+        let (validator_key, validator) = keyed_account();
+        let (delegator_key, delegator) = keyed_account();
+        state.create_account(validator, 1_000_000);
+        state.create_account(delegator, 1_000_000);
+        state.advance_epoch();
+
+        let stake = state
+            .apply_transaction(&staking_tx(
+                &validator_key,
+                5_000,
+                0,
+                AevumTransactionType::Stake,
+            ))
+            .unwrap();
+        let unstake = state
+            .apply_transaction(&staking_tx(
+                &validator_key,
+                1_500,
+                1,
+                AevumTransactionType::Unstake,
+            ))
+            .unwrap();
+        let delegate = state
+            .apply_transaction(&staking_tx(
+                &delegator_key,
+                2_000,
+                0,
+                AevumTransactionType::Delegate { validator },
+            ))
+            .unwrap();
+        let undelegate = state
+            .apply_transaction(&staking_tx(
+                &delegator_key,
+                500,
+                1,
+                AevumTransactionType::Undelegate { validator },
+            ))
+            .unwrap();
+
+        let expected = vec![
+            TxEvent::Staked {
+                validator,
+                amount: 5_000,
+            },
+            TxEvent::Unstaked {
+                validator,
+                amount: 1_500,
+                unlock_epoch: 1,
+            },
+            TxEvent::Delegated {
+                delegator,
+                validator,
+                amount: 2_000,
+            },
+            TxEvent::Undelegated {
+                delegator,
+                validator,
+                amount: 500,
+            },
+        ];
+        for (receipt, event) in [stake, unstake, delegate, undelegate].iter().zip(&expected) {
+            assert!(receipt.is_success());
+            assert_eq!(receipt.events, vec![event.clone()]);
+        }
+        assert_eq!(state.drain_events(), expected);
+        assert!(state.events.is_empty());
+
+        // Execucao que falha nao emite eventos
+        let receipt = state
+            .apply_transaction(&staking_tx(
+                &delegator_key,
+                1,
+                2,
+                AevumTransactionType::Unstake,
+            ))
+            .unwrap();
+        assert!(!receipt.is_success());
+        assert!(receipt.events.is_empty());
+        assert!(state.drain_events().is_empty());
+
+        // Transferencias nao emitem eventos de staking
+        let receipt = state
+            .apply_transaction(&transfer_tx(&delegator_key, validator, 10, 3))
+            .unwrap();
+        assert!(receipt.events.is_empty());
+    }
+
+    fn governance_tx(
+        from: &KeyPair,
+        nonce: u64,
+        tx_type: AevumTransactionType,
+    ) -> AevumTransaction {
+        let address = utils::address_from_key(&from.public_key);
+        let tx = AevumTransaction::new(address, Hash256::zero(), 0, nonce, 100_000, 1, tx_type);
+        signed(tx, from)
+    }
+
+    #[test]
+    fn test_create_proposal_and_weighted_vote() {
+        let mut state = AevumState::new();
+        let (proposer_key, proposer) = keyed_account();
+        let (voter_key, voter) = keyed_account();
+        state.create_account(proposer, 1_000_000);
+        state.create_account(voter, 1_000_000);
+        state.register_validator(voter, 4_000).unwrap();
+
+        let create = AevumTransactionType::CreateProposal {
+            title: "Aumentar validadores".to_string(),
+            description: "Passar de 21 para 31".to_string(),
+            voting_period: 100,
+            action: None,
+        };
+        assert!(state
+            .apply_transaction(&governance_tx(&proposer_key, 0, create))
+            .unwrap()
+            .is_success());
+        assert_eq!(state.next_proposal_id, 1);
+        assert_eq!(state.proposals[&0].proposer, proposer);
+        assert_eq!(state.proposals[&0].voting_end, 100);
+
+        // Peso acima do stake real e rejeitado
+        let overweight = AevumTransactionType::Vote {
+            proposal_id: 0,
+            support: true,
+            weight: 4_001,
+        };
+        assert!(!state
+            .apply_transaction(&governance_tx(&voter_key, 0, overweight))
+            .unwrap()
+            .is_success());
+
+        let vote = AevumTransactionType::Vote {
+            proposal_id: 0,
+            support: true,
+            weight: 4_000,
+        };
+        assert!(state
+            .apply_transaction(&governance_tx(&voter_key, 1, vote.clone()))
+            .unwrap()
+            .is_success());
+        assert_eq!(state.proposals[&0].votes_for, 4_000);
+
+        // Voto duplicado
+        assert!(!state
+            .apply_transaction(&governance_tx(&voter_key, 2, vote))
+            .unwrap()
+            .is_success());
+        assert_eq!(state.proposals[&0].votes_for, 4_000);
+        assert_eq!(state.proposals[&0].voter_count, 1);
+    }
+
+    fn proposal_with_vote(
+        state: &mut AevumState,
+        proposer_key: &KeyPair,
+        action: ProposalAction,
+        support: bool,
+    ) -> u64 {
+        let proposer = utils::address_from_key(&proposer_key.public_key);
+        if state.get_account(&proposer).is_none() {
+            state.create_account(proposer, 1_000_000);
+            state.register_validator(proposer, 4_000).unwrap();
+        }
+        let nonce = state.get_account(&proposer).unwrap().nonce;
+        let create = AevumTransactionType::CreateProposal {
+            title: "Stake minimo".to_string(),
+            description: String::new(),
+            voting_period: 10,
+            action: Some(action),
+        };
+        let vote = AevumTransactionType::Vote {
+            proposal_id: state.next_proposal_id,
+            support,
+            weight: 4_000,
+        };
+        let id = state.next_proposal_id;
+        for (offset, tx_type) in (0..).zip([create, vote]) {
+            assert!(state
+                .apply_transaction(&governance_tx(proposer_key, nonce + offset, tx_type))
+                .unwrap()
+                .is_success());
+        }
+        state.proposals.get_mut(&id).unwrap().finalize(11).unwrap();
+        id
+    }
+
+    #[test]
+    fn test_passed_proposal_changes_min_validator_stake() {
+        let mut state = AevumState::new();
+        let (proposer_key, _) = keyed_account();
+        let id = proposal_with_vote(
+            &mut state,
+            &proposer_key,
+            ProposalAction::SetMinValidatorStake(5_000),
+            true,
+        );
+        assert_eq!(state.config.min_validator_stake, 1_000);
+
+        state.execute_proposal(id).unwrap();
+        assert_eq!(state.config.min_validator_stake, 5_000);
+        assert_eq!(state.proposals[&id].status, ProposalStatus::Executed);
+
+        // Executar de novo e rejeitado
+        assert!(state.execute_proposal(id).is_err());
+        assert!(state.execute_proposal(id + 1).is_err());
+    }
+
+    #[test]
+    fn test_rejected_or_invalid_proposal_has_no_effect() {
+        let mut state = AevumState::new();
+        let (proposer_key, _) = keyed_account();
+        let rejected = proposal_with_vote(
+            &mut state,
+            &proposer_key,
+            ProposalAction::SetMinValidatorStake(5_000),
+            false,
+        );
+        assert_eq!(state.proposals[&rejected].status, ProposalStatus::Rejected);
+        assert!(state.execute_proposal(rejected).is_err());
+        assert_eq!(state.config.min_validator_stake, 1_000);
+        assert_eq!(state.proposals[&rejected].status, ProposalStatus::Rejected);
+
+        // Aprovada, mas com valor que invalida a configuracao
+        let invalid = proposal_with_vote(
+            &mut state,
+            &proposer_key,
+            ProposalAction::SetMaxValidators(0),
+            true,
+        );
+        assert!(state.execute_proposal(invalid).is_err());
+        assert_eq!(state.config.max_validators, 21);
+        assert_eq!(state.proposals[&invalid].status, ProposalStatus::Passed);
+    }
+
+    #[test]
+    fn test_vote_on_missing_or_expired_proposal() {
+        let mut state = AevumState::new();
+        let (voter_key, voter) = keyed_account();
+        state.create_account(voter, 1_000_000);
+        state.register_validator(voter, 4_000).unwrap();
+
+        let vote = AevumTransactionType::Vote {
+            proposal_id: 0,
+            support: false,
+            weight: 1,
+        };
+        assert!(!state
+            .apply_transaction(&governance_tx(&voter_key, 0, vote.clone()))
+            .unwrap()
+            .is_success());
+
+        let create = AevumTransactionType::CreateProposal {
+            title: "Curta".to_string(),
+            description: String::new(),
+            voting_period: 10,
+            action: None,
+        };
+        state
+            .apply_transaction(&governance_tx(&voter_key, 1, create))
+            .unwrap();
+        state.block_height = 11;
+        assert!(!state
+            .apply_transaction(&governance_tx(&voter_key, 2, vote))
+            .unwrap()
+            .is_success());
+    }
+
+    #[test]
+    fn test_block_fees_are_split_between_burn_and_producer() {
+        let config = DposConfig {
+            fee_burn_bps: 3_000,
+            ..DposConfig::default()
+        };
+        let mut state = AevumState::with_config(config);
+        let (alice_key, alice) = keyed_account();
+        let bob = Hash256::keccak256(b"bob");
+        let producer = Hash256::keccak256(b"producer");
+        state.create_account(alice, 1_000_000);
+        let supply_before = state.total_supply();
+
+        let mut first = transfer_tx(&alice_key, bob, 1_000, 0);
+        first.gas_price = 10;
+        let mut second = transfer_tx(&alice_key, bob, 2_000, 1);
+        second.gas_price = 10;
+        let (first, second) = (signed(first, &alice_key), signed(second, &alice_key));
+        let receipts = state.apply_block(&[first, second], &producer).unwrap();
+
+        let fees: u128 = receipts.iter().map(|r| r.fee).sum();
+        assert_eq!(fees, 2 * u128::from(GAS_TRANSFER) * 10);
+        let burned = fees * 3 / 10;
+        assert_eq!(state.burned_supply, burned);
+        assert_eq!(state.get_account(&producer).unwrap().balance, fees - burned);
+        assert_eq!(state.total_supply(), supply_before - burned);
+        assert_eq!(
+            state.get_account(&alice).unwrap().balance,
+            1_000_000 - 3_000 - fees
+        );
+    }
+
+    #[test]
+    fn test_block_prunes_unused_empty_accounts() {
+        let mut state = AevumState::new();
+        let (alice_key, alice) = keyed_account();
+        let bob = Hash256::keccak256(b"bob");
+        let unused = Hash256::keccak256(b"unused");
+        let validator = Hash256::keccak256(b"validator");
+        let producer = Hash256::keccak256(b"producer");
+        let fee = u128::from(GAS_TRANSFER);
+        state.create_account(alice, 1_000 + fee);
+        state.create_account(unused, 0);
+        state.create_account(validator, 0);
+        state
+            .validators
+            .insert(validator, ValidatorInfo::new(validator, 0));
+
+        let root_before_pruning = state.state_root();
+        assert!(state.account_proof(&unused).is_err());
+
+        // Alice gasta tudo e fica com saldo zero, mas com nonce 1
+        state
+            .apply_block(&[transfer_tx(&alice_key, bob, 1_000, 0)], &producer)
+            .unwrap();
+        let drained = state.get_account(&alice).unwrap();
+        assert_eq!(drained.balance, 0);
+        assert_eq!(drained.nonce, 1);
+        assert!(state.get_account(&unused).is_none());
+        assert!(state.get_account(&validator).is_some());
+
+        // O nonce retido continua impedindo o replay
+        state.credit(alice, 1_000 + fee);
+        assert!(state
+            .apply_transaction(&transfer_tx(&alice_key, bob, 1_000, 0))
+            .is_err());
+
+        // A conta vazia nao entrava na root mesmo antes de ser podada
+        let mut unpruned = AevumState::new();
+        unpruned.create_account(alice, 1_000 + fee);
+        unpruned.create_account(validator, 0);
+        unpruned
+            .validators
+            .insert(validator, ValidatorInfo::new(validator, 0));
+        assert_eq!(unpruned.state_root(), root_before_pruning);
+    }
+
+    #[test]
+    fn test_rejected_block_leaves_state_unchanged() {
+        let mut state = AevumState::new();
+        let (alice_key, alice) = keyed_account();
+        let bob = Hash256::keccak256(b"bob");
+        let producer = Hash256::keccak256(b"producer");
+        state.create_account(alice, 1_000_000);
+
+        // Segundo nonce repetido invalida o bloco inteiro
+        let block = [
+            transfer_tx(&alice_key, bob, 1_000, 0),
+            transfer_tx(&alice_key, bob, 1_000, 0),
+        ];
+        assert!(state.apply_block(&block, &producer).is_err());
+        assert_eq!(state.get_account(&alice).unwrap().balance, 1_000_000);
+        assert_eq!(state.get_account(&alice).unwrap().nonce, 0);
+        assert!(state.get_account(&producer).is_none());
+        assert_eq!(state.burned_supply, 0);
+    }
 }
@@ -0,0 +1,586 @@
+//! Transacoes do Aevum
+//!
+//! Transacoes seguem o modelo de contas: cada uma parte de um endereco
+//! `from`, consome gas e carrega o `nonce` esperado da conta de origem.
+
+use crate::governance::ProposalAction;
+use crate::placeholder::utils;
+use serde::{Deserialize, Serialize};
+use shared::encoding::write_bytes;
+use shared::{
+    sign_transaction_hash, verify_transaction_signature, BlockchainError, Hash256, KeyPair,
+    PublicKey, Result, Signature, SignatureAlgorithm,
+};
+
+/// Gas fixo de uma transferencia simples
+pub const GAS_TRANSFER: u64 = 21_000;
+
+/// Gas fixo de operacoes de stake e delegacao
+pub const GAS_STAKING: u64 = 50_000;
+
+/// Gas fixo de operacoes de governanca
+pub const GAS_GOVERNANCE: u64 = 100_000;
+
+/// Gas cobrado por byte serializado ao comparar precos efetivos
+pub const GAS_PER_BYTE: u64 = 16;
+
+/// Tipos de transacao suportados pelo Aevum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AevumTransactionType {
+    /// Transfere `value` para `to`
+    Transfer,
+    /// Coloca `value` em stake proprio como validador
+    Stake,
+    /// Retira `value` do stake proprio, liberado apos `unstake_delay` epocas
+    Unstake,
+    /// Delega `value` a um validador
+    Delegate {
+        /// Validador que recebe a delegacao
+        validator: Hash256,
+    },
+    /// Retira `value` delegado a um validador
+    Undelegate {
+        /// Validador que recebeu a delegacao
+        validator: Hash256,
+    },
+    /// Cria uma proposta de governanca
+    CreateProposal {
+        /// Titulo curto
+        title: String,
+        /// Descricao detalhada
+        description: String,
+        /// Duracao da votacao em blocos
+        voting_period: u64,
+        /// Mudanca aplicada se a proposta for aprovada e executada
+        #[serde(default)]
+        action: Option<ProposalAction>,
+    },
+    /// Vota em uma proposta de governanca
+    Vote {
+        /// Proposta votada
+        proposal_id: u64,
+        /// A favor ou contra
+        support: bool,
+        /// Peso declarado do voto
+        weight: u128,
+    },
+}
+
+impl AevumTransactionType {
+    /// Gas consumido pela execucao deste tipo de transacao
+    #[must_use]
+    pub const fn intrinsic_gas(&self) -> u64 {
+        match self {
+            Self::Transfer => GAS_TRANSFER,
+            Self::Stake | Self::Unstake | Self::Delegate { .. } | Self::Undelegate { .. } => {
+                GAS_STAKING
+            }
+            Self::CreateProposal { .. } | Self::Vote { .. } => GAS_GOVERNANCE,
+        }
+    }
+
+    /// Codificacao binaria deterministica: um byte de tag seguido dos campos
+    fn write_canonical(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Transfer => out.push(0),
+            Self::Stake => out.push(1),
+            Self::Unstake => out.push(2),
+            Self::Delegate { validator } => {
+                out.push(3);
+                out.extend_from_slice(validator.as_bytes());
+            }
+            Self::Undelegate { validator } => {
+                out.push(4);
+                out.extend_from_slice(validator.as_bytes());
+            }
+            Self::CreateProposal {
+                title,
+                description,
+                voting_period,
+                action,
+            } => {
+                out.push(5);
+                write_bytes(out, title.as_bytes());
+                write_bytes(out, description.as_bytes());
+                out.extend_from_slice(&voting_period.to_le_bytes());
+                match action {
+                    Some(action) => {
+                        out.push(1);
+                        action.write_canonical(out);
+                    }
+                    None => out.push(0),
+                }
+            }
+            Self::Vote {
+                proposal_id,
+                support,
+                weight,
+            } => {
+                out.push(6);
+                out.extend_from_slice(&proposal_id.to_le_bytes());
+                out.push(u8::from(*support));
+                out.extend_from_slice(&weight.to_le_bytes());
+            }
+        }
+    }
+}
+
+/// Transacao do Aevum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AevumTransaction {
+    /// Endereco de origem
+    pub from: Hash256,
+    /// Endereco de destino (ignorado por tipos que nao transferem)
+    pub to: Hash256,
+    /// Valor movimentado
+    pub value: u128,
+    /// Nonce esperado da conta de origem
+    pub nonce: u64,
+    /// Gas maximo que o remetente aceita pagar
+    pub gas_limit: u64,
+    /// Preco por unidade de gas
+    pub gas_price: u128,
+    /// Tipo da transacao
+    pub tx_type: AevumTransactionType,
+    /// Assinatura do remetente
+    pub signature: Option<Signature>,
+}
+
+impl AevumTransaction {
+    /// Cria uma transacao nao assinada
+    #[must_use]
+    pub const fn new(
+        from: Hash256,
+        to: Hash256,
+        value: u128,
+        nonce: u64,
+        gas_limit: u64,
+        gas_price: u128,
+        tx_type: AevumTransactionType,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            value,
+            nonce,
+            gas_limit,
+            gas_price,
+            tx_type,
+            signature: None,
+        }
+    }
+
+    /// Codificacao binaria deterministica dos campos assinados
+    ///
+    /// Campos na ordem da declaracao, inteiros little-endian e textos
+    /// prefixados pelo tamanho; a assinatura fica de fora.
+    #[must_use]
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32 + 32 + 16 + 8 + 8 + 16 + 1);
+        out.extend_from_slice(self.from.as_bytes());
+        out.extend_from_slice(self.to.as_bytes());
+        out.extend_from_slice(&self.value.to_le_bytes());
+        out.extend_from_slice(&self.nonce.to_le_bytes());
+        out.extend_from_slice(&self.gas_limit.to_le_bytes());
+        out.extend_from_slice(&self.gas_price.to_le_bytes());
+        self.tx_type.write_canonical(&mut out);
+        out
+    }
+
+    /// Hash da transacao (forma canonica), sem a assinatura
+    ///
+    /// # Errors
+    ///
+    /// Nao falha desde que o hash deixou de usar JSON; o `Result` e mantido
+    /// por compatibilidade
+    pub fn hash(&self) -> Result<Hash256> {
+        Ok(Hash256::keccak256(&self.to_canonical_bytes()))
+    }
+
+    /// Assina a transacao com ML-DSA-44, o nivel usado pelo Aevum
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o par de chaves nao for ML-DSA-44 ou se a
+    /// assinatura falhar
+    pub fn sign(&mut self, keypair: &KeyPair) -> Result<()> {
+        if keypair.algorithm() != SignatureAlgorithm::MLDSA44 {
+            return Err(BlockchainError::InvalidTransaction(
+                "Transacoes Aevum exigem chaves ML-DSA-44".to_string(),
+            ));
+        }
+
+        self.signature = Some(sign_transaction_hash(&self.hash()?, keypair)?);
+        Ok(())
+    }
+
+    /// Verifica a assinatura contra o hash da transacao e o endereco `from`
+    ///
+    /// Retorna `false` se a transacao nao estiver assinada, se foi assinada
+    /// por outra chave, se `pubkey` nao corresponder a `from` ou se algum
+    /// campo foi alterado depois da assinatura.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a serializacao falhar ou a assinatura estiver malformada
+    pub fn verify_signature(&self, pubkey: &PublicKey) -> Result<bool> {
+        let Some(signature) = &self.signature else {
+            return Ok(false);
+        };
+        if signature.public_key() != pubkey || utils::address_from_key(pubkey) != self.from {
+            return Ok(false);
+        }
+
+        verify_transaction_signature(&self.hash()?, signature)
+    }
+
+    /// Verifica que a transacao foi assinada pela chave dona de `from`
+    ///
+    /// O endereco e derivado da chave publica embutida na assinatura, entao
+    /// assinar com a propria chave declarando o `from` de outra conta e
+    /// rejeitado sem que o verificador precise conhecer a chave de antemao.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a transacao nao estiver assinada, se a chave da
+    /// assinatura nao corresponder a `from` ou se a assinatura for invalida
+    pub fn verify_sender(&self) -> Result<()> {
+        let signature = self.signature.as_ref().ok_or_else(|| {
+            BlockchainError::InvalidTransaction("Transacao nao assinada".to_string())
+        })?;
+        if utils::address_from_key(signature.public_key()) != self.from {
+            return Err(BlockchainError::InvalidTransaction(
+                "Remetente nao corresponde a chave da assinatura".to_string(),
+            ));
+        }
+        if !verify_transaction_signature(&self.hash()?, signature)? {
+            return Err(BlockchainError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Tamanho da transacao serializada, incluindo a assinatura
+    #[must_use]
+    pub fn size(&self) -> usize {
+        serde_json::to_vec(self).map_or(0, |bytes| bytes.len())
+    }
+
+    /// Preco efetivo por unidade de gas, considerando o tamanho
+    ///
+    /// Divide a taxa cobrada (`intrinsic_gas * gas_price`) pelo gas
+    /// intrinseco somado a `GAS_PER_BYTE` por byte serializado, de modo que
+    /// transacoes maiores pagando a mesma taxa tenham prioridade menor.
+    /// A divisao e feita por partes para nao transbordar com `gas_price`
+    /// proximo de `u128::MAX`.
+    #[must_use]
+    pub fn effective_gas_price(&self) -> u128 {
+        let intrinsic = u128::from(self.tx_type.intrinsic_gas());
+        let size_gas = u128::from(GAS_PER_BYTE).saturating_mul(self.size() as u128);
+        let total = intrinsic.saturating_add(size_gas).max(1);
+        // intrinsic <= total, logo nenhum dos produtos transborda
+        let quotient = self.gas_price / total;
+        let remainder = self.gas_price % total;
+        quotient * intrinsic + remainder * intrinsic / total
+    }
+
+    /// Taxa maxima que a transacao pode custar
+    #[must_use]
+    pub fn max_fee(&self) -> u128 {
+        u128::from(self.gas_limit) * self.gas_price
+    }
+}
+
+/// Resultado da execucao de uma transacao
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxStatus {
+    /// Executada com sucesso
+    Success,
+    /// Incluida e cobrada, mas sem efeito
+    Failed(String),
+}
+
+/// Evento emitido pela execucao de uma transacao
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxEvent {
+    /// Stake proprio adicionado a um validador
+    Staked {
+        /// Validador que recebeu o stake
+        validator: Hash256,
+        /// Valor adicionado
+        amount: u128,
+    },
+    /// Stake proprio retirado de um validador
+    Unstaked {
+        /// Validador que retirou o stake
+        validator: Hash256,
+        /// Valor retirado
+        amount: u128,
+        /// Epoca a partir da qual o valor fica disponivel
+        unlock_epoch: u64,
+    },
+    /// Valor delegado a um validador
+    Delegated {
+        /// Conta que delegou
+        delegator: Hash256,
+        /// Validador que recebeu a delegacao
+        validator: Hash256,
+        /// Valor delegado
+        amount: u128,
+    },
+    /// Delegacao retirada de um validador
+    Undelegated {
+        /// Conta que retirou a delegacao
+        delegator: Hash256,
+        /// Validador que tinha a delegacao
+        validator: Hash256,
+        /// Valor retirado
+        amount: u128,
+    },
+    /// wBND emitido por um lock comprovado no Bond
+    WrappedMinted {
+        /// Transacao de lock no Bond
+        bond_txid: Hash256,
+        /// Conta que recebeu o wBND
+        recipient: Hash256,
+        /// Valor emitido
+        amount: u128,
+    },
+    /// wBND queimado para saque no Bond
+    WrappedBurned {
+        /// Conta que queimou o wBND
+        from: Hash256,
+        /// Valor a liberar no Bond
+        amount: u128,
+        /// Script do Bond que recebe o saque
+        bond_script: Vec<u8>,
+    },
+}
+
+/// Recibo de execucao de uma transacao
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxReceipt {
+    /// Hash da transacao executada
+    pub tx_hash: Hash256,
+    /// Resultado da execucao
+    pub status: TxStatus,
+    /// Gas consumido
+    pub gas_used: u64,
+    /// Taxa cobrada (`gas_used * gas_price`)
+    pub fee: u128,
+    /// Eventos emitidos; vazio quando a execucao falha
+    #[serde(default)]
+    pub events: Vec<TxEvent>,
+}
+
+impl TxReceipt {
+    /// Verifica se a execucao teve sucesso
+    #[must_use]
+    pub const fn is_success(&self) -> bool {
+        matches!(self.status, TxStatus::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer() -> AevumTransaction {
+        transfer_from(Hash256::keccak256(b"from"))
+    }
+
+    fn transfer_from(from: Hash256) -> AevumTransaction {
+        AevumTransaction::new(
+            from,
+            Hash256::keccak256(b"to"),
+            100,
+            0,
+            GAS_TRANSFER,
+            1,
+            AevumTransactionType::Transfer,
+        )
+    }
+
+    #[test]
+    fn test_sign_uses_mldsa44() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let mut tx = transfer();
+        tx.sign(&keypair).unwrap();
+
+        let signature = tx.signature.as_ref().unwrap();
+        assert_eq!(signature.algorithm(), SignatureAlgorithm::MLDSA44);
+        assert!(verify_transaction_signature(&tx.hash().unwrap(), signature).unwrap());
+
+        // Chaves do Bond (ML-DSA-65) nao assinam transacoes Aevum
+        let bond_keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
+        let mut tx = transfer();
+        assert!(tx.sign(&bond_keypair).is_err());
+        assert!(tx.signature.is_none());
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let sender = utils::address_from_key(&keypair.public_key);
+        let mut tx = transfer_from(sender);
+
+        assert!(!tx.verify_signature(&keypair.public_key).unwrap());
+        tx.sign(&keypair).unwrap();
+        assert!(tx.verify_signature(&keypair.public_key).unwrap());
+
+        // Campo alterado depois da assinatura
+        let mut mutated = tx.clone();
+        mutated.value += 1;
+        assert!(!mutated.verify_signature(&keypair.public_key).unwrap());
+
+        // Chave que nao corresponde ao remetente
+        let other = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        assert!(!tx.verify_signature(&other.public_key).unwrap());
+        let mut foreign = transfer();
+        foreign.sign(&keypair).unwrap();
+        assert!(!foreign.verify_signature(&keypair.public_key).unwrap());
+    }
+
+    #[test]
+    fn test_verify_sender_rejects_spoofed_from() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let sender = utils::address_from_key(&keypair.public_key);
+
+        let mut tx = transfer_from(sender);
+        assert!(tx.verify_sender().is_err());
+        tx.sign(&keypair).unwrap();
+        tx.verify_sender().unwrap();
+
+        // Assinada com a propria chave, mas declarando a conta da vitima
+        let victim = utils::address_from_key(
+            &KeyPair::generate(SignatureAlgorithm::MLDSA44)
+                .unwrap()
+                .public_key,
+        );
+        let mut spoofed = transfer_from(victim);
+        spoofed.sign(&keypair).unwrap();
+        assert!(matches!(
+            spoofed.verify_sender(),
+            Err(BlockchainError::InvalidTransaction(_))
+        ));
+
+        // Trocar o `from` depois de assinar tambem invalida
+        let mut redirected = tx.clone();
+        redirected.from = victim;
+        assert!(redirected.verify_sender().is_err());
+    }
+
+    #[test]
+    fn test_canonical_bytes_independent_of_json_layout() {
+        let vote = AevumTransaction::new(
+            Hash256::keccak256(b"from"),
+            Hash256::zero(),
+            0,
+            4,
+            GAS_GOVERNANCE,
+            7,
+            AevumTransactionType::Vote {
+                proposal_id: 2,
+                support: true,
+                weight: 500,
+            },
+        );
+        assert_eq!(vote.to_canonical_bytes(), vote.clone().to_canonical_bytes());
+
+        // JSON com campos em outra ordem desserializa para o mesmo hash
+        let json = serde_json::to_value(&vote).unwrap();
+        let fields: Vec<String> = json
+            .as_object()
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|(key, value)| format!("\n  \"{key}\": {value}"))
+            .collect();
+        let text = format!("{{{}\n}}", fields.join(","));
+        assert!(text.find("\"tx_type\"") < text.find("\"from\""));
+        let parsed: AevumTransaction = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed.hash().unwrap(), vote.hash().unwrap());
+
+        // Textos prefixados nao se confundem ao mudar a fronteira entre eles
+        let proposal = |title: &str, description: &str| {
+            AevumTransaction::new(
+                Hash256::zero(),
+                Hash256::zero(),
+                0,
+                0,
+                GAS_GOVERNANCE,
+                1,
+                AevumTransactionType::CreateProposal {
+                    title: title.to_string(),
+                    description: description.to_string(),
+                    voting_period: 10,
+                    action: None,
+                },
+            )
+        };
+        assert_ne!(
+            proposal("ab", "c").hash().unwrap(),
+            proposal("a", "bc").hash().unwrap()
+        );
+
+        // A assinatura nao entra na forma canonica
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let mut signed = vote.clone();
+        signed.sign(&keypair).unwrap();
+        assert_eq!(signed.to_canonical_bytes(), vote.to_canonical_bytes());
+    }
+
+    #[test]
+    fn test_effective_gas_price_accounts_for_size() {
+        let short = AevumTransaction::new(
+            Hash256::keccak256(b"from"),
+            Hash256::zero(),
+            0,
+            0,
+            GAS_GOVERNANCE,
+            100,
+            AevumTransactionType::CreateProposal {
+                title: "a".to_string(),
+                description: String::new(),
+                voting_period: 10,
+                action: None,
+            },
+        );
+        let long = AevumTransaction {
+            tx_type: AevumTransactionType::CreateProposal {
+                title: "a".to_string(),
+                description: "x".repeat(10_000),
+                voting_period: 10,
+                action: None,
+            },
+            ..short.clone()
+        };
+
+        // Mesmo preco nominal, mas a transacao maior vale menos por gas
+        assert!(long.size() > short.size());
+        assert!(short.effective_gas_price() > long.effective_gas_price());
+        assert!(short.effective_gas_price() < short.gas_price);
+
+        // Preco nominal maior compensa o tamanho
+        let generous = AevumTransaction {
+            gas_price: 1_000,
+            ..long
+        };
+        assert!(generous.effective_gas_price() > short.effective_gas_price());
+    }
+
+    #[test]
+    fn test_effective_gas_price_does_not_overflow() {
+        let tx = AevumTransaction::new(
+            Hash256::keccak256(b"from"),
+            Hash256::keccak256(b"to"),
+            1,
+            0,
+            GAS_TRANSFER,
+            u128::MAX,
+            AevumTransactionType::Transfer,
+        );
+        let price = tx.effective_gas_price();
+        assert!(price > 0);
+        assert!(price < u128::MAX);
+    }
+}
@@ -1,8 +1,9 @@
-use crate::transaction::Transaction;
-use crate::utxo::{Utxo, UtxoSet};
+use crate::blockchain::{GenesisConfig, NetworkParams};
+use crate::transaction::{Transaction, TxOutput, WITNESS_SCALE_FACTOR};
+use crate::utxo::UtxoSet;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use shared::{BlockchainError, Hash256, Result};
+use shared::{compact_to_target, BlockchainError, Hash256, Result, MAX_TARGET_BITS};
 
 /// Cabeçalho do bloco
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -15,8 +16,8 @@ pub struct BlockHeader {
     pub merkle_root: Hash256,
     /// Timestamp do bloco
     pub timestamp: DateTime<Utc>,
-    /// Dificuldade alvo (número de zeros iniciais requeridos)
-    pub difficulty: u32,
+    /// Alvo de dificuldade no formato compacto (nBits)
+    pub bits: u32,
     /// Nonce para mineração
     pub nonce: u64,
 }
@@ -29,7 +30,7 @@ impl BlockHeader {
         previous_hash: Hash256,
         merkle_root: Hash256,
         timestamp: DateTime<Utc>,
-        difficulty: u32,
+        bits: u32,
         nonce: u64,
     ) -> Self {
         Self {
@@ -37,7 +38,7 @@ impl BlockHeader {
             previous_hash,
             merkle_root,
             timestamp,
-            difficulty,
+            bits,
             nonce,
         }
     }
@@ -53,14 +54,14 @@ impl BlockHeader {
         Ok(Hash256::keccak256(&serialized))
     }
 
-    /// Verifica se o hash do cabeçalho atende à dificuldade
+    /// Verifica se o hash do cabeçalho atende ao alvo de `bits`
     ///
     /// # Errors
     ///
     /// Retorna erro se o cálculo do hash falhar
-    pub fn meets_difficulty(&self) -> Result<bool> {
+    pub fn meets_target(&self) -> Result<bool> {
         let hash = self.hash()?;
-        Ok(hash.meets_difficulty(self.difficulty))
+        Ok(hash.meets_target(&compact_to_target(self.bits)))
     }
 }
 
@@ -74,6 +75,13 @@ pub struct Block {
 }
 
 impl Block {
+    /// Estimativa do tamanho do cabeçalho serializado
+    pub const HEADER_SIZE: usize = 200;
+
+    /// Peso máximo do bloco: 1MB de dados comuns, ou mais quando parte do
+    /// bloco são assinaturas
+    pub const MAX_WEIGHT: usize = 4_000_000;
+
     /// Cria um novo bloco
     #[must_use]
     pub const fn new(header: BlockHeader, transactions: Vec<Transaction>) -> Self {
@@ -85,26 +93,60 @@ impl Block {
 
     /// Cria o bloco gênese (primeiro bloco da blockchain)
     ///
+    /// O gênese é determinístico: usa o timestamp fixo dos parâmetros e
+    /// inclui o hash canônico de `NetworkParams` no `script_sig` da coinbase,
+    /// logo após a altura. Redes com parâmetros diferentes produzem gêneses
+    /// diferentes mesmo com o mesmo script.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o timestamp for inválido ou se a mineração ou
+    /// cálculo do merkle root falharem
+    pub fn genesis(params: &NetworkParams, genesis_script: Vec<u8>) -> Result<Self> {
+        Self::genesis_with(params, &GenesisConfig::single(params, genesis_script))
+    }
+
+    /// Cria o bloco gênese a partir de uma alocação inicial de saldos
+    ///
+    /// Cada alocação vira uma saída da coinbase, na ordem da configuração,
+    /// e o timestamp do bloco é o da configuração.
+    ///
     /// # Errors
     ///
-    /// Retorna erro se a mineração ou cálculo do merkle root falharem
-    pub fn genesis(genesis_reward: u64, genesis_script: Vec<u8>) -> Result<Self> {
-        let coinbase = Transaction::coinbase(0, genesis_reward, genesis_script);
+    /// Retorna erro se a configuração for inválida (veja
+    /// [`GenesisConfig::validate`]) ou se o timestamp for inválido
+    pub fn genesis_with(params: &NetworkParams, config: &GenesisConfig) -> Result<Self> {
+        config.validate()?;
+
+        let mut coinbase = Transaction::coinbase(0, 0, Vec::new());
+        coinbase.outputs = config
+            .allocations
+            .iter()
+            .map(|(script, amount)| TxOutput::new(*amount, script.clone()))
+            .collect();
+        coinbase.inputs[0]
+            .script_sig
+            .extend_from_slice(params.hash()?.as_bytes());
         let merkle_root = calculate_merkle_root(std::slice::from_ref(&coinbase))?;
 
+        let timestamp = DateTime::from_timestamp(config.timestamp, 0).ok_or_else(|| {
+            BlockchainError::InvalidBlock("Invalid genesis timestamp".to_string())
+        })?;
+
         let mut header = BlockHeader::new(
             1,
             Hash256::zero(), // Bloco gênese não tem antecessor
             merkle_root,
-            Utc::now(),
-            1, // Dificuldade muito baixa para gênese
+            timestamp,
+            MAX_TARGET_BITS, // Alvo mais fácil para o gênese
             0,
         );
 
         // Minerar o bloco gênese
+        let target = compact_to_target(header.bits);
         loop {
             let hash = header.hash()?;
-            if hash.meets_difficulty(header.difficulty) {
+            if hash.meets_target(&target) {
                 break;
             }
             header.nonce += 1;
@@ -155,6 +197,31 @@ impl Block {
         Ok(u64::from_le_bytes(height_bytes))
     }
 
+    /// Prova de inclusão da transação `tx_index` na merkle root do bloco
+    ///
+    /// Retorna os hashes irmãos da folha até a raiz; o flag indica se o
+    /// irmão fica à direita. Em níveis com número ímpar de nós o último
+    /// hash é o próprio irmão, como em `calculate_merkle_root`.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o índice não existir ou se o hash de alguma
+    /// transação falhar
+    pub fn merkle_proof(&self, tx_index: usize) -> Result<Vec<(Hash256, bool)>> {
+        if tx_index >= self.transactions.len() {
+            return Err(BlockchainError::InvalidTransaction(format!(
+                "Transaction index {tx_index} out of range"
+            )));
+        }
+
+        let hashes: Vec<Hash256> = self
+            .transactions
+            .iter()
+            .map(Transaction::hash)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(merkle_path(hashes, tx_index))
+    }
+
     /// Validação básica do bloco
     ///
     /// # Errors
@@ -198,7 +265,7 @@ impl Block {
         }
 
         // Verificar se atende à dificuldade
-        if !self.header.meets_difficulty()? {
+        if !self.header.meets_target()? {
             return Err(BlockchainError::InsufficientDifficulty);
         }
 
@@ -211,58 +278,33 @@ impl Block {
     ///
     /// Retorna erro se alguma UTXO requerida não for encontrada ou se ocorrer erro na aplicação
     pub fn apply_to_utxo_set(&self, utxo_set: &mut UtxoSet) -> Result<()> {
-        let block_height = self.height()?;
-
-        for tx in &self.transactions {
-            let txid = tx.hash()?;
-
-            // Remover UTXOs gastos
-            if !tx.is_coinbase() {
-                for input in &tx.inputs {
-                    if !utxo_set.contains(&input.previous_output) {
-                        return Err(BlockchainError::UtxoNotFound);
-                    }
-                    utxo_set.remove_utxo(&input.previous_output);
-                }
-            }
-
-            // Criar novos UTXOs
-            for (output_index, output) in tx.outputs.iter().enumerate() {
-                let utxo = Utxo::new(
-                    txid,
-                    output_index.try_into().map_err(|_| {
-                        BlockchainError::InvalidBlock("Output index overflow".to_string())
-                    })?,
-                    output.value,
-                    output.script_pubkey.clone(),
-                    block_height,
-                );
-                utxo_set.add_utxo(utxo);
-            }
-        }
-
-        Ok(())
+        utxo_set.apply_block(self).map(drop)
     }
 
     /// Calcula o tamanho do bloco em bytes
+    ///
+    /// As transações entram pelo tamanho real da forma canônica
+    /// (`Transaction::size`), assinaturas incluídas.
     #[must_use]
     pub fn size(&self) -> usize {
-        // Estimativa simplificada
-        const HEADER_SIZE: usize = 200; // Estimativa para cabeçalho serializado
-        let transactions_size: usize = self
-            .transactions
-            .iter()
-            .map(Transaction::estimated_size)
-            .sum();
+        let transactions_size: usize = self.transactions.iter().map(Transaction::size).sum();
+
+        Self::HEADER_SIZE + transactions_size
+    }
+
+    /// Peso do bloco: o cabeçalho conta como dado comum e cada transação
+    /// pelo seu `Transaction::weight`
+    #[must_use]
+    pub fn weight(&self) -> usize {
+        let transactions_weight: usize = self.transactions.iter().map(Transaction::weight).sum();
 
-        HEADER_SIZE + transactions_size
+        Self::HEADER_SIZE * WITNESS_SCALE_FACTOR + transactions_weight
     }
 
-    /// Verifica se o bloco excede o tamanho máximo (4MB)
+    /// Verifica se o bloco excede o peso máximo (`MAX_WEIGHT`)
     #[must_use]
     pub fn exceeds_max_size(&self) -> bool {
-        const MAX_BLOCK_SIZE: usize = 4_000_000; // 4MB
-        self.size() > MAX_BLOCK_SIZE
+        self.weight() > Self::MAX_WEIGHT
     }
 }
 
@@ -276,14 +318,21 @@ pub fn calculate_merkle_root(transactions: &[Transaction]) -> Result<Hash256> {
         return Ok(Hash256::zero());
     }
 
-    let mut hashes: Vec<Hash256> = transactions
+    let hashes: Vec<Hash256> = transactions
         .iter()
         .map(Transaction::hash)
         .collect::<Result<Vec<_>>>()?;
+    Ok(merkle_root_of(hashes))
+}
 
-    // Se há apenas uma transação, retorna seu hash
-    if hashes.len() == 1 {
-        return Ok(hashes[0]);
+/// Merkle root de uma lista de folhas
+///
+/// Uma lista vazia tem root zero e uma única folha é a própria root. Em
+/// níveis com número ímpar de nós o último é combinado consigo mesmo.
+#[must_use]
+pub fn merkle_root_of(mut hashes: Vec<Hash256>) -> Hash256 {
+    if hashes.is_empty() {
+        return Hash256::zero();
     }
 
     // Construir árvore merkle
@@ -291,25 +340,67 @@ pub fn calculate_merkle_root(transactions: &[Transaction]) -> Result<Hash256> {
         let mut next_level = Vec::new();
 
         for chunk in hashes.chunks(2) {
-            let mut data = Vec::new();
-            data.extend_from_slice(chunk[0].as_bytes());
-
-            if chunk.len() == 2 {
-                // Combinar dois hashes
-                data.extend_from_slice(chunk[1].as_bytes());
-            } else {
-                // Hash ímpar - combina consigo mesmo
-                data.extend_from_slice(chunk[0].as_bytes());
-            }
-
-            let combined = Hash256::keccak256(&data);
-            next_level.push(combined);
+            // Hash ímpar - combina consigo mesmo
+            let right = chunk.get(1).unwrap_or(&chunk[0]);
+            next_level.push(merkle_parent(&chunk[0], right));
         }
 
         hashes = next_level;
     }
 
-    Ok(hashes[0])
+    hashes[0]
+}
+
+/// Caminho da folha `index` até a merkle root de `hashes`
+///
+/// Retorna os hashes irmãos da folha até a raiz; o flag indica se o
+/// irmão fica à direita. Verificável com `verify_merkle_proof`.
+#[must_use]
+pub fn merkle_path(mut hashes: Vec<Hash256>, mut index: usize) -> Vec<(Hash256, bool)> {
+    let mut proof = Vec::new();
+    while hashes.len() > 1 && index < hashes.len() {
+        let sibling_on_right = index.is_multiple_of(2);
+        let sibling = if sibling_on_right {
+            hashes.get(index + 1).unwrap_or(&hashes[index])
+        } else {
+            &hashes[index - 1]
+        };
+        proof.push((*sibling, sibling_on_right));
+
+        hashes = hashes
+            .chunks(2)
+            .map(|chunk| merkle_parent(&chunk[0], chunk.get(1).unwrap_or(&chunk[0])))
+            .collect();
+        index /= 2;
+    }
+    proof
+}
+
+/// Verifica uma prova gerada por `Block::merkle_proof`
+#[must_use]
+pub fn verify_merkle_proof(
+    tx_hash: &Hash256,
+    proof: &[(Hash256, bool)],
+    merkle_root: &Hash256,
+) -> bool {
+    let computed = proof
+        .iter()
+        .fold(*tx_hash, |current, (sibling, sibling_on_right)| {
+            if *sibling_on_right {
+                merkle_parent(&current, sibling)
+            } else {
+                merkle_parent(sibling, &current)
+            }
+        });
+    computed == *merkle_root
+}
+
+/// Combina dois nós da árvore merkle
+fn merkle_parent(left: &Hash256, right: &Hash256) -> Hash256 {
+    let mut data = Vec::with_capacity(64);
+    data.extend_from_slice(left.as_bytes());
+    data.extend_from_slice(right.as_bytes());
+    Hash256::keccak256(&data)
 }
 
 #[cfg(test)]
@@ -318,7 +409,7 @@ mod tests {
 
     #[test]
     fn test_genesis_block_creation() {
-        let genesis = Block::genesis(5000, vec![1, 2, 3]).unwrap();
+        let genesis = Block::genesis(&NetworkParams::default(), vec![1, 2, 3]).unwrap();
 
         assert_eq!(genesis.transactions.len(), 1);
         assert!(genesis.transactions[0].is_coinbase());
@@ -332,10 +423,10 @@ mod tests {
                 let hash = genesis.hash().unwrap();
                 println!("Hash do bloco: {hash}");
                 println!("Zeros iniciais: {}", hash.leading_zeros());
-                println!("Dificuldade: {}", genesis.header.difficulty);
+                println!("Alvo (nBits): {:#010x}", genesis.header.bits);
                 println!(
-                    "Atende dificuldade: {}",
-                    hash.meets_difficulty(genesis.header.difficulty)
+                    "Atende alvo: {}",
+                    hash.meets_target(&compact_to_target(genesis.header.bits))
                 );
                 panic!("Validação do bloco gênese falhou");
             }
@@ -344,7 +435,7 @@ mod tests {
 
     #[test]
     fn test_block_hash() {
-        let genesis = Block::genesis(5000, vec![1, 2, 3]).unwrap();
+        let genesis = Block::genesis(&NetworkParams::default(), vec![1, 2, 3]).unwrap();
         let hash = genesis.hash().unwrap();
 
         // Hash deve ser determinístico
@@ -352,6 +443,29 @@ mod tests {
         assert_eq!(hash, hash2);
     }
 
+    #[test]
+    fn test_genesis_commits_to_params() {
+        let params = NetworkParams::default();
+        let genesis = Block::genesis(&params, vec![1, 2, 3]).unwrap();
+
+        // Mesmos parâmetros geram sempre o mesmo gênese
+        let again = Block::genesis(&params, vec![1, 2, 3]).unwrap();
+        assert_eq!(genesis.hash().unwrap(), again.hash().unwrap());
+        assert_eq!(
+            genesis.header.timestamp.timestamp(),
+            params.genesis_timestamp
+        );
+
+        // Qualquer parâmetro diferente muda o compromisso
+        let other = NetworkParams {
+            max_block_size: 1_000_000,
+            ..NetworkParams::default()
+        };
+        assert_ne!(params.hash().unwrap(), other.hash().unwrap());
+        let other_genesis = Block::genesis(&other, vec![1, 2, 3]).unwrap();
+        assert_ne!(genesis.hash().unwrap(), other_genesis.hash().unwrap());
+    }
+
     #[test]
     fn test_merkle_root_calculation() {
         let tx1 = Transaction::coinbase(0, 5000, vec![1, 2, 3]);
@@ -364,10 +478,52 @@ mod tests {
         assert_ne!(single_tx_root, Hash256::zero());
     }
 
+    #[test]
+    fn test_merkle_proof_for_every_transaction() {
+        // Cinco transações forçam duplicação em dois níveis da árvore
+        for count in 1..=5 {
+            let transactions: Vec<Transaction> = (0..count)
+                .map(|i| Transaction::coinbase(i, 5000, vec![1, 2, 3]))
+                .collect();
+            let merkle_root = calculate_merkle_root(&transactions).unwrap();
+            let header = BlockHeader::new(1, Hash256::zero(), merkle_root, Utc::now(), 1, 0);
+            let block = Block::new(header, transactions);
+
+            for (index, tx) in block.transactions.iter().enumerate() {
+                let proof = block.merkle_proof(index).unwrap();
+                assert!(verify_merkle_proof(
+                    &tx.hash().unwrap(),
+                    &proof,
+                    &block.header.merkle_root
+                ));
+            }
+            assert!(block.merkle_proof(block.transactions.len()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_merkle_proof_rejects_wrong_hash() {
+        let transactions: Vec<Transaction> = (0..3)
+            .map(|i| Transaction::coinbase(i, 5000, vec![1, 2, 3]))
+            .collect();
+        let merkle_root = calculate_merkle_root(&transactions).unwrap();
+        let header = BlockHeader::new(1, Hash256::zero(), merkle_root, Utc::now(), 1, 0);
+        let block = Block::new(header, transactions);
+
+        let proof = block.merkle_proof(1).unwrap();
+        let wrong_hash = Hash256::keccak256(b"not in block");
+        assert!(!verify_merkle_proof(&wrong_hash, &proof, &merkle_root));
+
+        // A prova de outra posição também não serve
+        let other_proof = block.merkle_proof(0).unwrap();
+        let tx_hash = block.transactions[1].hash().unwrap();
+        assert!(!verify_merkle_proof(&tx_hash, &other_proof, &merkle_root));
+    }
+
     #[test]
     fn test_utxo_set_application() {
         let mut utxo_set = UtxoSet::new();
-        let genesis = Block::genesis(5000, vec![1, 2, 3]).unwrap();
+        let genesis = Block::genesis(&NetworkParams::default(), vec![1, 2, 3]).unwrap();
 
         // Aplicar bloco gênese
         genesis.apply_to_utxo_set(&mut utxo_set).unwrap();
@@ -378,7 +534,7 @@ mod tests {
 
     #[test]
     fn test_block_size_limits() {
-        let genesis = Block::genesis(5000, vec![1, 2, 3]).unwrap();
+        let genesis = Block::genesis(&NetworkParams::default(), vec![1, 2, 3]).unwrap();
 
         // Bloco gênese não deve exceder tamanho máximo
         assert!(!genesis.exceeds_max_size());
@@ -387,4 +543,50 @@ mod tests {
         assert!(genesis.size() > 0);
         assert!(genesis.size() < 1000); // Deve ser pequeno para um bloco simples
     }
+
+    #[test]
+    fn test_size_and_weight_account_for_signatures() {
+        use crate::script::{p2pkh_script_pubkey, p2pkh_script_sig};
+        use crate::transaction::TxInput;
+        use crate::utxo::OutPoint;
+        use shared::{sign_transaction_hash, KeyPair, SignatureAlgorithm};
+
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
+        let script_pubkey = p2pkh_script_pubkey(&keypair.public_key);
+        let inputs = (0..3)
+            .map(|vout| TxInput::new(OutPoint::new(Hash256::keccak256(b"prev"), vout), vec![], 0))
+            .collect();
+        let mut tx = Transaction::new(1, inputs, vec![TxOutput::new(1_000, script_pubkey)], 0);
+        for index in 0..tx.inputs.len() {
+            let sighash = tx.sighash(index, &[]);
+            let signature = sign_transaction_hash(&sighash, &keypair).unwrap();
+            tx.inputs[index].script_sig = p2pkh_script_sig(&signature);
+        }
+
+        let coinbase = Transaction::coinbase(1, 5000, vec![1, 2, 3]);
+        let block = Block::new(
+            BlockHeader::new(1, Hash256::zero(), Hash256::zero(), Utc::now(), 0, 0),
+            vec![coinbase.clone(), tx.clone()],
+        );
+
+        // O tamanho real segue a forma canônica, assinaturas incluídas
+        assert_eq!(tx.size(), tx.to_canonical_bytes().len());
+        assert_eq!(coinbase.size(), coinbase.to_canonical_bytes().len());
+        assert_eq!(
+            block.size(),
+            Block::HEADER_SIZE + coinbase.size() + tx.size()
+        );
+
+        // No peso, só os bytes fora das assinaturas são multiplicados
+        let witness = tx.witness_size();
+        assert!(witness > 3 * 5_000);
+        assert_eq!(
+            tx.weight(),
+            (tx.size() - witness) * WITNESS_SCALE_FACTOR + witness
+        );
+        assert_eq!(coinbase.witness_size(), 0);
+        assert_eq!(coinbase.weight(), coinbase.size() * WITNESS_SCALE_FACTOR);
+        assert!(block.weight() < block.size() * 2);
+        assert!(!block.exceeds_max_size());
+    }
 }
@@ -1,13 +1,18 @@
-use crate::block::Block;
-use crate::mining::{DifficultyAdjuster, Miner, MiningResult};
-use crate::transaction::Transaction;
-use crate::utxo::UtxoSet;
+use crate::block::{Block, BlockHeader};
+use crate::fee_estimator::FeeEstimator;
+use crate::mempool::BondMempool;
+use crate::mining::{DifficultyAdjuster, Miner, MiningHandle, MiningResult};
+use crate::storage::{MemoryStore, Store};
+use crate::transaction::{Transaction, WITNESS_SCALE_FACTOR};
+use crate::utxo::{BlockUndo, OutPoint, Utxo, UtxoSet};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use shared::{BlockchainError, Hash256, Result};
-use std::collections::HashMap;
+use shared::{target_work, BlockchainError, ConsensusEngine, Hash256, NetworkType, Result};
+use std::collections::{HashMap, HashSet};
+use std::io::{ErrorKind, Read, Write};
 
 /// Estado da blockchain Bond
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug)]
 pub struct Blockchain {
     /// Cadeia de blocos
     blocks: Vec<Block>,
@@ -17,6 +22,83 @@ pub struct Blockchain {
     block_index: HashMap<Hash256, usize>,
     /// Parâmetros da rede
     network_params: NetworkParams,
+    /// Armazenamento persistente de blocos e UTXOs
+    store: Box<dyn Store>,
+    /// Blocos fora da cadeia ativa, indexados pelo hash
+    side_blocks: HashMap<Hash256, Block>,
+    /// Blocos de `side_blocks` que falharam ao conectar numa reorganização
+    invalid_blocks: HashSet<Hash256>,
+    /// Maior número de blocos desconectados em uma reorganização
+    reorg_depth: u64,
+    /// Última reorganização ainda não consumida
+    last_reorg: Option<ChainReorg>,
+    /// Diferença (segundos) entre o horário da rede e o relógio local
+    time_offset: i64,
+    /// Taxas de entrada dos blocos recentes, para sugerir taxas
+    fee_estimator: FeeEstimator,
+    /// Hash da transação para (índice do bloco, posição no bloco) na cadeia
+    /// ativa; `None` enquanto o índice estiver desativado
+    tx_index: Option<HashMap<Hash256, (usize, usize)>>,
+}
+
+/// Situação de uma ponta de cadeia conhecida
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChainTipStatus {
+    /// Ponta da cadeia ativa
+    Active,
+    /// Ramo válido com menos trabalho que a cadeia ativa
+    ValidFork,
+    /// Ramo com algum bloco que falhou na validação
+    Invalid,
+}
+
+impl std::fmt::Display for ChainTipStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Active => "active",
+            Self::ValidFork => "valid-fork",
+            Self::Invalid => "invalid",
+        })
+    }
+}
+
+/// Ponta de cadeia conhecida, no estilo do `getchaintips` do Bitcoin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainTip {
+    /// Hash do último bloco do ramo
+    pub hash: Hash256,
+    /// Altura do último bloco do ramo
+    pub height: u64,
+    /// Blocos do ramo fora da cadeia ativa (zero para a ponta ativa)
+    pub branch_len: u64,
+    /// Situação do ramo
+    pub status: ChainTipStatus,
+}
+
+/// Hashes esparsos da cadeia ativa usados para achar o ancestral comum
+/// com um par, mesmo que ele esteja em outro ramo
+///
+/// Do mais recente para o gênese: as `LOCATOR_DENSE_ENTRIES` alturas mais
+/// recentes e depois alturas com espaçamento que dobra a cada passo.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockLocator {
+    /// Hashes em ordem decrescente de altura; o último é o gênese
+    pub hashes: Vec<Hash256>,
+}
+
+/// Alturas consecutivas no início de um `BlockLocator`
+pub const LOCATOR_DENSE_ENTRIES: u64 = 10;
+
+/// Troca da cadeia ativa por um ramo com mais trabalho acumulado
+#[derive(Debug, Clone)]
+pub struct ChainReorg {
+    /// Altura do ancestral comum
+    pub fork_height: u64,
+    /// Blocos removidos da cadeia ativa, em ordem crescente de altura
+    pub disconnected: Vec<Block>,
+    /// Blocos do novo ramo, em ordem crescente de altura
+    pub connected: Vec<Block>,
 }
 
 /// Parâmetros da rede Bond
@@ -32,6 +114,59 @@ pub struct NetworkParams {
     pub difficulty_adjustment_period: u64,
     /// Tamanho máximo do bloco (bytes)
     pub max_block_size: usize,
+    /// Distância máxima (em blocos) acima da ponta aceita para um bloco recebido
+    pub max_future_height: u64,
+    /// Timestamp fixo do bloco gênese (segundos desde a época Unix)
+    pub genesis_timestamp: i64,
+    /// Blocos entre cada halving da recompensa (zero desativa o halving)
+    pub halving_interval: u64,
+    /// Tolerância (segundos) para timestamps à frente do relógio do nó
+    pub max_future_block_time: u64,
+    /// Rede a que os parâmetros pertencem; entra no hash comprometido no
+    /// gênese, então redes diferentes nunca compartilham a cadeia
+    #[serde(default)]
+    pub network: NetworkType,
+    /// Máximo de blocos da cadeia ativa que uma reorganização pode desfazer;
+    /// ramos que divergem mais fundo são rejeitados
+    #[serde(default = "default_max_reorg_depth")]
+    pub max_reorg_depth: u64,
+    /// Valor mínimo (em Elos) de cada saída de uma transação comum
+    #[serde(default = "default_dust_threshold")]
+    pub dust_threshold: u64,
+    /// Tamanho máximo de uma transação (bytes, por `Transaction::size`)
+    #[serde(default = "default_max_tx_size")]
+    pub max_tx_size: usize,
+    /// Peso máximo do bloco (`Block::weight`)
+    #[serde(default = "default_max_block_weight")]
+    pub max_block_weight: usize,
+}
+
+/// Profundidade máxima de reorganização padrão
+pub const DEFAULT_MAX_REORG_DEPTH: u64 = 100;
+
+/// Valor mínimo padrão de uma saída, em Elos
+pub const DEFAULT_DUST_THRESHOLD: u64 = 10;
+
+/// Tamanho máximo padrão de uma transação, em bytes
+pub const DEFAULT_MAX_TX_SIZE: usize = 100_000;
+
+const fn default_max_reorg_depth() -> u64 {
+    DEFAULT_MAX_REORG_DEPTH
+}
+
+const fn default_dust_threshold() -> u64 {
+    DEFAULT_DUST_THRESHOLD
+}
+
+/// Peso máximo padrão de um bloco
+pub const DEFAULT_MAX_BLOCK_WEIGHT: usize = Block::MAX_WEIGHT;
+
+const fn default_max_tx_size() -> usize {
+    DEFAULT_MAX_TX_SIZE
+}
+
+const fn default_max_block_weight() -> usize {
+    DEFAULT_MAX_BLOCK_WEIGHT
 }
 
 impl Default for NetworkParams {
@@ -42,189 +177,1163 @@ impl Default for NetworkParams {
             target_block_time: 600,             // 10 minutos
             difficulty_adjustment_period: 2016, // ~2 semanas
             max_block_size: 4_000_000,          // 4MB
+            max_future_height: 100,
+            genesis_timestamp: 1_735_689_600, // 2025-01-01 00:00:00 UTC
+            halving_interval: 210_000,        // ~4 anos com blocos de 10 minutos
+            max_future_block_time: 2 * 60 * 60, // 2 horas
+            network: NetworkType::Devnet,
+            max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
+            dust_threshold: DEFAULT_DUST_THRESHOLD,
+            max_tx_size: DEFAULT_MAX_TX_SIZE,
+            max_block_weight: DEFAULT_MAX_BLOCK_WEIGHT,
+        }
+    }
+}
+
+/// Alocação inicial de saldos gravada no bloco gênese
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisConfig {
+    /// Pares (script, valor em Elos), um por saída da coinbase do gênese
+    pub allocations: Vec<(Vec<u8>, u64)>,
+    /// Timestamp do gênese (segundos desde a época Unix)
+    pub timestamp: i64,
+    /// Total que as alocações precisam somar
+    pub premine: u64,
+}
+
+impl GenesisConfig {
+    /// Gênese com uma única alocação de `initial_reward` para `script`
+    ///
+    /// É o gênese usado por [`Blockchain::new`].
+    #[must_use]
+    pub fn single(params: &NetworkParams, script: Vec<u8>) -> Self {
+        Self {
+            allocations: vec![(script, params.initial_reward)],
+            timestamp: params.genesis_timestamp,
+            premine: params.initial_reward,
+        }
+    }
+
+    /// Soma das alocações, ou `None` em caso de overflow
+    #[must_use]
+    pub fn total_allocation(&self) -> Option<u64> {
+        self.allocations
+            .iter()
+            .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+    }
+
+    /// Valida a configuração
+    ///
+    /// # Errors
+    ///
+    /// Retorna `InvalidBlock` se não houver alocações, se alguma for zero ou
+    /// se o total diferir de `premine`
+    pub fn validate(&self) -> Result<()> {
+        if self.allocations.is_empty() {
+            return Err(BlockchainError::InvalidBlock(
+                "Genesis has no allocations".to_string(),
+            ));
+        }
+        if self.allocations.iter().any(|(_, amount)| *amount == 0) {
+            return Err(BlockchainError::InvalidBlock(
+                "Genesis allocation with zero amount".to_string(),
+            ));
+        }
+        match self.total_allocation() {
+            Some(total) if total == self.premine => Ok(()),
+            total => Err(BlockchainError::InvalidBlock(format!(
+                "Genesis allocations total {total:?}, expected premine of {}",
+                self.premine
+            ))),
+        }
+    }
+}
+
+impl NetworkParams {
+    /// Parâmetros da rede `network`
+    ///
+    /// A Devnet usa os valores padrão. Mainnet e Testnet partem de
+    /// dificuldades reais; a Regtest minera com o alvo mínimo, sem reajuste
+    /// (período de um bloco) e com halving rápido, para testes locais.
+    #[must_use]
+    pub fn for_network(network: NetworkType) -> Self {
+        let defaults = Self::default();
+        match network {
+            NetworkType::Mainnet => Self {
+                initial_difficulty: 20,
+                network,
+                ..defaults
+            },
+            NetworkType::Testnet => Self {
+                initial_difficulty: 12,
+                target_block_time: 120,            // 2 minutos
+                difficulty_adjustment_period: 720, // ~1 dia
+                genesis_timestamp: 1_735_776_000,  // 2025-01-02 00:00:00 UTC
+                network,
+                ..defaults
+            },
+            NetworkType::Devnet => defaults,
+            NetworkType::Regtest => Self {
+                initial_difficulty: 1,
+                target_block_time: 1,
+                difficulty_adjustment_period: 1,
+                halving_interval: 150,
+                max_future_height: 10_000,
+                network,
+                ..defaults
+            },
         }
     }
+
+    /// Magic bytes da rede destes parâmetros
+    #[must_use]
+    pub const fn magic_bytes(&self) -> [u8; 4] {
+        self.network.magic_bytes()
+    }
+
+    /// Hash canônico dos parâmetros, comprometido no bloco gênese
+    ///
+    /// A serialização JSON de uma struct segue a ordem de declaração dos
+    /// campos e `NetworkParams` não contém mapas, então o resultado é
+    /// determinístico entre nós.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a serialização falhar
+    pub fn hash(&self) -> Result<Hash256> {
+        let serialized = serde_json::to_vec(self)
+            .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+        Ok(Hash256::keccak256(&serialized))
+    }
 }
 
 impl Blockchain {
-    /// Cria uma nova blockchain com bloco gênese
+    /// Número de blocos recentes usados no cálculo do median-time-past
+    pub const MEDIAN_TIME_SPAN: usize = 11;
+
+    /// Máximo de blocos mantidos fora da cadeia ativa
+    pub const MAX_SIDE_BLOCKS: usize = 1_000;
+
+    /// Bytes reservados para o script de recompensa ao montar um bloco
+    pub const COINBASE_SCRIPT_RESERVE: usize = 100;
+
+    /// Cria uma nova blockchain com bloco gênese, mantida apenas em memória
     ///
     /// # Errors
     ///
     /// Retorna erro se não conseguir criar o bloco gênese ou aplicá-lo ao conjunto UTXO
     pub fn new(network_params: NetworkParams, genesis_script: Vec<u8>) -> Result<Self> {
-        let genesis_block = Block::genesis(network_params.initial_reward, genesis_script)?;
-        let genesis_hash = genesis_block.hash()?;
+        Self::with_store(network_params, genesis_script, Box::new(MemoryStore::new()))
+    }
 
-        let mut utxo_set = UtxoSet::new();
-        genesis_block.apply_to_utxo_set(&mut utxo_set)?;
+    /// Abre uma blockchain sobre um store persistente
+    ///
+    /// Se o store já contiver blocos, a cadeia e os UTXOs são carregados dele;
+    /// caso contrário, o bloco gênese é criado e gravado.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o store não puder ser lido/gravado ou se o gênese falhar
+    pub fn with_store(
+        network_params: NetworkParams,
+        genesis_script: Vec<u8>,
+        store: Box<dyn Store>,
+    ) -> Result<Self> {
+        let genesis = GenesisConfig::single(&network_params, genesis_script);
+        Self::with_genesis(network_params, &genesis, store)
+    }
+
+    /// Abre uma blockchain cujo gênese distribui os saldos de `genesis`
+    ///
+    /// Se o store já contiver blocos, `genesis` é ignorado e a cadeia
+    /// gravada é carregada.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a configuração do gênese for inválida ou se o store
+    /// não puder ser lido/gravado
+    pub fn with_genesis(
+        network_params: NetworkParams,
+        genesis: &GenesisConfig,
+        store: Box<dyn Store>,
+    ) -> Result<Self> {
+        let mut blockchain = Self::empty(network_params, store);
+
+        if let Some(tip) = blockchain.store.tip()? {
+            blockchain.load_from_store(tip)?;
+        } else {
+            let genesis_block = Block::genesis_with(&blockchain.network_params, genesis)?;
+            let undo = blockchain.utxo_set.apply_block(&genesis_block)?;
+            blockchain.persist_block(0, &genesis_block, &undo)?;
+            blockchain.block_index.insert(genesis_block.hash()?, 0);
+            blockchain.blocks.push(genesis_block);
+        }
 
-        let mut block_index = HashMap::new();
-        block_index.insert(genesis_hash, 0);
+        Ok(blockchain)
+    }
 
-        Ok(Self {
-            blocks: vec![genesis_block],
-            utxo_set,
-            block_index,
+    /// Blockchain sem nenhum bloco, nem mesmo o gênese
+    fn empty(network_params: NetworkParams, store: Box<dyn Store>) -> Self {
+        Self {
+            blocks: Vec::new(),
+            utxo_set: UtxoSet::new(),
+            block_index: HashMap::new(),
             network_params,
-        })
+            store,
+            side_blocks: HashMap::new(),
+            invalid_blocks: HashSet::new(),
+            reorg_depth: 0,
+            last_reorg: None,
+            time_offset: 0,
+            fee_estimator: FeeEstimator::default(),
+            tx_index: None,
+        }
+    }
+
+    /// Carrega blocos e UTXOs gravados até a altura `tip`
+    fn load_from_store(&mut self, tip: u64) -> Result<()> {
+        for height in 0..=tip {
+            let block = self.store.get_block(height)?.ok_or_else(|| {
+                BlockchainError::IoError(format!("Bloco ausente no store na altura {height}"))
+            })?;
+            if height == 0 {
+                self.check_genesis_commitment(&block)?;
+            }
+            self.block_index.insert(block.hash()?, self.blocks.len());
+            self.blocks.push(block);
+        }
+
+        for utxo in self.store.utxos()? {
+            self.utxo_set.add_utxo(utxo);
+        }
+
+        Ok(())
+    }
+
+    /// Grava um bloco, as alterações de UTXO que ele produz e o seu registro
+    /// de desfazimento
+    fn persist_block(&mut self, height: u64, block: &Block, undo: &BlockUndo) -> Result<()> {
+        for tx in &block.transactions {
+            let txid = tx.hash()?;
+
+            if !tx.is_coinbase() {
+                for input in &tx.inputs {
+                    self.store.delete_utxo(&input.previous_output)?;
+                }
+            }
+
+            let is_coinbase = tx.is_coinbase() && height > 0;
+            for (output_index, output) in tx.outputs.iter().enumerate() {
+                let vout = output_index.try_into().map_err(|_| {
+                    BlockchainError::InvalidBlock("Output index overflow".to_string())
+                })?;
+                self.store.put_utxo(
+                    &Utxo::new(
+                        txid,
+                        vout,
+                        output.value,
+                        output.script_pubkey.clone(),
+                        height,
+                    )
+                    .with_coinbase(is_coinbase),
+                )?;
+            }
+        }
+
+        // Bloco gravado por último: o tip só avança após os UTXOs
+        self.store.put_undo(height, undo)?;
+        self.store.put_block(height, block)
     }
 
     /// Adiciona um bloco à blockchain
     ///
+    /// Blocos que estendem a ponta são validados e aplicados. Blocos cujo
+    /// pai é outro bloco conhecido ficam guardados como ramo lateral; se o
+    /// ramo passar a ter mais trabalho acumulado que a cadeia ativa, a
+    /// cadeia é reorganizada para ele (ver `take_last_reorg`).
+    ///
     /// # Errors
     ///
     /// Retorna erro se o bloco não for válido ou não puder ser aplicado
     pub fn add_block(&mut self, block: Block) -> Result<()> {
+        if block.header.previous_hash != self.get_latest_block().hash()? {
+            return self.add_side_block(block);
+        }
+        self.connect_block(block)
+    }
+
+    /// Grava a cadeia ativa, do gênese à ponta, em um arquivo de bootstrap
+    ///
+    /// Cada bloco é gravado como um tamanho `u32` little-endian seguido do
+    /// bloco serializado em JSON, o mesmo formato lido por
+    /// `import_from_reader`.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a serialização ou a gravação falharem
+    pub fn export_to_writer<W: Write>(&self, mut writer: W) -> Result<()> {
+        for block in &self.blocks {
+            let data = serde_json::to_vec(block)
+                .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+            let len = u32::try_from(data.len()).map_err(|_| {
+                BlockchainError::SerializationError("Block too large to export".to_string())
+            })?;
+            writer
+                .write_all(&len.to_le_bytes())
+                .and_then(|()| writer.write_all(&data))
+                .map_err(|e| BlockchainError::IoError(e.to_string()))?;
+        }
+        writer
+            .flush()
+            .map_err(|e| BlockchainError::IoError(e.to_string()))
+    }
+
+    /// Importa blocos gravados por `export_to_writer`
+    ///
+    /// Blocos já presentes na cadeia (como o gênese) são pulados; os
+    /// demais precisam estender a ponta e passam pela validação completa de
+    /// `validate_block`. Retorna o número de blocos conectados.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a leitura falhar, se um registro estiver truncado ou
+    /// for maior que o permitido, ou se um bloco for inválido; os blocos
+    /// importados antes do erro continuam conectados
+    pub fn import_from_reader<R: Read>(&mut self, mut reader: R) -> Result<usize> {
+        // JSON representa cada byte de script como um número, então um
+        // bloco serializado pode ocupar algumas vezes o tamanho máximo
+        let max_record = self.network_params.max_block_size.saturating_mul(4);
+        let mut imported = 0;
+
+        loop {
+            let mut len = [0u8; 4];
+            match reader.read_exact(&mut len) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(BlockchainError::IoError(e.to_string())),
+            }
+            let len = u32::from_le_bytes(len) as usize;
+            if len > max_record {
+                return Err(BlockchainError::InvalidBlock(format!(
+                    "Bootstrap record of {len} bytes exceeds the limit of {max_record}"
+                )));
+            }
+
+            let mut data = vec![0u8; len];
+            reader
+                .read_exact(&mut data)
+                .map_err(|e| BlockchainError::IoError(e.to_string()))?;
+            let block: Block = serde_json::from_slice(&data)
+                .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+
+            if self.block_index.contains_key(&block.hash()?) {
+                continue;
+            }
+            let height = block.height()?;
+            self.connect_block(block).map_err(|e| {
+                BlockchainError::InvalidBlock(format!("Block at height {height}: {e}"))
+            })?;
+            imported += 1;
+        }
+
+        Ok(imported)
+    }
+
+    /// Valida e aplica um bloco que estende a ponta atual
+    fn connect_block(&mut self, block: Block) -> Result<()> {
         // Validar bloco
         self.validate_block(&block)?;
 
-        // Aplicar ao UTXO set
-        let mut new_utxo_set = self.utxo_set.clone();
-        block.apply_to_utxo_set(&mut new_utxo_set)?;
+        // Taxas calculadas antes de os inputs saírem do UTXO set
+        let fee_rates = block.transactions[1..]
+            .iter()
+            .map(|tx| tx.fee_rate(&self.utxo_set))
+            .collect::<Result<Vec<_>>>()?;
 
-        // Adicionar à cadeia
+        // Aplicar ao UTXO set; se a gravação falhar, o bloco é desfeito
         let block_hash = block.hash()?;
         let block_index = self.blocks.len();
+        let undo = self.utxo_set.apply_block(&block)?;
+        if let Err(e) = self.persist_block(block.height()?, &block, &undo) {
+            self.utxo_set.undo_block(&undo)?;
+            return Err(e);
+        }
 
+        self.fee_estimator.record_block(
+            &fee_rates,
+            block.weight(),
+            self.network_params.max_block_weight,
+        );
         self.blocks.push(block);
-        self.utxo_set = new_utxo_set;
         self.block_index.insert(block_hash, block_index);
-
-        Ok(())
+        self.reindex_transactions_from(block_index)
     }
 
-    /// Valida um bloco antes de adicioná-lo
+    /// Ativa o índice de transações usado por `get_transaction`
+    ///
+    /// O índice ocupa memória proporcional ao número de transações da
+    /// cadeia, por isso começa desativado. Os blocos já conectados são
+    /// indexados na ativação.
     ///
     /// # Errors
     ///
-    /// Retorna erro se o bloco não atender aos critérios de validação
-    pub fn validate_block(&self, block: &Block) -> Result<()> {
-        // Validação básica do bloco
-        block.validate_basic()?;
+    /// Retorna erro se o hash de alguma transação não puder ser calculado
+    pub fn enable_txindex(&mut self) -> Result<()> {
+        if self.tx_index.is_none() {
+            self.tx_index = Some(HashMap::new());
+            self.reindex_transactions_from(0)?;
+        }
+        Ok(())
+    }
 
-        // Verificar se não excede tamanho máximo
-        if block.size() > self.network_params.max_block_size {
+    /// Verifica se o índice de transações está ativo
+    #[must_use]
+    pub const fn has_txindex(&self) -> bool {
+        self.tx_index.is_some()
+    }
+
+    /// Busca uma transação da cadeia ativa pelo hash, junto com o seu bloco
+    ///
+    /// Retorna `None` se a transação não estiver na cadeia ativa ou se o
+    /// índice não tiver sido ativado com `enable_txindex`.
+    #[must_use]
+    pub fn get_transaction(&self, txid: &Hash256) -> Option<(&Transaction, &Block)> {
+        let &(block_index, position) = self.tx_index.as_ref()?.get(txid)?;
+        let block = self.blocks.get(block_index)?;
+        Some((block.transactions.get(position)?, block))
+    }
+
+    /// Refaz o índice de transações a partir do bloco `start` da cadeia ativa
+    fn reindex_transactions_from(&mut self, start: usize) -> Result<()> {
+        let Some(tx_index) = self.tx_index.as_mut() else {
+            return Ok(());
+        };
+        tx_index.retain(|_, (block_index, _)| *block_index < start);
+        for (block_index, block) in self.blocks.iter().enumerate().skip(start) {
+            for (position, tx) in block.transactions.iter().enumerate() {
+                tx_index.insert(tx.hash()?, (block_index, position));
+            }
+        }
+        Ok(())
+    }
+
+    /// Guarda um bloco fora da cadeia ativa e reorganiza se o ramo ficar mais pesado
+    fn add_side_block(&mut self, block: Block) -> Result<()> {
+        let hash = block.hash()?;
+        if self.block_index.contains_key(&hash) || self.side_blocks.contains_key(&hash) {
             return Err(BlockchainError::InvalidBlock(
-                "Block exceeds maximum size".to_string(),
+                "Block already known".to_string(),
             ));
         }
 
-        // Verificar se referencia o último bloco
-        let last_block = self.get_latest_block();
-        let last_hash = last_block.hash()?;
-
-        if block.header.previous_hash != last_hash {
+        let previous_hash = block.header.previous_hash;
+        if self.invalid_blocks.contains(&previous_hash) {
             return Err(BlockchainError::InvalidBlock(
-                "Invalid previous hash".to_string(),
+                "Block extends an invalid block".to_string(),
             ));
         }
+        let parent_height = self
+            .get_block_by_hash(&previous_hash)
+            .or_else(|| self.side_blocks.get(&previous_hash))
+            .ok_or_else(|| BlockchainError::InvalidBlock("Invalid previous hash".to_string()))?
+            .height()?;
 
-        // Verificar altura do bloco
-        let expected_height = self.height() + 1;
-        let actual_height = block.height()?;
-        if actual_height != expected_height {
+        // Validação sem contexto; o restante ocorre ao conectar o ramo
+        block.validate_basic()?;
+        if block.height()? != parent_height + 1 {
             return Err(BlockchainError::InvalidBlock(
                 "Invalid block height".to_string(),
             ));
         }
-
-        // Verificar dificuldade
-        let expected_difficulty = self.get_next_difficulty();
-        if block.header.difficulty != expected_difficulty {
+        if block.size() > self.network_params.max_block_size {
             return Err(BlockchainError::InvalidBlock(
-                "Invalid difficulty".to_string(),
+                "Block exceeds maximum size".to_string(),
             ));
         }
-
-        // Verificar recompensa de coinbase
-        let expected_reward = self.calculate_block_reward(expected_height);
-        let coinbase = &block.transactions[0];
-        let total_fees = self.calculate_total_fees(&block.transactions[1..])?;
-        let expected_coinbase_value = expected_reward + total_fees;
-
-        if coinbase.total_output_value()? != expected_coinbase_value {
+        if block.weight() > self.network_params.max_block_weight {
             return Err(BlockchainError::InvalidBlock(
-                "Invalid coinbase reward".to_string(),
+                "Block exceeds maximum weight".to_string(),
+            ));
+        }
+        if self.side_blocks.len() >= Self::MAX_SIDE_BLOCKS {
+            return Err(BlockchainError::InvalidBlock(
+                "Too many side blocks".to_string(),
             ));
         }
 
-        // Validar todas as transações não-coinbase
-        for tx in &block.transactions[1..] {
-            self.validate_transaction(tx)?;
+        // Percorrer o ramo até encontrar a cadeia ativa
+        let mut branch = vec![hash];
+        let mut cursor = previous_hash;
+        while let Some(side) = self.side_blocks.get(&cursor) {
+            branch.push(cursor);
+            cursor = side.header.previous_hash;
+        }
+        branch.reverse();
+        let fork_index = self.block_index[&cursor] + 1;
+
+        // Ramos que reescreveriam blocos além da profundidade máxima são
+        // inválidos, por mais trabalho que acumulem
+        let depth = (self.blocks.len() - fork_index) as u64;
+        if depth > self.network_params.max_reorg_depth {
+            return Err(BlockchainError::InvalidBlock(format!(
+                "Fork at height {} would roll back {depth} blocks (max reorg depth {})",
+                fork_index - 1,
+                self.network_params.max_reorg_depth
+            )));
         }
+        self.side_blocks.insert(hash, block);
 
-        Ok(())
+        if branch.iter().any(|hash| self.invalid_blocks.contains(hash)) {
+            return Ok(());
+        }
+
+        let branch_work: u128 = branch
+            .iter()
+            .map(|hash| block_work(&self.side_blocks[hash]))
+            .sum();
+        let active_work: u128 = self.blocks[fork_index..].iter().map(block_work).sum();
+        if branch_work <= active_work {
+            return Ok(());
+        }
+
+        self.reorganize(fork_index, &branch)
     }
 
-    /// Valida uma transação
-    ///
-    /// # Errors
+    /// Troca os blocos acima de `fork_index` pelos blocos de `branch`
     ///
-    /// Retorna erro se a transação não for válida
-    pub fn validate_transaction(&self, tx: &Transaction) -> Result<()> {
-        // Validação básica
-        tx.validate_basic()?;
-
-        // Verificar se todos os inputs referenciam UTXOs existentes
-        for input in &tx.inputs {
-            if !self.utxo_set.contains(&input.previous_output) {
-                return Err(BlockchainError::UtxoNotFound);
+    /// Se algum bloco do ramo for inválido, a cadeia anterior é restaurada
+    /// e o bloco inválido fica marcado, junto com o ramo que o contém.
+    fn reorganize(&mut self, fork_index: usize, branch: &[Hash256]) -> Result<()> {
+        let old_utxo_set = self.utxo_set.clone();
+        let disconnected = self.blocks.split_off(fork_index);
+        for block in &disconnected {
+            self.block_index.remove(&block.hash()?);
+        }
+        self.rollback_utxo_set(fork_index, disconnected.len())?;
+
+        let mut connected = Vec::new();
+        let mut undos = Vec::new();
+        for hash in branch {
+            let Some(block) = self.side_blocks.remove(hash) else {
+                continue;
+            };
+            let applied = self
+                .validate_block(&block)
+                .and_then(|()| self.utxo_set.apply_block(&block));
+
+            match applied {
+                Ok(undo) => {
+                    undos.push(undo);
+                    self.block_index.insert(*hash, self.blocks.len());
+                    self.blocks.push(block.clone());
+                    connected.push(block);
+                }
+                Err(e) => {
+                    self.side_blocks.insert(*hash, block);
+                    self.invalid_blocks.insert(*hash);
+                    // Os blocos do ramo conectados até aqui voltam a ser
+                    // laterais e saem do índice da cadeia ativa
+                    for block in self.blocks.split_off(fork_index) {
+                        let hash = block.hash()?;
+                        self.block_index.remove(&hash);
+                        self.side_blocks.insert(hash, block);
+                    }
+                    for block in &disconnected {
+                        self.block_index.insert(block.hash()?, self.blocks.len());
+                        self.blocks.push(block.clone());
+                    }
+                    self.utxo_set = old_utxo_set;
+                    return Err(e);
+                }
             }
         }
 
-        // Verificar se os valores batem (inputs >= outputs)
-        let input_value = tx.total_input_value(&self.utxo_set)?;
-        let output_value = tx.total_output_value()?;
+        self.persist_reorg(
+            fork_index,
+            fork_index + disconnected.len(),
+            &old_utxo_set,
+            &undos,
+        )?;
+        self.reindex_transactions_from(fork_index)?;
 
-        if input_value < output_value {
-            return Err(BlockchainError::InsufficientFunds);
+        for block in &disconnected {
+            self.side_blocks.insert(block.hash()?, block.clone());
         }
-
-        // TODO: Verificar assinaturas (será implementado no Sprint 2 com ML-DSA)
+        let depth = disconnected.len() as u64;
+        self.reorg_depth = self.reorg_depth.max(depth);
+        let fork_height = self.blocks[fork_index - 1].height()?;
+        self.last_reorg = Some(ChainReorg {
+            fork_height,
+            disconnected,
+            connected,
+        });
 
         Ok(())
     }
 
-    /// Minera o próximo bloco
-    ///
-    /// # Errors
+    /// Desfaz no conjunto UTXO os `count` blocos desconectados a partir de
+    /// `fork_index`, do mais alto ao mais baixo
     ///
-    /// Retorna erro se as transações não forem válidas ou se a mineração falhar
-    pub fn mine_next_block(
-        &self,
-        miner: &Miner,
-        transactions: Vec<Transaction>,
-    ) -> Result<MiningResult> {
-        // Validar transações
-        for tx in &transactions {
-            self.validate_transaction(tx)?;
+    /// Stores gravados antes dos registros de desfazimento não os têm; nesse
+    /// caso o conjunto é reconstruído desde o gênese.
+    fn rollback_utxo_set(&mut self, fork_index: usize, count: usize) -> Result<()> {
+        for height in (fork_index..fork_index + count).rev() {
+            let Some(undo) = self.store.get_undo(height as u64)? else {
+                self.utxo_set = self.replay_utxo_set()?;
+                return Ok(());
+            };
+            self.utxo_set.undo_block(&undo)?;
         }
+        Ok(())
+    }
 
-        let previous_hash = self.get_latest_block().hash()?;
-        let block_height = self.height() + 1;
-        let reward = self.calculate_block_reward(block_height);
-        let total_fees = self.calculate_total_fees(&transactions)?;
-        let total_reward = reward + total_fees;
-        let difficulty = self.get_next_difficulty();
+    /// Reconstrói o conjunto UTXO aplicando a cadeia ativa desde o gênese
+    fn replay_utxo_set(&self) -> Result<UtxoSet> {
+        let mut utxo_set = UtxoSet::new();
+        for block in &self.blocks {
+            block.apply_to_utxo_set(&mut utxo_set)?;
+        }
+        Ok(utxo_set)
+    }
 
-        miner.mine_block_with_difficulty(
-            previous_hash,
-            transactions,
-            block_height,
-            total_reward,
-            difficulty,
-        )
+    /// Grava no store os blocos do novo ramo, seus registros de
+    /// desfazimento e a diferença de UTXOs
+    fn persist_reorg(
+        &mut self,
+        fork_index: usize,
+        old_len: usize,
+        old_utxo_set: &UtxoSet,
+        undos: &[BlockUndo],
+    ) -> Result<()> {
+        for utxo in old_utxo_set.iter() {
+            if !self.utxo_set.contains(&utxo.outpoint()) {
+                self.store.delete_utxo(&utxo.outpoint())?;
+            }
+        }
+        for utxo in self.utxo_set.iter() {
+            if old_utxo_set.get_utxo(&utxo.outpoint()) != Some(utxo) {
+                self.store.put_utxo(utxo)?;
+            }
+        }
+
+        for height in (self.blocks.len()..old_len).rev() {
+            self.store.delete_block(height as u64)?;
+            self.store.delete_undo(height as u64)?;
+        }
+        for ((height, block), undo) in self.blocks.iter().enumerate().skip(fork_index).zip(undos) {
+            self.store.put_undo(height as u64, undo)?;
+            self.store.put_block(height as u64, block)?;
+        }
+        Ok(())
     }
 
-    /// Obtém o último bloco
-    ///
-    /// # Panics
+    /// Pontas de todos os ramos conhecidos
     ///
-    /// Nunca deve entrar em pânico pois o bloco gênese sempre existe
+    /// A ponta ativa vem primeiro; as dos ramos laterais seguem por altura
+    /// decrescente. `branch_len` conta os blocos entre a ponta e o ancestral
+    /// comum com a cadeia ativa.
     #[must_use]
-    pub fn get_latest_block(&self) -> &Block {
-        self.blocks.last().unwrap() // Genesis sempre existe
+    pub fn get_chain_tips(&self) -> Vec<ChainTip> {
+        let parents: HashSet<Hash256> = self
+            .side_blocks
+            .values()
+            .map(|block| block.header.previous_hash)
+            .collect();
+
+        let mut forks: Vec<ChainTip> = self
+            .side_blocks
+            .iter()
+            .filter(|(hash, _)| !parents.contains(*hash))
+            .filter_map(|(hash, block)| {
+                let mut branch_len = 0;
+                let mut invalid = false;
+                let mut cursor = *hash;
+                while let Some(side) = self.side_blocks.get(&cursor) {
+                    branch_len += 1;
+                    invalid |= self.invalid_blocks.contains(&cursor);
+                    cursor = side.header.previous_hash;
+                }
+                Some(ChainTip {
+                    hash: *hash,
+                    height: block.height().ok()?,
+                    branch_len,
+                    status: if invalid {
+                        ChainTipStatus::Invalid
+                    } else {
+                        ChainTipStatus::ValidFork
+                    },
+                })
+            })
+            .collect();
+        forks.sort_by(|a, b| {
+            b.height
+                .cmp(&a.height)
+                .then_with(|| a.hash.as_bytes().cmp(b.hash.as_bytes()))
+        });
+
+        let mut tips = Vec::with_capacity(forks.len() + 1);
+        if let Ok(hash) = self.get_latest_block().hash() {
+            tips.push(ChainTip {
+                hash,
+                height: self.height(),
+                branch_len: 0,
+                status: ChainTipStatus::Active,
+            });
+        }
+        tips.extend(forks);
+        tips
+    }
+
+    /// Retira a última reorganização, para que a mempool reinjete suas transações
+    pub fn take_last_reorg(&mut self) -> Option<ChainReorg> {
+        self.last_reorg.take()
+    }
+
+    /// Maior número de blocos desconectados em uma reorganização
+    #[must_use]
+    pub const fn reorg_depth(&self) -> u64 {
+        self.reorg_depth
+    }
+
+    /// Hash do bloco gênese, que identifica a rede e seus parâmetros
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o cálculo do hash falhar
+    pub fn genesis_hash(&self) -> Result<Hash256> {
+        self.blocks[0].hash()
+    }
+
+    /// Parâmetros da rede em uso
+    #[must_use]
+    pub const fn network_params(&self) -> &NetworkParams {
+        &self.network_params
+    }
+
+    /// Verifica se um gênese gravado foi criado com os parâmetros atuais
+    fn check_genesis_commitment(&self, genesis: &Block) -> Result<()> {
+        let expected = self.network_params.hash()?;
+        let committed = genesis
+            .transactions
+            .first()
+            .and_then(|coinbase| coinbase.inputs.first())
+            .and_then(|input| input.script_sig.get(8..));
+
+        if committed != Some(expected.as_bytes().as_slice()) {
+            return Err(BlockchainError::InvalidBlock(
+                "Genesis block was created with different network params".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Verifica se a altura alegada por um bloco está dentro da janela aceita
+    ///
+    /// Blocos que afirmam estar mais de `max_future_height` blocos acima da
+    /// ponta atual são rejeitados sem serem guardados, para que um par não
+    /// consiga esgotar memória com alturas fictícias.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a altura exceder a janela configurada
+    pub fn check_height_window(&self, height: u64) -> Result<()> {
+        let max_height = self
+            .height()
+            .saturating_add(self.network_params.max_future_height);
+        if height > max_height {
+            return Err(BlockchainError::InvalidBlock(format!(
+                "Block height {height} exceeds acceptance window (max {max_height})"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Mediana dos timestamps dos últimos `MEDIAN_TIME_SPAN` blocos
+    #[must_use]
+    pub fn median_time_past(&self) -> DateTime<Utc> {
+        let start = self.blocks.len().saturating_sub(Self::MEDIAN_TIME_SPAN);
+        let mut timestamps: Vec<DateTime<Utc>> = self.blocks[start..]
+            .iter()
+            .map(|block| block.header.timestamp)
+            .collect();
+        timestamps.sort_unstable();
+        timestamps[timestamps.len() / 2]
+    }
+
+    /// Ajusta o horário da rede em relação ao relógio local
+    ///
+    /// O nó de rede calcula o deslocamento a partir da mediana dos horários
+    /// informados pelos pares.
+    pub fn set_time_offset(&mut self, offset_secs: i64) {
+        self.time_offset = offset_secs;
+    }
+
+    /// Horário da rede: relógio local mais o deslocamento informado pelos pares
+    #[must_use]
+    pub fn adjusted_time(&self) -> DateTime<Utc> {
+        let now = Utc::now();
+        now.checked_add_signed(Duration::seconds(self.time_offset))
+            .unwrap_or(now)
+    }
+
+    /// Verifica o timestamp de um bloco candidato à próxima altura
+    ///
+    /// O timestamp não pode estar mais de `max_future_block_time` segundos
+    /// à frente de `now` nem ser anterior ao median-time-past da cadeia,
+    /// o que impede um minerador de distorcer o ajuste de dificuldade.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o timestamp estiver fora desses limites
+    pub fn check_block_time(&self, timestamp: DateTime<Utc>, now: DateTime<Utc>) -> Result<()> {
+        let tolerance = i64::try_from(self.network_params.max_future_block_time)
+            .map_or(Duration::MAX, Duration::seconds);
+        let max_timestamp = now
+            .checked_add_signed(tolerance)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        if timestamp > max_timestamp {
+            return Err(BlockchainError::InvalidBlock(format!(
+                "Block timestamp {timestamp} is too far in the future (max {max_timestamp})"
+            )));
+        }
+
+        let median = self.median_time_past();
+        if timestamp < median {
+            return Err(BlockchainError::InvalidBlock(format!(
+                "Block timestamp {timestamp} is before median time past {median}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Revalida a cadeia ativa inteira a partir do gênese
+    ///
+    /// Os blocos são reaplicados, em ordem, a uma cadeia em memória nova,
+    /// passando por `validate_block` (encadeamento, dificuldade, merkle root,
+    /// recompensa da coinbase e scripts) com um conjunto UTXO reconstruído do
+    /// zero. Ao final, o conjunto reconstruído precisa ser igual ao atual.
+    ///
+    /// # Errors
+    ///
+    /// Retorna `InvalidBlock` com a altura do primeiro bloco inconsistente
+    pub fn verify_chain(&self) -> Result<()> {
+        let at_height = |height: usize, error: BlockchainError| {
+            BlockchainError::InvalidBlock(format!("Block at height {height}: {error}"))
+        };
+
+        let mut replay = Self::empty(self.network_params.clone(), Box::new(MemoryStore::new()));
+        replay.time_offset = self.time_offset;
+
+        let genesis = self.blocks.first().ok_or_else(|| {
+            BlockchainError::InvalidBlock("Chain has no genesis block".to_string())
+        })?;
+        replay
+            .check_genesis_commitment(genesis)
+            .and_then(|()| genesis.validate_basic())
+            .and_then(|()| replay.utxo_set.apply_block(genesis))
+            .map_err(|e| at_height(0, e))?;
+        replay.block_index.insert(genesis.hash()?, 0);
+        replay.blocks.push(genesis.clone());
+
+        for (height, block) in self.blocks.iter().enumerate().skip(1) {
+            replay
+                .connect_block(block.clone())
+                .map_err(|e| at_height(height, e))?;
+        }
+
+        if replay.utxo_set != self.utxo_set {
+            return Err(BlockchainError::InvalidBlock(
+                "UTXO set does not match the replayed chain".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Valida um bloco antes de adicioná-lo
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o bloco não atender aos critérios de validação
+    pub fn validate_block(&self, block: &Block) -> Result<()> {
+        // Validação básica do bloco
+        block.validate_basic()?;
+
+        // Rejeitar alturas implausíveis antes de qualquer outro trabalho
+        self.check_height_window(block.height()?)?;
+
+        // Verificar se não excede tamanho máximo
+        if block.size() > self.network_params.max_block_size {
+            return Err(BlockchainError::InvalidBlock(
+                "Block exceeds maximum size".to_string(),
+            ));
+        }
+        if block.weight() > self.network_params.max_block_weight {
+            return Err(BlockchainError::InvalidBlock(
+                "Block exceeds maximum weight".to_string(),
+            ));
+        }
+
+        // Verificar se referencia o último bloco
+        let last_block = self.get_latest_block();
+        let last_hash = last_block.hash()?;
+
+        if block.header.previous_hash != last_hash {
+            return Err(BlockchainError::InvalidBlock(
+                "Invalid previous hash".to_string(),
+            ));
+        }
+
+        // Verificar altura do bloco
+        let expected_height = self.height() + 1;
+        let actual_height = block.height()?;
+        if actual_height != expected_height {
+            return Err(BlockchainError::InvalidBlock(
+                "Invalid block height".to_string(),
+            ));
+        }
+
+        // Verificar timestamp contra o horário da rede e o median-time-past
+        self.check_block_time(block.header.timestamp, self.adjusted_time())?;
+
+        // Verificar dificuldade
+        let expected_difficulty = self.get_next_difficulty();
+        if block.header.bits != expected_difficulty {
+            return Err(BlockchainError::InvalidBlock(
+                "Invalid difficulty".to_string(),
+            ));
+        }
+
+        // Nenhum OutPoint pode ser gasto duas vezes no mesmo bloco; cada
+        // transação isolada ainda seria válida contra o UTXO set
+        let mut spent = HashSet::new();
+        for tx in &block.transactions[1..] {
+            for input in &tx.inputs {
+                let outpoint = input.previous_output;
+                if !spent.insert(outpoint) {
+                    return Err(BlockchainError::InvalidBlock(format!(
+                        "Double spend of output {}:{} within block",
+                        outpoint.txid, outpoint.vout
+                    )));
+                }
+            }
+        }
+
+        // Verificar recompensa de coinbase
+        let expected_reward = self.calculate_block_reward(expected_height);
+        let coinbase = &block.transactions[0];
+        let total_fees = self.calculate_total_fees(&block.transactions[1..])?;
+        let expected_coinbase_value = expected_reward + total_fees;
+
+        if coinbase.total_output_value()? != expected_coinbase_value {
+            return Err(BlockchainError::InvalidBlock(
+                "Invalid coinbase reward".to_string(),
+            ));
+        }
+
+        // Validar todas as transações não-coinbase
+        for tx in &block.transactions[1..] {
+            self.validate_transaction(tx)?;
+        }
+
+        // As transações precisam seguir a ordem de `canonical_order`
+        let priorities = block.transactions[1..]
+            .iter()
+            .map(|tx| self.canonical_priority(tx))
+            .collect::<Result<Vec<_>>>()?;
+        if priorities.windows(2).any(|pair| pair[0] < pair[1]) {
+            return Err(BlockchainError::InvalidBlock(
+                "Transactions are not in canonical order".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Valida uma transação
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a transação não for válida
+    pub fn validate_transaction(&self, tx: &Transaction) -> Result<()> {
+        // Validação básica
+        tx.validate_basic()?;
+
+        // Limites de política da rede: tamanho e saídas de poeira
+        let size = tx.size();
+        if size > self.network_params.max_tx_size {
+            return Err(BlockchainError::InvalidTransaction(format!(
+                "Transaction size {size} exceeds maximum {}",
+                self.network_params.max_tx_size
+            )));
+        }
+        if let Some(output) = tx
+            .outputs
+            .iter()
+            .find(|output| output.value < self.network_params.dust_threshold)
+        {
+            return Err(BlockchainError::InvalidTransaction(format!(
+                "Output value {} is below the dust threshold {}",
+                output.value, self.network_params.dust_threshold
+            )));
+        }
+
+        // Verificar se todos os inputs referenciam UTXOs existentes e se os
+        // de coinbase já maturaram no próximo bloco
+        let spend_height = self.height() + 1;
+        for input in &tx.inputs {
+            let utxo = self
+                .utxo_set
+                .get_utxo(&input.previous_output)
+                .ok_or(BlockchainError::UtxoNotFound)?;
+            if !utxo.is_spendable_at(spend_height) {
+                return Err(BlockchainError::InvalidTransaction(
+                    "Spends immature coinbase output".to_string(),
+                ));
+            }
+        }
+
+        // Verificar se os valores batem (inputs >= outputs)
+        let input_value = tx.total_input_value(&self.utxo_set)?;
+        let output_value = tx.total_output_value()?;
+
+        if input_value < output_value {
+            return Err(BlockchainError::InsufficientFunds);
+        }
+
+        // Verificar o lock_time contra o próximo bloco, usando o
+        // median-time-past para locks por timestamp
+        let median_time = u64::try_from(self.median_time_past().timestamp()).unwrap_or(0);
+        if !tx.is_final(spend_height, median_time) {
+            return Err(BlockchainError::InvalidTransaction(
+                "Transaction lock time not reached".to_string(),
+            ));
+        }
+
+        // Verificar se cada input está autorizado a gastar o UTXO referenciado
+        if !tx.validate_scripts(&self.utxo_set, spend_height)? {
+            return Err(BlockchainError::InvalidTransaction(
+                "Script validation failed".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Simula a aceitação de uma transação sem alterar o estado
+    ///
+    /// Diferente de `validate_transaction`, não para no primeiro problema:
+    /// reporta quais inputs não foram encontrados, a taxa calculada e o
+    /// resultado da validação dos scripts.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro apenas se o hash da transação não puder ser calculado
+    pub fn simulate_transaction(&self, tx: &Transaction) -> Result<SimulationResult> {
+        let mut result = SimulationResult {
+            txid: tx.hash()?,
+            inputs_resolved: false,
+            missing_inputs: Vec::new(),
+            fee: None,
+            scripts_valid: false,
+            error: None,
+        };
+
+        if let Err(e) = tx.validate_basic() {
+            result.error = Some(e);
+            return Ok(result);
+        }
+
+        result.missing_inputs = tx
+            .inputs
+            .iter()
+            .map(|input| input.previous_output)
+            .filter(|outpoint| !self.utxo_set.contains(outpoint))
+            .collect();
+        result.inputs_resolved = result.missing_inputs.is_empty();
+
+        if !result.inputs_resolved {
+            result.error = Some(BlockchainError::UtxoNotFound);
+            return Ok(result);
+        }
+
+        match tx.fee(&self.utxo_set) {
+            Ok(fee) => result.fee = Some(fee),
+            Err(_) => result.error = Some(BlockchainError::InsufficientFunds),
+        }
+
+        match tx.validate_scripts(&self.utxo_set, self.height() + 1) {
+            Ok(valid) => result.scripts_valid = valid,
+            Err(e) => result.error = result.error.or(Some(e)),
+        }
+
+        if !result.scripts_valid && result.error.is_none() {
+            result.error = Some(BlockchainError::InvalidTransaction(
+                "Script validation failed".to_string(),
+            ));
+        }
+
+        Ok(result)
+    }
+
+    /// Minera o próximo bloco
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se as transações não forem válidas ou se a mineração falhar
+    pub fn mine_next_block(
+        &self,
+        miner: &Miner,
+        transactions: Vec<Transaction>,
+    ) -> Result<MiningResult> {
+        self.start_mining_next_block(miner, transactions)?
+            .join()
+            .ok_or(BlockchainError::NonceNotFound)
+    }
+
+    /// Inicia a mineração do próximo bloco sem bloquear a thread chamadora
+    ///
+    /// Como `mine_next_block`, mas devolve o `MiningHandle` para que a
+    /// mineração possa ser cancelada se outro bloco chegar antes.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se as transações não forem válidas ou se o merkle root
+    /// não puder ser calculado
+    pub fn start_mining_next_block(
+        &self,
+        miner: &Miner,
+        transactions: Vec<Transaction>,
+    ) -> Result<MiningHandle> {
+        // Validar transações
+        for tx in &transactions {
+            self.validate_transaction(tx)?;
+        }
+        let transactions = self.canonical_order(transactions)?;
+
+        let previous_hash = self.get_latest_block().hash()?;
+        let block_height = self.height() + 1;
+        let reward = self.calculate_block_reward(block_height);
+        let total_fees = self.calculate_total_fees(&transactions)?;
+        let total_reward = reward + total_fees;
+        let difficulty = self.get_next_difficulty();
+
+        miner.start_mining(
+            previous_hash,
+            transactions,
+            block_height,
+            total_reward,
+            difficulty,
+        )
+    }
+
+    /// Obtém o último bloco
+    ///
+    /// # Panics
+    ///
+    /// Nunca deve entrar em pânico pois o bloco gênese sempre existe
+    #[must_use]
+    pub fn get_latest_block(&self) -> &Block {
+        self.blocks.last().unwrap() // Genesis sempre existe
     }
 
     /// Obtém um bloco pelo hash
@@ -245,6 +1354,119 @@ impl Blockchain {
         }
     }
 
+    /// Localizador da cadeia ativa, para sincronização headers-first
+    #[must_use]
+    pub fn block_locator(&self) -> BlockLocator {
+        let mut hashes = Vec::new();
+        let mut height = self.height();
+        let mut step = 1;
+        loop {
+            if let Some(hash) = self
+                .get_block_by_height(height)
+                .and_then(|block| block.hash().ok())
+            {
+                hashes.push(hash);
+            }
+            if height == 0 {
+                break;
+            }
+            if hashes.len() as u64 >= LOCATOR_DENSE_ENTRIES {
+                step *= 2;
+            }
+            height = height.saturating_sub(step);
+        }
+        BlockLocator { hashes }
+    }
+
+    /// Altura do ancestral comum com a cadeia descrita por `locator`
+    ///
+    /// É a altura do primeiro hash do localizador que está na cadeia
+    /// ativa, ou `None` se nenhum estiver (gêneses diferentes).
+    #[must_use]
+    pub fn find_fork_point(&self, locator: &BlockLocator) -> Option<u64> {
+        locator
+            .hashes
+            .iter()
+            .find_map(|hash| self.block_index.get(hash))
+            .map(|&index| index as u64)
+    }
+
+    /// Cabeçalhos da cadeia ativa depois do ancestral comum com `locator`
+    ///
+    /// Devolve no máximo `max` cabeçalhos, em ordem crescente de altura;
+    /// nenhum se o localizador não tiver ancestral comum.
+    #[must_use]
+    pub fn headers_after(&self, locator: &BlockLocator, max: usize) -> Vec<BlockHeader> {
+        let Some(fork_height) = self.find_fork_point(locator) else {
+            return Vec::new();
+        };
+        self.blocks
+            .iter()
+            .skip(
+                usize::try_from(fork_height)
+                    .unwrap_or(usize::MAX)
+                    .saturating_add(1),
+            )
+            .take(max)
+            .map(|block| block.header.clone())
+            .collect()
+    }
+
+    /// Valida cabeçalhos recebidos antes de baixar os corpos dos blocos
+    ///
+    /// O primeiro cabeçalho precisa estender um bloco conhecido (da cadeia
+    /// ativa ou de um ramo lateral) e cada um dos seguintes o anterior;
+    /// todos precisam atender ao próprio alvo de dificuldade. As demais
+    /// regras dependem das transações e ficam para a validação do bloco.
+    ///
+    /// # Errors
+    ///
+    /// Retorna `InvalidBlock` se a sequência não se ligar a um bloco
+    /// conhecido ou não for contínua, ou `InsufficientDifficulty` se algum
+    /// cabeçalho não atender ao seu alvo
+    pub fn validate_headers(&self, headers: &[BlockHeader]) -> Result<()> {
+        let Some(first) = headers.first() else {
+            return Ok(());
+        };
+        let parent = first.previous_hash;
+        if !self.block_index.contains_key(&parent) && !self.side_blocks.contains_key(&parent) {
+            return Err(BlockchainError::InvalidBlock(format!(
+                "Headers do not connect to a known block: {parent}"
+            )));
+        }
+
+        let mut expected_parent = parent;
+        for header in headers {
+            if header.previous_hash != expected_parent {
+                return Err(BlockchainError::InvalidBlock(
+                    "Headers are not a continuous chain".to_string(),
+                ));
+            }
+            if !header.meets_target()? {
+                return Err(BlockchainError::InsufficientDifficulty);
+            }
+            expected_parent = header.hash()?;
+        }
+        Ok(())
+    }
+
+    /// Verifica se uma transação tem ao menos `confirmations` confirmações
+    ///
+    /// Só a cadeia ativa conta: uma transação que saiu dela numa
+    /// reorganização deixa de ser final. O bloco que inclui a transação
+    /// conta como a primeira confirmação.
+    #[must_use]
+    pub fn is_transaction_final(&self, txid: &Hash256, confirmations: u64) -> bool {
+        let found = self.blocks.iter().rposition(|block| {
+            block
+                .transactions
+                .iter()
+                .any(|tx| tx.hash().is_ok_and(|hash| hash == *txid))
+        });
+
+        found.is_some_and(|index| (self.blocks.len() - index) as u64 >= confirmations)
+    }
+
     /// Altura atual da blockchain
     #[must_use]
     #[allow(clippy::cast_possible_truncation)] // Altura nunca será maior que usize::MAX
@@ -259,11 +1481,21 @@ impl Blockchain {
     }
 
     /// Calcula a recompensa para um bloco na altura especificada
+    ///
+    /// A recompensa cai pela metade a cada `halving_interval` blocos, até
+    /// chegar a zero.
     #[must_use]
-    pub const fn calculate_block_reward(&self, _height: u64) -> u64 {
-        // Implementação simplificada - recompensa constante
-        // Na versão final, implementará inflação adaptativa
-        self.network_params.initial_reward
+    pub const fn calculate_block_reward(&self, height: u64) -> u64 {
+        let interval = self.network_params.halving_interval;
+        if interval == 0 {
+            return self.network_params.initial_reward;
+        }
+
+        let halvings = height / interval;
+        if halvings >= u64::BITS as u64 {
+            return 0;
+        }
+        self.network_params.initial_reward >> halvings
     }
 
     /// Calcula o total de taxas de um conjunto de transações
@@ -284,7 +1516,64 @@ impl Blockchain {
         Ok(total_fees)
     }
 
-    /// Obtém a dificuldade para o próximo bloco
+    /// Taxa por byte sugerida para inclusão em até `target_blocks` blocos
+    ///
+    /// Baseada nos blocos conectados desde que o nó iniciou; veja
+    /// [`FeeEstimator::estimate_fee`].
+    #[must_use]
+    pub fn estimate_fee(&self, target_blocks: u64) -> u64 {
+        self.fee_estimator.estimate_fee(target_blocks)
+    }
+
+    /// Seleciona transações da mempool para o próximo bloco
+    ///
+    /// O orçamento é `max_block_weight` descontados o cabeçalho e uma
+    /// coinbase com até `COINBASE_SCRIPT_RESERVE` bytes de script, de modo
+    /// que o bloco minerado sempre passe em `validate_block`.
+    #[must_use]
+    pub fn select_mempool_transactions(&self, mempool: &BondMempool) -> Vec<Transaction> {
+        let reward_script = vec![0; Self::COINBASE_SCRIPT_RESERVE];
+        let coinbase_weight = Transaction::coinbase(self.height() + 1, 0, reward_script).weight();
+        let budget = self
+            .network_params
+            .max_block_weight
+            .saturating_sub(Block::HEADER_SIZE * WITNESS_SCALE_FACTOR + coinbase_weight);
+        mempool.select_transactions(budget, &self.utxo_set)
+    }
+
+    /// Ordena as transações de um bloco na ordem canônica
+    ///
+    /// As transações de um bloco só gastam saídas já confirmadas
+    /// (`validate_block` rejeita gastos entre transações do mesmo bloco),
+    /// então a ordem depende apenas delas: a de maior taxa por byte vem
+    /// antes e empates são desfeitos pelo txid. Assim, mineradores que
+    /// montam um bloco com o mesmo conjunto de transações chegam à mesma
+    /// ordem e à mesma merkle root. A coinbase não passa por aqui: o `Miner`
+    /// sempre a coloca em primeiro. `validate_block` rejeita blocos cujas
+    /// transações fujam dessa ordem.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o hash ou a taxa de alguma transação falhar
+    pub fn canonical_order(&self, transactions: Vec<Transaction>) -> Result<Vec<Transaction>> {
+        let mut prioritized = transactions
+            .into_iter()
+            .map(|tx| Ok((self.canonical_priority(&tx)?, tx)))
+            .collect::<Result<Vec<_>>>()?;
+        prioritized.sort_by(|(a, _), (b, _)| b.cmp(a));
+        Ok(prioritized.into_iter().map(|(_, tx)| tx).collect())
+    }
+
+    /// Chave de `tx` na ordem canônica, com a taxa contra o UTXO set atual
+    fn canonical_priority(&self, tx: &Transaction) -> Result<CanonicalPriority> {
+        Ok(CanonicalPriority {
+            fee: tx.fee(&self.utxo_set)?,
+            size: tx.size().max(1) as u64,
+            txid: tx.hash()?,
+        })
+    }
+
+    /// Obtém o alvo compacto (nBits) para o próximo bloco
     #[must_use]
     pub fn get_next_difficulty(&self) -> u32 {
         let adjuster = DifficultyAdjuster::new(
@@ -292,7 +1581,7 @@ impl Blockchain {
             self.network_params.difficulty_adjustment_period,
         );
 
-        let current_difficulty = self.get_latest_block().header.difficulty;
+        let current_difficulty = self.get_latest_block().header.bits;
 
         adjuster
             .calculate_new_difficulty(current_difficulty, &self.blocks)
@@ -307,6 +1596,10 @@ impl Blockchain {
 
     /// Cria uma transação simples
     ///
+    /// Os UTXOs em `reserved` (tipicamente `BondMempool::reserved_outpoints`)
+    /// são ignorados na seleção, para não gerar um gasto duplo com uma
+    /// transação ainda não confirmada.
+    ///
     /// # Errors
     ///
     /// Retorna erro se não houver UTXOs suficientes ou se a transação não puder ser criada
@@ -316,13 +1609,21 @@ impl Blockchain {
         to_script: Vec<u8>,
         amount: u64,
         fee: u64,
+        reserved: Option<&HashSet<OutPoint>>,
     ) -> Result<Transaction> {
         let total_needed = amount + fee;
 
         // Encontrar UTXOs suficientes
-        let utxos = self
-            .utxo_set
-            .find_utxos_for_amount(from_script, total_needed)?;
+        let utxos = match reserved {
+            Some(reserved) => self.utxo_set.find_utxos_for_amount_excluding(
+                from_script,
+                total_needed,
+                reserved,
+            )?,
+            None => self
+                .utxo_set
+                .find_utxos_for_amount(from_script, total_needed)?,
+        };
 
         // Calcular valor total dos UTXOs selecionados
         let total_input: u64 = utxos.iter().map(|utxo| utxo.value).sum();
@@ -342,9 +1643,10 @@ impl Blockchain {
         // Criar outputs
         let mut outputs = vec![crate::transaction::TxOutput::new(amount, to_script)];
 
-        // Adicionar troco se necessário
+        // Adicionar troco se necessário; troco abaixo do limite de poeira
+        // fica como taxa
         let change = total_input - total_needed;
-        if change > 0 {
+        if change >= self.network_params.dust_threshold.max(1) {
             outputs.push(crate::transaction::TxOutput::new(
                 change,
                 from_script.to_vec(),
@@ -357,11 +1659,20 @@ impl Blockchain {
     /// Estatísticas da blockchain
     #[must_use]
     pub fn stats(&self) -> BlockchainStats {
+        // O gênese emite a pré-mineração configurada, não a recompensa
+        let premine: u64 = self.blocks[0]
+            .transactions
+            .iter()
+            .flat_map(|tx| &tx.outputs)
+            .map(|output| output.value)
+            .sum();
         let total_supply = self
             .blocks
             .iter()
+            .skip(1)
             .map(|block| self.calculate_block_reward(block.height().unwrap_or(0)))
-            .sum();
+            .sum::<u64>()
+            + premine;
 
         #[allow(clippy::cast_possible_truncation)] // Conversões controladas
         BlockchainStats {
@@ -374,11 +1685,60 @@ impl Blockchain {
                 .sum(),
             total_utxos: self.utxo_set.len() as u64,
             total_supply,
-            difficulty: self.get_latest_block().header.difficulty,
+            difficulty: self.get_latest_block().header.bits,
+            reorg_depth: self.reorg_depth,
         }
     }
 }
 
+/// Consenso por prova de trabalho da Bond, para o nó P2P
+impl ConsensusEngine for Blockchain {
+    type Block = Block;
+    type Transaction = Transaction;
+    type Producer = Miner;
+
+    fn validate_block(&self, block: &Block) -> Result<()> {
+        Self::validate_block(self, block)
+    }
+
+    fn add_block(&mut self, block: Block) -> Result<()> {
+        Self::add_block(self, block)
+    }
+
+    fn produce_block(&self, miner: &Miner, transactions: Vec<Transaction>) -> Result<Block> {
+        Ok(self.mine_next_block(miner, transactions)?.block)
+    }
+
+    fn tip_height(&self) -> u64 {
+        self.height()
+    }
+}
+
+/// Resultado da simulação de uma transação
+#[derive(Debug)]
+pub struct SimulationResult {
+    /// Hash da transação simulada
+    pub txid: Hash256,
+    /// Todos os inputs referenciam UTXOs existentes
+    pub inputs_resolved: bool,
+    /// Inputs que não foram encontrados no conjunto de UTXOs
+    pub missing_inputs: Vec<OutPoint>,
+    /// Taxa paga, se os inputs cobrem os outputs
+    pub fee: Option<u64>,
+    /// Todos os scripts foram executados com sucesso
+    pub scripts_valid: bool,
+    /// Primeiro motivo de rejeição encontrado
+    pub error: Option<BlockchainError>,
+}
+
+impl SimulationResult {
+    /// Verifica se a transação seria aceita
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
 /// Estatísticas da blockchain
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockchainStats {
@@ -388,12 +1748,44 @@ pub struct BlockchainStats {
     pub total_utxos: u64,
     pub total_supply: u64,
     pub difficulty: u32,
+    /// Maior reorganização observada (blocos desconectados)
+    pub reorg_depth: u64,
+}
+
+/// Prioridade de uma transação em `Blockchain::canonical_order`
+///
+/// A maior vem primeiro: maior taxa por byte e, no empate, menor txid.
+#[derive(Debug, PartialEq, Eq)]
+struct CanonicalPriority {
+    fee: u64,
+    size: u64,
+    txid: Hash256,
+}
+
+impl Ord for CanonicalPriority {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (u128::from(self.fee) * u128::from(other.size))
+            .cmp(&(u128::from(other.fee) * u128::from(self.size)))
+            .then_with(|| other.txid.as_bytes().cmp(self.txid.as_bytes()))
+    }
+}
+
+impl PartialOrd for CanonicalPriority {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Trabalho esperado para encontrar um bloco (~2^256 / alvo)
+fn block_work(block: &Block) -> u128 {
+    target_work(block.header.bits)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::mining::MinerConfig;
+    use crate::transaction::{TxInput, TxOutput};
 
     #[test]
     fn test_blockchain_creation() {
@@ -414,6 +1806,7 @@ mod tests {
             reward_script: vec![4, 5, 6],
             threads: 1,
             difficulty: 1, // Muito baixa para teste
+            throttle: None,
         };
         let miner = Miner::new(miner_config);
 
@@ -452,6 +1845,7 @@ mod tests {
                 to_script,
                 1000, // Valor
                 100,  // Taxa
+                None,
             )
             .unwrap();
 
@@ -461,6 +1855,1050 @@ mod tests {
         assert_eq!(tx.outputs[1].value, 3900); // 5000 - 1000 - 100
     }
 
+    #[test]
+    fn test_validate_transaction_rejects_invalid_unlock() {
+        let owner = shared::KeyPair::generate(shared::SignatureAlgorithm::MLDSA65).unwrap();
+        let thief = shared::KeyPair::generate(shared::SignatureAlgorithm::MLDSA65).unwrap();
+        let genesis_script = crate::script::p2pkh_script_pubkey(&owner.public_key);
+        let blockchain = Blockchain::new(NetworkParams::default(), genesis_script.clone()).unwrap();
+
+        let mut tx = blockchain
+            .create_transaction(&genesis_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+
+        // Sem script de desbloqueio
+        assert!(blockchain.validate_transaction(&tx).is_err());
+
+        // Assinado por uma chave que não corresponde ao hash do P2PKH
+        let signature =
+            shared::sign_transaction_hash(&tx.sighash(0, &genesis_script), &thief).unwrap();
+        tx.inputs[0].script_sig = crate::script::p2pkh_script_sig(&signature);
+        assert!(matches!(
+            blockchain.validate_transaction(&tx),
+            Err(BlockchainError::InvalidTransaction(_))
+        ));
+
+        // Script que qualquer um pode gastar continua válido
+        let open_script = vec![crate::script::OpCode::Op1 as u8];
+        let open_chain = Blockchain::new(NetworkParams::default(), open_script.clone()).unwrap();
+        let tx = open_chain
+            .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+        open_chain.validate_transaction(&tx).unwrap();
+    }
+
+    #[test]
+    fn test_dust_output_is_rejected() {
+        let open_script = vec![crate::script::OpCode::Op1 as u8];
+        let blockchain = Blockchain::new(NetworkParams::default(), open_script.clone()).unwrap();
+        let mut tx = blockchain
+            .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+        blockchain.validate_transaction(&tx).unwrap();
+
+        tx.outputs
+            .push(TxOutput::new(DEFAULT_DUST_THRESHOLD - 1, vec![7, 8, 9]));
+        let err = blockchain.validate_transaction(&tx).unwrap_err();
+        assert!(err.to_string().contains("dust threshold"), "{err}");
+
+        // Troco abaixo do limite vira taxa em vez de uma saída de poeira
+        let genesis_value = blockchain.get_balance(&open_script);
+        let tx = blockchain
+            .create_transaction(
+                &open_script,
+                vec![4, 5, 6],
+                genesis_value - 100 - (DEFAULT_DUST_THRESHOLD - 1),
+                100,
+                None,
+            )
+            .unwrap();
+        assert_eq!(tx.outputs.len(), 1);
+        blockchain.validate_transaction(&tx).unwrap();
+    }
+
+    #[test]
+    fn test_oversized_transaction_is_rejected() {
+        let open_script = vec![crate::script::OpCode::Op1 as u8];
+        let params = NetworkParams {
+            max_tx_size: 1_000,
+            ..NetworkParams::default()
+        };
+        let blockchain = Blockchain::new(params, open_script.clone()).unwrap();
+        let mut tx = blockchain
+            .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+        blockchain.validate_transaction(&tx).unwrap();
+
+        tx.outputs[0].value = DEFAULT_DUST_THRESHOLD;
+        tx.outputs.extend(vec![
+            TxOutput::new(DEFAULT_DUST_THRESHOLD, vec![4, 5, 6]);
+            60
+        ]);
+        assert!(tx.size() > 1_000);
+        let err = blockchain.validate_transaction(&tx).unwrap_err();
+        assert!(err.to_string().contains("exceeds maximum"), "{err}");
+    }
+
+    #[test]
+    fn test_coinbase_spend_requires_maturity() {
+        let open_script = vec![crate::script::OpCode::Op1 as u8];
+        let miner = Miner::new(MinerConfig {
+            reward_script: open_script.clone(),
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let mut blockchain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let mine_to = |blockchain: &mut Blockchain, height: u64| {
+            while blockchain.height() < height {
+                let block = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+                blockchain.add_block(block).unwrap();
+            }
+        };
+
+        mine_to(&mut blockchain, 1);
+        let coinbase = &blockchain.get_latest_block().transactions[0];
+        let outpoint = crate::utxo::OutPoint::new(coinbase.hash().unwrap(), 0);
+        assert!(
+            blockchain
+                .utxo_set()
+                .get_utxo(&outpoint)
+                .unwrap()
+                .is_coinbase
+        );
+        let spend = Transaction::new(
+            1,
+            vec![crate::transaction::TxInput::new(outpoint, vec![], 0)],
+            vec![crate::transaction::TxOutput::new(1000, vec![4, 5, 6])],
+            0,
+        );
+
+        // Incluída no bloco 51: apenas 50 blocos após a coinbase
+        mine_to(&mut blockchain, 50);
+        assert!(matches!(
+            blockchain.validate_transaction(&spend),
+            Err(BlockchainError::InvalidTransaction(_))
+        ));
+
+        // Incluída no bloco 101: 100 blocos após a coinbase
+        mine_to(&mut blockchain, 100);
+        blockchain.validate_transaction(&spend).unwrap();
+    }
+
+    #[test]
+    fn test_locked_output_spendable_from_unlock_height() {
+        // <3> OP_CHECKLOCKTIMEVERIFY OP_DROP OP_1
+        let mut locked_script = Vec::new();
+        crate::script::push_data(&mut locked_script, &[3]);
+        locked_script.push(crate::script::OpCode::OpCheckLockTimeVerify as u8);
+        locked_script.push(crate::script::OpCode::OpDrop as u8);
+        locked_script.push(crate::script::OpCode::Op1 as u8);
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let mut blockchain =
+            Blockchain::new(NetworkParams::default(), locked_script.clone()).unwrap();
+        let tx = blockchain
+            .create_transaction(&locked_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+
+        // Bloco 2 ainda não alcança a altura 3
+        let block = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        blockchain.add_block(block).unwrap();
+        assert!(blockchain.validate_transaction(&tx).is_err());
+
+        // O bloco 3 pode incluir o gasto
+        let block = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        blockchain.add_block(block).unwrap();
+        blockchain.validate_transaction(&tx).unwrap();
+    }
+
+    #[test]
+    fn test_transaction_lock_time_enforced() {
+        let open_script = vec![crate::script::OpCode::Op1 as u8];
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let mut blockchain =
+            Blockchain::new(NetworkParams::default(), open_script.clone()).unwrap();
+        let mut tx = blockchain
+            .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+        tx.lock_time = 2;
+
+        // Final apenas em blocos acima da altura do lock_time
+        assert!(blockchain.validate_transaction(&tx).is_err());
+        let block = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        blockchain.add_block(block).unwrap();
+        assert!(blockchain.validate_transaction(&tx).is_err());
+        let block = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        blockchain.add_block(block).unwrap();
+        blockchain.validate_transaction(&tx).unwrap();
+
+        // Sequência máxima em todos os inputs desativa o lock
+        let mut opted_out = tx.clone();
+        opted_out.lock_time = 1_000;
+        for input in &mut opted_out.inputs {
+            input.sequence = u32::MAX;
+        }
+        blockchain.validate_transaction(&opted_out).unwrap();
+    }
+
+    #[test]
+    fn test_get_transaction_by_id() {
+        let open_script = vec![crate::script::OpCode::Op1 as u8];
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let mut blockchain =
+            Blockchain::new(NetworkParams::default(), open_script.clone()).unwrap();
+        let tx = blockchain
+            .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+        let txid = tx.hash().unwrap();
+        let block = blockchain
+            .mine_next_block(&miner, vec![tx.clone()])
+            .unwrap()
+            .block;
+        blockchain.add_block(block).unwrap();
+
+        // Sem o índice, nenhuma busca é feita
+        assert!(blockchain.get_transaction(&txid).is_none());
+
+        // A ativação indexa os blocos já conectados
+        blockchain.enable_txindex().unwrap();
+        let (found, block) = blockchain.get_transaction(&txid).unwrap();
+        assert_eq!(found, &tx);
+        assert_eq!(block.height().unwrap(), 1);
+
+        // Blocos novos entram no índice ao serem conectados
+        let block = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        let coinbase_id = block.transactions[0].hash().unwrap();
+        blockchain.add_block(block).unwrap();
+        let (_, block) = blockchain.get_transaction(&coinbase_id).unwrap();
+        assert_eq!(block.height().unwrap(), 2);
+
+        assert!(blockchain
+            .get_transaction(&Hash256::keccak256(b"desconhecida"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_block_spending_same_output_twice_is_rejected() {
+        let open_script = vec![crate::script::OpCode::Op1 as u8];
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let mut blockchain =
+            Blockchain::new(NetworkParams::default(), open_script.clone()).unwrap();
+        let tx = blockchain
+            .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+        let mut conflicting = tx.clone();
+        conflicting.outputs[0].script_pubkey = vec![7, 8, 9];
+        let outpoint = tx.inputs[0].previous_output;
+
+        // Cada transação é válida sozinha, então o minerador monta o bloco
+        let block = blockchain
+            .mine_next_block(&miner, vec![tx, conflicting])
+            .unwrap()
+            .block;
+
+        let err = blockchain.add_block(block).unwrap_err();
+        let expected = format!(
+            "Double spend of output {}:{} within block",
+            outpoint.txid, outpoint.vout
+        );
+        assert!(
+            matches!(&err, BlockchainError::InvalidBlock(reason) if *reason == expected),
+            "{err}"
+        );
+        assert_eq!(blockchain.height(), 0);
+        assert!(blockchain.utxo_set().contains(&outpoint));
+    }
+
+    #[test]
+    fn test_mempool_selection_fits_block() {
+        let open_script = vec![crate::script::OpCode::Op1 as u8];
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        // Transação de 1 input e 2 outputs, como as de create_transaction
+        let tx_weight = Transaction::new(
+            1,
+            vec![TxInput::new(OutPoint::new(Hash256::zero(), 0), vec![], 0)],
+            vec![
+                TxOutput::new(0, vec![4, 5, 6]),
+                TxOutput::new(0, open_script.clone()),
+            ],
+            0,
+        )
+        .weight();
+        let reward_script = vec![0; Blockchain::COINBASE_SCRIPT_RESERVE];
+        let coinbase_weight = Transaction::coinbase(1, 0, reward_script).weight();
+        let exact_fit = Block::HEADER_SIZE * WITNESS_SCALE_FACTOR + coinbase_weight + tx_weight;
+
+        for (max_block_weight, expected) in [(exact_fit - 1, 0), (exact_fit, 1)] {
+            let params = NetworkParams {
+                max_block_weight,
+                ..NetworkParams::default()
+            };
+            let mut blockchain = Blockchain::new(params, open_script.clone()).unwrap();
+            let tx = blockchain
+                .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+                .unwrap();
+            let mut mempool = BondMempool::new();
+            mempool
+                .add_transaction(tx, blockchain.utxo_set(), 1, 0)
+                .unwrap();
+
+            let selected = blockchain.select_mempool_transactions(&mempool);
+            assert_eq!(selected.len(), expected);
+
+            let result = blockchain.mine_next_block(&miner, selected).unwrap();
+            blockchain.add_block(result.block).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_block_reward_halving() {
+        let params = NetworkParams {
+            halving_interval: 100,
+            ..NetworkParams::default()
+        };
+        let blockchain = Blockchain::new(params, vec![1, 2, 3]).unwrap();
+
+        // Constante dentro do intervalo
+        assert_eq!(blockchain.calculate_block_reward(1), 5000);
+        assert_eq!(blockchain.calculate_block_reward(99), 5000);
+
+        // Metade exatamente na fronteira
+        assert_eq!(blockchain.calculate_block_reward(100), 2500);
+        assert_eq!(blockchain.calculate_block_reward(199), 2500);
+        assert_eq!(blockchain.calculate_block_reward(200), 1250);
+
+        // 5000 >> 13 == 0
+        assert_eq!(blockchain.calculate_block_reward(1_300), 0);
+        assert_eq!(blockchain.calculate_block_reward(u64::MAX), 0);
+    }
+
+    #[test]
+    fn test_future_height_window() {
+        let mut blockchain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+
+        let tip_hash = blockchain.get_latest_block().hash().unwrap();
+        let far_ahead = miner
+            .mine_block(tip_hash, vec![], blockchain.height() + 1000, 5000)
+            .unwrap();
+        let err = blockchain.add_block(far_ahead.block).unwrap_err();
+        assert!(err.to_string().contains("acceptance window"));
+        assert_eq!(blockchain.height(), 0);
+
+        let next = blockchain.mine_next_block(&miner, vec![]).unwrap();
+        blockchain.add_block(next.block).unwrap();
+        assert_eq!(blockchain.height(), 1);
+    }
+
+    /// Refaz a prova de trabalho após alterar o cabeçalho
+    fn remine(block: &mut Block) {
+        while !block.header.meets_target().unwrap() {
+            block.header.nonce += 1;
+        }
+    }
+
+    #[test]
+    fn test_future_block_time_rejected() {
+        let mut blockchain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+
+        let mut future = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        future.header.timestamp = Utc::now() + Duration::hours(3);
+        remine(&mut future);
+        let err = blockchain.add_block(future).unwrap_err();
+        assert!(err.to_string().contains("too far in the future"));
+
+        let mut within = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        within.header.timestamp = Utc::now() + Duration::hours(1);
+        remine(&mut within);
+        blockchain.add_block(within).unwrap();
+        assert_eq!(blockchain.height(), 1);
+    }
+
+    #[test]
+    fn test_future_block_time_uses_network_time() {
+        let mut blockchain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let mut ahead = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        ahead.header.timestamp = Utc::now() + Duration::hours(3);
+        remine(&mut ahead);
+
+        // Com o horário da rede duas horas à frente, a janela se amplia
+        blockchain.set_time_offset(2 * 60 * 60);
+        blockchain.add_block(ahead).unwrap();
+        assert_eq!(blockchain.height(), 1);
+
+        blockchain.set_time_offset(-2 * 60 * 60);
+        let mut next = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        next.header.timestamp = Utc::now() + Duration::hours(3) + Duration::seconds(1);
+        remine(&mut next);
+        assert!(blockchain.add_block(next).is_err());
+    }
+
+    #[test]
+    fn test_block_before_median_time_past_rejected() {
+        let mut blockchain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        for _ in 0..3 {
+            let block = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+            blockchain.add_block(block).unwrap();
+        }
+        let median = blockchain.median_time_past();
+        assert_eq!(
+            median,
+            blockchain.get_block_by_height(2).unwrap().header.timestamp
+        );
+
+        let mut stale = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        stale.header.timestamp = median - Duration::seconds(1);
+        remine(&mut stale);
+        let err = blockchain.add_block(stale).unwrap_err();
+        assert!(err.to_string().contains("median time past"));
+        assert_eq!(blockchain.height(), 3);
+    }
+
+    fn mine_blocks(
+        blockchain: &mut Blockchain,
+        reward_script: Vec<u8>,
+        count: usize,
+    ) -> Vec<Block> {
+        let miner = Miner::new(MinerConfig {
+            reward_script,
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        (0..count)
+            .map(|_| {
+                let block = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+                blockchain.add_block(block.clone()).unwrap();
+                block
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_canonical_order_is_independent_of_input_order() {
+        let open_script = vec![crate::script::OpCode::Op1 as u8];
+        let mut blockchain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let spend = |outpoint: OutPoint, value: u64, fee: u64| {
+            Transaction::new(
+                1,
+                vec![TxInput::new(outpoint, vec![], 0)],
+                vec![TxOutput::new(value - fee, open_script.clone())],
+                0,
+            )
+        };
+        let mut txs = Vec::new();
+        for (seed, fee) in [(b"a", 300), (b"b", 100), (b"c", 300), (b"d", 200)] {
+            let txid = Hash256::keccak256(seed);
+            blockchain
+                .utxo_set
+                .add_utxo(Utxo::new(txid, 0, 10_000, open_script.clone(), 0));
+            txs.push(spend(OutPoint { txid, vout: 0 }, 10_000, fee));
+        }
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+
+        let forward = blockchain.mine_next_block(&miner, txs.clone()).unwrap();
+        let mut reversed = txs.clone();
+        reversed.reverse();
+        let backward = blockchain.mine_next_block(&miner, reversed).unwrap();
+        assert_eq!(forward.block.transactions, backward.block.transactions);
+        assert_eq!(
+            forward.block.header.merkle_root,
+            backward.block.header.merkle_root
+        );
+        assert!(forward.block.transactions[0].is_coinbase());
+
+        // Maior taxa primeiro; as duas de 300 pela ordem do txid
+        let fees: Vec<u64> = forward.block.transactions[1..]
+            .iter()
+            .map(|tx| 10_000 - tx.outputs[0].value)
+            .collect();
+        assert_eq!(fees, vec![300, 300, 200, 100]);
+        let tied: Vec<Hash256> = forward.block.transactions[1..3]
+            .iter()
+            .map(|tx| tx.hash().unwrap())
+            .collect();
+        assert!(tied[0].as_bytes() < tied[1].as_bytes());
+
+        // Um bloco válido em tudo, mas fora da ordem canônica, é rejeitado
+        blockchain.validate_block(&forward.block).unwrap();
+        let mut shuffled = forward.block.transactions[1..].to_vec();
+        shuffled.reverse();
+        let reward = blockchain.calculate_block_reward(1)
+            + blockchain.calculate_total_fees(&shuffled).unwrap();
+        let out_of_order = miner
+            .mine_block_with_difficulty(
+                blockchain.get_latest_block().hash().unwrap(),
+                shuffled,
+                1,
+                reward,
+                blockchain.get_next_difficulty(),
+            )
+            .unwrap()
+            .block;
+        let err = blockchain.validate_block(&out_of_order).unwrap_err();
+        assert!(err.to_string().contains("canonical order"), "{err}");
+    }
+
+    #[test]
+    fn test_create_transaction_skips_reserved_and_prefers_large_inputs() {
+        let mut blockchain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let genesis_output = blockchain.blocks[0].transactions[0].hash().unwrap();
+        for (seed, value) in [(b"a", 1_000), (b"b", 3_000), (b"c", 2_000)] {
+            let utxo = Utxo::new(Hash256::keccak256(seed), 0, value, vec![1, 2, 3], 0);
+            blockchain.utxo_set.add_utxo(utxo);
+        }
+        let input_values = |tx: &Transaction| -> Vec<u64> {
+            tx.inputs
+                .iter()
+                .map(|input| {
+                    blockchain
+                        .utxo_set
+                        .get_utxo(&input.previous_output)
+                        .unwrap()
+                        .value
+                })
+                .collect()
+        };
+
+        // 6.100 cabem em dois inputs (5.000 + 3.000), não em três ou quatro
+        let tx = blockchain
+            .create_transaction(&[1, 2, 3], vec![4, 5, 6], 6_000, 100, None)
+            .unwrap();
+        assert_eq!(input_values(&tx), vec![5_000, 3_000]);
+
+        // Com a coinbase do gênese gasta na mempool, ela não é selecionada
+        let reserved = HashSet::from([OutPoint {
+            txid: genesis_output,
+            vout: 0,
+        }]);
+        let tx = blockchain
+            .create_transaction(&[1, 2, 3], vec![4, 5, 6], 4_000, 100, Some(&reserved))
+            .unwrap();
+        assert_eq!(input_values(&tx), vec![3_000, 2_000]);
+        assert!(blockchain
+            .create_transaction(&[1, 2, 3], vec![4, 5, 6], 6_000, 100, Some(&reserved))
+            .is_err());
+    }
+
+    #[test]
+    fn test_network_params_differ_per_network() {
+        let networks = [
+            NetworkType::Mainnet,
+            NetworkType::Testnet,
+            NetworkType::Devnet,
+            NetworkType::Regtest,
+        ];
+        let params: Vec<NetworkParams> = networks
+            .iter()
+            .map(|network| NetworkParams::for_network(network.clone()))
+            .collect();
+
+        for (i, a) in params.iter().enumerate() {
+            assert_eq!(a.network, networks[i]);
+            for b in &params[i + 1..] {
+                assert_ne!(a.magic_bytes(), b.magic_bytes());
+                assert_ne!(a.hash().unwrap(), b.hash().unwrap());
+                assert_ne!(
+                    Block::genesis(a, vec![1, 2, 3]).unwrap().hash().unwrap(),
+                    Block::genesis(b, vec![1, 2, 3]).unwrap().hash().unwrap()
+                );
+            }
+        }
+
+        let mainnet = &params[0];
+        let regtest = &params[3];
+        assert!(mainnet.initial_difficulty > regtest.initial_difficulty);
+        assert_eq!(regtest.initial_difficulty, 1);
+        assert!(regtest.target_block_time < mainnet.target_block_time);
+        assert_eq!(
+            NetworkParams::for_network(NetworkType::Devnet)
+                .hash()
+                .unwrap(),
+            NetworkParams::default().hash().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_genesis_with_multiple_allocations() {
+        let params = NetworkParams::default();
+        let genesis = GenesisConfig {
+            allocations: vec![(vec![1], 1_000), (vec![2], 2_500), (vec![3], 500)],
+            timestamp: params.genesis_timestamp + 60,
+            premine: 4_000,
+        };
+        let blockchain =
+            Blockchain::with_genesis(params, &genesis, Box::new(MemoryStore::new())).unwrap();
+
+        assert_eq!(blockchain.get_balance(&[1]), 1_000);
+        assert_eq!(blockchain.get_balance(&[2]), 2_500);
+        assert_eq!(blockchain.get_balance(&[3]), 500);
+        assert_eq!(blockchain.stats().total_supply, 4_000);
+        assert_eq!(
+            blockchain.get_latest_block().header.timestamp.timestamp(),
+            genesis.timestamp
+        );
+        assert!(blockchain.verify_chain().is_ok());
+
+        // Alocações precisam somar exatamente a pré-mineração
+        let mismatched = GenesisConfig {
+            premine: 5_000,
+            ..genesis
+        };
+        assert!(Blockchain::with_genesis(
+            NetworkParams::default(),
+            &mismatched,
+            Box::new(MemoryStore::new())
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_export_and_import_bootstrap_file() {
+        let mut source = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        mine_blocks(&mut source, vec![4, 5, 6], 4);
+        let mut bootstrap = Vec::new();
+        source.export_to_writer(&mut bootstrap).unwrap();
+
+        let mut fresh = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        assert_eq!(fresh.import_from_reader(bootstrap.as_slice()).unwrap(), 4);
+        assert_eq!(fresh.height(), source.height());
+        assert_eq!(
+            fresh.get_latest_block().hash().unwrap(),
+            source.get_latest_block().hash().unwrap()
+        );
+        assert_eq!(
+            fresh.get_balance(&[4, 5, 6]),
+            source.get_balance(&[4, 5, 6])
+        );
+
+        // Reimportar não conecta nada; um arquivo truncado é rejeitado
+        assert_eq!(fresh.import_from_reader(bootstrap.as_slice()).unwrap(), 0);
+        let truncated = &bootstrap[..bootstrap.len() - 1];
+        let mut other = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        assert!(other.import_from_reader(truncated).is_err());
+        assert_eq!(other.height(), 3);
+    }
+
+    #[test]
+    fn test_verify_chain_detects_tampered_block() {
+        let mut blockchain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        mine_blocks(&mut blockchain, vec![4, 5, 6], 5);
+        assert!(blockchain.verify_chain().is_ok());
+
+        blockchain.blocks[3].header.merkle_root = Hash256::keccak256(b"tampered");
+        let error = blockchain.verify_chain().unwrap_err().to_string();
+        assert!(error.contains("height 3"), "{error}");
+    }
+
+    #[test]
+    fn test_locator_resolves_fork_with_divergent_peer() {
+        let mut node = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let shared = mine_blocks(&mut node, vec![4, 5, 6], 5);
+        let mut peer = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        for block in &shared {
+            peer.add_block(block.clone()).unwrap();
+        }
+        mine_blocks(&mut node, vec![4, 5, 6], 20);
+        let peer_branch = mine_blocks(&mut peer, vec![7, 8, 9], 8);
+
+        // 10 alturas densas, depois passos de 2, 4, 8 e 16 até o gênese
+        let locator = node.block_locator();
+        let heights: Vec<u64> = locator
+            .hashes
+            .iter()
+            .map(|hash| node.get_block_by_hash(hash).unwrap().height().unwrap())
+            .collect();
+        assert_eq!(
+            heights,
+            vec![25, 24, 23, 22, 21, 20, 19, 18, 17, 16, 14, 10, 2, 0]
+        );
+
+        // O localizador do par é denso perto da bifurcação: ancestral exato
+        assert_eq!(node.find_fork_point(&peer.block_locator()), Some(5));
+        // O do nó é esparso ali: o par acha o hash conhecido mais alto (2)
+        assert_eq!(peer.find_fork_point(&node.block_locator()), Some(2));
+
+        let headers = peer.headers_after(&node.block_locator(), 100);
+        assert_eq!(headers.len(), 11);
+        assert_eq!(headers[0], shared[2].header);
+        assert_eq!(headers[3], peer_branch[0].header);
+        assert_eq!(peer.headers_after(&node.block_locator(), 3).len(), 3);
+        node.validate_headers(&headers).unwrap();
+
+        // Cabeçalhos fora de ordem ou sem ancestral conhecido são rejeitados
+        let mut shuffled = headers.clone();
+        shuffled.swap(1, 2);
+        assert!(node.validate_headers(&shuffled).is_err());
+        assert!(node.validate_headers(&headers[4..]).is_err());
+
+        let other = Blockchain::new(NetworkParams::default(), vec![9, 9, 9]).unwrap();
+        assert_eq!(other.find_fork_point(&node.block_locator()), None);
+    }
+
+    #[test]
+    fn test_reorg_to_heavier_branch() {
+        let store = MemoryStore::new();
+        let mut node = Blockchain::with_store(
+            NetworkParams::default(),
+            vec![1, 2, 3],
+            Box::new(store.clone()),
+        )
+        .unwrap();
+        let mut rival = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        mine_blocks(&mut node, vec![4, 5, 6], 2);
+        let branch = mine_blocks(&mut rival, vec![7, 8, 9], 3);
+
+        // Ramo com o mesmo trabalho não substitui a cadeia ativa
+        node.add_block(branch[0].clone()).unwrap();
+        node.add_block(branch[1].clone()).unwrap();
+        assert_eq!(node.get_balance(&[4, 5, 6]), 10_000);
+        assert!(node.take_last_reorg().is_none());
+
+        node.add_block(branch[2].clone()).unwrap();
+        assert_eq!(node.height(), 3);
+        assert_eq!(
+            node.get_latest_block().hash().unwrap(),
+            branch[2].hash().unwrap()
+        );
+        assert_eq!(node.get_balance(&[4, 5, 6]), 0);
+        assert_eq!(node.get_balance(&[7, 8, 9]), 15_000);
+        assert_eq!(node.utxo_set().len(), rival.utxo_set().len());
+        assert_eq!(node.stats().reorg_depth, 2);
+
+        let reorg = node.take_last_reorg().unwrap();
+        assert_eq!(reorg.fork_height, 0);
+        assert_eq!(reorg.disconnected.len(), 2);
+        assert_eq!(reorg.connected.len(), 3);
+
+        // O store acompanha a nova cadeia
+        drop(node);
+        let reopened =
+            Blockchain::with_store(NetworkParams::default(), vec![1, 2, 3], Box::new(store))
+                .unwrap();
+        assert_eq!(reopened.height(), 3);
+        assert_eq!(reopened.get_balance(&[7, 8, 9]), 15_000);
+        assert_eq!(reopened.get_balance(&[4, 5, 6]), 0);
+    }
+
+    #[test]
+    fn test_reorg_deeper_than_max_depth_is_rejected() {
+        let params = NetworkParams {
+            max_reorg_depth: 2,
+            ..NetworkParams::default()
+        };
+        let mut node = Blockchain::new(params.clone(), vec![1, 2, 3]).unwrap();
+        let mut rival = Blockchain::new(params.clone(), vec![1, 2, 3]).unwrap();
+        mine_blocks(&mut node, vec![4, 5, 6], 3);
+        let branch = mine_blocks(&mut rival, vec![7, 8, 9], 5);
+        let tip = node.get_latest_block().hash().unwrap();
+
+        // O ramo diverge no gênese: trocar exigiria desfazer 3 blocos. O
+        // primeiro bloco é recusado e o resto do ramo fica sem pai conhecido
+        let err = node.add_block(branch[0].clone()).unwrap_err();
+        assert!(err.to_string().contains("max reorg depth"), "{err}");
+        for block in &branch[1..] {
+            assert!(node.add_block(block.clone()).is_err());
+        }
+        assert_eq!(node.height(), 3);
+        assert_eq!(node.get_latest_block().hash().unwrap(), tip);
+        assert!(node.take_last_reorg().is_none());
+        assert_eq!(node.stats().reorg_depth, 0);
+
+        // Dentro do limite a reorganização continua possível
+        let mut shallow = Blockchain::new(params, vec![1, 2, 3]).unwrap();
+        mine_blocks(&mut shallow, vec![4, 5, 6], 2);
+        for block in &branch {
+            shallow.add_block(block.clone()).unwrap();
+        }
+        assert_eq!(
+            shallow.get_latest_block().hash().unwrap(),
+            branch[4].hash().unwrap()
+        );
+    }
+
+    /// Bloco filho de `parent` com coinbase pagando `reward`
+    fn child_block(parent: &Block, reward: u64, reward_script: Vec<u8>) -> Block {
+        let height = parent.height().unwrap() + 1;
+        let transactions = vec![Transaction::coinbase(height, reward, reward_script)];
+        let header = crate::block::BlockHeader::new(
+            1,
+            parent.hash().unwrap(),
+            crate::block::calculate_merkle_root(&transactions).unwrap(),
+            Utc::now(),
+            parent.header.bits,
+            0,
+        );
+        let mut block = Block::new(header, transactions);
+        remine(&mut block);
+        block
+    }
+
+    #[test]
+    fn test_failed_reorg_drops_branch_from_index() {
+        let mut node = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let mut rival = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let mined = mine_blocks(&mut node, vec![4, 5, 6], 3);
+        let branch = mine_blocks(&mut rival, vec![7, 8, 9], 2);
+
+        // O terceiro bloco do ramo paga demais no coinbase e só falha ao
+        // ser conectado, depois que os dois primeiros já entraram
+        let invalid = child_block(&branch[1], 1_000_000, vec![7, 8, 9]);
+        let tail = child_block(&invalid, 5_000, vec![7, 8, 9]);
+        node.add_block(branch[0].clone()).unwrap();
+        node.add_block(branch[1].clone()).unwrap();
+        node.add_block(invalid.clone()).unwrap();
+        assert!(node.add_block(tail).is_err());
+
+        assert_eq!(node.height(), 3);
+        assert_eq!(
+            node.get_latest_block().hash().unwrap(),
+            mined[2].hash().unwrap()
+        );
+        for block in &branch {
+            assert!(node.get_block_by_hash(&block.hash().unwrap()).is_none());
+        }
+        for block in &mined {
+            let hash = block.hash().unwrap();
+            assert_eq!(node.get_block_by_hash(&hash).unwrap().hash().unwrap(), hash);
+        }
+
+        // A cadeia continua aceitando filhos do ramo e novos blocos
+        node.add_block(child_block(&branch[1], 5_000, vec![7, 8, 9]))
+            .unwrap();
+        mine_blocks(&mut node, vec![4, 5, 6], 1);
+        assert_eq!(node.height(), 4);
+        assert_eq!(node.get_balance(&[4, 5, 6]), 20_000);
+    }
+
+    #[test]
+    fn test_chain_tips_report_active_and_stale_branches() {
+        let mut node = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let mut rival = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let mined = mine_blocks(&mut node, vec![4, 5, 6], 2);
+        let branch = mine_blocks(&mut rival, vec![7, 8, 9], 3);
+
+        let tips = node.get_chain_tips();
+        assert_eq!(tips.len(), 1);
+        assert_eq!(tips[0].status, ChainTipStatus::Active);
+        assert_eq!(tips[0].height, 2);
+
+        // Ramo concorrente mais curto fica como fork válido
+        node.add_block(branch[0].clone()).unwrap();
+        let tips = node.get_chain_tips();
+        assert_eq!(tips.len(), 2);
+        assert_eq!(tips[0].hash, mined[1].hash().unwrap());
+        assert_eq!(tips[1].hash, branch[0].hash().unwrap());
+        assert_eq!(
+            (tips[1].height, tips[1].branch_len, tips[1].status),
+            (1, 1, ChainTipStatus::ValidFork)
+        );
+
+        // Depois da reorganização, a antiga cadeia ativa vira o fork
+        node.add_block(branch[1].clone()).unwrap();
+        node.add_block(branch[2].clone()).unwrap();
+        let tips = node.get_chain_tips();
+        assert_eq!(tips.len(), 2);
+        assert_eq!(tips[0].hash, branch[2].hash().unwrap());
+        assert_eq!(tips[0].status, ChainTipStatus::Active);
+        assert_eq!(tips[1].hash, mined[1].hash().unwrap());
+        assert_eq!(
+            (tips[1].height, tips[1].branch_len, tips[1].status),
+            (2, 2, ChainTipStatus::ValidFork)
+        );
+        assert_eq!(tips[1].status.to_string(), "valid-fork");
+    }
+
+    #[test]
+    fn test_chain_tips_report_invalid_branch() {
+        let mut node = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        mine_blocks(&mut node, vec![4, 5, 6], 1);
+        let genesis = node.get_block_by_height(0).unwrap().clone();
+
+        // Coinbase acima da recompensa só falha ao conectar o ramo
+        let inflated = child_block(&genesis, 1_000_000, vec![7, 8, 9]);
+        let on_top = child_block(&inflated, 5_000, vec![7, 8, 9]);
+        node.add_block(inflated.clone()).unwrap();
+        assert!(node.add_block(on_top.clone()).is_err());
+        assert_eq!(node.height(), 1);
+        assert_eq!(node.get_balance(&[4, 5, 6]), 5_000);
+
+        let tips = node.get_chain_tips();
+        assert_eq!(tips.len(), 2);
+        assert_eq!(tips[0].status, ChainTipStatus::Active);
+        assert_eq!(tips[1].hash, on_top.hash().unwrap());
+        assert_eq!(
+            (tips[1].height, tips[1].branch_len, tips[1].status),
+            (2, 2, ChainTipStatus::Invalid)
+        );
+
+        // Blocos sobre um bloco inválido são recusados
+        let descendant = child_block(&inflated, 5_000, vec![1]);
+        assert!(node.add_block(descendant).is_err());
+    }
+
+    #[test]
+    fn test_transaction_finality_follows_active_chain() {
+        let mut node = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let mut rival = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let mined = mine_blocks(&mut node, vec![4, 5, 6], 1);
+        let txid = mined[0].transactions[0].hash().unwrap();
+
+        assert!(node.is_transaction_final(&txid, 1));
+        assert!(!node.is_transaction_final(&txid, 3));
+        mine_blocks(&mut node, vec![4, 5, 6], 2);
+        assert!(node.is_transaction_final(&txid, 3));
+        assert!(!node.is_transaction_final(&Hash256::zero(), 0));
+
+        // Um ramo mais pesado remove o bloco da transação
+        for block in mine_blocks(&mut rival, vec![7, 8, 9], 4) {
+            node.add_block(block).unwrap();
+        }
+        assert_eq!(node.stats().reorg_depth, 3);
+        assert!(!node.is_transaction_final(&txid, 1));
+    }
+
+    #[test]
+    fn test_reopen_from_store() {
+        let store = MemoryStore::new();
+        let genesis_script = vec![1, 2, 3];
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+
+        let tip_hash = {
+            let mut blockchain = Blockchain::with_store(
+                NetworkParams::default(),
+                genesis_script.clone(),
+                Box::new(store.clone()),
+            )
+            .unwrap();
+
+            for _ in 0..10 {
+                let result = blockchain.mine_next_block(&miner, vec![]).unwrap();
+                blockchain.add_block(result.block).unwrap();
+            }
+
+            blockchain.get_latest_block().hash().unwrap()
+        };
+
+        // Reabrir a partir do mesmo store não recria o gênese
+        let reopened =
+            Blockchain::with_store(NetworkParams::default(), vec![9, 9, 9], Box::new(store))
+                .unwrap();
+
+        assert_eq!(reopened.height(), 10);
+        assert_eq!(reopened.get_latest_block().hash().unwrap(), tip_hash);
+        assert_eq!(reopened.get_balance(&genesis_script), 5000);
+        assert_eq!(reopened.get_balance(&[4, 5, 6]), 50_000);
+        assert_eq!(reopened.get_balance(&[9, 9, 9]), 0);
+        assert_eq!(reopened.utxo_set().len(), 11);
+    }
+
+    #[test]
+    fn test_reopen_with_different_params_fails() {
+        let store = MemoryStore::new();
+        Blockchain::with_store(
+            NetworkParams::default(),
+            vec![1, 2, 3],
+            Box::new(store.clone()),
+        )
+        .unwrap();
+
+        let other = NetworkParams {
+            target_block_time: 60,
+            ..NetworkParams::default()
+        };
+        assert!(Blockchain::with_store(other, vec![1, 2, 3], Box::new(store)).is_err());
+    }
+
+    #[test]
+    fn test_simulate_transaction() {
+        // Script que qualquer um pode gastar (OP_1)
+        let genesis_script = vec![crate::script::OpCode::Op1 as u8];
+        let blockchain = Blockchain::new(NetworkParams::default(), genesis_script.clone()).unwrap();
+        let utxos_before = blockchain.utxo_set().len();
+
+        let tx = blockchain
+            .create_transaction(&genesis_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+        let result = blockchain.simulate_transaction(&tx).unwrap();
+
+        assert!(result.is_valid());
+        assert!(result.inputs_resolved);
+        assert!(result.scripts_valid);
+        assert_eq!(result.fee, Some(100));
+        assert_eq!(blockchain.utxo_set().len(), utxos_before);
+
+        // Outputs maiores que os inputs
+        let mut underfunded = tx;
+        underfunded.outputs[0].value = 10_000;
+        let result = blockchain.simulate_transaction(&underfunded).unwrap();
+
+        assert!(!result.is_valid());
+        assert!(result.inputs_resolved);
+        assert_eq!(result.fee, None);
+        assert!(matches!(
+            result.error,
+            Some(BlockchainError::InsufficientFunds)
+        ));
+    }
+
     #[test]
     fn test_blockchain_stats() {
         let network_params = NetworkParams::default();
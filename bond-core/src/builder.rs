@@ -0,0 +1,254 @@
+//! Construção de transações P2PKH
+//!
+//! O `TransactionBuilder` reúne os destinatários e a taxa por byte e, em
+//! `build`, seleciona UTXOs da chave, calcula a taxa pelo tamanho da
+//! transação já assinada, devolve o troco e assina todos os inputs.
+
+use crate::blockchain::DEFAULT_DUST_THRESHOLD;
+use crate::script::{p2pkh_script_pubkey, p2pkh_script_sig};
+use crate::transaction::{Transaction, TxInput, TxOutput};
+use crate::utxo::UtxoSet;
+use shared::{sign_transaction_hash, BlockchainError, Hash256, KeyPair, Result};
+
+/// Taxa por byte padrão em Elos
+pub const DEFAULT_FEE_RATE: u64 = 1;
+
+/// Monta transações P2PKH a partir dos UTXOs de uma chave
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder {
+    recipients: Vec<TxOutput>,
+    fee_rate: u64,
+    lock_time: u32,
+    dust_threshold: u64,
+}
+
+impl Default for TransactionBuilder {
+    fn default() -> Self {
+        Self {
+            recipients: Vec::new(),
+            fee_rate: DEFAULT_FEE_RATE,
+            lock_time: 0,
+            dust_threshold: DEFAULT_DUST_THRESHOLD,
+        }
+    }
+}
+
+impl TransactionBuilder {
+    /// Cria um builder sem destinatários
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adiciona um output para `script` com `amount` Elos
+    #[must_use]
+    pub fn add_recipient(mut self, script: Vec<u8>, amount: u64) -> Self {
+        self.recipients.push(TxOutput::new(amount, script));
+        self
+    }
+
+    /// Define a taxa por byte (Elos/byte)
+    #[must_use]
+    pub const fn set_fee_rate(mut self, fee_rate: u64) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    /// Define o `lock_time` da transação
+    #[must_use]
+    pub const fn set_lock_time(mut self, lock_time: u32) -> Self {
+        self.lock_time = lock_time;
+        self
+    }
+
+    /// Define o valor mínimo do troco; abaixo dele o troco fica como taxa
+    #[must_use]
+    pub const fn set_dust_threshold(mut self, dust_threshold: u64) -> Self {
+        self.dust_threshold = dust_threshold;
+        self
+    }
+
+    /// Monta e assina a transação
+    ///
+    /// Gasta UTXOs do script P2PKH de `keypair` até cobrir os destinatários
+    /// mais a taxa de `fee_rate` por byte de `Transaction::size` da
+    /// transação assinada, que cresce com cada input selecionado. O que
+    /// sobra vai para `change_script`, a menos que fique abaixo do limite de
+    /// poeira: nesse caso, como em `Blockchain::create_transaction`, o troco
+    /// é omitido e vira taxa.
+    ///
+    /// Cada input é assinado sobre o seu próprio `Transaction::sighash`,
+    /// calculado com o script P2PKH do dono no lugar do `script_sig`.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se não houver destinatários, se algum valor for zero,
+    /// se os UTXOs da chave não cobrirem valor e taxa ou se a assinatura
+    /// falhar
+    pub fn build(
+        &self,
+        utxo_set: &UtxoSet,
+        change_script: Vec<u8>,
+        keypair: &KeyPair,
+    ) -> Result<Transaction> {
+        if self.recipients.is_empty() {
+            return Err(BlockchainError::InvalidTransaction(
+                "Transaction has no recipients".to_string(),
+            ));
+        }
+        if self.recipients.iter().any(|output| output.value == 0) {
+            return Err(BlockchainError::InvalidTransaction(
+                "Recipient amount must be positive".to_string(),
+            ));
+        }
+
+        let amount = self
+            .recipients
+            .iter()
+            .try_fold(0u64, |total, output| total.checked_add(output.value))
+            .ok_or_else(|| BlockchainError::InvalidTransaction("Amount overflow".to_string()))?;
+
+        let owner_script = p2pkh_script_pubkey(&keypair.public_key);
+        let mut outputs = self.recipients.clone();
+        outputs.push(TxOutput::new(0, change_script));
+
+        // As assinaturas de uma chave têm tamanho fixo, então um script de
+        // desbloqueio provisório já dá o tamanho final de cada input
+        let placeholder = p2pkh_script_sig(&sign_transaction_hash(&Hash256::zero(), keypair)?);
+
+        // A taxa depende do número de inputs, que depende da taxa
+        let mut target = amount;
+        let mut tx = loop {
+            let inputs: Vec<TxInput> = utxo_set
+                .find_utxos_for_amount(&owner_script, target)?
+                .iter()
+                .map(|utxo| TxInput::new(utxo.outpoint(), placeholder.clone(), 0))
+                .collect();
+            let tx = Transaction::new(1, inputs, outputs.clone(), self.lock_time);
+
+            let fee = self.fee_for(&tx)?;
+            let needed = amount
+                .checked_add(fee)
+                .ok_or_else(|| BlockchainError::InvalidTransaction("Fee overflow".to_string()))?;
+            if tx.total_input_value(utxo_set)? >= needed {
+                break tx;
+            }
+            target = needed;
+        };
+
+        let fee = self.fee_for(&tx)?;
+        let change = tx.total_input_value(utxo_set)? - amount - fee;
+        if change < self.dust_threshold.max(1) {
+            tx.outputs.pop();
+        } else if let Some(output) = tx.outputs.last_mut() {
+            output.value = change;
+        }
+
+        // Todos os inputs gastam o script do dono, mas cada um assina o seu sighash
+        let signatures = (0..tx.inputs.len())
+            .map(|index| sign_transaction_hash(&tx.sighash(index, &owner_script), keypair))
+            .collect::<Result<Vec<_>>>()?;
+        for (input, signature) in tx.inputs.iter_mut().zip(&signatures) {
+            input.script_sig = p2pkh_script_sig(signature);
+        }
+
+        Ok(tx)
+    }
+
+    /// Taxa de `tx` segundo `fee_rate`
+    fn fee_for(&self, tx: &Transaction) -> Result<u64> {
+        (tx.size() as u64)
+            .checked_mul(self.fee_rate)
+            .ok_or_else(|| BlockchainError::InvalidTransaction("Fee overflow".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script::{ScriptContext, ScriptVM};
+    use crate::utxo::Utxo;
+    use shared::{Hash256, SignatureAlgorithm};
+
+    fn funded(keypair: &KeyPair, values: &[u64]) -> UtxoSet {
+        let script = p2pkh_script_pubkey(&keypair.public_key);
+        let mut utxo_set = UtxoSet::new();
+        for (i, value) in values.iter().enumerate() {
+            let txid = Hash256::keccak256(&i.to_le_bytes());
+            utxo_set.add_utxo(Utxo::new(txid, 0, *value, script.clone(), 0));
+        }
+        utxo_set
+    }
+
+    #[test]
+    fn test_builder_balances_and_signs() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
+        let utxo_set = funded(&keypair, &[17_500, 17_500, 17_500]);
+
+        let tx = TransactionBuilder::new()
+            .add_recipient(vec![1], 2_500)
+            .add_recipient(vec![2], 1_500)
+            .set_fee_rate(2)
+            .build(&utxo_set, vec![9], &keypair)
+            .unwrap();
+
+        // 4.000 + taxa exigem dois inputs
+        assert_eq!(tx.inputs.len(), 2);
+        assert_eq!(tx.outputs.len(), 3);
+        let fee = tx.size() as u64 * 2;
+        assert_eq!(tx.fee(&utxo_set).unwrap(), fee);
+        assert_eq!(tx.outputs[2].script_pubkey, vec![9]);
+        assert_eq!(tx.outputs[2].value, 35_000 - 4_000 - fee);
+
+        for (index, input) in tx.inputs.iter().enumerate() {
+            let utxo = utxo_set.get_utxo(&input.previous_output).unwrap();
+            let context = ScriptContext::new(tx.sighash(index, &utxo.script), index);
+            assert!(ScriptVM::verify(&input.script_sig, &utxo.script, &context).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_builder_folds_dust_change_into_fee() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
+        let build = |value: u64| {
+            let utxo_set = funded(&keypair, &[value]);
+            let tx = TransactionBuilder::new()
+                .add_recipient(vec![1], 1_000)
+                .build(&utxo_set, vec![9], &keypair)
+                .unwrap();
+            let fee = tx.fee(&utxo_set).unwrap();
+            (tx, fee)
+        };
+
+        // Taxa de uma transação com troco, que não depende dos valores
+        let (tx, fee) = build(100_000);
+        assert_eq!(tx.outputs.len(), 2);
+
+        // Troco exatamente no limite de poeira é devolvido
+        let (tx, paid) = build(1_000 + fee + DEFAULT_DUST_THRESHOLD);
+        assert_eq!(tx.outputs.len(), 2);
+        assert_eq!(tx.outputs[1].value, DEFAULT_DUST_THRESHOLD);
+        assert_eq!(paid, fee);
+
+        // Um Elo abaixo do limite, o troco vira taxa
+        let (tx, paid) = build(1_000 + fee + DEFAULT_DUST_THRESHOLD - 1);
+        assert_eq!(tx.outputs.len(), 1);
+        assert_eq!(tx.outputs[0].value, 1_000);
+        assert_eq!(paid, fee + DEFAULT_DUST_THRESHOLD - 1);
+    }
+
+    #[test]
+    fn test_builder_insufficient_funds() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
+        let utxo_set = funded(&keypair, &[1_000]);
+
+        // O valor cabe, mas não sobra para a taxa
+        let result = TransactionBuilder::new()
+            .add_recipient(vec![1], 1_000)
+            .build(&utxo_set, vec![9], &keypair);
+        assert!(matches!(result, Err(BlockchainError::InsufficientFunds)));
+
+        let result = TransactionBuilder::new().build(&utxo_set, vec![9], &keypair);
+        assert!(result.is_err());
+    }
+}
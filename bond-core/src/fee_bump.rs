@@ -0,0 +1,398 @@
+//! Aumento automático de taxa para transações presas
+//!
+//! A política é opt-in: a carteira registra as transações que enviou em um
+//! `FeeBumper`, e a cada novo bloco as que continuam sem confirmação há
+//! `bump_after_blocks` blocos recebem uma taxa maior. A nova versão tira a
+//! diferença do output de troco, é assinada novamente e substitui a
+//! anterior na mempool via RBF.
+
+use crate::mempool::BondMempool;
+use crate::transaction::Transaction;
+use crate::utxo::UtxoSet;
+use shared::{BlockchainError, Hash256, Result};
+use std::collections::HashMap;
+
+/// Política de aumento automático de taxa
+#[derive(Debug, Clone)]
+pub struct FeeBumpPolicy {
+    /// Blocos sem confirmação antes de cada aumento
+    pub bump_after_blocks: u64,
+    /// Quanto a taxa sobe a cada aumento
+    pub fee_step: u64,
+    /// Taxa máxima que a carteira aceita pagar
+    pub max_fee: u64,
+}
+
+impl Default for FeeBumpPolicy {
+    fn default() -> Self {
+        Self {
+            bump_after_blocks: 6, // ~1 hora com blocos de 10 minutos
+            fee_step: 1_000,
+            max_fee: 10_000,
+        }
+    }
+}
+
+/// Resultado de `FeeBumper::bump_stuck`
+#[derive(Debug, Default)]
+pub struct BumpReport {
+    /// Substitutas aceitas na mempool, que devem ser retransmitidas
+    pub bumped: Vec<Transaction>,
+    /// Transações cujo aumento falhou, com o motivo; continuam acompanhadas
+    pub failed: Vec<(Hash256, BlockchainError)>,
+}
+
+/// Transação acompanhada pelo `FeeBumper`
+#[derive(Debug, Clone)]
+struct TrackedTransaction {
+    tx: Transaction,
+    change_index: usize,
+    fee: u64,
+    last_broadcast_height: u64,
+}
+
+/// Acompanha transações enviadas e aumenta a taxa das que ficam presas
+#[derive(Debug, Clone, Default)]
+pub struct FeeBumper {
+    /// Política aplicada a todas as transações acompanhadas
+    pub policy: FeeBumpPolicy,
+    tracked: HashMap<Hash256, TrackedTransaction>,
+}
+
+impl FeeBumper {
+    /// Cria um acompanhador com a política indicada
+    #[must_use]
+    pub fn new(policy: FeeBumpPolicy) -> Self {
+        Self {
+            policy,
+            tracked: HashMap::new(),
+        }
+    }
+
+    /// Passa a acompanhar uma transação transmitida na altura `height`
+    ///
+    /// `change_index` indica o output de troco, de onde sai a taxa extra.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a transação não sinalizar RBF, se o índice de troco
+    /// não existir ou se a taxa não puder ser calculada
+    pub fn track(
+        &mut self,
+        tx: Transaction,
+        change_index: usize,
+        utxo_set: &UtxoSet,
+        height: u64,
+    ) -> Result<Hash256> {
+        if !tx.signals_rbf() {
+            return Err(BlockchainError::InvalidTransaction(
+                "Transaction does not signal replacement".to_string(),
+            ));
+        }
+        if change_index >= tx.outputs.len() {
+            return Err(BlockchainError::InvalidTransaction(
+                "Change output index out of range".to_string(),
+            ));
+        }
+
+        let txid = tx.hash()?;
+        let fee = tx.fee(utxo_set)?;
+        self.tracked.insert(
+            txid,
+            TrackedTransaction {
+                tx,
+                change_index,
+                fee,
+                last_broadcast_height: height,
+            },
+        );
+        Ok(txid)
+    }
+
+    /// Para de acompanhar transações confirmadas em um bloco
+    pub fn remove_confirmed(&mut self, transactions: &[Transaction]) {
+        for tx in transactions {
+            if let Ok(txid) = tx.hash() {
+                self.tracked.remove(&txid);
+            }
+        }
+    }
+
+    /// Aumenta a taxa das transações presas há `bump_after_blocks` blocos
+    ///
+    /// Cada substituta é assinada por `sign` e colocada na mempool no lugar
+    /// da original. Transações que já pagam `max_fee`, ou cujo troco não
+    /// cobre o aumento sem cair abaixo do limite de poeira da mempool,
+    /// continuam acompanhadas sem novos aumentos.
+    ///
+    /// Uma assinatura que falha ou uma substituição recusada pela mempool
+    /// não interrompe as demais: o relatório traz as substitutas aceitas,
+    /// que devem ser retransmitidas, e os erros de cada transação que
+    /// continua com a taxa anterior.
+    pub fn bump_stuck<F>(
+        &mut self,
+        height: u64,
+        block_time: u64,
+        mempool: &mut BondMempool,
+        utxo_set: &UtxoSet,
+        mut sign: F,
+    ) -> BumpReport
+    where
+        F: FnMut(&mut Transaction) -> Result<()>,
+    {
+        let stuck: Vec<Hash256> = self
+            .tracked
+            .iter()
+            .filter(|(_, tracked)| {
+                height.saturating_sub(tracked.last_broadcast_height)
+                    >= self.policy.bump_after_blocks
+            })
+            .map(|(txid, _)| *txid)
+            .collect();
+
+        let mut report = BumpReport::default();
+        for txid in stuck {
+            let Some(tracked) = self.tracked.get(&txid) else {
+                continue;
+            };
+
+            let new_fee = tracked
+                .fee
+                .saturating_add(self.policy.fee_step)
+                .min(self.policy.max_fee);
+            let increase = new_fee.saturating_sub(tracked.fee);
+            let change = tracked.tx.outputs[tracked.change_index].value;
+            if increase == 0 || change < increase.saturating_add(mempool.dust_threshold()) {
+                continue;
+            }
+
+            let mut replacement = tracked.tx.clone();
+            replacement.outputs[tracked.change_index].value = change - increase;
+            for input in &mut replacement.inputs {
+                input.script_sig.clear();
+            }
+            if let Err(e) = sign(&mut replacement) {
+                report.failed.push((txid, e));
+                continue;
+            }
+
+            let new_txid = match mempool.replace_transaction(
+                replacement.clone(),
+                utxo_set,
+                height + 1,
+                block_time,
+            ) {
+                Ok(new_txid) => new_txid,
+                Err(e) => {
+                    report.failed.push((txid, e.into()));
+                    continue;
+                }
+            };
+
+            let change_index = tracked.change_index;
+            self.tracked.remove(&txid);
+            self.tracked.insert(
+                new_txid,
+                TrackedTransaction {
+                    tx: replacement.clone(),
+                    change_index,
+                    fee: new_fee,
+                    last_broadcast_height: height,
+                },
+            );
+            report.bumped.push(replacement);
+        }
+
+        report
+    }
+
+    /// Taxa atual de uma transação acompanhada
+    #[must_use]
+    pub fn current_fee(&self, txid: &Hash256) -> Option<u64> {
+        self.tracked.get(txid).map(|tracked| tracked.fee)
+    }
+
+    /// Número de transações acompanhadas
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tracked.len()
+    }
+
+    /// Verifica se nenhuma transação está sendo acompanhada
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tracked.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{TxInput, TxOutput};
+    use crate::utxo::{OutPoint, Utxo};
+
+    fn setup() -> (UtxoSet, BondMempool, Transaction) {
+        let mut utxo_set = UtxoSet::new();
+        let txid = Hash256::keccak256(b"funding");
+        utxo_set.add_utxo(Utxo::new(txid, 0, 10_000, vec![1], 0));
+
+        // 1.000 para o destinatário, 8.900 de troco, taxa 100
+        let tx = Transaction::new(
+            1,
+            vec![TxInput::new(OutPoint::new(txid, 0), vec![], 0)],
+            vec![TxOutput::new(1_000, vec![2]), TxOutput::new(8_900, vec![3])],
+            0,
+        );
+        let mut mempool = BondMempool::new();
+        mempool
+            .add_transaction(tx.clone(), &utxo_set, 1, 0)
+            .unwrap();
+        (utxo_set, mempool, tx)
+    }
+
+    fn no_signature(_: &mut Transaction) -> Result<()> {
+        Ok(())
+    }
+
+    #[test]
+    fn test_stuck_transaction_is_bumped_after_threshold() {
+        let (utxo_set, mut mempool, tx) = setup();
+        let mut bumper = FeeBumper::new(FeeBumpPolicy {
+            bump_after_blocks: 3,
+            fee_step: 400,
+            max_fee: 2_000,
+        });
+        let original_id = bumper.track(tx, 1, &utxo_set, 10).unwrap();
+
+        let report = bumper.bump_stuck(12, 0, &mut mempool, &utxo_set, no_signature);
+        assert!(report.bumped.is_empty());
+
+        let mut signed = 0;
+        let report = bumper.bump_stuck(13, 0, &mut mempool, &utxo_set, |_| {
+            signed += 1;
+            Ok(())
+        });
+        assert!(report.failed.is_empty());
+        let bumped = report.bumped;
+        assert_eq!(bumped.len(), 1);
+        assert_eq!(signed, 1);
+
+        let replacement = &bumped[0];
+        let replacement_id = replacement.hash().unwrap();
+        assert_eq!(replacement.fee(&utxo_set).unwrap(), 500);
+        assert_eq!(replacement.outputs[0].value, 1_000);
+        assert_eq!(bumper.current_fee(&replacement_id), Some(500));
+        assert!(!mempool.contains(&original_id));
+        assert!(mempool.contains(&replacement_id));
+        assert_eq!(bumper.len(), 1);
+
+        // O contador recomeça a partir da retransmissão
+        assert!(bumper
+            .bump_stuck(15, 0, &mut mempool, &utxo_set, no_signature)
+            .bumped
+            .is_empty());
+    }
+
+    #[test]
+    fn test_bumping_stops_at_max_fee() {
+        let (utxo_set, mut mempool, tx) = setup();
+        let mut bumper = FeeBumper::new(FeeBumpPolicy {
+            bump_after_blocks: 1,
+            fee_step: 400,
+            max_fee: 1_000,
+        });
+        bumper.track(tx, 1, &utxo_set, 0).unwrap();
+
+        let mut fees = Vec::new();
+        for height in 1..=5 {
+            for tx in bumper
+                .bump_stuck(height, 0, &mut mempool, &utxo_set, no_signature)
+                .bumped
+            {
+                fees.push(tx.fee(&utxo_set).unwrap());
+            }
+        }
+
+        assert_eq!(fees, vec![500, 900, 1_000]);
+        assert_eq!(mempool.len(), 1);
+    }
+
+    #[test]
+    fn test_refused_replacement_does_not_stop_other_bumps() {
+        let (mut utxo_set, mut mempool, tx) = setup();
+        let mut bumper = FeeBumper::new(FeeBumpPolicy {
+            bump_after_blocks: 1,
+            fee_step: 400,
+            max_fee: 2_000,
+        });
+        bumper.track(tx, 1, &utxo_set, 0).unwrap();
+
+        // A mempool guarda um gasto concorrente de taxa 3.000, que a
+        // substituta de taxa 500 não consegue tirar
+        let contested = OutPoint::new(Hash256::keccak256(b"contested"), 0);
+        utxo_set.add_utxo(Utxo::new(contested.txid, 0, 10_000, vec![1], 0));
+        let rival = Transaction::new(
+            1,
+            vec![TxInput::new(contested, vec![], 0)],
+            vec![TxOutput::new(7_000, vec![4])],
+            0,
+        );
+        mempool
+            .add_transaction(rival.clone(), &utxo_set, 1, 0)
+            .unwrap();
+        let stuck = Transaction::new(
+            1,
+            vec![TxInput::new(contested, vec![], 0)],
+            vec![TxOutput::new(1_000, vec![2]), TxOutput::new(8_900, vec![3])],
+            0,
+        );
+        let stuck_id = bumper.track(stuck, 1, &utxo_set, 0).unwrap();
+
+        let report = bumper.bump_stuck(1, 0, &mut mempool, &utxo_set, no_signature);
+        assert_eq!(report.bumped.len(), 1);
+        assert_eq!(report.bumped[0].fee(&utxo_set).unwrap(), 500);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, stuck_id);
+        assert!(mempool.contains(&rival.hash().unwrap()));
+        assert!(mempool.contains(&report.bumped[0].hash().unwrap()));
+
+        // A recusada continua acompanhada com a taxa anterior
+        assert_eq!(bumper.len(), 2);
+        assert_eq!(bumper.current_fee(&stuck_id), Some(100));
+    }
+
+    #[test]
+    fn test_bump_keeps_change_above_dust_threshold() {
+        let mut utxo_set = UtxoSet::new();
+        let txid = Hash256::keccak256(b"funding");
+        utxo_set.add_utxo(Utxo::new(txid, 0, 10_000, vec![1], 0));
+        let mut mempool = BondMempool::new();
+        let dust = mempool.dust_threshold();
+
+        // Troco de 400 + poeira - 1: o aumento de 400 deixaria poeira
+        let tx = Transaction::new(
+            1,
+            vec![TxInput::new(OutPoint::new(txid, 0), vec![], 0)],
+            vec![
+                TxOutput::new(9_500 - dust + 1, vec![2]),
+                TxOutput::new(400 + dust - 1, vec![3]),
+            ],
+            0,
+        );
+        mempool
+            .add_transaction(tx.clone(), &utxo_set, 1, 0)
+            .unwrap();
+        let mut bumper = FeeBumper::new(FeeBumpPolicy {
+            bump_after_blocks: 1,
+            fee_step: 400,
+            max_fee: 2_000,
+        });
+        let txid = bumper.track(tx, 1, &utxo_set, 0).unwrap();
+
+        let report = bumper.bump_stuck(1, 0, &mut mempool, &utxo_set, no_signature);
+        assert!(report.bumped.is_empty());
+        assert!(report.failed.is_empty());
+        assert!(mempool.contains(&txid));
+        assert_eq!(bumper.current_fee(&txid), Some(100));
+    }
+}
@@ -0,0 +1,139 @@
+//! Estimativa de taxas a partir do preenchimento dos blocos recentes
+//!
+//! Para cada bloco conectado, o estimador guarda a taxa por byte necessária
+//! para entrar nele: a menor taxa incluída, se o bloco estava cheio, ou o
+//! piso da rede, se ainda sobrava espaço. Para um alvo de `n` blocos, a
+//! estimativa é a maior, entre todas as janelas de `n` blocos consecutivos
+//! do histórico, da menor taxa de entrada da janela. Pagando esse valor, a
+//! transação teria sido incluída em até `n` blocos em qualquer trecho
+//! recente da cadeia.
+
+use std::collections::VecDeque;
+
+/// Número padrão de blocos mantidos no histórico
+pub const DEFAULT_FEE_HISTORY: usize = 100;
+
+/// Menor taxa por byte sugerida pelo estimador
+pub const MIN_FEE_RATE: u64 = 1;
+
+/// Ocupação (em % do tamanho máximo) a partir da qual um bloco é considerado cheio
+pub const FULL_BLOCK_PERCENT: usize = 90;
+
+/// Estimador de taxa por byte baseado nos últimos blocos
+#[derive(Debug, Clone)]
+pub struct FeeEstimator {
+    /// Taxa de entrada de cada bloco, do mais antigo ao mais recente
+    entry_rates: VecDeque<u64>,
+    /// Número máximo de blocos no histórico
+    max_blocks: usize,
+}
+
+impl Default for FeeEstimator {
+    fn default() -> Self {
+        Self::new(DEFAULT_FEE_HISTORY)
+    }
+}
+
+impl FeeEstimator {
+    /// Cria um estimador que considera os últimos `max_blocks` blocos
+    #[must_use]
+    pub fn new(max_blocks: usize) -> Self {
+        Self {
+            entry_rates: VecDeque::new(),
+            max_blocks: max_blocks.max(1),
+        }
+    }
+
+    /// Registra um bloco conectado
+    ///
+    /// `fee_rates` são as taxas por byte das transações não-coinbase do
+    /// bloco; `block_size` e `max_block_size` determinam se ele estava cheio.
+    pub fn record_block(&mut self, fee_rates: &[u64], block_size: usize, max_block_size: usize) {
+        let full =
+            block_size.saturating_mul(100) >= max_block_size.saturating_mul(FULL_BLOCK_PERCENT);
+        let entry_rate = if full {
+            fee_rates.iter().copied().min().unwrap_or(MIN_FEE_RATE)
+        } else {
+            MIN_FEE_RATE
+        };
+
+        if self.entry_rates.len() == self.max_blocks {
+            self.entry_rates.pop_front();
+        }
+        self.entry_rates.push_back(entry_rate.max(MIN_FEE_RATE));
+    }
+
+    /// Taxa por byte sugerida para inclusão em até `target_blocks` blocos
+    ///
+    /// Alvos menores nunca resultam em taxas menores. Sem histórico, retorna
+    /// o piso; com alvo maior que o histórico, considera o histórico inteiro.
+    #[must_use]
+    pub fn estimate_fee(&self, target_blocks: u64) -> u64 {
+        let len = self.entry_rates.len();
+        if len == 0 {
+            return MIN_FEE_RATE;
+        }
+        let target = usize::try_from(target_blocks)
+            .unwrap_or(usize::MAX)
+            .clamp(1, len);
+
+        (0..=len - target)
+            .filter_map(|start| self.entry_rates.range(start..start + target).min())
+            .max()
+            .copied()
+            .unwrap_or(MIN_FEE_RATE)
+    }
+
+    /// Número de blocos no histórico
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entry_rates.len()
+    }
+
+    /// Verifica se nenhum bloco foi registrado
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entry_rates.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_SIZE: usize = 1_000;
+
+    #[test]
+    fn test_faster_targets_cost_more() {
+        let mut estimator = FeeEstimator::default();
+        assert_eq!(estimator.estimate_fee(1), MIN_FEE_RATE);
+
+        // Blocos cheios com taxas de entrada 50, 20, 5 e 30; um bloco com
+        // espaço sobrando aceita qualquer taxa
+        for min_rate in [50, 20, 5, 30] {
+            estimator.record_block(&[min_rate, min_rate * 2], MAX_SIZE, MAX_SIZE);
+        }
+        estimator.record_block(&[100], MAX_SIZE / 2, MAX_SIZE);
+
+        let estimates: Vec<u64> = (1..=5)
+            .map(|target| estimator.estimate_fee(target))
+            .collect();
+        assert_eq!(estimates, vec![50, 20, 5, 5, 1]);
+        assert!(estimates.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        // Alvos além do histórico usam o histórico inteiro
+        assert_eq!(estimator.estimate_fee(1_000), 1);
+    }
+
+    #[test]
+    fn test_history_keeps_recent_blocks() {
+        let mut estimator = FeeEstimator::new(3);
+        estimator.record_block(&[500], MAX_SIZE, MAX_SIZE);
+        for _ in 0..3 {
+            estimator.record_block(&[10], MAX_SIZE, MAX_SIZE);
+        }
+
+        assert_eq!(estimator.len(), 3);
+        assert_eq!(estimator.estimate_fee(1), 10);
+    }
+}
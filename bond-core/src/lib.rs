@@ -1,15 +1,42 @@
 pub mod block;
 pub mod blockchain;
+pub mod builder;
+pub mod fee_bump;
+pub mod fee_estimator;
+pub mod mempool;
+pub mod mempool_store;
 pub mod mining;
+pub mod script;
+pub mod storage;
 pub mod transaction;
 pub mod utxo;
 
 // Re-exports principais
-pub use block::{Block, BlockHeader};
-pub use blockchain::{Blockchain, BlockchainStats, NetworkParams};
-pub use mining::{DifficultyAdjuster, Miner, MinerConfig, MiningResult};
-pub use transaction::{Transaction, TxInput, TxOutput};
-pub use utxo::{OutPoint, Utxo, UtxoSet};
+pub use block::{merkle_path, merkle_root_of, verify_merkle_proof, Block, BlockHeader};
+pub use blockchain::{
+    BlockLocator, Blockchain, BlockchainStats, ChainReorg, ChainTip, ChainTipStatus, GenesisConfig,
+    NetworkParams, SimulationResult, DEFAULT_DUST_THRESHOLD, DEFAULT_MAX_BLOCK_WEIGHT,
+    DEFAULT_MAX_REORG_DEPTH, DEFAULT_MAX_TX_SIZE, LOCATOR_DENSE_ENTRIES,
+};
+pub use builder::TransactionBuilder;
+pub use fee_bump::{BumpReport, FeeBumpPolicy, FeeBumper};
+pub use fee_estimator::FeeEstimator;
+pub use mempool::{
+    BondMempool, MempoolEntry, MempoolError, DEFAULT_MAX_ORPHANS, DEFAULT_MEMPOOL_TTL_SECS,
+    DEFAULT_RBF_FEE_INCREMENT,
+};
+pub use mempool_store::MempoolStore;
+pub use mining::{
+    DifficultyAdjuster, Miner, MinerConfig, MiningHandle, MiningResult, MiningThrottle,
+};
+pub use script::{
+    OpCode, ScriptContext, ScriptError, ScriptVM, StackItem, DEFAULT_SCRIPT_GAS_LIMIT,
+};
+#[cfg(feature = "storage")]
+pub use storage::SledStore;
+pub use storage::{MemoryStore, Store};
+pub use transaction::{Transaction, TxInput, TxOutput, MAX_RBF_SEQUENCE, WITNESS_SCALE_FACTOR};
+pub use utxo::{BlockUndo, OutPoint, Utxo, UtxoSet};
 
 // Re-exports de tipos compartilhados
 pub use shared::{BlockchainError, Hash256, Result};
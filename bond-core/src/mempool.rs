@@ -0,0 +1,1424 @@
+//! Mempool de transações Bond pendentes
+//!
+//! Transações são validadas contra o conjunto UTXO na entrada, e um
+//! mesmo `OutPoint` só pode ser gasto por uma transação da mempool. A
+//! seleção para blocos prioriza a maior taxa por unidade de peso, a mesma
+//! medida do limite do bloco.
+//!
+//! Transações com `lock_time` futuro não são rejeitadas: ficam em um pool
+//! adiado e são promovidas quando a altura ou o horário do lock é atingido.
+//!
+//! Transações que gastam outputs ainda desconhecidos (o pai não chegou)
+//! podem ser guardadas em um pool de órfãs, indexado pelos `OutPoint`s que
+//! faltam, e são promovidas quando esses outputs entram no conjunto UTXO.
+//! O pool tem tamanho máximo e descarta as órfãs mais antigas.
+//!
+//! Em uma reorganização, as transações dos blocos desconectados que a nova
+//! cadeia não confirmou voltam para a mempool se ainda forem válidas.
+//!
+//! Cada transação expira depois de `ttl_secs` na mempool (ver `expire`),
+//! para que as de taxa baixa demais para serem mineradas não fiquem para
+//! sempre.
+//!
+//! A mempool tem capacidade máxima, e a taxa por byte mínima aceita sobe
+//! quando ela se aproxima do limite e volta ao mínimo base quando esvazia
+//! (ver `current_min_fee`), para descartar spam de taxa baixa sob carga.
+//!
+//! Com um `MempoolStore` anexado, cada entrada e saída é gravada no fim do
+//! log em disco, que é compactado quando acumula registros mortos demais.
+
+use crate::block::Block;
+use crate::blockchain::DEFAULT_DUST_THRESHOLD;
+use crate::mempool_store::MempoolStore;
+use crate::transaction::Transaction;
+use crate::utxo::{OutPoint, UtxoSet};
+use serde::{Deserialize, Serialize};
+use shared::{dynamic_fee_floor, BlockchainError, Hash256};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+/// Motivos de rejeição de uma transação pela mempool
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MempoolError {
+    #[error("Transações coinbase não são retransmitidas")]
+    Coinbase,
+
+    #[error("Transação já está na mempool")]
+    AlreadyInMempool,
+
+    #[error("Input referencia UTXO inexistente")]
+    MissingInputs,
+
+    #[error("Output já gasto por outra transação da mempool")]
+    DoubleSpend,
+
+    #[error("Outputs excedem os inputs")]
+    NegativeFee,
+
+    #[error("Transação conflitante não sinaliza substituição")]
+    ReplacementNotSignaled,
+
+    #[error("Taxa da substituta não supera a das transações substituídas")]
+    ReplacementFeeTooLow,
+
+    #[error("Taxa por unidade de peso da substituta não supera a das transações substituídas")]
+    ReplacementFeeRateTooLow,
+
+    #[error("Mempool cheia")]
+    Full,
+
+    #[error("Taxa por byte abaixo do mínimo atual da mempool ({0})")]
+    FeeRateTooLow(u64),
+
+    #[error("Transação inválida: {0}")]
+    Invalid(String),
+
+    #[error("Falha ao persistir a mempool: {0}")]
+    Storage(String),
+}
+
+impl From<BlockchainError> for MempoolError {
+    fn from(error: BlockchainError) -> Self {
+        match error {
+            BlockchainError::UtxoNotFound => Self::MissingInputs,
+            other => Self::Invalid(other.to_string()),
+        }
+    }
+}
+
+impl From<MempoolError> for BlockchainError {
+    fn from(error: MempoolError) -> Self {
+        match error {
+            MempoolError::MissingInputs => Self::UtxoNotFound,
+            other => Self::InvalidTransaction(other.to_string()),
+        }
+    }
+}
+
+/// Resultado das operações da mempool
+type Result<T> = std::result::Result<T, MempoolError>;
+
+/// Tempo padrão que uma transação pode ficar na mempool (3 horas)
+pub const DEFAULT_MEMPOOL_TTL_SECS: u64 = 3 * 60 * 60;
+
+/// Aumento mínimo padrão (Elos) da taxa de uma substituta sobre a soma das
+/// taxas substituídas
+pub const DEFAULT_RBF_FEE_INCREMENT: u64 = 1;
+
+/// Número máximo padrão de transações órfãs guardadas
+pub const DEFAULT_MAX_ORPHANS: usize = 100;
+
+/// Número máximo padrão de transações (elegíveis e adiadas) na mempool
+pub const DEFAULT_MAX_MEMPOOL_TRANSACTIONS: usize = 50_000;
+
+/// Taxa por byte mínima padrão com a mempool pouco ocupada
+pub const DEFAULT_MIN_FEE_RATE: u64 = 0;
+
+/// Transação na mempool com taxa e tamanho pré-calculados
+#[derive(Debug, Clone)]
+pub struct MempoolEntry {
+    /// Transação pendente
+    pub tx: Transaction,
+    /// Taxa paga (inputs - outputs)
+    pub fee: u64,
+    /// Tamanho em bytes (`Transaction::size`)
+    pub size: usize,
+    /// Peso para o limite do bloco (`Transaction::weight`)
+    pub weight: usize,
+    /// Taxa por byte (`Transaction::fee_rate`)
+    pub fee_rate: u64,
+    /// Momento em que entrou na mempool (segundos desde a época Unix)
+    pub added_at: u64,
+}
+
+impl MempoolEntry {
+    /// Compara pela taxa por unidade de peso (`Transaction::weight`), pela
+    /// razão exata
+    fn cmp_fee_per_weight(&self, other: &Self) -> Ordering {
+        (u128::from(self.fee) * other.weight as u128)
+            .cmp(&(u128::from(other.fee) * self.weight as u128))
+    }
+}
+
+/// Transação cujos inputs referenciam outputs ainda desconhecidos
+#[derive(Debug, Clone)]
+struct OrphanEntry {
+    tx: Transaction,
+    /// Inputs que não estavam no conjunto UTXO ao guardar a órfã
+    missing: Vec<OutPoint>,
+    /// Momento em que foi guardada (segundos desde a época Unix)
+    added_at: u64,
+}
+
+/// Pool de transações aguardando inclusão em bloco
+#[derive(Debug, Clone)]
+pub struct BondMempool {
+    /// Transações elegíveis para o próximo bloco
+    transactions: HashMap<Hash256, MempoolEntry>,
+    /// Transações com time-lock ainda não atingido
+    deferred: HashMap<Hash256, MempoolEntry>,
+    /// `OutPoint`s gastos por transações da mempool
+    spent_outpoints: HashMap<OutPoint, Hash256>,
+    /// Log em disco, se a mempool for persistente
+    store: Option<MempoolStore>,
+    /// Segundos que uma transação pode ficar na mempool
+    ttl_secs: u64,
+    /// Aumento mínimo de taxa exigido de uma substituta (RBF)
+    rbf_fee_increment: u64,
+    /// Transações aguardando outputs ainda desconhecidos
+    orphans: HashMap<Hash256, OrphanEntry>,
+    /// Órfãs que esperam por cada `OutPoint` ausente
+    orphans_by_outpoint: HashMap<OutPoint, HashSet<Hash256>>,
+    /// Número máximo de órfãs guardadas
+    max_orphans: usize,
+    /// Número máximo de transações elegíveis e adiadas
+    max_transactions: usize,
+    /// Taxa por byte mínima com a mempool pouco ocupada
+    min_fee_rate: u64,
+    /// Valor mínimo de cada saída (ver `NetworkParams::dust_threshold`)
+    dust_threshold: u64,
+}
+
+impl Default for BondMempool {
+    fn default() -> Self {
+        Self {
+            transactions: HashMap::new(),
+            deferred: HashMap::new(),
+            spent_outpoints: HashMap::new(),
+            store: None,
+            ttl_secs: DEFAULT_MEMPOOL_TTL_SECS,
+            rbf_fee_increment: DEFAULT_RBF_FEE_INCREMENT,
+            orphans: HashMap::new(),
+            orphans_by_outpoint: HashMap::new(),
+            max_orphans: DEFAULT_MAX_ORPHANS,
+            max_transactions: DEFAULT_MAX_MEMPOOL_TRANSACTIONS,
+            min_fee_rate: DEFAULT_MIN_FEE_RATE,
+            dust_threshold: DEFAULT_DUST_THRESHOLD,
+        }
+    }
+}
+
+impl BondMempool {
+    /// Cria uma mempool vazia
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define por quantos segundos uma transação pode ficar na mempool
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl_secs: u64) -> Self {
+        self.ttl_secs = ttl_secs;
+        self
+    }
+
+    /// Segundos que uma transação pode ficar na mempool
+    #[must_use]
+    pub const fn ttl_secs(&self) -> u64 {
+        self.ttl_secs
+    }
+
+    /// Define o aumento mínimo de taxa (Elos) exigido de uma substituta
+    ///
+    /// Valores abaixo de 1 são tratados como 1: a taxa sempre precisa subir.
+    #[must_use]
+    pub const fn with_rbf_fee_increment(mut self, increment: u64) -> Self {
+        self.rbf_fee_increment = if increment == 0 { 1 } else { increment };
+        self
+    }
+
+    /// Aumento mínimo de taxa exigido de uma substituta
+    #[must_use]
+    pub const fn rbf_fee_increment(&self) -> u64 {
+        self.rbf_fee_increment
+    }
+
+    /// Define quantas transações órfãs podem ser guardadas
+    #[must_use]
+    pub const fn with_max_orphans(mut self, max_orphans: usize) -> Self {
+        self.max_orphans = max_orphans;
+        self
+    }
+
+    /// Número máximo de órfãs guardadas
+    #[must_use]
+    pub const fn max_orphans(&self) -> usize {
+        self.max_orphans
+    }
+
+    /// Define quantas transações (elegíveis e adiadas) a mempool comporta
+    #[must_use]
+    pub const fn with_max_transactions(mut self, max_transactions: usize) -> Self {
+        self.max_transactions = max_transactions;
+        self
+    }
+
+    /// Número máximo de transações na mempool
+    #[must_use]
+    pub const fn max_transactions(&self) -> usize {
+        self.max_transactions
+    }
+
+    /// Define a taxa por byte mínima aceita com a mempool pouco ocupada
+    #[must_use]
+    pub const fn with_min_fee_rate(mut self, min_fee_rate: u64) -> Self {
+        self.min_fee_rate = min_fee_rate;
+        self
+    }
+
+    /// Define o valor mínimo de cada saída, como `NetworkParams::dust_threshold`
+    #[must_use]
+    pub const fn with_dust_threshold(mut self, dust_threshold: u64) -> Self {
+        self.dust_threshold = dust_threshold;
+        self
+    }
+
+    /// Valor mínimo de cada saída aceito pela mempool
+    #[must_use]
+    pub const fn dust_threshold(&self) -> u64 {
+        self.dust_threshold
+    }
+
+    /// Taxa por byte mínima aceita agora
+    ///
+    /// Parte de `min_fee_rate` e sobe conforme a mempool se aproxima de
+    /// `max_transactions` (ver `shared::fee`); volta ao mínimo base quando
+    /// as transações saem.
+    #[must_use]
+    pub fn current_min_fee(&self) -> u64 {
+        let floor = dynamic_fee_floor(
+            u128::from(self.min_fee_rate),
+            self.len(),
+            self.max_transactions,
+        );
+        u64::try_from(floor).unwrap_or(u64::MAX)
+    }
+
+    /// Recarrega a mempool do log e passa a gravar nele
+    ///
+    /// As transações do log são revalidadas com `add_transaction`; as já
+    /// confirmadas ou que não são mais válidas são descartadas, e o log é
+    /// compactado com o conjunto resultante. O log não guarda o horário de
+    /// entrada, então o prazo das recarregadas recomeça em `block_time`.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o log não puder ser lido ou compactado
+    pub fn open(
+        store: MempoolStore,
+        utxo_set: &UtxoSet,
+        next_height: u64,
+        block_time: u64,
+    ) -> shared::Result<Self> {
+        let mut mempool = Self::new();
+        for tx in store.load()? {
+            let _ = mempool.add_transaction(tx, utxo_set, next_height, block_time);
+        }
+        mempool.store = Some(store);
+        mempool.compact_store()?;
+        Ok(mempool)
+    }
+
+    /// Reescreve o log apenas com as transações pendentes
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a gravação falhar
+    pub fn compact_store(&mut self) -> shared::Result<()> {
+        let Some(store) = self.store.as_mut() else {
+            return Ok(());
+        };
+        store.compact(
+            self.transactions
+                .values()
+                .chain(self.deferred.values())
+                .map(|entry| &entry.tx),
+        )
+    }
+
+    /// Log em disco, se a mempool for persistente
+    #[must_use]
+    pub const fn store(&self) -> Option<&MempoolStore> {
+        self.store.as_ref()
+    }
+
+    /// Adiciona uma transação à mempool
+    ///
+    /// `next_height` e `block_time` descrevem o próximo bloco; transações
+    /// que ainda não seriam finais nele vão para o pool adiado. `block_time`
+    /// também é registrado como o momento de entrada, base da expiração.
+    ///
+    /// Antes de consultar o conjunto UTXO, aplica as mesmas verificações sem
+    /// estado de `Blockchain::validate_transaction`: `validate_basic` e o
+    /// limite de poeira das saídas.
+    ///
+    /// # Errors
+    ///
+    /// Retorna o `MempoolError` correspondente se a transação for coinbase,
+    /// malformada, tiver saída abaixo de `dust_threshold`, já estiver na
+    /// mempool, gastar um UTXO inexistente ou já gasto por outra transação
+    /// pendente, se os outputs excederem os inputs, se a
+    /// mempool estiver cheia, se a taxa por byte ficar abaixo de
+    /// `current_min_fee` ou se a gravação no log falhar
+    pub fn add_transaction(
+        &mut self,
+        tx: Transaction,
+        utxo_set: &UtxoSet,
+        next_height: u64,
+        block_time: u64,
+    ) -> Result<Hash256> {
+        if tx.is_coinbase() {
+            return Err(MempoolError::Coinbase);
+        }
+        tx.validate_basic()?;
+        if let Some(output) = tx
+            .outputs
+            .iter()
+            .find(|output| output.value < self.dust_threshold)
+        {
+            return Err(MempoolError::Invalid(format!(
+                "Output value {} is below the dust threshold {}",
+                output.value, self.dust_threshold
+            )));
+        }
+
+        let txid = tx.hash()?;
+        if self.contains(&txid) {
+            return Err(MempoolError::AlreadyInMempool);
+        }
+
+        for input in &tx.inputs {
+            if !utxo_set.contains(&input.previous_output) {
+                return Err(MempoolError::MissingInputs);
+            }
+            if self.spent_outpoints.contains_key(&input.previous_output) {
+                return Err(MempoolError::DoubleSpend);
+            }
+        }
+
+        let fee = tx.fee(utxo_set).map_err(|_| MempoolError::NegativeFee)?;
+        let fee_rate = tx.fee_rate(utxo_set)?;
+        if self.len() >= self.max_transactions {
+            return Err(MempoolError::Full);
+        }
+        let min_fee_rate = self.current_min_fee();
+        if fee_rate < min_fee_rate {
+            return Err(MempoolError::FeeRateTooLow(min_fee_rate));
+        }
+        if let Some(store) = self.store.as_mut() {
+            store
+                .append_add(&tx)
+                .map_err(|e| MempoolError::Storage(e.to_string()))?;
+        }
+        for input in &tx.inputs {
+            self.spent_outpoints.insert(input.previous_output, txid);
+        }
+
+        let entry = MempoolEntry {
+            size: tx.size(),
+            weight: tx.weight(),
+            fee,
+            fee_rate,
+            added_at: block_time,
+            tx,
+        };
+        if entry.tx.is_final(next_height, block_time) {
+            self.transactions.insert(txid, entry);
+        } else {
+            self.deferred.insert(txid, entry);
+        }
+
+        Ok(txid)
+    }
+
+    /// Guarda uma transação que gasta outputs ausentes de `utxo_set`
+    ///
+    /// Usado quando `add_transaction` rejeita a transação com
+    /// `MissingInputs`. Com o pool cheio, a órfã mais antiga é descartada.
+    /// Retorna `false` se a transação não for órfã (nenhum input ausente),
+    /// for coinbase, já estiver na mempool ou se o pool não aceitar órfãs.
+    pub fn add_orphan(&mut self, tx: Transaction, utxo_set: &UtxoSet, added_at: u64) -> bool {
+        if tx.is_coinbase() || self.max_orphans == 0 {
+            return false;
+        }
+        let Ok(txid) = tx.hash() else {
+            return false;
+        };
+        if self.contains(&txid) || self.orphans.contains_key(&txid) {
+            return false;
+        }
+        let missing: Vec<OutPoint> = tx
+            .inputs
+            .iter()
+            .map(|input| input.previous_output)
+            .filter(|outpoint| !utxo_set.contains(outpoint))
+            .collect();
+        if missing.is_empty() {
+            return false;
+        }
+
+        while self.orphans.len() >= self.max_orphans {
+            let oldest = self
+                .orphans
+                .iter()
+                .min_by_key(|(txid, entry)| (entry.added_at, *txid.as_bytes()))
+                .map(|(txid, _)| *txid);
+            match oldest {
+                Some(oldest) => self.remove_orphan(&oldest),
+                None => break,
+            };
+        }
+
+        for outpoint in &missing {
+            self.orphans_by_outpoint
+                .entry(*outpoint)
+                .or_default()
+                .insert(txid);
+        }
+        self.orphans.insert(
+            txid,
+            OrphanEntry {
+                tx,
+                missing,
+                added_at,
+            },
+        );
+        true
+    }
+
+    /// Retira do pool as órfãs cujos inputs estão todos em `utxo_set`
+    ///
+    /// As transações retornadas ainda precisam passar pela validação
+    /// completa (por exemplo, `add_transaction`).
+    pub fn take_ready_orphans(&mut self, utxo_set: &UtxoSet) -> Vec<Transaction> {
+        let candidates: HashSet<Hash256> = self
+            .orphans_by_outpoint
+            .iter()
+            .filter(|(outpoint, _)| utxo_set.contains(outpoint))
+            .flat_map(|(_, txids)| txids.iter().copied())
+            .collect();
+        let mut ready: Vec<Hash256> = candidates
+            .into_iter()
+            .filter(|txid| {
+                self.orphans.get(txid).is_some_and(|entry| {
+                    entry
+                        .tx
+                        .inputs
+                        .iter()
+                        .all(|input| utxo_set.contains(&input.previous_output))
+                })
+            })
+            .collect();
+        ready.sort_by_key(|txid| {
+            self.orphans
+                .get(txid)
+                .map_or((0, [0; 32]), |entry| (entry.added_at, *txid.as_bytes()))
+        });
+
+        ready
+            .iter()
+            .filter_map(|txid| self.remove_orphan(txid))
+            .collect()
+    }
+
+    /// Promove para a mempool as órfãs cujos pais já estão em `utxo_set`
+    ///
+    /// Cada órfã pronta é revalidada com `add_transaction`; as que falharem
+    /// são descartadas. Retorna os ids das transações promovidas.
+    pub fn promote_orphans(
+        &mut self,
+        utxo_set: &UtxoSet,
+        next_height: u64,
+        block_time: u64,
+    ) -> Vec<Hash256> {
+        self.take_ready_orphans(utxo_set)
+            .into_iter()
+            .filter_map(|tx| {
+                self.add_transaction(tx, utxo_set, next_height, block_time)
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Remove uma órfã e seus registros no índice de `OutPoint`s
+    fn remove_orphan(&mut self, txid: &Hash256) -> Option<Transaction> {
+        let entry = self.orphans.remove(txid)?;
+        for outpoint in &entry.missing {
+            if let Some(waiting) = self.orphans_by_outpoint.get_mut(outpoint) {
+                waiting.remove(txid);
+                if waiting.is_empty() {
+                    self.orphans_by_outpoint.remove(outpoint);
+                }
+            }
+        }
+        Some(entry.tx)
+    }
+
+    /// Substitui transações conflitantes por uma versão com taxa maior
+    ///
+    /// Todas as transações que gastam algum input de `tx` são removidas,
+    /// desde que sinalizem RBF, que a taxa de `tx` supere a soma das taxas
+    /// substituídas em pelo menos `rbf_fee_increment` e que sua taxa por
+    /// unidade de peso seja maior que a de cada substituída, a mesma medida
+    /// que ordena a seleção para blocos. Sem conflitos, equivale a
+    /// `add_transaction`.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se algum conflito não sinalizar RBF, se a taxa ou a
+    /// taxa por unidade de peso não superarem as das substituídas ou se `tx`
+    /// for inválida
+    pub fn replace_transaction(
+        &mut self,
+        tx: Transaction,
+        utxo_set: &UtxoSet,
+        next_height: u64,
+        block_time: u64,
+    ) -> Result<Hash256> {
+        let mut conflicts: Vec<Hash256> = tx
+            .inputs
+            .iter()
+            .filter_map(|input| self.spent_outpoints.get(&input.previous_output))
+            .copied()
+            .collect();
+        conflicts.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        conflicts.dedup();
+
+        let replaced: Vec<&MempoolEntry> =
+            conflicts.iter().filter_map(|txid| self.get(txid)).collect();
+        if !replaced.is_empty() {
+            if replaced.iter().any(|entry| !entry.tx.signals_rbf()) {
+                return Err(MempoolError::ReplacementNotSignaled);
+            }
+
+            let fee = tx.fee(utxo_set).map_err(|_| MempoolError::NegativeFee)?;
+            let replaced_fee = replaced
+                .iter()
+                .fold(0u64, |total, entry| total.saturating_add(entry.fee));
+            if fee < replaced_fee.saturating_add(self.rbf_fee_increment) {
+                return Err(MempoolError::ReplacementFeeTooLow);
+            }
+
+            // Razão exata por unidade de peso, como em `cmp_fee_per_weight`
+            let weight = tx.weight().max(1) as u128;
+            if replaced.iter().any(|entry| {
+                u128::from(fee) * entry.weight.max(1) as u128 <= u128::from(entry.fee) * weight
+            }) {
+                return Err(MempoolError::ReplacementFeeRateTooLow);
+            }
+        }
+
+        let removed: Vec<MempoolEntry> = conflicts
+            .iter()
+            .filter_map(|txid| self.remove_entry(txid))
+            .collect();
+        match self.add_transaction(tx, utxo_set, next_height, block_time) {
+            Ok(txid) => Ok(txid),
+            Err(error) => {
+                // Restaura as originais se a substituta for inválida
+                for entry in removed {
+                    self.restore_entry(entry, next_height, block_time);
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Promove transações adiadas cujo time-lock foi atingido
+    ///
+    /// Retorna o número de transações promovidas.
+    pub fn promote_unlocked(&mut self, next_height: u64, block_time: u64) -> usize {
+        let unlocked: Vec<Hash256> = self
+            .deferred
+            .iter()
+            .filter(|(_, entry)| entry.tx.is_final(next_height, block_time))
+            .map(|(txid, _)| *txid)
+            .collect();
+
+        for txid in &unlocked {
+            if let Some(entry) = self.deferred.remove(txid) {
+                self.transactions.insert(*txid, entry);
+            }
+        }
+
+        unlocked.len()
+    }
+
+    /// Seleciona transações elegíveis para um bloco
+    ///
+    /// Ordena por taxa por unidade de peso decrescente e para assim que a próxima
+    /// transação não couber em `max_weight` (ver `Transaction::weight`).
+    /// Transações cujos inputs não estão mais em `utxo_set` são ignoradas.
+    #[must_use]
+    pub fn select_transactions(&self, max_weight: usize, utxo_set: &UtxoSet) -> Vec<Transaction> {
+        let mut selected = Vec::new();
+        let mut total_weight = 0;
+        let available = self.sorted_entries().into_iter().filter(|entry| {
+            entry
+                .tx
+                .inputs
+                .iter()
+                .all(|input| utxo_set.contains(&input.previous_output))
+        });
+        for entry in available {
+            if total_weight + entry.weight > max_weight {
+                break;
+            }
+            total_weight += entry.weight;
+            selected.push(entry.tx.clone());
+        }
+
+        selected
+    }
+
+    /// Transações elegíveis ordenadas por taxa por unidade de peso
+    /// decrescente
+    #[must_use]
+    pub fn sorted_entries(&self) -> Vec<&MempoolEntry> {
+        let mut entries: Vec<&MempoolEntry> = self.transactions.values().collect();
+        entries.sort_by(|a, b| b.cmp_fee_per_weight(a));
+        entries
+    }
+
+    /// Remove transações confirmadas em um bloco
+    ///
+    /// Compacta o log quando ele acumula registros mortos demais. Falhas de
+    /// gravação aqui são toleradas: uma entrada que sobrar no log é
+    /// descartada ao recarregar, pois seus inputs já foram gastos.
+    pub fn remove_transactions(&mut self, transactions: &[Transaction]) {
+        for tx in transactions {
+            if let Ok(txid) = tx.hash() {
+                let removed = self.transactions.remove(&txid).is_some()
+                    || self.deferred.remove(&txid).is_some();
+                if removed {
+                    self.log_removal(&txid);
+                }
+            }
+            for input in &tx.inputs {
+                self.spent_outpoints.remove(&input.previous_output);
+            }
+        }
+
+        if self
+            .store
+            .as_ref()
+            .is_some_and(|store| store.needs_compaction(self.len()))
+        {
+            let _ = self.compact_store();
+        }
+    }
+
+    /// Remove as transações que estão na mempool há `ttl_secs` ou mais
+    ///
+    /// Vale para as elegíveis, as adiadas e as órfãs. Retorna o número de
+    /// transações removidas.
+    pub fn expire(&mut self, now: u64) -> usize {
+        let expired: Vec<Hash256> = self
+            .transactions
+            .iter()
+            .chain(&self.deferred)
+            .filter(|(_, entry)| now.saturating_sub(entry.added_at) >= self.ttl_secs)
+            .map(|(txid, _)| *txid)
+            .collect();
+
+        let expired_orphans: Vec<Hash256> = self
+            .orphans
+            .iter()
+            .filter(|(_, entry)| now.saturating_sub(entry.added_at) >= self.ttl_secs)
+            .map(|(txid, _)| *txid)
+            .collect();
+
+        for txid in &expired {
+            self.remove_entry(txid);
+        }
+        for txid in &expired_orphans {
+            self.remove_orphan(txid);
+        }
+        if self
+            .store
+            .as_ref()
+            .is_some_and(|store| store.needs_compaction(self.len()))
+        {
+            let _ = self.compact_store();
+        }
+
+        expired.len() + expired_orphans.len()
+    }
+
+    /// Grava a saída de uma transação no log, se houver
+    fn log_removal(&mut self, txid: &Hash256) {
+        if let Some(store) = self.store.as_mut() {
+            let _ = store.append_remove(txid);
+        }
+    }
+
+    /// Reinjeta as transações de blocos desconectados por uma reorganização
+    ///
+    /// `disconnected` são os blocos removidos da cadeia ativa e `connected`
+    /// os blocos da nova cadeia, ambos em ordem crescente de altura;
+    /// `utxo_set` é o conjunto UTXO já na nova ponta. Transações confirmadas
+    /// em `connected` saem da mempool; as demais transações desconectadas
+    /// são revalidadas com `add_transaction` e descartadas se não forem mais
+    /// válidas (por exemplo, gastas de novo pela nova cadeia). Retorna os
+    /// ids das transações reinjetadas.
+    pub fn reinject_disconnected(
+        &mut self,
+        disconnected: &[Block],
+        connected: &[Block],
+        utxo_set: &UtxoSet,
+        next_height: u64,
+        block_time: u64,
+    ) -> Vec<Hash256> {
+        let mut confirmed = HashSet::new();
+        for block in connected {
+            self.remove_transactions(&block.transactions);
+            confirmed.extend(block.transactions.iter().filter_map(|tx| tx.hash().ok()));
+        }
+
+        let mut reinjected = Vec::new();
+        for tx in disconnected
+            .iter()
+            .flat_map(|block| block.transactions.iter())
+            .filter(|tx| !tx.is_coinbase())
+        {
+            if tx.hash().is_ok_and(|txid| confirmed.contains(&txid)) {
+                continue;
+            }
+            if let Ok(txid) = self.add_transaction(tx.clone(), utxo_set, next_height, block_time) {
+                reinjected.push(txid);
+            }
+        }
+
+        reinjected
+    }
+
+    /// Remove uma transação e libera os outputs que ela gastava
+    fn remove_entry(&mut self, txid: &Hash256) -> Option<MempoolEntry> {
+        let entry = self
+            .transactions
+            .remove(txid)
+            .or_else(|| self.deferred.remove(txid))?;
+        for input in &entry.tx.inputs {
+            self.spent_outpoints.remove(&input.previous_output);
+        }
+        self.log_removal(txid);
+        Some(entry)
+    }
+
+    /// Reinsere uma entrada já validada
+    fn restore_entry(&mut self, entry: MempoolEntry, next_height: u64, block_time: u64) {
+        let Ok(txid) = entry.tx.hash() else {
+            return;
+        };
+        for input in &entry.tx.inputs {
+            self.spent_outpoints.insert(input.previous_output, txid);
+        }
+        if let Some(store) = self.store.as_mut() {
+            let _ = store.append_add(&entry.tx);
+        }
+        if entry.tx.is_final(next_height, block_time) {
+            self.transactions.insert(txid, entry);
+        } else {
+            self.deferred.insert(txid, entry);
+        }
+    }
+
+    /// Obtém uma transação da mempool (elegível ou adiada)
+    #[must_use]
+    pub fn get(&self, txid: &Hash256) -> Option<&MempoolEntry> {
+        self.transactions
+            .get(txid)
+            .or_else(|| self.deferred.get(txid))
+    }
+
+    /// Verifica se a transação está na mempool (elegível ou adiada)
+    #[must_use]
+    pub fn contains(&self, txid: &Hash256) -> bool {
+        self.transactions.contains_key(txid) || self.deferred.contains_key(txid)
+    }
+
+    /// `OutPoint`s gastos por transações da mempool
+    ///
+    /// Passado a `Blockchain::create_transaction` para que novas transações
+    /// não gastem UTXOs já reservados.
+    #[must_use]
+    pub fn reserved_outpoints(&self) -> HashSet<OutPoint> {
+        self.spent_outpoints.keys().copied().collect()
+    }
+
+    /// Número total de transações (elegíveis e adiadas)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.transactions.len() + self.deferred.len()
+    }
+
+    /// Verifica se não há transações elegíveis nem adiadas
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty() && self.deferred.is_empty()
+    }
+
+    /// Número de transações aguardando o time-lock
+    #[must_use]
+    pub fn deferred_len(&self) -> usize {
+        self.deferred.len()
+    }
+
+    /// Verifica se a transação está no pool de órfãs
+    #[must_use]
+    pub fn is_orphan(&self, txid: &Hash256) -> bool {
+        self.orphans.contains_key(txid)
+    }
+
+    /// Número de transações órfãs guardadas
+    #[must_use]
+    pub fn orphan_len(&self) -> usize {
+        self.orphans.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::{calculate_merkle_root, BlockHeader};
+    use crate::transaction::{TxInput, TxOutput, LOCKTIME_THRESHOLD};
+    use crate::utxo::Utxo;
+    use chrono::Utc;
+
+    const NO_LIMIT: usize = usize::MAX;
+
+    fn funded_outpoint(utxo_set: &mut UtxoSet, seed: &[u8], value: u64) -> OutPoint {
+        let txid = Hash256::keccak256(seed);
+        utxo_set.add_utxo(Utxo::new(txid, 0, value, vec![1], 0));
+        OutPoint { txid, vout: 0 }
+    }
+
+    fn spend(outpoints: &[OutPoint], output_value: u64, lock_time: u32) -> Transaction {
+        let inputs = outpoints
+            .iter()
+            .map(|outpoint| TxInput::new(*outpoint, vec![], 0))
+            .collect();
+        Transaction::new(
+            1,
+            inputs,
+            vec![TxOutput::new(output_value, vec![2])],
+            lock_time,
+        )
+    }
+
+    fn block_with(height: u64, transactions: Vec<Transaction>) -> Block {
+        let mut all = vec![Transaction::coinbase(height, 5_000, vec![1])];
+        all.extend(transactions);
+        let header = BlockHeader::new(
+            1,
+            Hash256::zero(),
+            calculate_merkle_root(&all).unwrap(),
+            Utc::now(),
+            0,
+            0,
+        );
+        Block::new(header, all)
+    }
+
+    #[test]
+    fn test_fee_floor_rises_under_load_and_relaxes() {
+        let mut utxo_set = UtxoSet::new();
+        let mut mempool = BondMempool::new().with_max_transactions(4);
+        assert_eq!(mempool.current_min_fee(), 0);
+
+        // Taxa alta o bastante para qualquer piso desta mempool
+        let mut admitted = Vec::new();
+        for seed in [b"a", b"b", b"c"] {
+            let outpoint = funded_outpoint(&mut utxo_set, seed, 100_000);
+            let tx = spend(&[outpoint], 1_000, 0);
+            mempool
+                .add_transaction(tx.clone(), &utxo_set, 1, 0)
+                .unwrap();
+            admitted.push(tx);
+        }
+        let floor = mempool.current_min_fee();
+        assert!(floor > 0);
+
+        // Uma transação de taxa zero passa a ser recusada antes do limite
+        let cheap_input = funded_outpoint(&mut utxo_set, b"cheap", 1_000);
+        let cheap = spend(&[cheap_input], 1_000, 0);
+        assert_eq!(
+            mempool.add_transaction(cheap.clone(), &utxo_set, 1, 0),
+            Err(MempoolError::FeeRateTooLow(floor))
+        );
+
+        let rich = funded_outpoint(&mut utxo_set, b"d", 100_000);
+        mempool
+            .add_transaction(spend(&[rich], 1_000, 0), &utxo_set, 1, 0)
+            .unwrap();
+        let last = funded_outpoint(&mut utxo_set, b"e", 100_000);
+        assert_eq!(
+            mempool.add_transaction(spend(&[last], 1_000, 0), &utxo_set, 1, 0),
+            Err(MempoolError::Full)
+        );
+
+        // Quando as transações são confirmadas, o piso volta ao mínimo base
+        admitted.push(spend(&[rich], 1_000, 0));
+        mempool.remove_transactions(&admitted);
+        assert_eq!(mempool.current_min_fee(), 0);
+        mempool.add_transaction(cheap, &utxo_set, 1, 0).unwrap();
+    }
+
+    #[test]
+    fn test_height_locked_transaction_is_deferred() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 1_000);
+        let b = funded_outpoint(&mut utxo_set, b"b", 1_000);
+        let mut mempool = BondMempool::new();
+        let unlocked = spend(&[a], 900, 0);
+        let locked = spend(&[b], 900, 10);
+
+        mempool
+            .add_transaction(unlocked.clone(), &utxo_set, 5, 0)
+            .unwrap();
+        mempool
+            .add_transaction(locked.clone(), &utxo_set, 5, 0)
+            .unwrap();
+
+        assert_eq!(
+            mempool.select_transactions(NO_LIMIT, &utxo_set),
+            vec![unlocked.clone()]
+        );
+        assert_eq!(mempool.deferred_len(), 1);
+
+        // Altura 10 ainda não satisfaz lock_time 10
+        assert_eq!(mempool.promote_unlocked(10, 0), 0);
+        assert!(!mempool
+            .select_transactions(NO_LIMIT, &utxo_set)
+            .contains(&locked));
+
+        assert_eq!(mempool.promote_unlocked(11, 0), 1);
+        assert!(mempool
+            .select_transactions(NO_LIMIT, &utxo_set)
+            .contains(&locked));
+        assert_eq!(mempool.deferred_len(), 0);
+
+        mempool.remove_transactions(&[unlocked, locked]);
+        assert!(mempool.is_empty());
+    }
+
+    #[test]
+    fn test_time_locked_transaction_is_deferred() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 1_000);
+        let mut mempool = BondMempool::new();
+        let unlock_at = LOCKTIME_THRESHOLD + 1_000;
+        let locked = spend(&[a], 900, unlock_at);
+
+        mempool
+            .add_transaction(locked.clone(), &utxo_set, 1, u64::from(unlock_at) - 1)
+            .unwrap();
+        assert!(mempool.select_transactions(NO_LIMIT, &utxo_set).is_empty());
+        assert!(mempool.add_transaction(locked, &utxo_set, 1, 0).is_err());
+
+        assert_eq!(mempool.promote_unlocked(1, u64::from(unlock_at) + 1), 1);
+        assert_eq!(mempool.select_transactions(NO_LIMIT, &utxo_set).len(), 1);
+    }
+
+    #[test]
+    fn test_stale_transactions_expire_after_ttl() {
+        let mut utxo_set = UtxoSet::new();
+        let old_input = funded_outpoint(&mut utxo_set, b"old", 1_000);
+        let fresh_input = funded_outpoint(&mut utxo_set, b"fresh", 1_000);
+        let locked_input = funded_outpoint(&mut utxo_set, b"locked", 1_000);
+        let mut mempool = BondMempool::new().with_ttl(600);
+        let start = 1_700_000_000;
+
+        let old = mempool
+            .add_transaction(spend(&[old_input], 900, 0), &utxo_set, 1, start)
+            .unwrap();
+        let locked = mempool
+            .add_transaction(spend(&[locked_input], 900, 50), &utxo_set, 1, start)
+            .unwrap();
+        let fresh = mempool
+            .add_transaction(spend(&[fresh_input], 900, 0), &utxo_set, 1, start + 300)
+            .unwrap();
+
+        assert_eq!(mempool.expire(start + 599), 0);
+        assert_eq!(mempool.expire(start + 600), 2);
+        assert!(!mempool.contains(&old));
+        assert!(!mempool.contains(&locked));
+        assert!(mempool.contains(&fresh));
+        assert_eq!(mempool.deferred_len(), 0);
+
+        // O input da expirada fica livre para outra transação
+        mempool
+            .add_transaction(spend(&[old_input], 800, 0), &utxo_set, 1, start + 600)
+            .unwrap();
+        assert_eq!(mempool.len(), 2);
+    }
+
+    #[test]
+    fn test_orphan_is_promoted_after_parent_confirms() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 1_000);
+        let mut mempool = BondMempool::new();
+        let parent = spend(&[a], 900, 0);
+        let parent_output = OutPoint {
+            txid: parent.hash().unwrap(),
+            vout: 0,
+        };
+        let child = spend(&[parent_output], 800, 0);
+        let child_id = child.hash().unwrap();
+
+        // O filho chega antes do pai
+        assert_eq!(
+            mempool.add_transaction(child.clone(), &utxo_set, 1, 0),
+            Err(MempoolError::MissingInputs)
+        );
+        assert!(mempool.add_orphan(child, &utxo_set, 0));
+        assert!(mempool.is_orphan(&child_id));
+        assert!(!mempool.contains(&child_id));
+
+        mempool
+            .add_transaction(parent.clone(), &utxo_set, 1, 0)
+            .unwrap();
+        assert!(mempool.promote_orphans(&utxo_set, 1, 0).is_empty());
+
+        // Confirmado o pai, o filho é validado e entra na mempool
+        let block = block_with(1, vec![parent.clone()]);
+        utxo_set.apply_block(&block).unwrap();
+        mempool.remove_transactions(&block.transactions);
+        assert_eq!(mempool.promote_orphans(&utxo_set, 2, 0), vec![child_id]);
+        assert!(mempool.contains(&child_id));
+        assert_eq!(mempool.orphan_len(), 0);
+        assert_eq!(mempool.get(&child_id).unwrap().fee, 100);
+    }
+
+    #[test]
+    fn test_orphan_pool_is_capped() {
+        let utxo_set = UtxoSet::new();
+        let mut mempool = BondMempool::new().with_max_orphans(2);
+        let orphans: Vec<Transaction> = (0u8..3)
+            .map(|i| {
+                let missing = OutPoint {
+                    txid: Hash256::keccak256(&[i]),
+                    vout: 0,
+                };
+                spend(&[missing], 100, 0)
+            })
+            .collect();
+
+        for (added_at, tx) in (0u64..).zip(&orphans) {
+            assert!(mempool.add_orphan(tx.clone(), &utxo_set, added_at));
+        }
+
+        assert_eq!(mempool.orphan_len(), 2);
+        assert!(!mempool.is_orphan(&orphans[0].hash().unwrap()));
+        assert!(mempool.is_orphan(&orphans[2].hash().unwrap()));
+        assert_eq!(mempool.expire(DEFAULT_MEMPOOL_TTL_SECS + 1), 1);
+        assert_eq!(mempool.orphan_len(), 1);
+    }
+
+    #[test]
+    fn test_double_spend_rejected() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 1_000);
+        let mut mempool = BondMempool::new();
+
+        mempool
+            .add_transaction(spend(&[a], 900, 0), &utxo_set, 1, 0)
+            .unwrap();
+        assert_eq!(
+            mempool.add_transaction(spend(&[a], 800, 0), &utxo_set, 1, 0),
+            Err(MempoolError::DoubleSpend)
+        );
+
+        // UTXO inexistente e outputs acima dos inputs também são rejeitados
+        let missing = OutPoint {
+            txid: Hash256::keccak256(b"missing"),
+            vout: 0,
+        };
+        assert!(mempool
+            .add_transaction(spend(&[missing], 100, 0), &utxo_set, 1, 0)
+            .is_err());
+        let b = funded_outpoint(&mut utxo_set, b"b", 1_000);
+        assert!(mempool
+            .add_transaction(spend(&[b], 2_000, 0), &utxo_set, 1, 0)
+            .is_err());
+
+        assert_eq!(mempool.len(), 1);
+    }
+
+    #[test]
+    fn test_malformed_and_dust_transactions_rejected() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 1_000);
+        let mut mempool = BondMempool::new();
+
+        // Sem outputs ou com output de valor zero: falha em `validate_basic`
+        let mut empty = spend(&[a], 900, 0);
+        empty.outputs.clear();
+        assert!(matches!(
+            mempool.add_transaction(empty, &utxo_set, 1, 0),
+            Err(MempoolError::Invalid(_))
+        ));
+        assert!(matches!(
+            mempool.add_transaction(spend(&[a], 0, 0), &utxo_set, 1, 0),
+            Err(MempoolError::Invalid(_))
+        ));
+
+        // Saída abaixo do limite de poeira, com ou sem limite configurado
+        let dust = spend(&[a], DEFAULT_DUST_THRESHOLD - 1, 0);
+        let Err(MempoolError::Invalid(reason)) =
+            mempool.add_transaction(dust.clone(), &utxo_set, 1, 0)
+        else {
+            panic!("dust output accepted");
+        };
+        assert!(reason.contains("dust threshold"), "{reason}");
+        assert!(mempool.is_empty());
+
+        let mut lenient = BondMempool::new().with_dust_threshold(1);
+        lenient.add_transaction(dust, &utxo_set, 1, 0).unwrap();
+        assert_eq!(lenient.len(), 1);
+    }
+
+    #[test]
+    fn test_replace_requires_higher_fee_and_signal() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 1_000);
+        let mut mempool = BondMempool::new();
+        let original = spend(&[a], 900, 0);
+        let original_id = mempool.add_transaction(original, &utxo_set, 1, 0).unwrap();
+
+        // Taxa igual não substitui
+        assert!(mempool
+            .replace_transaction(spend(&[a], 900, 1), &utxo_set, 1, 0)
+            .is_err());
+        assert!(mempool.contains(&original_id));
+
+        let bumped_id = mempool
+            .replace_transaction(spend(&[a], 850, 0), &utxo_set, 1, 0)
+            .unwrap();
+        assert!(!mempool.contains(&original_id));
+        assert!(mempool.contains(&bumped_id));
+        assert_eq!(mempool.len(), 1);
+
+        // Sequência máxima desativa a substituição
+        let b = funded_outpoint(&mut utxo_set, b"b", 1_000);
+        let mut final_tx = spend(&[b], 900, 0);
+        final_tx.inputs[0].sequence = u32::MAX;
+        mempool.add_transaction(final_tx, &utxo_set, 1, 0).unwrap();
+        assert!(mempool
+            .replace_transaction(spend(&[b], 100, 0), &utxo_set, 1, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_replacement_needs_fee_increment_and_higher_rate() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 1_000);
+        let mut mempool = BondMempool::new().with_rbf_fee_increment(100);
+        let original_id = mempool
+            .add_transaction(spend(&[a], 900, 0), &utxo_set, 1, 0)
+            .unwrap();
+
+        // Taxa 150 supera a original (100), mas não pelo incremento exigido
+        assert_eq!(
+            mempool.replace_transaction(spend(&[a], 850, 0), &utxo_set, 1, 0),
+            Err(MempoolError::ReplacementFeeTooLow)
+        );
+
+        // Taxa 220 com muitos outputs: taxa total maior, taxa por peso menor
+        let mut bloated = spend(&[a], 0, 0);
+        bloated.outputs = vec![TxOutput::new(39, vec![2; 64]); 20];
+        assert_eq!(bloated.fee(&utxo_set).unwrap(), 220);
+        assert_eq!(
+            mempool.replace_transaction(bloated, &utxo_set, 1, 0),
+            Err(MempoolError::ReplacementFeeRateTooLow)
+        );
+        assert!(mempool.contains(&original_id));
+
+        let bumped_id = mempool
+            .replace_transaction(spend(&[a], 800, 0), &utxo_set, 1, 0)
+            .unwrap();
+        assert!(!mempool.contains(&original_id));
+        assert!(mempool.contains(&bumped_id));
+        assert_eq!(mempool.len(), 1);
+    }
+
+    #[test]
+    fn test_replacement_compares_fee_per_weight() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 10_000);
+        let b = funded_outpoint(&mut utxo_set, b"b", 20_000);
+        let mut mempool = BondMempool::new();
+
+        // Menos por byte (8 contra 10), mas mais por unidade de peso (6,4
+        // contra 2,5): substitui, como a seleção a preferiria
+        let plain = spend(&[a], 10_000 - 890, 0);
+        let plain_id = mempool
+            .add_transaction(plain.clone(), &utxo_set, 1, 0)
+            .unwrap();
+        let mut signed = spend(&[a], 10_000 - 8_712, 0);
+        signed.inputs[0].script_sig = vec![0; 1_000];
+        assert!(plain.fee_rate(&utxo_set).unwrap() > signed.fee_rate(&utxo_set).unwrap());
+        let signed_id = mempool
+            .replace_transaction(signed, &utxo_set, 1, 0)
+            .unwrap();
+        assert!(!mempool.contains(&plain_id));
+        assert!(mempool.contains(&signed_id));
+
+        // Mais por byte (9 contra 8), mas sem assinatura todo byte pesa 4:
+        // menos por unidade de peso que a original assinada
+        let mut original = spend(&[b], 20_000 - 8_712, 0);
+        original.inputs[0].script_sig = vec![0; 1_000];
+        let original_id = mempool
+            .add_transaction(original.clone(), &utxo_set, 1, 0)
+            .unwrap();
+        let mut heavy = spend(&[b], 0, 0);
+        heavy.outputs = vec![TxOutput::new(100, vec![2; 64]); 15];
+        let fee = 9 * heavy.size() as u64;
+        heavy.outputs[0].value = 20_000 - 1_400 - fee;
+        assert_eq!(heavy.fee(&utxo_set).unwrap(), fee);
+        assert!(fee > 8_712);
+        assert!(heavy.fee_rate(&utxo_set).unwrap() > original.fee_rate(&utxo_set).unwrap());
+        assert_eq!(
+            mempool.replace_transaction(heavy, &utxo_set, 1, 0),
+            Err(MempoolError::ReplacementFeeRateTooLow)
+        );
+        assert!(mempool.contains(&original_id));
+    }
+
+    #[test]
+    fn test_selection_orders_by_fee_rate_and_respects_size() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 1_000);
+        let b = funded_outpoint(&mut utxo_set, b"b", 1_000);
+        let c1 = funded_outpoint(&mut utxo_set, b"c1", 1_000);
+        let c2 = funded_outpoint(&mut utxo_set, b"c2", 1_000);
+        let mut mempool = BondMempool::new();
+
+        let low = spend(&[a], 990, 0); // taxa 10
+        let high = spend(&[b], 500, 0); // taxa 500
+                                        // Taxa absoluta maior, mas com dois inputs a taxa por byte é menor
+        let large = spend(&[c1, c2], 1_400, 0); // taxa 600
+        for tx in [&low, &high, &large] {
+            mempool
+                .add_transaction(tx.clone(), &utxo_set, 1, 0)
+                .unwrap();
+        }
+
+        let selected = mempool.select_transactions(NO_LIMIT, &utxo_set);
+        assert_eq!(selected, vec![high.clone(), large.clone(), low.clone()]);
+
+        // Orçamento para apenas duas transações pequenas
+        let budget = high.weight() + low.weight();
+        let selected = mempool.select_transactions(budget, &utxo_set);
+        assert_eq!(selected, vec![high]);
+    }
+
+    #[test]
+    fn test_fee_rate_counts_signature_bytes() {
+        use crate::builder::TransactionBuilder;
+        use crate::script::p2pkh_script_pubkey;
+        use shared::{KeyPair, SignatureAlgorithm};
+
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.add_utxo(Utxo::new(
+            Hash256::keccak256(b"a"),
+            0,
+            100_000,
+            p2pkh_script_pubkey(&keypair.public_key),
+            0,
+        ));
+        let tx = TransactionBuilder::new()
+            .add_recipient(vec![1], 1_000)
+            .set_fee_rate(2)
+            .build(&utxo_set, vec![9], &keypair)
+            .unwrap();
+
+        // A assinatura ML-DSA domina o tamanho e entra na taxa por byte
+        assert!(tx.witness_size() > tx.size() / 2);
+        assert_eq!(tx.fee(&utxo_set).unwrap(), tx.size() as u64 * 2);
+        assert_eq!(tx.fee_rate(&utxo_set).unwrap(), 2);
+
+        assert_eq!(
+            BondMempool::new()
+                .with_min_fee_rate(3)
+                .add_transaction(tx.clone(), &utxo_set, 1, 0),
+            Err(MempoolError::FeeRateTooLow(3))
+        );
+        let mut mempool = BondMempool::new().with_min_fee_rate(2);
+        let txid = mempool
+            .add_transaction(tx.clone(), &utxo_set, 1, 0)
+            .unwrap();
+        let entry = mempool.get(&txid).unwrap();
+        assert_eq!(entry.size, tx.size());
+        assert_eq!(entry.fee_rate, 2);
+    }
+
+    #[test]
+    fn test_selection_ranks_by_fee_per_weight() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 10_000);
+        let b = funded_outpoint(&mut utxo_set, b"b", 10_000);
+        let mut mempool = BondMempool::new();
+
+        // Sem assinatura: 89 bytes, peso 356, taxa 890 (10 por byte)
+        let plain = spend(&[a], 10_000 - 890, 0);
+        // Com 1.000 bytes de assinatura: 1.089 bytes, peso 1.356, taxa 8.712
+        // (8 por byte, mas mais de 6 por unidade de peso contra 2,5)
+        let mut signed = spend(&[b], 10_000 - 8_712, 0);
+        signed.inputs[0].script_sig = vec![0; 1_000];
+        assert_eq!(signed.weight(), 1_356);
+        assert!(plain.fee_rate(&utxo_set).unwrap() > signed.fee_rate(&utxo_set).unwrap());
+        for tx in [&plain, &signed] {
+            mempool
+                .add_transaction(tx.clone(), &utxo_set, 1, 0)
+                .unwrap();
+        }
+
+        let selected = mempool.select_transactions(NO_LIMIT, &utxo_set);
+        assert_eq!(selected, vec![signed.clone(), plain.clone()]);
+
+        // Com orçamento para uma só, fica a que paga mais pelo peso gasto
+        let selected = mempool.select_transactions(signed.weight(), &utxo_set);
+        assert_eq!(selected, vec![signed]);
+    }
+
+    #[test]
+    fn test_reorg_reinjects_disconnected_transactions() {
+        let mut utxo_set = UtxoSet::new();
+        let a = funded_outpoint(&mut utxo_set, b"a", 1_000);
+        let b = funded_outpoint(&mut utxo_set, b"b", 1_000);
+        let c = funded_outpoint(&mut utxo_set, b"c", 1_000);
+
+        let only_old = spend(&[a], 900, 0);
+        let in_both = spend(&[b], 900, 0);
+        let replaced = spend(&[c], 900, 0);
+        let conflicting = spend(&[c], 800, 0);
+        let disconnected = vec![block_with(
+            1,
+            vec![only_old.clone(), in_both.clone(), replaced],
+        )];
+
+        // A nova cadeia confirma `in_both` e gasta `c` com outra transação
+        let connected = vec![block_with(1, vec![in_both.clone(), conflicting])];
+        let mut new_utxo_set = utxo_set.clone();
+        new_utxo_set.remove_utxo(&b);
+        new_utxo_set.remove_utxo(&c);
+
+        let mut mempool = BondMempool::new();
+        mempool
+            .add_transaction(in_both.clone(), &utxo_set, 1, 0)
+            .unwrap();
+
+        let reinjected =
+            mempool.reinject_disconnected(&disconnected, &connected, &new_utxo_set, 2, 0);
+
+        assert_eq!(reinjected, vec![only_old.hash().unwrap()]);
+        assert!(!mempool.contains(&in_both.hash().unwrap()));
+        assert_eq!(
+            mempool.select_transactions(NO_LIMIT, &new_utxo_set),
+            vec![only_old]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Persistência da mempool em um log de gravação incremental
+//!
+//! Cada transação aceita e cada remoção viram uma linha anexada ao fim do
+//! arquivo, sem reescrever o que já foi gravado. Como remoções só marcam
+//! entradas como mortas, o log cresce além do conjunto vivo; a compactação
+//! reescreve o arquivo apenas com as transações ainda pendentes.
+
+use crate::transaction::Transaction;
+use serde::{Deserialize, Serialize};
+use shared::{BlockchainError, Hash256, Result};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Registros mortos tolerados antes de compactar, além do conjunto vivo
+pub const COMPACTION_SLACK: usize = 64;
+
+/// Operação gravada no log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum MempoolRecord {
+    Add(Transaction),
+    Remove(Hash256),
+}
+
+/// Log em disco das transações da mempool
+#[derive(Debug, Clone)]
+pub struct MempoolStore {
+    path: PathBuf,
+    /// Registros no arquivo desde a última compactação
+    records: usize,
+}
+
+impl MempoolStore {
+    /// Abre o log em `path`, que é criado na primeira gravação
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o arquivo existir e não puder ser lido
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let mut store = Self {
+            path: path.into(),
+            records: 0,
+        };
+        store.records = store.read_records()?.len();
+        Ok(store)
+    }
+
+    /// Caminho do arquivo do log
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Número de registros gravados desde a última compactação
+    #[must_use]
+    pub const fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Anexa uma transação aceita pela mempool
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a gravação falhar
+    pub fn append_add(&mut self, tx: &Transaction) -> Result<()> {
+        self.append(&MempoolRecord::Add(tx.clone()))
+    }
+
+    /// Anexa a remoção de uma transação
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a gravação falhar
+    pub fn append_remove(&mut self, txid: &Hash256) -> Result<()> {
+        self.append(&MempoolRecord::Remove(*txid))
+    }
+
+    /// Transações vivas no log, na ordem em que foram adicionadas
+    ///
+    /// Uma linha final incompleta (gravação interrompida) é ignorada.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o arquivo não puder ser lido
+    pub fn load(&self) -> Result<Vec<Transaction>> {
+        let mut order = Vec::new();
+        let mut live = HashMap::new();
+        for record in self.read_records()? {
+            match record {
+                MempoolRecord::Add(tx) => {
+                    let txid = tx.hash()?;
+                    if live.insert(txid, tx).is_none() {
+                        order.push(txid);
+                    }
+                }
+                MempoolRecord::Remove(txid) => {
+                    live.remove(&txid);
+                }
+            }
+        }
+
+        Ok(order
+            .into_iter()
+            .filter_map(|txid| live.remove(&txid))
+            .collect())
+    }
+
+    /// Verifica se o log acumulou registros mortos demais para `live`
+    /// transações vivas
+    #[must_use]
+    pub const fn needs_compaction(&self, live: usize) -> bool {
+        self.records > live.saturating_mul(2).saturating_add(COMPACTION_SLACK)
+    }
+
+    /// Reescreve o log contendo apenas `live`
+    ///
+    /// O novo conteúdo é gravado em um arquivo temporário e renomeado,
+    /// para que uma falha no meio não perca o log anterior.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a gravação ou a troca de arquivos falhar
+    pub fn compact<'a>(&mut self, live: impl IntoIterator<Item = &'a Transaction>) -> Result<()> {
+        let mut data = Vec::new();
+        let mut records = 0;
+        for tx in live {
+            data.extend(encode(&MempoolRecord::Add(tx.clone()))?);
+            records += 1;
+        }
+
+        let temp = self.path.with_extension("compact");
+        fs::write(&temp, data).map_err(|e| BlockchainError::IoError(e.to_string()))?;
+        fs::rename(&temp, &self.path).map_err(|e| BlockchainError::IoError(e.to_string()))?;
+        self.records = records;
+        Ok(())
+    }
+
+    fn append(&mut self, record: &MempoolRecord) -> Result<()> {
+        let line = encode(record)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| BlockchainError::IoError(e.to_string()))?;
+        file.write_all(&line)
+            .map_err(|e| BlockchainError::IoError(e.to_string()))?;
+        self.records += 1;
+        Ok(())
+    }
+
+    fn read_records(&self) -> Result<Vec<MempoolRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let data = fs::read(&self.path).map_err(|e| BlockchainError::IoError(e.to_string()))?;
+        Ok(data
+            .split(|&byte| byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map_while(|line| serde_json::from_slice(line).ok())
+            .collect())
+    }
+}
+
+/// Um registro por linha
+fn encode(record: &MempoolRecord) -> Result<Vec<u8>> {
+    let mut line = serde_json::to_vec(record)
+        .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mempool::BondMempool;
+    use crate::transaction::{TxInput, TxOutput};
+    use crate::utxo::{OutPoint, Utxo, UtxoSet};
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("bond-mempool-{}.log", rand::random::<u64>()))
+    }
+
+    fn funded_spend(utxo_set: &mut UtxoSet, seed: &[u8]) -> (OutPoint, Transaction) {
+        let txid = Hash256::keccak256(seed);
+        utxo_set.add_utxo(Utxo::new(txid, 0, 1_000, vec![1], 0));
+        let outpoint = OutPoint { txid, vout: 0 };
+        let tx = Transaction::new(
+            1,
+            vec![TxInput::new(outpoint, vec![], 0)],
+            vec![TxOutput::new(900, vec![2])],
+            0,
+        );
+        (outpoint, tx)
+    }
+
+    #[test]
+    fn test_adds_append_without_rewriting() {
+        let path = temp_path();
+        let mut utxo_set = UtxoSet::new();
+        let (_, first) = funded_spend(&mut utxo_set, b"a");
+        let (_, second) = funded_spend(&mut utxo_set, b"b");
+        let mut mempool =
+            BondMempool::open(MempoolStore::open(&path).unwrap(), &utxo_set, 1, 0).unwrap();
+
+        mempool.add_transaction(first, &utxo_set, 1, 0).unwrap();
+        let after_first = fs::read(&path).unwrap();
+        mempool.add_transaction(second, &utxo_set, 1, 0).unwrap();
+        let after_second = fs::read(&path).unwrap();
+
+        assert!(after_second.starts_with(&after_first));
+        assert!(after_second.len() > after_first.len());
+        assert_eq!(mempool.store().unwrap().records(), 2);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_compaction_and_reload_keep_live_set() {
+        let path = temp_path();
+        let mut utxo_set = UtxoSet::new();
+        let (confirmed_input, confirmed) = funded_spend(&mut utxo_set, b"a");
+        let (_, pending) = funded_spend(&mut utxo_set, b"b");
+        let (_, spent_elsewhere) = funded_spend(&mut utxo_set, b"c");
+        let mut mempool =
+            BondMempool::open(MempoolStore::open(&path).unwrap(), &utxo_set, 1, 0).unwrap();
+        for tx in [&confirmed, &pending, &spent_elsewhere] {
+            mempool
+                .add_transaction(tx.clone(), &utxo_set, 1, 0)
+                .unwrap();
+        }
+
+        // Confirmação grava a remoção; a compactação a descarta
+        mempool.remove_transactions(std::slice::from_ref(&confirmed));
+        utxo_set.remove_utxo(&confirmed_input);
+        assert_eq!(mempool.store().unwrap().records(), 4);
+        mempool.compact_store().unwrap();
+        assert_eq!(mempool.store().unwrap().records(), 2);
+        let logged = MempoolStore::open(&path).unwrap().load().unwrap();
+        assert_eq!(logged.len(), 2);
+        assert!(!logged.contains(&confirmed));
+
+        // Input gasto por outro bloco enquanto o nó estava parado
+        utxo_set.remove_utxo(&spent_elsewhere.inputs[0].previous_output);
+        drop(mempool);
+        let reloaded =
+            BondMempool::open(MempoolStore::open(&path).unwrap(), &utxo_set, 1, 0).unwrap();
+        assert_eq!(reloaded.len(), 1);
+        assert!(reloaded.contains(&pending.hash().unwrap()));
+        assert_eq!(reloaded.store().unwrap().records(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_dead_records_trigger_compaction() {
+        let path = temp_path();
+        let mut utxo_set = UtxoSet::new();
+        let mut mempool =
+            BondMempool::open(MempoolStore::open(&path).unwrap(), &utxo_set, 1, 0).unwrap();
+
+        for i in 0..40u32 {
+            let (_, tx) = funded_spend(&mut utxo_set, &i.to_le_bytes());
+            mempool
+                .add_transaction(tx.clone(), &utxo_set, 1, 0)
+                .unwrap();
+            mempool.remove_transactions(&[tx]);
+        }
+
+        // 80 registros mortos ultrapassariam a folga sem compactação
+        let records = mempool.store().unwrap().records();
+        assert!(records <= COMPACTION_SLACK + 1);
+        assert!(mempool.store().unwrap().load().unwrap().is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+}
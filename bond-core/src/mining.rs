@@ -1,10 +1,30 @@
 use crate::block::{calculate_merkle_root, Block, BlockHeader};
 use crate::transaction::Transaction;
 use chrono::Utc;
-use shared::{BlockchainError, Hash256, Result};
-use std::sync::atomic::{AtomicBool, Ordering};
+use shared::{
+    compact_from_leading_zeros, compact_to_target, scale_target, target_to_compact,
+    BlockchainError, Hash256, Result, MAX_TARGET_BITS,
+};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Hashes que cada thread acumula antes de somá-los ao contador comum
+const ATTEMPTS_FLUSH_INTERVAL: u64 = 1024;
+
+/// Limite de uso de CPU do minerador
+///
+/// Cada thread dorme `pause` a cada `hashes_per_pause` hashes, o que limita
+/// a taxa de cada uma a no máximo `hashes_per_pause / pause` hashes por
+/// segundo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MiningThrottle {
+    /// Hashes calculados entre duas pausas
+    pub hashes_per_pause: u64,
+    /// Duração de cada pausa
+    pub pause: Duration,
+}
 
 /// Configuração do minerador
 #[derive(Debug, Clone)]
@@ -13,8 +33,10 @@ pub struct MinerConfig {
     pub reward_script: Vec<u8>,
     /// Número de threads para mineração
     pub threads: usize,
-    /// Dificuldade alvo
+    /// Dificuldade usada por `mine_block`, em bits zero iniciais
     pub difficulty: u32,
+    /// Pausas para limitar o uso de CPU; `None` usa os núcleos a 100%
+    pub throttle: Option<MiningThrottle>,
 }
 
 impl Default for MinerConfig {
@@ -23,6 +45,7 @@ impl Default for MinerConfig {
             reward_script: vec![0x76, 0xa9, 0x14], // Script P2PKH placeholder
             threads: num_cpus::get().unwrap_or(1),
             difficulty: 20, // Dificuldade inicial
+            throttle: None,
         }
     }
 }
@@ -33,7 +56,64 @@ pub struct MiningResult {
     pub block: Block,
     pub hash: Hash256,
     pub nonce: u64,
+    /// Hashes calculados por todas as threads até a solução
     pub attempts: u64,
+    /// Tempo de mineração até a solução
+    pub duration_secs: f64,
+    /// Taxa de hash observada (`attempts / duration_secs`)
+    pub hash_rate: f64,
+}
+
+/// Mineração em andamento nas threads de trabalho
+///
+/// Devolvido por `Miner::start_mining`; permite cancelar a mineração de
+/// outra thread, por exemplo quando chega um bloco concorrente.
+pub struct MiningHandle {
+    is_mining: Arc<AtomicBool>,
+    result: Arc<Mutex<Option<MiningResult>>>,
+    workers: Vec<JoinHandle<()>>,
+    attempts: Arc<AtomicU64>,
+    started_at: Instant,
+}
+
+impl MiningHandle {
+    /// Pede às threads de trabalho que parem
+    pub fn cancel(&self) {
+        self.is_mining.store(false, Ordering::SeqCst);
+    }
+
+    /// Verifica se as threads ainda estão minerando
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.workers.iter().any(|worker| !worker.is_finished())
+    }
+
+    /// Hashes calculados até agora por todas as threads
+    ///
+    /// Cada thread soma seus hashes em lotes, então o valor pode ficar
+    /// um pouco atrás do real enquanto a mineração roda.
+    #[must_use]
+    pub fn attempts(&self) -> u64 {
+        self.attempts.load(Ordering::Relaxed)
+    }
+
+    /// Taxa de hash observada desde o início da mineração
+    #[must_use]
+    pub fn hash_rate(&self) -> f64 {
+        hash_rate(self.attempts(), self.started_at.elapsed())
+    }
+
+    /// Espera as threads terminarem
+    ///
+    /// Retorna `None` se a mineração foi cancelada ou o espaço de nonce
+    /// se esgotou sem solução.
+    #[must_use]
+    pub fn join(self) -> Option<MiningResult> {
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+        self.result.lock().ok()?.take()
+    }
 }
 
 /// Minerador de blocos
@@ -69,11 +149,11 @@ impl Miner {
             transactions,
             block_height,
             reward,
-            self.config.difficulty,
+            compact_from_leading_zeros(self.config.difficulty),
         )
     }
 
-    /// Minera um bloco com dificuldade específica
+    /// Minera um bloco com o alvo compacto (nBits) indicado
     ///
     /// # Errors
     ///
@@ -84,8 +164,29 @@ impl Miner {
         transactions: Vec<Transaction>,
         block_height: u64,
         reward: u64,
-        difficulty: u32,
+        bits: u32,
     ) -> Result<MiningResult> {
+        self.start_mining(previous_hash, transactions, block_height, reward, bits)?
+            .join()
+            .ok_or(BlockchainError::NonceNotFound)
+    }
+
+    /// Inicia a mineração de um bloco sem bloquear a thread chamadora
+    ///
+    /// As threads de trabalho rodam até encontrar uma solução ou até
+    /// `MiningHandle::cancel` (ou `stop_mining`) ser chamado.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se não conseguir calcular o merkle root
+    pub fn start_mining(
+        &self,
+        previous_hash: Hash256,
+        transactions: Vec<Transaction>,
+        block_height: u64,
+        reward: u64,
+        bits: u32,
+    ) -> Result<MiningHandle> {
         // Criar transação coinbase
         let coinbase =
             Transaction::coinbase(block_height, reward, self.config.reward_script.clone());
@@ -103,84 +204,93 @@ impl Miner {
             previous_hash,
             merkle_root,
             Utc::now(),
-            difficulty, // Usar dificuldade fornecida
-            0,          // nonce será incrementado durante a mineração
+            bits, // Usar alvo fornecido
+            0,    // nonce será incrementado durante a mineração
         );
 
         // Minerar com múltiplas threads
-        self.mine_header_parallel(&header, &all_transactions)
+        Ok(self.mine_header_parallel(&header, &all_transactions))
     }
 
-    /// Mineração paralela do cabeçalho
+    /// Distribui a mineração do cabeçalho entre as threads
     fn mine_header_parallel(
         &self,
         header: &BlockHeader,
         transactions: &[Transaction],
-    ) -> Result<MiningResult> {
+    ) -> MiningHandle {
         let is_mining = Arc::clone(&self.is_mining);
         is_mining.store(true, Ordering::SeqCst);
 
         let result = Arc::new(Mutex::new(None));
-        let mut handles = vec![];
+        let attempts = Arc::new(AtomicU64::new(0));
+        let started_at = Instant::now();
+        let threads = self.config.threads.max(1);
+        let mut workers = vec![];
 
         // Dividir o espaço de nonce entre threads
-        let nonce_per_thread = u64::MAX / self.config.threads as u64;
+        let nonce_per_thread = u64::MAX / threads as u64;
 
-        for thread_id in 0..self.config.threads {
+        for thread_id in 0..threads {
             let header_clone = header.clone();
             let transactions_clone = transactions.to_owned();
             let is_mining_clone = Arc::clone(&is_mining);
             let result_clone = Arc::clone(&result);
+            let attempts_clone = Arc::clone(&attempts);
+            let throttle = self.config.throttle;
 
             let start_nonce = thread_id as u64 * nonce_per_thread;
-            let end_nonce = if thread_id == self.config.threads - 1 {
+            let end_nonce = if thread_id == threads - 1 {
                 u64::MAX
             } else {
                 (thread_id as u64 + 1) * nonce_per_thread
             };
 
-            let handle = thread::spawn(move || {
+            let worker = thread::spawn(move || {
                 Self::mine_header_range(
                     header_clone,
                     &transactions_clone,
-                    start_nonce,
-                    end_nonce,
+                    start_nonce..end_nonce,
+                    throttle,
                     &is_mining_clone,
-                    &result_clone,
+                    &MiningProgress {
+                        attempts: &attempts_clone,
+                        started_at,
+                        result: &result_clone,
+                    },
                 );
             });
 
-            handles.push(handle);
+            workers.push(worker);
         }
 
-        // Esperar todas as threads terminarem
-        for handle in handles {
-            handle.join().unwrap();
+        MiningHandle {
+            is_mining,
+            result,
+            workers,
+            attempts,
+            started_at,
         }
-
-        // Extrair resultado
-        let result = result
-            .lock()
-            .unwrap()
-            .take()
-            .ok_or(BlockchainError::NonceNotFound)?;
-
-        Ok(result)
     }
 
     /// Minera um cabeçalho em um intervalo de nonce específico
-    #[allow(clippy::needless_pass_by_value)] // Arc types are cheap to clone
     fn mine_header_range(
         mut header: BlockHeader,
         transactions: &[Transaction],
-        start_nonce: u64,
-        end_nonce: u64,
+        nonces: std::ops::Range<u64>,
+        throttle: Option<MiningThrottle>,
         is_mining: &Arc<AtomicBool>,
-        result: &Arc<Mutex<Option<MiningResult>>>,
+        progress: &MiningProgress<'_>,
     ) {
+        let MiningProgress {
+            attempts: total_attempts,
+            started_at,
+            result,
+        } = progress;
         let mut attempts = 0u64;
+        let mut unflushed = 0u64;
+        let target = compact_to_target(header.bits);
 
-        for nonce in start_nonce..end_nonce {
+        for nonce in nonces {
             // Verificar se outra thread já encontrou solução
             if !is_mining.load(Ordering::SeqCst) {
                 break;
@@ -193,19 +303,28 @@ impl Miner {
 
             header.nonce = nonce;
             attempts += 1;
+            unflushed += 1;
+            if unflushed == ATTEMPTS_FLUSH_INTERVAL {
+                total_attempts.fetch_add(std::mem::take(&mut unflushed), Ordering::Relaxed);
+            }
 
             // Calcular hash
             if let Ok(hash) = header.hash() {
-                if hash.meets_difficulty(header.difficulty) {
+                if hash.meets_target(&target) {
                     // Encontrou solução!
                     is_mining.store(false, Ordering::SeqCst);
 
+                    let pending = std::mem::take(&mut unflushed);
+                    let total = total_attempts.fetch_add(pending, Ordering::Relaxed) + pending;
+                    let elapsed = started_at.elapsed();
                     let block = Block::new(header, transactions.to_owned());
                     let mining_result = MiningResult {
                         block,
                         hash,
                         nonce,
-                        attempts,
+                        attempts: total,
+                        duration_secs: elapsed.as_secs_f64(),
+                        hash_rate: hash_rate(total, elapsed),
                     };
 
                     *result.lock().unwrap() = Some(mining_result);
@@ -214,10 +333,21 @@ impl Miner {
             }
 
             // Atualizar timestamp periodicamente
-            if attempts % 100_000 == 0 {
+            if attempts.is_multiple_of(100_000) {
                 header.timestamp = Utc::now();
             }
+
+            if let Some(throttle) = throttle {
+                if throttle.hashes_per_pause > 0
+                    && attempts.is_multiple_of(throttle.hashes_per_pause)
+                {
+                    total_attempts.fetch_add(std::mem::take(&mut unflushed), Ordering::Relaxed);
+                    thread::sleep(throttle.pause);
+                }
+            }
         }
+
+        total_attempts.fetch_add(unflushed, Ordering::Relaxed);
     }
 
     /// Para a mineração
@@ -289,61 +419,64 @@ impl DifficultyAdjuster {
         }
     }
 
-    /// Calcula a nova dificuldade baseada no histórico de blocos
+    /// Calcula o alvo (nBits) do próximo bloco a partir do histórico
     ///
-    /// # Errors
-    ///
-    /// Retorna erro se os blocos não tiverem timestamps válidos
+    /// O alvo só muda quando o número de blocos é múltiplo de
+    /// `adjustment_period`: ele é multiplicado pela razão entre o tempo real
+    /// e o esperado do último período, limitada a 4x em cada direção, e
+    /// nunca fica mais fácil que `MAX_TARGET_BITS`.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Pode entrar em pânico se o slice de blocos estiver vazio (verificado antes)
-    #[allow(clippy::cast_possible_truncation)] // Conversões seguras para cálculos de dificuldade
-    #[allow(clippy::cast_sign_loss)] // Conversões seguras de duração
-    #[allow(clippy::cast_precision_loss)] // Conversões necessárias para cálculos
-    pub fn calculate_new_difficulty(
-        &self,
-        current_difficulty: u32,
-        blocks: &[Block],
-    ) -> Result<u32> {
+    /// Retorna erro se o período de ajuste não couber em `usize`
+    pub fn calculate_new_difficulty(&self, current_bits: u32, blocks: &[Block]) -> Result<u32> {
         let adjustment_period_usize = usize::try_from(self.adjustment_period).map_err(|_| {
             BlockchainError::InvalidBlock("Adjustment period too large".to_string())
         })?;
-
-        if blocks.len() < adjustment_period_usize {
-            return Ok(current_difficulty); // Não ajustar ainda
+        let expected_time = self
+            .target_block_time
+            .saturating_mul(self.adjustment_period.saturating_sub(1));
+
+        if expected_time == 0
+            || blocks.len() < adjustment_period_usize
+            || !blocks.len().is_multiple_of(adjustment_period_usize)
+        {
+            return Ok(current_bits); // Não ajustar ainda
         }
 
         let recent_blocks = &blocks[blocks.len() - adjustment_period_usize..];
 
         // Calcular tempo real entre o primeiro e último bloco
-        let first_timestamp = recent_blocks.first().unwrap().header.timestamp;
-        let last_timestamp = recent_blocks.last().unwrap().header.timestamp;
-
-        let actual_time =
-            u64::try_from((last_timestamp - first_timestamp).num_seconds()).map_err(|_| {
-                BlockchainError::InvalidBlock("Invalid timestamp difference".to_string())
-            })?;
-        let expected_time = self.target_block_time * (self.adjustment_period - 1);
-
-        // Calcular fator de ajuste
-        let adjustment_factor = actual_time as f64 / expected_time as f64;
-
-        // Limitar ajuste a 4x para cima ou para baixo
-        let clamped_factor = adjustment_factor.clamp(0.25, 4.0);
-
-        // Nova dificuldade (inversa do fator)
-        let new_difficulty = if clamped_factor > 1.0 {
-            // Blocos muito lentos - diminuir dificuldade
-            let decrease = (clamped_factor - 1.0) as u32;
-            current_difficulty.saturating_sub(decrease).max(1)
-        } else {
-            // Blocos muito rápidos - aumentar dificuldade
-            let increase = ((1.0 / clamped_factor) - 1.0) as u32;
-            current_difficulty.saturating_add(increase)
-        };
+        let first_timestamp = recent_blocks[0].header.timestamp;
+        let last_timestamp = recent_blocks[adjustment_period_usize - 1].header.timestamp;
+        let actual_time = u64::try_from((last_timestamp - first_timestamp).num_seconds())
+            .unwrap_or(0)
+            .clamp((expected_time / 4).max(1), expected_time.saturating_mul(4));
+
+        // Blocos rápidos reduzem o alvo (mais difícil), lentos o aumentam
+        let new_target = scale_target(&compact_to_target(current_bits), actual_time, expected_time);
+        if new_target > compact_to_target(MAX_TARGET_BITS) {
+            return Ok(MAX_TARGET_BITS);
+        }
+        Ok(target_to_compact(&new_target))
+    }
+}
+
+/// Estado compartilhado pelas threads de uma mineração
+struct MiningProgress<'a> {
+    attempts: &'a AtomicU64,
+    started_at: Instant,
+    result: &'a Mutex<Option<MiningResult>>,
+}
 
-        Ok(new_difficulty.min(32)) // Limitar dificuldade máxima
+/// Hashes por segundo em `elapsed`
+#[allow(clippy::cast_precision_loss)] // Conversão intencional para cálculo de taxa
+fn hash_rate(attempts: u64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        attempts as f64 / secs
+    } else {
+        0.0
     }
 }
 
@@ -360,6 +493,8 @@ mod num_cpus {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared::target_work;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn test_miner_creation() {
@@ -375,6 +510,7 @@ mod tests {
             reward_script: vec![1, 2, 3],
             threads: 1,
             difficulty: 1, // Dificuldade muito baixa para teste rápido
+            throttle: None,
         };
 
         let miner = Miner::new(config);
@@ -396,25 +532,95 @@ mod tests {
     #[test]
     fn test_difficulty_adjustment() {
         let adjuster = DifficultyAdjuster::new(600, 10); // 10 blocos para teste
+        let bits = compact_from_leading_zeros(20);
+
+        // Blocos de 5 min (muito rápido)
+        let blocks = blocks_with_spacing(10, bits, 300);
+        let new_bits = adjuster.calculate_new_difficulty(bits, &blocks).unwrap();
+
+        // Dificuldade deve aumentar pois blocos estão sendo minerados muito rapidamente
+        assert!(target_work(new_bits) > target_work(bits));
+
+        // Fora da fronteira do período o alvo não muda
+        let blocks = blocks_with_spacing(11, bits, 300);
+        assert_eq!(
+            adjuster.calculate_new_difficulty(bits, &blocks).unwrap(),
+            bits
+        );
+    }
 
-        // Criar blocos simulados
-        let mut blocks = vec![];
+    fn blocks_with_spacing(count: u64, bits: u32, spacing_secs: i64) -> Vec<Block> {
         let mut timestamp = Utc::now();
+        (0..count)
+            .map(|i| {
+                let coinbase = Transaction::coinbase(i, 5000, vec![1, 2, 3]);
+                let merkle_root = calculate_merkle_root(std::slice::from_ref(&coinbase)).unwrap();
+                let header = BlockHeader::new(1, Hash256::zero(), merkle_root, timestamp, bits, 0);
+                timestamp += chrono::Duration::seconds(spacing_secs);
+                Block::new(header, vec![coinbase])
+            })
+            .collect()
+    }
 
-        for i in 0..10 {
-            let coinbase = Transaction::coinbase(i, 5000, vec![1, 2, 3]);
-            let merkle_root = calculate_merkle_root(std::slice::from_ref(&coinbase)).unwrap();
+    #[test]
+    fn test_target_scales_with_block_time() {
+        let adjuster = DifficultyAdjuster::new(600, 10);
+        let bits = compact_from_leading_zeros(20);
+        let target = compact_to_target(bits);
+        let work = target_work(bits);
+        let close = |value: u128, expected: u128| value.abs_diff(expected) * 1_000 < expected;
+
+        // Blocos 2x mais rápidos: alvo cai pela metade, trabalho dobra
+        let fast = adjuster
+            .calculate_new_difficulty(bits, &blocks_with_spacing(10, bits, 300))
+            .unwrap();
+        assert_eq!(fast, target_to_compact(&scale_target(&target, 1, 2)));
+        assert!(close(target_work(fast), work * 2));
 
-            let header = BlockHeader::new(1, Hash256::zero(), merkle_root, timestamp, 20, 0);
+        // Blocos 2x mais lentos: alvo dobra, trabalho cai pela metade
+        let slow = adjuster
+            .calculate_new_difficulty(bits, &blocks_with_spacing(10, bits, 1200))
+            .unwrap();
+        assert_eq!(slow, target_to_compact(&scale_target(&target, 2, 1)));
+        assert!(close(target_work(slow), work / 2));
 
-            blocks.push(Block::new(header, vec![coinbase]));
-            timestamp += chrono::Duration::seconds(300); // Blocos de 5 min (muito rápido)
-        }
+        // Ajuste limitado a 4x em cada direção
+        let very_fast = adjuster
+            .calculate_new_difficulty(bits, &blocks_with_spacing(10, bits, 1))
+            .unwrap();
+        assert!(close(target_work(very_fast), work * 4));
+        let very_slow = adjuster
+            .calculate_new_difficulty(bits, &blocks_with_spacing(10, bits, 100_000))
+            .unwrap();
+        assert!(close(target_work(very_slow), work / 4));
 
-        let new_difficulty = adjuster.calculate_new_difficulty(20, &blocks).unwrap();
+        // Nunca mais fácil que o alvo máximo
+        let easiest = adjuster
+            .calculate_new_difficulty(MAX_TARGET_BITS, &blocks_with_spacing(10, bits, 1200))
+            .unwrap();
+        assert_eq!(easiest, MAX_TARGET_BITS);
+    }
 
-        // Dificuldade deve aumentar pois blocos estão sendo minerados muito rapidamente
-        assert!(new_difficulty > 20);
+    #[test]
+    fn test_small_persistent_deviation_retargets() {
+        let adjuster = DifficultyAdjuster::new(600, 10);
+        let start = compact_from_leading_zeros(20);
+
+        // Blocos 10% mais rápidos ou mais lentos movem o alvo a cada período,
+        // sempre na mesma direção e na proporção do desvio
+        for (spacing, ratio) in [(540, 10.0 / 9.0), (660, 10.0 / 11.0)] {
+            let mut bits = start;
+            for _ in 0..4 {
+                let blocks = blocks_with_spacing(10, bits, spacing);
+                let new_bits = adjuster.calculate_new_difficulty(bits, &blocks).unwrap();
+                assert_ne!(new_bits, bits);
+
+                #[allow(clippy::cast_precision_loss)]
+                let change = target_work(new_bits) as f64 / target_work(bits) as f64;
+                assert!((change - ratio).abs() < 0.001, "{spacing}s: {change}");
+                bits = new_bits;
+            }
+        }
     }
 
     #[test]
@@ -431,4 +637,89 @@ mod tests {
         assert!(hashrate > 0.0);
         println!("Estimated hashrate: {hashrate:.2} H/s");
     }
+
+    #[test]
+    fn test_cancel_mining_handle() {
+        let config = MinerConfig {
+            threads: 2,
+            ..MinerConfig::default()
+        };
+        let miner = Miner::new(config);
+
+        // Alvo impossível de atingir em tempo de teste
+        let handle = miner
+            .start_mining(
+                Hash256::zero(),
+                vec![],
+                1,
+                5000,
+                compact_from_leading_zeros(200),
+            )
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(handle.is_running());
+        assert!(miner.is_mining());
+
+        let cancelled_at = Instant::now();
+        handle.cancel();
+        assert!(handle.join().is_none());
+        assert!(cancelled_at.elapsed() < Duration::from_secs(1));
+        assert!(!miner.is_mining());
+    }
+
+    #[test]
+    fn test_mining_result_reports_hash_rate() {
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![1, 2, 3],
+            threads: 2,
+            difficulty: 8,
+            throttle: None,
+        });
+        let result = miner.mine_block(Hash256::zero(), vec![], 1, 5000).unwrap();
+
+        assert!(result.attempts >= 1);
+        assert!(result.duration_secs > 0.0);
+        #[allow(clippy::cast_precision_loss)]
+        let expected = result.attempts as f64 / result.duration_secs;
+        assert!((result.hash_rate - expected).abs() <= expected * 1e-9);
+    }
+
+    #[test]
+    fn test_throttled_miner_has_lower_hash_rate() {
+        let measure = |throttle: Option<MiningThrottle>| {
+            let miner = Miner::new(MinerConfig {
+                threads: 1,
+                throttle,
+                ..MinerConfig::default()
+            });
+            // Alvo impossível: mede a taxa durante a mesma janela de tempo
+            let handle = miner
+                .start_mining(
+                    Hash256::zero(),
+                    vec![],
+                    1,
+                    5000,
+                    compact_from_leading_zeros(200),
+                )
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(300));
+            let rate = handle.hash_rate();
+            handle.cancel();
+            assert!(handle.join().is_none());
+            rate
+        };
+
+        let unthrottled = measure(None);
+        // No máximo 10 hashes a cada 5 ms: 2000 H/s
+        let throttled = measure(Some(MiningThrottle {
+            hashes_per_pause: 10,
+            pause: Duration::from_millis(5),
+        }));
+        assert!(throttled > 0.0);
+        assert!(throttled <= 2_000.0, "throttled: {throttled:.0} H/s");
+        assert!(
+            throttled < unthrottled,
+            "{throttled:.0} >= {unthrottled:.0}"
+        );
+    }
 }
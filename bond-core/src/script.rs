@@ -0,0 +1,1055 @@
+//! Máquina virtual de scripts do Bond
+//!
+//! Scripts seguem o modelo de pilha do Bitcoin: o `script_sig` de um input
+//! é executado primeiro e o `script_pubkey` do UTXO gasto é executado em
+//! seguida sobre a mesma pilha. O gasto é válido se o topo final for verdadeiro.
+
+use chrono::Utc;
+use shared::{
+    signature_from_bytes, verify_transaction_signature, BlockchainError, Hash256, PublicKey,
+    Signature,
+};
+use thiserror::Error;
+
+/// Tamanho máximo de um script (assinaturas ML-DSA são grandes)
+pub const MAX_SCRIPT_SIZE: usize = 20_000;
+
+/// Número máximo de itens na pilha
+pub const MAX_STACK_SIZE: usize = 1_000;
+
+/// Número máximo de operações (não-push) por execução
+pub const MAX_OPS: usize = 201;
+
+/// Número máximo de chaves públicas em um `OP_CHECKMULTISIG`
+pub const MAX_PUBKEYS_PER_MULTISIG: usize = 20;
+
+/// Limite padrão de gas por validação (`script_sig` + `script_pubkey`)
+///
+/// Comporta com folga um multisig com o número máximo de chaves.
+pub const DEFAULT_SCRIPT_GAS_LIMIT: u64 = 50_000;
+
+/// Gas de um push de dados
+pub const GAS_PUSH: u64 = 1;
+
+/// Gas de uma verificação de assinatura ML-DSA
+pub const GAS_CHECKSIG: u64 = 1_000;
+
+/// Maior opcode de push direto (0x01..=0x4b empilham N bytes)
+const MAX_DIRECT_PUSH: u8 = 0x4b;
+
+/// Opcodes suportados pela VM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum OpCode {
+    /// Empilha um vetor vazio (falso)
+    Op0 = 0x00,
+    /// Próximo byte indica o tamanho do push
+    OpPushData1 = 0x4c,
+    /// Próximos 2 bytes (LE) indicam o tamanho do push
+    OpPushData2 = 0x4d,
+    /// Próximos 4 bytes (LE) indicam o tamanho do push
+    OpPushData4 = 0x4e,
+    /// Empilha verdadeiro
+    Op1 = 0x4f,
+    /// Início de bloco condicional
+    OpIf = 0x50,
+    /// Ramo alternativo do bloco condicional
+    OpElse = 0x51,
+    /// Fim de bloco condicional
+    OpEndIf = 0x52,
+    /// Falha se o topo não for verdadeiro
+    OpVerify = 0x53,
+    /// Marca o script como não gastável
+    OpReturn = 0x54,
+    /// Duplica o topo da pilha
+    OpDup = 0x60,
+    /// Remove o topo da pilha
+    OpDrop = 0x61,
+    /// Troca os dois itens do topo
+    OpSwap = 0x62,
+    /// Compara os dois itens do topo byte a byte
+    OpEqual = 0x70,
+    /// `OP_EQUAL` seguido de `OP_VERIFY`
+    OpEqualVerify = 0x71,
+    /// Soma os dois números do topo
+    OpAdd = 0x80,
+    /// Subtrai os dois números do topo
+    OpSub = 0x81,
+    /// Compara numericamente os dois itens do topo
+    OpNumEqual = 0x82,
+    /// Keccak-256 do topo da pilha
+    OpHash256 = 0xa0,
+    /// Falha se a altura do bloco não tiver alcançado o número no topo,
+    /// que permanece na pilha
+    OpCheckLockTimeVerify = 0xb1,
+    /// Verifica uma assinatura ML-DSA contra o hash da transação
+    OpCheckSig = 0xac,
+    /// `OP_CHECKSIG` seguido de `OP_VERIFY`
+    OpCheckSigVerify = 0xad,
+    /// Verifica m-de-n assinaturas ML-DSA
+    OpCheckMultiSig = 0xae,
+}
+
+impl OpCode {
+    /// Custo em gas do opcode
+    ///
+    /// `OP_CHECKMULTISIG` cobra este valor uma vez e mais `GAS_CHECKSIG` por
+    /// chave pública.
+    #[must_use]
+    pub const fn gas_cost(self) -> u64 {
+        match self {
+            Self::Op0 | Self::Op1 | Self::OpPushData1 | Self::OpPushData2 | Self::OpPushData4 => {
+                GAS_PUSH
+            }
+            Self::OpIf
+            | Self::OpElse
+            | Self::OpEndIf
+            | Self::OpVerify
+            | Self::OpReturn
+            | Self::OpCheckLockTimeVerify => 1,
+            Self::OpDup | Self::OpDrop | Self::OpSwap => 2,
+            Self::OpEqual | Self::OpEqualVerify | Self::OpAdd | Self::OpSub | Self::OpNumEqual => 3,
+            Self::OpHash256 => 30,
+            Self::OpCheckSig | Self::OpCheckSigVerify => GAS_CHECKSIG,
+            Self::OpCheckMultiSig => 10,
+        }
+    }
+}
+
+impl TryFrom<u8> for OpCode {
+    type Error = ScriptError;
+
+    fn try_from(byte: u8) -> std::result::Result<Self, Self::Error> {
+        match byte {
+            0x00 => Ok(Self::Op0),
+            0x4c => Ok(Self::OpPushData1),
+            0x4d => Ok(Self::OpPushData2),
+            0x4e => Ok(Self::OpPushData4),
+            0x4f => Ok(Self::Op1),
+            0x50 => Ok(Self::OpIf),
+            0x51 => Ok(Self::OpElse),
+            0x52 => Ok(Self::OpEndIf),
+            0x53 => Ok(Self::OpVerify),
+            0x54 => Ok(Self::OpReturn),
+            0x60 => Ok(Self::OpDup),
+            0x61 => Ok(Self::OpDrop),
+            0x62 => Ok(Self::OpSwap),
+            0x70 => Ok(Self::OpEqual),
+            0x71 => Ok(Self::OpEqualVerify),
+            0x80 => Ok(Self::OpAdd),
+            0x81 => Ok(Self::OpSub),
+            0x82 => Ok(Self::OpNumEqual),
+            0xa0 => Ok(Self::OpHash256),
+            0xb1 => Ok(Self::OpCheckLockTimeVerify),
+            0xac => Ok(Self::OpCheckSig),
+            0xad => Ok(Self::OpCheckSigVerify),
+            0xae => Ok(Self::OpCheckMultiSig),
+            other => Err(ScriptError::InvalidOpcode(other)),
+        }
+    }
+}
+
+/// Erros de execução de script
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ScriptError {
+    #[error("Opcode inválido: 0x{0:02x}")]
+    InvalidOpcode(u8),
+
+    #[error("Opcode não implementado: {0:?}")]
+    UnimplementedOpcode(OpCode),
+
+    #[error("Pilha vazia")]
+    StackUnderflow,
+
+    #[error("Pilha excedeu o tamanho máximo")]
+    StackOverflow,
+
+    #[error("Script excede o tamanho máximo")]
+    ScriptTooLarge,
+
+    #[error("Script excede o número máximo de operações")]
+    TooManyOps,
+
+    #[error("Push de dados inválido")]
+    InvalidPushData,
+
+    #[error("Número inválido na pilha")]
+    InvalidNumber,
+
+    #[error("Verificação falhou")]
+    VerifyFailed,
+
+    #[error("Script marcado como não gastável")]
+    EarlyReturn,
+
+    #[error("OP_IF/OP_ELSE/OP_ENDIF desbalanceados")]
+    UnbalancedConditional,
+
+    #[error("Contagem inválida de chaves ou assinaturas no multisig")]
+    InvalidMultisigCount,
+
+    #[error("Altura de desbloqueio {0} ainda não alcançada")]
+    LockTimeNotReached(i64),
+
+    #[error("Gas esgotado: limite de {limit}")]
+    OutOfGas { limit: u64 },
+}
+
+impl From<ScriptError> for BlockchainError {
+    fn from(error: ScriptError) -> Self {
+        Self::InvalidTransaction(format!("Script inválido: {error}"))
+    }
+}
+
+/// Item da pilha da VM
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StackItem {
+    /// Dados arbitrários
+    Data(Vec<u8>),
+    /// Número inteiro
+    Number(i64),
+    /// Valor booleano
+    Boolean(bool),
+}
+
+impl StackItem {
+    /// Verifica se o item é considerado verdadeiro
+    #[must_use]
+    pub fn is_true(&self) -> bool {
+        match self {
+            Self::Data(data) => data.iter().any(|&b| b != 0),
+            Self::Number(n) => *n != 0,
+            Self::Boolean(b) => *b,
+        }
+    }
+
+    /// Representação em bytes do item
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Data(data) => data.clone(),
+            Self::Number(0) | Self::Boolean(false) => vec![],
+            Self::Number(n) => {
+                let bytes = n.to_le_bytes();
+                let len = 8 - bytes.iter().rev().take_while(|&&b| b == 0).count();
+                bytes[..len].to_vec()
+            }
+            Self::Boolean(true) => vec![1],
+        }
+    }
+
+    /// Interpreta o item como número (little-endian, até 8 bytes)
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se os dados excederem 8 bytes
+    pub fn to_number(&self) -> std::result::Result<i64, ScriptError> {
+        match self {
+            Self::Number(n) => Ok(*n),
+            Self::Boolean(b) => Ok(i64::from(*b)),
+            Self::Data(data) => {
+                if data.len() > 8 {
+                    return Err(ScriptError::InvalidNumber);
+                }
+                let mut bytes = [0u8; 8];
+                bytes[..data.len()].copy_from_slice(data);
+                Ok(i64::from_le_bytes(bytes))
+            }
+        }
+    }
+}
+
+/// Contexto de execução de um script
+#[derive(Debug, Clone)]
+pub struct ScriptContext {
+    /// Hash assinado pelo input (`Transaction::sighash`)
+    pub transaction_hash: Hash256,
+    /// Índice do input sendo validado
+    pub input_index: usize,
+    /// Altura do bloco que incluirá a transação (`OP_CHECKLOCKTIMEVERIFY`)
+    pub block_height: u64,
+}
+
+impl ScriptContext {
+    /// Cria um novo contexto de execução na altura zero
+    #[must_use]
+    pub const fn new(transaction_hash: Hash256, input_index: usize) -> Self {
+        Self {
+            transaction_hash,
+            input_index,
+            block_height: 0,
+        }
+    }
+
+    /// Define a altura do bloco que incluirá a transação
+    #[must_use]
+    pub const fn with_block_height(mut self, block_height: u64) -> Self {
+        self.block_height = block_height;
+        self
+    }
+}
+
+/// Máquina virtual de pilha para scripts Bond
+#[derive(Debug)]
+pub struct ScriptVM {
+    stack: Vec<StackItem>,
+    ops_count: usize,
+    /// Pilha de condições dos blocos `OP_IF` abertos
+    exec_stack: Vec<bool>,
+    /// Gas consumido desde a criação da VM
+    gas_used: u64,
+    /// Gas máximo que a VM pode consumir
+    gas_limit: u64,
+}
+
+impl Default for ScriptVM {
+    fn default() -> Self {
+        Self::with_gas_limit(DEFAULT_SCRIPT_GAS_LIMIT)
+    }
+}
+
+impl ScriptVM {
+    /// Cria uma nova VM com pilha vazia e o limite de gas padrão
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cria uma nova VM com pilha vazia e limite de gas `gas_limit`
+    #[must_use]
+    pub const fn with_gas_limit(gas_limit: u64) -> Self {
+        Self {
+            stack: Vec::new(),
+            ops_count: 0,
+            exec_stack: Vec::new(),
+            gas_used: 0,
+            gas_limit,
+        }
+    }
+
+    /// Gas consumido por todas as execuções desta VM
+    #[must_use]
+    pub const fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    /// Limite de gas desta VM
+    #[must_use]
+    pub const fn gas_limit(&self) -> u64 {
+        self.gas_limit
+    }
+
+    /// Valida um gasto executando `script_sig` seguido de `script_pubkey`
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se algum dos scripts for malformado ou abortar
+    pub fn verify(
+        script_sig: &[u8],
+        script_pubkey: &[u8],
+        context: &ScriptContext,
+    ) -> std::result::Result<bool, ScriptError> {
+        let mut vm = Self::new();
+        vm.execute(script_sig, context)?;
+        vm.execute(script_pubkey, context)
+    }
+
+    /// Retorna a pilha atual
+    #[must_use]
+    pub fn stack(&self) -> &[StackItem] {
+        &self.stack
+    }
+
+    /// Executa um script e retorna se o topo final é verdadeiro
+    ///
+    /// Cada opcode percorrido, inclusive em ramos não executados, é cobrado
+    /// conforme [`OpCode::gas_cost`]; o total fica disponível em
+    /// [`ScriptVM::gas_used`] para o cálculo de taxas.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se o script for malformado, exceder limites (incluindo o
+    /// de gas) ou abortar
+    pub fn execute(
+        &mut self,
+        script: &[u8],
+        context: &ScriptContext,
+    ) -> std::result::Result<bool, ScriptError> {
+        if script.len() > MAX_SCRIPT_SIZE {
+            return Err(ScriptError::ScriptTooLarge);
+        }
+
+        let mut pc = 0;
+        while pc < script.len() {
+            let byte = script[pc];
+            pc += 1;
+
+            // Dentro de um ramo não executado, apenas avançamos pelo script
+            let executing = self.exec_stack.iter().all(|&branch| branch);
+
+            if (0x01..=MAX_DIRECT_PUSH).contains(&byte) {
+                self.charge_gas(GAS_PUSH)?;
+                let data = read_bytes(script, &mut pc, usize::from(byte))?;
+                if executing {
+                    self.push(StackItem::Data(data))?;
+                }
+                continue;
+            }
+
+            let opcode = OpCode::try_from(byte)?;
+            self.charge_gas(opcode.gas_cost())?;
+            if !matches!(
+                opcode,
+                OpCode::Op0
+                    | OpCode::Op1
+                    | OpCode::OpPushData1
+                    | OpCode::OpPushData2
+                    | OpCode::OpPushData4
+            ) {
+                self.ops_count += 1;
+                if self.ops_count > MAX_OPS {
+                    return Err(ScriptError::TooManyOps);
+                }
+            }
+
+            match opcode {
+                OpCode::OpPushData1 | OpCode::OpPushData2 | OpCode::OpPushData4 => {
+                    let width = match opcode {
+                        OpCode::OpPushData1 => 1,
+                        OpCode::OpPushData2 => 2,
+                        _ => 4,
+                    };
+                    let len_bytes = read_bytes(script, &mut pc, width)?;
+                    let mut len = [0u8; 4];
+                    len[..width].copy_from_slice(&len_bytes);
+                    let len = usize::try_from(u32::from_le_bytes(len))
+                        .map_err(|_| ScriptError::InvalidPushData)?;
+                    let data = read_bytes(script, &mut pc, len)?;
+                    if executing {
+                        self.push(StackItem::Data(data))?;
+                    }
+                }
+                OpCode::OpIf => {
+                    let condition = executing && self.pop()?.is_true();
+                    self.exec_stack.push(condition);
+                }
+                OpCode::OpElse => {
+                    let branch = self
+                        .exec_stack
+                        .last_mut()
+                        .ok_or(ScriptError::UnbalancedConditional)?;
+                    *branch = !*branch;
+                }
+                OpCode::OpEndIf => {
+                    self.exec_stack
+                        .pop()
+                        .ok_or(ScriptError::UnbalancedConditional)?;
+                }
+                _ if !executing => {}
+                OpCode::Op0 => self.push(StackItem::Data(vec![]))?,
+                OpCode::Op1 => self.push(StackItem::Boolean(true))?,
+                OpCode::OpVerify => self.op_verify()?,
+                OpCode::OpReturn => return Err(ScriptError::EarlyReturn),
+                OpCode::OpDup => {
+                    let top = self
+                        .stack
+                        .last()
+                        .ok_or(ScriptError::StackUnderflow)?
+                        .clone();
+                    self.push(top)?;
+                }
+                OpCode::OpDrop => {
+                    self.pop()?;
+                }
+                OpCode::OpSwap => {
+                    let a = self.pop()?;
+                    let b = self.pop()?;
+                    self.push(a)?;
+                    self.push(b)?;
+                }
+                OpCode::OpEqual => self.op_equal()?,
+                OpCode::OpEqualVerify => {
+                    self.op_equal()?;
+                    self.op_verify()?;
+                }
+                OpCode::OpAdd | OpCode::OpSub => {
+                    let b = self.pop()?.to_number()?;
+                    let a = self.pop()?.to_number()?;
+                    let result = if opcode == OpCode::OpAdd {
+                        a.checked_add(b)
+                    } else {
+                        a.checked_sub(b)
+                    };
+                    self.push(StackItem::Number(result.ok_or(ScriptError::InvalidNumber)?))?;
+                }
+                OpCode::OpNumEqual => {
+                    let b = self.pop()?.to_number()?;
+                    let a = self.pop()?.to_number()?;
+                    self.push(StackItem::Boolean(a == b))?;
+                }
+                OpCode::OpHash256 => {
+                    let data = self.pop()?.to_bytes();
+                    let hash = Hash256::keccak256(&data);
+                    self.push(StackItem::Data(hash.as_bytes().to_vec()))?;
+                }
+                OpCode::OpCheckLockTimeVerify => self.op_checklocktimeverify(context)?,
+                OpCode::OpCheckSig => self.op_checksig(context)?,
+                OpCode::OpCheckSigVerify => {
+                    self.op_checksig(context)?;
+                    self.op_verify()?;
+                }
+                OpCode::OpCheckMultiSig => self.op_checkmultisig(context)?,
+            }
+        }
+
+        if !self.exec_stack.is_empty() {
+            return Err(ScriptError::UnbalancedConditional);
+        }
+
+        Ok(self.stack.last().is_some_and(StackItem::is_true))
+    }
+
+    fn charge_gas(&mut self, gas: u64) -> std::result::Result<(), ScriptError> {
+        let total = self.gas_used.saturating_add(gas);
+        if total > self.gas_limit {
+            return Err(ScriptError::OutOfGas {
+                limit: self.gas_limit,
+            });
+        }
+        self.gas_used = total;
+        Ok(())
+    }
+
+    fn push(&mut self, item: StackItem) -> std::result::Result<(), ScriptError> {
+        if self.stack.len() >= MAX_STACK_SIZE {
+            return Err(ScriptError::StackOverflow);
+        }
+        self.stack.push(item);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> std::result::Result<StackItem, ScriptError> {
+        self.stack.pop().ok_or(ScriptError::StackUnderflow)
+    }
+
+    fn op_verify(&mut self) -> std::result::Result<(), ScriptError> {
+        if self.pop()?.is_true() {
+            Ok(())
+        } else {
+            Err(ScriptError::VerifyFailed)
+        }
+    }
+
+    fn op_equal(&mut self) -> std::result::Result<(), ScriptError> {
+        let b = self.pop()?.to_bytes();
+        let a = self.pop()?.to_bytes();
+        self.push(StackItem::Boolean(a == b))
+    }
+
+    /// Exige que `context.block_height` tenha alcançado a altura no topo da
+    /// pilha (`<altura> OP_CHECKLOCKTIMEVERIFY OP_DROP ...`)
+    fn op_checklocktimeverify(
+        &self,
+        context: &ScriptContext,
+    ) -> std::result::Result<(), ScriptError> {
+        let lock_height = self
+            .stack
+            .last()
+            .ok_or(ScriptError::StackUnderflow)?
+            .to_number()?;
+        let lock_height_u64 = u64::try_from(lock_height).map_err(|_| ScriptError::InvalidNumber)?;
+        if context.block_height < lock_height_u64 {
+            return Err(ScriptError::LockTimeNotReached(lock_height));
+        }
+        Ok(())
+    }
+
+    /// Verifica uma assinatura ML-DSA (`<sig> <pubkey> OP_CHECKSIG`)
+    fn op_checksig(&mut self, context: &ScriptContext) -> std::result::Result<(), ScriptError> {
+        let pubkey = self.pop()?.to_bytes();
+        let signature = self.pop()?.to_bytes();
+
+        let valid = check_signature(&signature, &pubkey, &context.transaction_hash);
+        self.push(StackItem::Boolean(valid))
+    }
+
+    /// Verifica m-de-n assinaturas ML-DSA
+    /// (`<sig_1> .. <sig_m> <m> <pk_1> .. <pk_n> <n> OP_CHECKMULTISIG`)
+    ///
+    /// As assinaturas devem seguir a mesma ordem das chaves, e cada chave
+    /// valida no máximo uma assinatura.
+    fn op_checkmultisig(
+        &mut self,
+        context: &ScriptContext,
+    ) -> std::result::Result<(), ScriptError> {
+        let n = self.pop_count(MAX_PUBKEYS_PER_MULTISIG)?;
+
+        // Cada chave conta como uma operação, como no Bitcoin
+        self.ops_count += n;
+        if self.ops_count > MAX_OPS {
+            return Err(ScriptError::TooManyOps);
+        }
+        self.charge_gas(GAS_CHECKSIG.saturating_mul(n as u64))?;
+
+        let pubkeys = self.pop_many(n)?;
+        let m = self.pop_count(n)?;
+        let signatures = self.pop_many(m)?;
+
+        let mut keys = pubkeys.iter();
+        let mut matched = 0;
+        for signature in &signatures {
+            // Avança pelas chaves até encontrar a que valida esta assinatura
+            if keys
+                .by_ref()
+                .any(|pubkey| check_signature(signature, pubkey, &context.transaction_hash))
+            {
+                matched += 1;
+            } else {
+                break;
+            }
+        }
+
+        self.push(StackItem::Boolean(matched == m))
+    }
+
+    /// Desempilha um contador entre 0 e `max`
+    fn pop_count(&mut self, max: usize) -> std::result::Result<usize, ScriptError> {
+        let count = self.pop()?.to_number()?;
+        usize::try_from(count)
+            .ok()
+            .filter(|&count| count <= max)
+            .ok_or(ScriptError::InvalidMultisigCount)
+    }
+
+    /// Desempilha `count` itens, preservando a ordem em que foram empilhados
+    fn pop_many(&mut self, count: usize) -> std::result::Result<Vec<Vec<u8>>, ScriptError> {
+        if self.stack.len() < count {
+            return Err(ScriptError::StackUnderflow);
+        }
+        let items = self.stack.split_off(self.stack.len() - count);
+        Ok(items.iter().map(StackItem::to_bytes).collect())
+    }
+}
+
+/// Verifica assinatura e chave pública brutas contra um hash de transação
+///
+/// Qualquer dado malformado resulta em assinatura inválida, nunca em pânico.
+fn check_signature(signature: &[u8], pubkey: &[u8], transaction_hash: &Hash256) -> bool {
+    if signature.is_empty() || pubkey.is_empty() {
+        return false;
+    }
+
+    let Ok(public_key) = PublicKey::from_bytes(pubkey.to_vec()) else {
+        return false;
+    };
+    let signature = signature_from_bytes(signature.to_vec(), public_key, Utc::now());
+
+    verify_transaction_signature(transaction_hash, &signature).unwrap_or(false)
+}
+
+fn read_bytes(
+    script: &[u8],
+    pc: &mut usize,
+    len: usize,
+) -> std::result::Result<Vec<u8>, ScriptError> {
+    let end = pc.checked_add(len).ok_or(ScriptError::InvalidPushData)?;
+    let data = script.get(*pc..end).ok_or(ScriptError::InvalidPushData)?;
+    *pc = end;
+    Ok(data.to_vec())
+}
+
+/// Adiciona um push de dados ao script usando a codificação mínima
+pub fn push_data(script: &mut Vec<u8>, data: &[u8]) {
+    match data.len() {
+        0 => script.push(OpCode::Op0 as u8),
+        #[allow(clippy::cast_possible_truncation)] // Intervalo verificado pelo match
+        len @ 1..=0x4b => script.push(len as u8),
+        #[allow(clippy::cast_possible_truncation)]
+        len @ 0x4c..=0xff => {
+            script.push(OpCode::OpPushData1 as u8);
+            script.push(len as u8);
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        len @ 0x100..=0xffff => {
+            script.push(OpCode::OpPushData2 as u8);
+            script.extend_from_slice(&(len as u16).to_le_bytes());
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        len => {
+            script.push(OpCode::OpPushData4 as u8);
+            script.extend_from_slice(&(len as u32).to_le_bytes());
+        }
+    }
+    script.extend_from_slice(data);
+}
+
+/// Cria um `script_pubkey` P2PKH: `OP_DUP OP_HASH256 <hash> OP_EQUALVERIFY OP_CHECKSIG`
+#[must_use]
+pub fn p2pkh_script_pubkey(public_key: &PublicKey) -> Vec<u8> {
+    let pubkey_hash = Hash256::keccak256(public_key.as_bytes());
+    let mut script = vec![OpCode::OpDup as u8, OpCode::OpHash256 as u8];
+    push_data(&mut script, pubkey_hash.as_bytes());
+    script.push(OpCode::OpEqualVerify as u8);
+    script.push(OpCode::OpCheckSig as u8);
+    script
+}
+
+/// Cria um `script_sig` P2PKH: `<sig> <pubkey>`
+#[must_use]
+pub fn p2pkh_script_sig(signature: &Signature) -> Vec<u8> {
+    let mut script = Vec::new();
+    push_data(&mut script, signature.as_bytes());
+    push_data(&mut script, signature.public_key().as_bytes());
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::Transaction;
+    use shared::{sign_transaction_hash, KeyPair, SignatureAlgorithm};
+
+    #[test]
+    fn test_p2pkh_checksig() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
+        let tx = Transaction::coinbase(1, 5000, vec![1, 2, 3]);
+        let tx_hash = tx.hash().unwrap();
+        let context = ScriptContext::new(tx_hash, 0);
+
+        let script_pubkey = p2pkh_script_pubkey(&keypair.public_key);
+        let signature = sign_transaction_hash(&tx_hash, &keypair).unwrap();
+
+        // Assinatura válida
+        let script_sig = p2pkh_script_sig(&signature);
+        assert!(ScriptVM::verify(&script_sig, &script_pubkey, &context).unwrap());
+
+        // Assinatura adulterada
+        let mut tampered = signature.as_bytes().to_vec();
+        tampered[10] ^= 0xff;
+        let mut tampered_sig = Vec::new();
+        push_data(&mut tampered_sig, &tampered);
+        push_data(&mut tampered_sig, keypair.public_key.as_bytes());
+        assert!(!ScriptVM::verify(&tampered_sig, &script_pubkey, &context).unwrap());
+
+        // Assinatura válida sobre outro hash
+        let other_context = ScriptContext::new(Hash256::keccak256(b"outra tx"), 0);
+        assert!(!ScriptVM::verify(&script_sig, &script_pubkey, &other_context).unwrap());
+    }
+
+    #[test]
+    fn test_checksig_rejects_malformed_pubkey() {
+        let context = ScriptContext::new(Hash256::zero(), 0);
+        let mut script = Vec::new();
+        push_data(&mut script, &[1, 2, 3]);
+        push_data(&mut script, &[4, 5, 6]);
+        script.push(OpCode::OpCheckSig as u8);
+
+        let mut vm = ScriptVM::new();
+        assert!(!vm.execute(&script, &context).unwrap());
+        assert_eq!(vm.stack(), &[StackItem::Boolean(false)]);
+    }
+
+    fn conditional_script(condition: &[u8]) -> Vec<u8> {
+        // <cond> OP_IF <1> OP_ELSE <2> OP_ENDIF
+        let mut script = Vec::new();
+        push_data(&mut script, condition);
+        script.push(OpCode::OpIf as u8);
+        push_data(&mut script, &[1]);
+        script.push(OpCode::OpElse as u8);
+        push_data(&mut script, &[2]);
+        script.push(OpCode::OpEndIf as u8);
+        script
+    }
+
+    #[test]
+    fn test_if_true_branch() {
+        let context = ScriptContext::new(Hash256::zero(), 0);
+        let mut vm = ScriptVM::new();
+
+        assert!(vm.execute(&conditional_script(&[1]), &context).unwrap());
+        assert_eq!(vm.stack(), &[StackItem::Data(vec![1])]);
+    }
+
+    #[test]
+    fn test_if_false_takes_else() {
+        let context = ScriptContext::new(Hash256::zero(), 0);
+        let mut vm = ScriptVM::new();
+
+        assert!(vm.execute(&conditional_script(&[0]), &context).unwrap());
+        assert_eq!(vm.stack(), &[StackItem::Data(vec![2])]);
+    }
+
+    #[test]
+    fn test_nested_if() {
+        let context = ScriptContext::new(Hash256::zero(), 0);
+
+        // <1> OP_IF <0> OP_IF <3> OP_ELSE <4> OP_ENDIF OP_ELSE <5> OP_ENDIF
+        let mut script = Vec::new();
+        push_data(&mut script, &[1]);
+        script.push(OpCode::OpIf as u8);
+        push_data(&mut script, &[0]);
+        script.push(OpCode::OpIf as u8);
+        push_data(&mut script, &[3]);
+        script.push(OpCode::OpElse as u8);
+        push_data(&mut script, &[4]);
+        script.push(OpCode::OpEndIf as u8);
+        script.push(OpCode::OpElse as u8);
+        push_data(&mut script, &[5]);
+        script.push(OpCode::OpEndIf as u8);
+
+        let mut vm = ScriptVM::new();
+        assert!(vm.execute(&script, &context).unwrap());
+        assert_eq!(vm.stack(), &[StackItem::Data(vec![4])]);
+
+        // Ramo externo falso ignora todo o bloco interno
+        script[1] = 0;
+        let mut vm = ScriptVM::new();
+        assert!(vm.execute(&script, &context).unwrap());
+        assert_eq!(vm.stack(), &[StackItem::Data(vec![5])]);
+    }
+
+    #[test]
+    fn test_unbalanced_conditionals() {
+        let context = ScriptContext::new(Hash256::zero(), 0);
+
+        let dangling_endif = vec![OpCode::Op1 as u8, OpCode::OpEndIf as u8];
+        assert_eq!(
+            ScriptVM::new().execute(&dangling_endif, &context),
+            Err(ScriptError::UnbalancedConditional)
+        );
+
+        let missing_endif = vec![OpCode::Op1 as u8, OpCode::OpIf as u8, OpCode::Op1 as u8];
+        assert_eq!(
+            ScriptVM::new().execute(&missing_endif, &context),
+            Err(ScriptError::UnbalancedConditional)
+        );
+    }
+
+    fn multisig_script_pubkey(m: u8, keypairs: &[KeyPair]) -> Vec<u8> {
+        let mut script = Vec::new();
+        push_data(&mut script, &[m]);
+        for keypair in keypairs {
+            push_data(&mut script, keypair.public_key.as_bytes());
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        push_data(&mut script, &[keypairs.len() as u8]);
+        script.push(OpCode::OpCheckMultiSig as u8);
+        script
+    }
+
+    #[test]
+    fn test_checkmultisig_2_of_3() {
+        let keypairs: Vec<KeyPair> = (0..3)
+            .map(|_| KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap())
+            .collect();
+        let tx_hash = Hash256::keccak256(b"multisig tx");
+        let context = ScriptContext::new(tx_hash, 0);
+        let script_pubkey = multisig_script_pubkey(2, &keypairs);
+
+        let sig_0 = sign_transaction_hash(&tx_hash, &keypairs[0]).unwrap();
+        let sig_2 = sign_transaction_hash(&tx_hash, &keypairs[2]).unwrap();
+
+        let mut script_sig = Vec::new();
+        push_data(&mut script_sig, sig_0.as_bytes());
+        push_data(&mut script_sig, sig_2.as_bytes());
+        assert!(ScriptVM::verify(&script_sig, &script_pubkey, &context).unwrap());
+
+        // Segunda assinatura adulterada
+        let mut tampered = sig_2.as_bytes().to_vec();
+        tampered[10] ^= 0xff;
+        let mut bad_script_sig = Vec::new();
+        push_data(&mut bad_script_sig, sig_0.as_bytes());
+        push_data(&mut bad_script_sig, &tampered);
+        assert!(!ScriptVM::verify(&bad_script_sig, &script_pubkey, &context).unwrap());
+
+        // A mesma assinatura não pode ser usada duas vezes
+        let mut reused = Vec::new();
+        push_data(&mut reused, sig_0.as_bytes());
+        push_data(&mut reused, sig_0.as_bytes());
+        assert!(!ScriptVM::verify(&reused, &script_pubkey, &context).unwrap());
+    }
+
+    #[test]
+    fn test_checkmultisig_rejects_invalid_counts() {
+        let context = ScriptContext::new(Hash256::zero(), 0);
+        let keypairs: Vec<KeyPair> = (0..2)
+            .map(|_| KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap())
+            .collect();
+
+        // m > n
+        let mut script = vec![OpCode::Op1 as u8, OpCode::Op1 as u8, OpCode::Op1 as u8];
+        script.extend(multisig_script_pubkey(3, &keypairs));
+        assert_eq!(
+            ScriptVM::new().execute(&script, &context),
+            Err(ScriptError::InvalidMultisigCount)
+        );
+
+        // Pilha sem as assinaturas exigidas
+        let script = multisig_script_pubkey(2, &keypairs);
+        assert_eq!(
+            ScriptVM::new().execute(&script, &context),
+            Err(ScriptError::StackUnderflow)
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_and_equal() {
+        let context = ScriptContext::new(Hash256::zero(), 0);
+        let mut script = Vec::new();
+        push_data(&mut script, &[2]);
+        push_data(&mut script, &[3]);
+        script.push(OpCode::OpAdd as u8);
+        push_data(&mut script, &[5]);
+        script.push(OpCode::OpEqual as u8);
+
+        assert!(ScriptVM::new().execute(&script, &context).unwrap());
+    }
+
+    #[test]
+    fn test_numequal_compares_numbers() {
+        let context = ScriptContext::new(Hash256::zero(), 0);
+        let numequal = |a: &[u8], b: &[u8]| {
+            let mut script = Vec::new();
+            push_data(&mut script, a);
+            push_data(&mut script, b);
+            script.push(OpCode::OpNumEqual as u8);
+            let mut vm = ScriptVM::new();
+            vm.execute(&script, &context).map(|_| vm.stack().to_vec())
+        };
+
+        // Compara valores, não bytes: [5] e [5, 0] são ambos 5
+        assert_eq!(numequal(&[5], &[5]), Ok(vec![StackItem::Boolean(true)]));
+        assert_eq!(numequal(&[5], &[5, 0]), Ok(vec![StackItem::Boolean(true)]));
+        assert_eq!(numequal(&[5], &[6]), Ok(vec![StackItem::Boolean(false)]));
+        let mut script = Vec::new();
+        push_data(&mut script, &[2]);
+        push_data(&mut script, &[3]);
+        script.push(OpCode::OpAdd as u8);
+        push_data(&mut script, &[5]);
+        script.push(OpCode::OpNumEqual as u8);
+        assert!(ScriptVM::new().execute(&script, &context).unwrap());
+
+        // Itens que não são números válidos abortam
+        assert_eq!(numequal(&[0; 9], &[5]), Err(ScriptError::InvalidNumber));
+    }
+
+    #[test]
+    fn test_checksigverify_aborts_on_bad_signature() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
+        let tx_hash = Hash256::keccak256(b"tx");
+        let context = ScriptContext::new(tx_hash, 0);
+        let signature = sign_transaction_hash(&tx_hash, &keypair).unwrap();
+
+        // <sig> <pubkey> OP_CHECKSIGVERIFY OP_1
+        let script_pubkey = |pubkey: &[u8]| {
+            let mut script = Vec::new();
+            push_data(&mut script, pubkey);
+            script.push(OpCode::OpCheckSigVerify as u8);
+            script.push(OpCode::Op1 as u8);
+            script
+        };
+        let mut script_sig = Vec::new();
+        push_data(&mut script_sig, signature.as_bytes());
+
+        let valid = script_pubkey(keypair.public_key.as_bytes());
+        let mut vm = ScriptVM::new();
+        assert!(vm
+            .execute(&[script_sig.clone(), valid].concat(), &context)
+            .unwrap());
+        assert_eq!(vm.stack(), &[StackItem::Boolean(true)]);
+
+        // Sem o VERIFY o script seguiria; com ele a execução é abortada
+        let other = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
+        let wrong_key = script_pubkey(other.public_key.as_bytes());
+        assert_eq!(
+            ScriptVM::new().execute(&[script_sig, wrong_key].concat(), &context),
+            Err(ScriptError::VerifyFailed)
+        );
+    }
+
+    #[test]
+    fn test_crypto_ops_cost_more_gas_than_arithmetic() {
+        let context = ScriptContext::new(Hash256::zero(), 0);
+
+        // <2> <3> OP_ADD <5> OP_EQUAL
+        let mut arithmetic = Vec::new();
+        push_data(&mut arithmetic, &[2]);
+        push_data(&mut arithmetic, &[3]);
+        arithmetic.push(OpCode::OpAdd as u8);
+        push_data(&mut arithmetic, &[5]);
+        arithmetic.push(OpCode::OpEqual as u8);
+
+        // <dados> OP_HASH256 OP_HASH256 <sig> <pk> OP_CHECKSIG
+        let mut crypto = Vec::new();
+        push_data(&mut crypto, b"dados");
+        crypto.push(OpCode::OpHash256 as u8);
+        crypto.push(OpCode::OpHash256 as u8);
+        push_data(&mut crypto, &[1, 2, 3]);
+        push_data(&mut crypto, &[4, 5, 6]);
+        crypto.push(OpCode::OpCheckSig as u8);
+
+        let mut arithmetic_vm = ScriptVM::new();
+        arithmetic_vm.execute(&arithmetic, &context).unwrap();
+        let mut crypto_vm = ScriptVM::new();
+        crypto_vm.execute(&crypto, &context).unwrap();
+
+        assert_eq!(
+            arithmetic_vm.gas_used(),
+            3 * GAS_PUSH + 2 * OpCode::OpAdd.gas_cost()
+        );
+        assert_eq!(
+            crypto_vm.gas_used(),
+            3 * GAS_PUSH + 2 * OpCode::OpHash256.gas_cost() + GAS_CHECKSIG
+        );
+        assert!(crypto_vm.gas_used() > arithmetic_vm.gas_used());
+    }
+
+    #[test]
+    fn test_exceeding_gas_limit_aborts() {
+        let context = ScriptContext::new(Hash256::zero(), 0);
+        let mut script = Vec::new();
+        push_data(&mut script, b"dados");
+        for _ in 0..10 {
+            script.push(OpCode::OpHash256 as u8);
+        }
+
+        let limit = GAS_PUSH + 5 * OpCode::OpHash256.gas_cost();
+        let mut vm = ScriptVM::with_gas_limit(limit);
+        assert_eq!(
+            vm.execute(&script, &context),
+            Err(ScriptError::OutOfGas { limit })
+        );
+        assert!(vm.gas_used() <= limit);
+
+        let mut vm = ScriptVM::with_gas_limit(GAS_PUSH + 10 * OpCode::OpHash256.gas_cost());
+        assert!(vm.execute(&script, &context).unwrap());
+    }
+
+    #[test]
+    fn test_checklocktimeverify() {
+        // <10> OP_CHECKLOCKTIMEVERIFY OP_DROP OP_1
+        let mut script = Vec::new();
+        push_data(&mut script, &[10]);
+        script.push(OpCode::OpCheckLockTimeVerify as u8);
+        script.push(OpCode::OpDrop as u8);
+        script.push(OpCode::Op1 as u8);
+
+        let context = ScriptContext::new(Hash256::zero(), 0).with_block_height(9);
+        assert_eq!(
+            ScriptVM::new().execute(&script, &context),
+            Err(ScriptError::LockTimeNotReached(10))
+        );
+
+        let context = context.with_block_height(10);
+        assert!(ScriptVM::new().execute(&script, &context).unwrap());
+
+        // Altura negativa nunca é alcançável
+        let mut negative = Vec::new();
+        push_data(&mut negative, &(-1i64).to_le_bytes());
+        negative.push(OpCode::OpCheckLockTimeVerify as u8);
+        assert_eq!(
+            ScriptVM::new().execute(&negative, &context),
+            Err(ScriptError::InvalidNumber)
+        );
+    }
+}
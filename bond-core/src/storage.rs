@@ -0,0 +1,333 @@
+//! Armazenamento persistente da blockchain Bond
+//!
+//! O `Blockchain` mantém blocos e UTXOs em memória para acesso rápido, mas
+//! grava cada alteração em um `Store` para que o estado sobreviva a reinícios.
+//! Junto de cada bloco fica o seu registro de desfazimento (`BlockUndo`),
+//! usado para reverter os UTXOs em uma reorganização sem reprocessar a cadeia.
+
+use crate::block::Block;
+use crate::utxo::{BlockUndo, OutPoint, Utxo};
+use shared::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Backend de armazenamento de blocos e UTXOs
+pub trait Store: std::fmt::Debug + Send + Sync {
+    /// Grava um bloco na altura indicada
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a gravação falhar
+    fn put_block(&mut self, height: u64, block: &Block) -> Result<()>;
+
+    /// Lê o bloco da altura indicada
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a leitura ou desserialização falhar
+    fn get_block(&self, height: u64) -> Result<Option<Block>>;
+
+    /// Remove o bloco da altura indicada (usado ao encurtar a cadeia)
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a remoção falhar
+    fn delete_block(&mut self, height: u64) -> Result<()>;
+
+    /// Grava (ou sobrescreve) um UTXO
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a gravação falhar
+    fn put_utxo(&mut self, utxo: &Utxo) -> Result<()>;
+
+    /// Remove um UTXO gasto
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a remoção falhar
+    fn delete_utxo(&mut self, outpoint: &OutPoint) -> Result<()>;
+
+    /// Lista todos os UTXOs gravados
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a leitura falhar
+    fn utxos(&self) -> Result<Vec<Utxo>>;
+
+    /// Grava o registro de desfazimento do bloco na altura indicada
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a gravação falhar
+    fn put_undo(&mut self, height: u64, undo: &BlockUndo) -> Result<()>;
+
+    /// Lê o registro de desfazimento da altura indicada
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a leitura ou desserialização falhar
+    fn get_undo(&self, height: u64) -> Result<Option<BlockUndo>>;
+
+    /// Remove o registro de desfazimento da altura indicada
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a remoção falhar
+    fn delete_undo(&mut self, height: u64) -> Result<()>;
+
+    /// Altura do último bloco gravado, ou `None` para um store vazio
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a leitura falhar
+    fn tip(&self) -> Result<Option<u64>>;
+}
+
+#[derive(Debug, Default)]
+struct MemoryStoreInner {
+    blocks: BTreeMap<u64, Block>,
+    utxos: HashMap<OutPoint, Utxo>,
+    undo: BTreeMap<u64, BlockUndo>,
+}
+
+/// Store em memória
+///
+/// Clones compartilham o mesmo conteúdo, o que permite reabrir uma
+/// blockchain a partir do mesmo store em testes.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    inner: Arc<Mutex<MemoryStoreInner>>,
+}
+
+impl MemoryStore {
+    /// Cria um store vazio
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryStoreInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Store for MemoryStore {
+    fn put_block(&mut self, height: u64, block: &Block) -> Result<()> {
+        self.lock().blocks.insert(height, block.clone());
+        Ok(())
+    }
+
+    fn get_block(&self, height: u64) -> Result<Option<Block>> {
+        Ok(self.lock().blocks.get(&height).cloned())
+    }
+
+    fn delete_block(&mut self, height: u64) -> Result<()> {
+        self.lock().blocks.remove(&height);
+        Ok(())
+    }
+
+    fn put_utxo(&mut self, utxo: &Utxo) -> Result<()> {
+        self.lock().utxos.insert(utxo.outpoint(), utxo.clone());
+        Ok(())
+    }
+
+    fn delete_utxo(&mut self, outpoint: &OutPoint) -> Result<()> {
+        self.lock().utxos.remove(outpoint);
+        Ok(())
+    }
+
+    fn utxos(&self) -> Result<Vec<Utxo>> {
+        Ok(self.lock().utxos.values().cloned().collect())
+    }
+
+    fn put_undo(&mut self, height: u64, undo: &BlockUndo) -> Result<()> {
+        self.lock().undo.insert(height, undo.clone());
+        Ok(())
+    }
+
+    fn get_undo(&self, height: u64) -> Result<Option<BlockUndo>> {
+        Ok(self.lock().undo.get(&height).cloned())
+    }
+
+    fn delete_undo(&mut self, height: u64) -> Result<()> {
+        self.lock().undo.remove(&height);
+        Ok(())
+    }
+
+    fn tip(&self) -> Result<Option<u64>> {
+        Ok(self.lock().blocks.keys().next_back().copied())
+    }
+}
+
+#[cfg(feature = "storage")]
+pub use sled_store::SledStore;
+
+#[cfg(feature = "storage")]
+mod sled_store {
+    use super::Store;
+    use crate::block::Block;
+    use crate::utxo::{BlockUndo, OutPoint, Utxo};
+    use shared::{BlockchainError, Result};
+    use std::path::Path;
+
+    fn io_error(error: impl std::fmt::Display) -> BlockchainError {
+        BlockchainError::IoError(error.to_string())
+    }
+
+    fn serialization_error(error: impl std::fmt::Display) -> BlockchainError {
+        BlockchainError::SerializationError(error.to_string())
+    }
+
+    fn outpoint_key(outpoint: &OutPoint) -> Vec<u8> {
+        let mut key = outpoint.txid.as_bytes().to_vec();
+        key.extend_from_slice(&outpoint.vout.to_be_bytes());
+        key
+    }
+
+    /// Store persistente em disco baseado em `sled`
+    #[derive(Debug, Clone)]
+    pub struct SledStore {
+        blocks: sled::Tree,
+        utxos: sled::Tree,
+        undo: sled::Tree,
+    }
+
+    impl SledStore {
+        /// Abre (ou cria) um store no diretório indicado
+        ///
+        /// # Errors
+        ///
+        /// Retorna erro se o banco não puder ser aberto
+        pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+            let db = sled::open(path).map_err(io_error)?;
+            Ok(Self {
+                blocks: db.open_tree("blocks").map_err(io_error)?,
+                utxos: db.open_tree("utxos").map_err(io_error)?,
+                undo: db.open_tree("undo").map_err(io_error)?,
+            })
+        }
+    }
+
+    impl Store for SledStore {
+        fn put_block(&mut self, height: u64, block: &Block) -> Result<()> {
+            let value = serde_json::to_vec(block).map_err(serialization_error)?;
+            // Chave big-endian mantém a ordem por altura
+            self.blocks
+                .insert(height.to_be_bytes(), value)
+                .map_err(io_error)?;
+            self.blocks.flush().map_err(io_error)?;
+            Ok(())
+        }
+
+        fn get_block(&self, height: u64) -> Result<Option<Block>> {
+            self.blocks
+                .get(height.to_be_bytes())
+                .map_err(io_error)?
+                .map(|value| serde_json::from_slice(&value).map_err(serialization_error))
+                .transpose()
+        }
+
+        fn delete_block(&mut self, height: u64) -> Result<()> {
+            self.blocks.remove(height.to_be_bytes()).map_err(io_error)?;
+            self.blocks.flush().map_err(io_error)?;
+            Ok(())
+        }
+
+        fn put_utxo(&mut self, utxo: &Utxo) -> Result<()> {
+            let value = serde_json::to_vec(utxo).map_err(serialization_error)?;
+            self.utxos
+                .insert(outpoint_key(&utxo.outpoint()), value)
+                .map_err(io_error)?;
+            Ok(())
+        }
+
+        fn delete_utxo(&mut self, outpoint: &OutPoint) -> Result<()> {
+            self.utxos
+                .remove(outpoint_key(outpoint))
+                .map_err(io_error)?;
+            Ok(())
+        }
+
+        fn utxos(&self) -> Result<Vec<Utxo>> {
+            self.utxos
+                .iter()
+                .map(|entry| {
+                    let (_, value) = entry.map_err(io_error)?;
+                    serde_json::from_slice(&value).map_err(serialization_error)
+                })
+                .collect()
+        }
+
+        fn put_undo(&mut self, height: u64, undo: &BlockUndo) -> Result<()> {
+            let value = serde_json::to_vec(undo).map_err(serialization_error)?;
+            self.undo
+                .insert(height.to_be_bytes(), value)
+                .map_err(io_error)?;
+            Ok(())
+        }
+
+        fn get_undo(&self, height: u64) -> Result<Option<BlockUndo>> {
+            self.undo
+                .get(height.to_be_bytes())
+                .map_err(io_error)?
+                .map(|value| serde_json::from_slice(&value).map_err(serialization_error))
+                .transpose()
+        }
+
+        fn delete_undo(&mut self, height: u64) -> Result<()> {
+            self.undo.remove(height.to_be_bytes()).map_err(io_error)?;
+            Ok(())
+        }
+
+        fn tip(&self) -> Result<Option<u64>> {
+            let Some((key, _)) = self.blocks.last().map_err(io_error)? else {
+                return Ok(None);
+            };
+            let bytes: [u8; 8] = key
+                .as_ref()
+                .try_into()
+                .map_err(|_| BlockchainError::IoError("Chave de bloco inválida".to_string()))?;
+            Ok(Some(u64::from_be_bytes(bytes)))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "storage"))]
+mod tests {
+    use super::*;
+    use crate::blockchain::{Blockchain, NetworkParams};
+    use crate::mining::{Miner, MinerConfig};
+
+    #[test]
+    fn test_sled_store_survives_reopen() {
+        let path = std::env::temp_dir().join(format!("bond-sled-{}", rand::random::<u64>()));
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+
+        {
+            let store = SledStore::open(&path).unwrap();
+            let mut blockchain =
+                Blockchain::with_store(NetworkParams::default(), vec![1, 2, 3], Box::new(store))
+                    .unwrap();
+            for _ in 0..10 {
+                let result = blockchain.mine_next_block(&miner, vec![]).unwrap();
+                blockchain.add_block(result.block).unwrap();
+            }
+        }
+
+        let store = SledStore::open(&path).unwrap();
+        let reopened =
+            Blockchain::with_store(NetworkParams::default(), vec![1, 2, 3], Box::new(store))
+                .unwrap();
+        assert_eq!(reopened.height(), 10);
+        assert_eq!(reopened.get_balance(&[4, 5, 6]), 50_000);
+
+        drop(reopened);
+        let _ = std::fs::remove_dir_all(&path);
+    }
+}
@@ -1,7 +1,20 @@
+use crate::script::{ScriptContext, ScriptVM};
 use crate::utxo::OutPoint;
 use serde::{Deserialize, Serialize};
+use shared::encoding::{write_bytes, write_len};
 use shared::{BlockchainError, Hash256, Result};
 
+/// Valores de `lock_time` abaixo deste limite são alturas de bloco;
+/// a partir dele, timestamps Unix
+pub const LOCKTIME_THRESHOLD: u32 = 500_000_000;
+
+/// Maior sequência que ainda sinaliza substituição por taxa (BIP 125)
+pub const MAX_RBF_SEQUENCE: u32 = u32::MAX - 2;
+
+/// Quantas vezes um byte fora das assinaturas pesa mais que um byte de
+/// assinatura (ver `Transaction::weight`)
+pub const WITNESS_SCALE_FACTOR: usize = 4;
+
 /// Input de transação
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TxInput {
@@ -38,6 +51,25 @@ impl TxInput {
         }
     }
 
+    /// Codificação binária determinística do input
+    #[must_use]
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32 + 4 + 8 + self.script_sig.len() + 4);
+        self.write_canonical(&mut out);
+        out
+    }
+
+    fn write_canonical(&self, out: &mut Vec<u8>) {
+        self.write_canonical_with_script(out, &self.script_sig);
+    }
+
+    fn write_canonical_with_script(&self, out: &mut Vec<u8>, script: &[u8]) {
+        out.extend_from_slice(self.previous_output.txid.as_bytes());
+        out.extend_from_slice(&self.previous_output.vout.to_le_bytes());
+        write_bytes(out, script);
+        out.extend_from_slice(&self.sequence.to_le_bytes());
+    }
+
     /// Verifica se este input é de uma transação coinbase
     #[must_use]
     pub fn is_coinbase(&self) -> bool {
@@ -63,6 +95,19 @@ impl TxOutput {
             script_pubkey,
         }
     }
+
+    /// Codificação binária determinística do output
+    #[must_use]
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(8 + 8 + self.script_pubkey.len());
+        self.write_canonical(&mut out);
+        out
+    }
+
+    fn write_canonical(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.value.to_le_bytes());
+        write_bytes(out, &self.script_pubkey);
+    }
 }
 
 /// Transação na blockchain Bond
@@ -107,15 +152,97 @@ impl Transaction {
         Self::new(1, inputs, outputs, 0)
     }
 
-    /// Calcula o hash da transação (SHA3-256)
+    /// Codificação binária determinística da transação
+    ///
+    /// Campos na ordem da declaração, inteiros little-endian e listas e
+    /// scripts prefixados pelo tamanho. É a forma usada para o id; o JSON
+    /// continua sendo o formato de transporte na rede.
+    #[must_use]
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&self.version.to_le_bytes());
+        write_len(&mut out, self.inputs.len());
+        for input in &self.inputs {
+            input.write_canonical(&mut out);
+        }
+        write_len(&mut out, self.outputs.len());
+        for output in &self.outputs {
+            output.write_canonical(&mut out);
+        }
+        out.extend_from_slice(&self.lock_time.to_le_bytes());
+        out
+    }
+
+    /// Calcula o hash da transação (Keccak-256 da forma canônica)
     ///
     /// # Errors
     ///
-    /// Retorna erro se a serialização da transação falhar
+    /// Não falha mais desde que o hash deixou de usar JSON; o `Result` é
+    /// mantido por compatibilidade
     pub fn hash(&self) -> Result<Hash256> {
-        let serialized = serde_json::to_vec(self)
-            .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
-        Ok(Hash256::keccak256(&serialized))
+        Ok(Hash256::keccak256(&self.to_canonical_bytes()))
+    }
+
+    /// Hash assinado pelo input `input_index`
+    ///
+    /// Igual à forma canônica, mas sem os scripts de desbloqueio: o input
+    /// assinado leva no lugar o `script_pubkey` do UTXO que gasta e os
+    /// demais ficam vazios, e o índice do input é anexado ao fim. Assim a
+    /// assinatura cobre a estrutura da transação, mas não a si mesma nem as
+    /// assinaturas dos outros inputs.
+    #[must_use]
+    pub fn sighash(&self, input_index: usize, prev_script_pubkey: &[u8]) -> Hash256 {
+        let mut out = Vec::new();
+        out.extend_from_slice(&self.version.to_le_bytes());
+        write_len(&mut out, self.inputs.len());
+        for (index, input) in self.inputs.iter().enumerate() {
+            let script: &[u8] = if index == input_index {
+                prev_script_pubkey
+            } else {
+                &[]
+            };
+            input.write_canonical_with_script(&mut out, script);
+        }
+        write_len(&mut out, self.outputs.len());
+        for output in &self.outputs {
+            output.write_canonical(&mut out);
+        }
+        out.extend_from_slice(&self.lock_time.to_le_bytes());
+        write_len(&mut out, input_index);
+        Hash256::keccak256(&out)
+    }
+
+    /// Verifica se a transação aceita ser substituída por outra com taxa maior
+    ///
+    /// Basta um input com sequência até `MAX_RBF_SEQUENCE`.
+    #[must_use]
+    pub fn signals_rbf(&self) -> bool {
+        self.inputs
+            .iter()
+            .any(|input| input.sequence <= MAX_RBF_SEQUENCE)
+    }
+
+    /// Verifica se a transação pode ser incluída em um bloco
+    ///
+    /// Segue a regra do Bitcoin: `lock_time` zero ou já ultrapassado (pela
+    /// altura ou pelo timestamp do bloco) torna a transação final, assim
+    /// como inputs que desativam o lock com sequência máxima.
+    #[must_use]
+    pub fn is_final(&self, block_height: u64, block_time: u64) -> bool {
+        if self.lock_time == 0 {
+            return true;
+        }
+
+        let threshold = if self.lock_time < LOCKTIME_THRESHOLD {
+            block_height
+        } else {
+            block_time
+        };
+        if u64::from(self.lock_time) < threshold {
+            return true;
+        }
+
+        self.inputs.iter().all(|input| input.sequence == u32::MAX)
     }
 
     /// Verifica se é uma transação de coinbase
@@ -185,6 +312,17 @@ impl Transaction {
             .ok_or_else(|| BlockchainError::InvalidTransaction("Negative fee".to_string()))
     }
 
+    /// Taxa por byte: taxa dividida pelo tamanho real (`size`), com as
+    /// assinaturas incluídas
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se a taxa não puder ser calculada
+    pub fn fee_rate(&self, utxo_set: &crate::utxo::UtxoSet) -> Result<u64> {
+        let size = self.size().max(1) as u64;
+        Ok(self.fee(utxo_set)? / size)
+    }
+
     /// Validação básica da transação
     ///
     /// # Errors
@@ -231,17 +369,84 @@ impl Transaction {
         Ok(())
     }
 
-    /// Tamanho estimado da transação em bytes
+    /// Executa os scripts de todos os inputs contra os UTXOs gastos
+    ///
+    /// Cada input é verificado contra o seu `sighash`. `block_height` é a
+    /// altura do bloco que incluirá a transação, usada
+    /// por `OP_CHECKLOCKTIMEVERIFY`. Retorna `false` se algum script falhar
+    /// ou não deixar verdadeiro na pilha.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se algum UTXO não for encontrado
+    pub fn validate_scripts(
+        &self,
+        utxo_set: &crate::utxo::UtxoSet,
+        block_height: u64,
+    ) -> Result<bool> {
+        if self.is_coinbase() {
+            return Ok(true);
+        }
+
+        for (index, input) in self.inputs.iter().enumerate() {
+            let utxo = utxo_set
+                .get_utxo(&input.previous_output)
+                .ok_or(BlockchainError::UtxoNotFound)?;
+
+            let context = ScriptContext::new(self.sighash(index, &utxo.script), index)
+                .with_block_height(block_height);
+            if !ScriptVM::verify(&input.script_sig, &utxo.script, &context).unwrap_or(false) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Tamanho real da transação em bytes, na codificação canônica
+    ///
+    /// Igual a `to_canonical_bytes().len()`, sem montar os bytes.
     #[must_use]
-    pub const fn estimated_size(&self) -> usize {
-        // Estimativa baseada na serialização JSON (simplificada)
-        // Na implementação real com ML-DSA, seria muito maior
-        let base_size = 4 + 4; // version + lock_time
-        let inputs_size = self.inputs.len() * (32 + 4 + 100 + 4); // txid + vout + script_sig + sequence
-        let outputs_size = self.outputs.len() * (8 + 100); // value + script_pubkey
+    pub fn size(&self) -> usize {
+        // version + contagem de inputs + contagem de outputs + lock_time
+        let base_size = 4 + 8 + 8 + 4;
+        let inputs_size: usize = self
+            .inputs
+            .iter()
+            .map(|input| 32 + 4 + 8 + input.script_sig.len() + 4)
+            .sum();
+        let outputs_size: usize = self
+            .outputs
+            .iter()
+            .map(|output| 8 + 8 + output.script_pubkey.len())
+            .sum();
 
         base_size + inputs_size + outputs_size
     }
+
+    /// Bytes de assinatura: os scripts de desbloqueio dos inputs
+    ///
+    /// A coinbase não tem assinaturas; seu `script_sig` carrega a altura e
+    /// conta como dado comum.
+    #[must_use]
+    pub fn witness_size(&self) -> usize {
+        if self.is_coinbase() {
+            return 0;
+        }
+        self.inputs.iter().map(|input| input.script_sig.len()).sum()
+    }
+
+    /// Peso da transação para o limite do bloco
+    ///
+    /// Bytes de assinatura contam uma vez e os demais `WITNESS_SCALE_FACTOR`
+    /// vezes, para que as assinaturas ML-DSA (alguns KB por input) não
+    /// esgotem o bloco na mesma proporção que os dados que ficam no
+    /// conjunto UTXO.
+    #[must_use]
+    pub fn weight(&self) -> usize {
+        let witness = self.witness_size();
+        (self.size() - witness) * WITNESS_SCALE_FACTOR + witness
+    }
 }
 
 #[cfg(test)]
@@ -272,6 +477,86 @@ mod tests {
         assert_eq!(hash, hash2);
     }
 
+    #[test]
+    fn test_sighash_stable_across_signing() {
+        use crate::script::{p2pkh_script_pubkey, p2pkh_script_sig};
+        use shared::{sign_transaction_hash, KeyPair, SignatureAlgorithm};
+
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let script = p2pkh_script_pubkey(&keypair.public_key);
+        let mut utxo_set = UtxoSet::new();
+        let mut inputs = Vec::new();
+        for seed in [b"a", b"b"] {
+            let utxo = Utxo::new(Hash256::keccak256(seed), 0, 1_000, script.clone(), 0);
+            inputs.push(TxInput::new(utxo.outpoint(), vec![], 0));
+            utxo_set.add_utxo(utxo);
+        }
+        let mut tx = Transaction::new(1, inputs, vec![TxOutput::new(1_900, vec![9])], 0);
+
+        let before: Vec<Hash256> = (0..2).map(|i| tx.sighash(i, &script)).collect();
+        let unsigned_id = tx.hash().unwrap();
+        assert_ne!(before[0], before[1]);
+
+        for (input, sighash) in tx.inputs.iter_mut().zip(&before) {
+            let signature = sign_transaction_hash(sighash, &keypair).unwrap();
+            input.script_sig = p2pkh_script_sig(&signature);
+        }
+
+        // O id muda com as assinaturas; o sighash não
+        assert_ne!(tx.hash().unwrap(), unsigned_id);
+        let after: Vec<Hash256> = (0..2).map(|i| tx.sighash(i, &script)).collect();
+        assert_eq!(before, after);
+        assert!(tx.validate_scripts(&utxo_set, 1).unwrap());
+
+        // Alterar um output invalida as assinaturas
+        tx.outputs[0].value = 1_800;
+        assert_ne!(tx.sighash(0, &script), before[0]);
+        assert!(!tx.validate_scripts(&utxo_set, 1).unwrap());
+    }
+
+    #[test]
+    fn test_canonical_bytes_independent_of_json_layout() {
+        let outpoint = OutPoint {
+            txid: Hash256::keccak256(b"prev"),
+            vout: 3,
+        };
+        let tx = Transaction::new(
+            2,
+            vec![TxInput::new(outpoint, vec![7, 7], 5)],
+            vec![TxOutput::new(900, vec![1, 2]), TxOutput::new(50, vec![])],
+            10,
+        );
+        let same = Transaction::new(
+            2,
+            vec![TxInput::new(outpoint, vec![7, 7], 5)],
+            vec![TxOutput::new(900, vec![1, 2]), TxOutput::new(50, vec![])],
+            10,
+        );
+        assert_eq!(tx.to_canonical_bytes(), same.to_canonical_bytes());
+        assert_eq!(tx.hash().unwrap(), same.hash().unwrap());
+
+        // Mesmo conteúdo em JSON com campos reordenados e espaços extras
+        let json = serde_json::to_value(&tx).unwrap();
+        let reordered = format!(
+            "{{ \"lock_time\": 10, \"outputs\": {}, \"inputs\": {},  \"version\": 2 }}",
+            json["outputs"], json["inputs"]
+        );
+        assert_ne!(reordered.as_bytes(), serde_json::to_vec(&tx).unwrap());
+        let parsed: Transaction = serde_json::from_str(&reordered).unwrap();
+        assert_eq!(parsed.hash().unwrap(), tx.hash().unwrap());
+
+        // Prefixos de tamanho impedem que bytes migrem entre campos
+        let mut shifted = tx.clone();
+        shifted.inputs[0].script_sig = vec![7];
+        shifted.outputs[0].script_pubkey = vec![7, 1, 2];
+        assert_ne!(shifted.to_canonical_bytes(), tx.to_canonical_bytes());
+        assert_eq!(
+            tx.outputs[1].to_canonical_bytes(),
+            [50u64.to_le_bytes(), 0u64.to_le_bytes()].concat()
+        );
+        assert_eq!(tx.inputs[0].to_canonical_bytes().len(), 32 + 4 + 8 + 2 + 4);
+    }
+
     #[test]
     fn test_transaction_validation() {
         let coinbase = Transaction::coinbase(100, 5000, vec![1, 2, 3]);
@@ -311,4 +596,37 @@ mod tests {
 
         assert_eq!(tx.fee(&utxo_set).unwrap(), 100);
     }
+
+    #[test]
+    fn test_fee_rate_accounts_for_size() {
+        let mut utxo_set = UtxoSet::new();
+        let outpoints: Vec<OutPoint> = (0..3u32)
+            .map(|vout| {
+                let utxo = Utxo::new(Hash256::zero(), vout, 10_000, vec![1], 0);
+                let outpoint = utxo.outpoint();
+                utxo_set.add_utxo(utxo);
+                outpoint
+            })
+            .collect();
+        let spend = |inputs: &[OutPoint], output_value: u64| {
+            Transaction::new(
+                1,
+                inputs
+                    .iter()
+                    .map(|outpoint| TxInput::new(*outpoint, vec![], 0))
+                    .collect(),
+                vec![TxOutput::new(output_value, vec![2])],
+                0,
+            )
+        };
+
+        // Pequena (89 bytes) pagando 890: 10 por byte
+        let small = spend(&outpoints[..1], 9_110);
+        // Grande (137 bytes) pagando taxa absoluta maior, 1.096: 8 por byte
+        let large = spend(&outpoints[1..], 20_000 - 1_096);
+
+        assert_eq!(small.fee_rate(&utxo_set).unwrap(), 10);
+        assert_eq!(large.fee_rate(&utxo_set).unwrap(), 8);
+        assert!(large.fee(&utxo_set).unwrap() > small.fee(&utxo_set).unwrap());
+    }
 }
@@ -1,6 +1,10 @@
+use crate::block::Block;
 use serde::{Deserialize, Serialize};
 use shared::{BlockchainError, Hash256, Result};
 
+/// Blocos que um output de coinbase precisa esperar antes de ser gasto
+pub const COINBASE_MATURITY: u64 = 100;
+
 /// Representa uma saída de transação não gasta (UTXO)
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Utxo {
@@ -14,6 +18,9 @@ pub struct Utxo {
     pub script: Vec<u8>,
     /// Altura do bloco onde foi criado (para controle de maturidade)
     pub block_height: u64,
+    /// Criado por uma transação coinbase (sujeito a maturação)
+    #[serde(default)]
+    pub is_coinbase: bool,
 }
 
 impl Utxo {
@@ -32,9 +39,17 @@ impl Utxo {
             value,
             script,
             block_height,
+            is_coinbase: false,
         }
     }
 
+    /// Marca se o UTXO foi criado por uma transação coinbase
+    #[must_use]
+    pub const fn with_coinbase(mut self, is_coinbase: bool) -> Self {
+        self.is_coinbase = is_coinbase;
+        self
+    }
+
     /// Obtém o identificador único do UTXO
     #[must_use]
     pub const fn outpoint(&self) -> OutPoint {
@@ -49,11 +64,18 @@ impl Utxo {
     #[must_use]
     pub const fn is_mature(&self, current_height: u64, is_coinbase: bool) -> bool {
         if is_coinbase {
-            current_height >= self.block_height + 100
+            current_height >= self.block_height + COINBASE_MATURITY
         } else {
             true
         }
     }
+
+    /// Verifica se o UTXO pode ser gasto por uma transação em `spend_height`,
+    /// de acordo com a sua própria origem
+    #[must_use]
+    pub const fn is_spendable_at(&self, spend_height: u64) -> bool {
+        self.is_mature(spend_height, self.is_coinbase)
+    }
 }
 
 /// Identificador único de um UTXO (`OutPoint`)
@@ -71,8 +93,20 @@ impl OutPoint {
     }
 }
 
+/// Registro de desfazimento de um bloco aplicado ao conjunto UTXO
+///
+/// Guarda a diferença líquida do bloco: outputs criados e gastos dentro do
+/// mesmo bloco não aparecem em nenhuma das listas.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockUndo {
+    /// UTXOs que existiam antes do bloco e foram removidos por ele
+    pub spent: Vec<Utxo>,
+    /// Outpoints criados pelo bloco
+    pub created: Vec<OutPoint>,
+}
+
 /// Conjunto de UTXOs para controle de estado
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UtxoSet {
     utxos: std::collections::HashMap<OutPoint, Utxo>,
 }
@@ -97,6 +131,11 @@ impl UtxoSet {
         self.utxos.remove(outpoint)
     }
 
+    /// Itera sobre todos os UTXOs do conjunto
+    pub fn iter(&self) -> impl Iterator<Item = &Utxo> {
+        self.utxos.values()
+    }
+
     /// Obtém um UTXO do conjunto
     #[must_use]
     pub fn get_utxo(&self, outpoint: &OutPoint) -> Option<&Utxo> {
@@ -119,25 +158,82 @@ impl UtxoSet {
             .sum()
     }
 
+    /// Lista os UTXOs de um script, em páginas
+    ///
+    /// Ordenados pelo `OutPoint` (txid e depois vout), para que a mesma
+    /// página traga os mesmos UTXOs enquanto o conjunto não mudar. Pula os
+    /// primeiros `offset` e devolve no máximo `limit`.
+    #[must_use]
+    pub fn get_utxos_for_script(
+        &self,
+        script: &[u8],
+        offset: usize,
+        limit: usize,
+    ) -> Vec<(OutPoint, &Utxo)> {
+        let mut utxos: Vec<(OutPoint, &Utxo)> = self
+            .utxos
+            .iter()
+            .filter(|(_, utxo)| utxo.script == script)
+            .map(|(outpoint, utxo)| (*outpoint, utxo))
+            .collect();
+        utxos.sort_by(|(a, _), (b, _)| {
+            a.txid
+                .as_bytes()
+                .cmp(b.txid.as_bytes())
+                .then_with(|| a.vout.cmp(&b.vout))
+        });
+        utxos.into_iter().skip(offset).take(limit).collect()
+    }
+
     /// Encontra UTXOs suficientes para cobrir um valor específico
     ///
+    /// Os maiores UTXOs são escolhidos primeiro, o que cobre o valor com o
+    /// menor número de inputs e, portanto, a menor taxa por tamanho.
+    ///
     /// # Errors
     ///
     /// Retorna erro se não houver UTXOs suficientes para o valor solicitado
     pub fn find_utxos_for_amount(&self, script: &[u8], amount: u64) -> Result<Vec<&Utxo>> {
-        let mut selected_utxos = Vec::new();
-        let mut total_value = 0u64;
+        self.find_utxos_for_amount_excluding(script, amount, &std::collections::HashSet::new())
+    }
 
-        for utxo in self.utxos.values() {
-            if utxo.script == script {
-                selected_utxos.push(utxo);
-                total_value = total_value.checked_add(utxo.value).ok_or_else(|| {
-                    BlockchainError::InvalidTransaction("Overflow in UTXO selection".to_string())
-                })?;
+    /// Como `find_utxos_for_amount`, ignorando os `OutPoint`s de `reserved`
+    ///
+    /// Usado com os `OutPoint`s gastos pela mempool, para que a nova
+    /// transação não conflite com uma ainda não confirmada.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se os UTXOs não reservados não cobrirem o valor
+    pub fn find_utxos_for_amount_excluding(
+        &self,
+        script: &[u8],
+        amount: u64,
+        reserved: &std::collections::HashSet<OutPoint>,
+    ) -> Result<Vec<&Utxo>> {
+        let mut candidates: Vec<&Utxo> = self
+            .utxos
+            .values()
+            .filter(|utxo| utxo.script == script && !reserved.contains(&utxo.outpoint()))
+            .collect();
+        // Empates desfeitos pelo OutPoint para a seleção ser determinística
+        candidates.sort_by(|a, b| {
+            b.value
+                .cmp(&a.value)
+                .then_with(|| a.txid.as_bytes().cmp(b.txid.as_bytes()))
+                .then_with(|| a.vout.cmp(&b.vout))
+        });
 
-                if total_value >= amount {
-                    return Ok(selected_utxos);
-                }
+        let mut selected_utxos = Vec::new();
+        let mut total_value = 0u64;
+        for utxo in candidates {
+            selected_utxos.push(utxo);
+            total_value = total_value.checked_add(utxo.value).ok_or_else(|| {
+                BlockchainError::InvalidTransaction("Overflow in UTXO selection".to_string())
+            })?;
+
+            if total_value >= amount {
+                return Ok(selected_utxos);
             }
         }
 
@@ -148,6 +244,93 @@ impl UtxoSet {
         Ok(selected_utxos)
     }
 
+    /// Aplica um bloco ao conjunto e retorna o registro para desfazê-lo
+    ///
+    /// Se algum input não existir, as alterações já feitas são revertidas
+    /// e o conjunto fica como estava.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se um input gastar um UTXO inexistente
+    pub fn apply_block(&mut self, block: &Block) -> Result<BlockUndo> {
+        let mut undo = BlockUndo::default();
+        if let Err(e) = self.apply_block_with_undo(block, &mut undo) {
+            self.undo_block(&undo)?;
+            return Err(e);
+        }
+        Ok(undo)
+    }
+
+    fn apply_block_with_undo(&mut self, block: &Block, undo: &mut BlockUndo) -> Result<()> {
+        let block_height = block.height()?;
+
+        for tx in &block.transactions {
+            let txid = tx.hash()?;
+
+            // Remover UTXOs gastos
+            if !tx.is_coinbase() {
+                for input in &tx.inputs {
+                    let outpoint = input.previous_output;
+                    let spent = self
+                        .utxos
+                        .remove(&outpoint)
+                        .ok_or(BlockchainError::UtxoNotFound)?;
+                    if let Some(index) = undo.created.iter().position(|o| *o == outpoint) {
+                        undo.created.swap_remove(index);
+                    } else {
+                        undo.spent.push(spent);
+                    }
+                }
+            }
+
+            // Criar novos UTXOs; os do gênese são a alocação inicial e não
+            // passam por maturação
+            let is_coinbase = tx.is_coinbase() && block_height > 0;
+            for (output_index, output) in tx.outputs.iter().enumerate() {
+                let utxo = Utxo::new(
+                    txid,
+                    output_index.try_into().map_err(|_| {
+                        BlockchainError::InvalidBlock("Output index overflow".to_string())
+                    })?,
+                    output.value,
+                    output.script_pubkey.clone(),
+                    block_height,
+                )
+                .with_coinbase(is_coinbase);
+                let outpoint = utxo.outpoint();
+                if let Some(replaced) = self.utxos.insert(outpoint, utxo) {
+                    undo.spent.push(replaced);
+                }
+                undo.created.push(outpoint);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Desfaz um bloco aplicado com `apply_block`
+    ///
+    /// Deve ser chamado na ordem inversa da aplicação: o conjunto precisa
+    /// estar exatamente como o bloco o deixou.
+    ///
+    /// # Errors
+    ///
+    /// Retorna erro se algum UTXO criado pelo bloco não estiver no conjunto,
+    /// caso em que nada é alterado
+    pub fn undo_block(&mut self, undo: &BlockUndo) -> Result<()> {
+        if !undo.created.iter().all(|outpoint| self.contains(outpoint)) {
+            return Err(BlockchainError::UtxoNotFound);
+        }
+
+        for outpoint in &undo.created {
+            self.utxos.remove(outpoint);
+        }
+        for utxo in &undo.spent {
+            self.add_utxo(utxo.clone());
+        }
+        Ok(())
+    }
+
     /// Retorna o número total de UTXOs
     #[must_use]
     pub fn len(&self) -> usize {
@@ -229,4 +412,153 @@ mod tests {
 
         assert_eq!(utxo_set.get_balance_for_script(&script), 3000);
     }
+
+    #[test]
+    fn test_utxos_for_script_paginate_in_stable_order() {
+        let mut utxo_set = UtxoSet::new();
+        for seed in [b"c", b"a", b"b"] {
+            let txid = Hash256::keccak256(seed);
+            utxo_set.add_utxo(Utxo::new(txid, 1, 100, vec![1, 2, 3], 0));
+            utxo_set.add_utxo(Utxo::new(txid, 0, 200, vec![1, 2, 3], 0));
+        }
+        utxo_set.add_utxo(Utxo::new(Hash256::keccak256(b"other"), 0, 50, vec![9], 0));
+
+        let all = utxo_set.get_utxos_for_script(&[1, 2, 3], 0, usize::MAX);
+        assert_eq!(all.len(), 6);
+        assert!(all
+            .iter()
+            .all(|(outpoint, utxo)| *outpoint == utxo.outpoint()));
+        assert!(all.windows(2).all(|pair| {
+            let (a, b) = (pair[0].0, pair[1].0);
+            (a.txid.as_bytes(), a.vout) < (b.txid.as_bytes(), b.vout)
+        }));
+
+        // Páginas consecutivas cobrem o conjunto sem repetir nem pular UTXOs
+        let mut paged = Vec::new();
+        for offset in (0..6).step_by(4) {
+            paged.extend(utxo_set.get_utxos_for_script(&[1, 2, 3], offset, 4));
+        }
+        assert_eq!(paged, all);
+        assert_eq!(utxo_set.get_utxos_for_script(&[1, 2, 3], 2, 2), all[2..4]);
+        assert!(utxo_set.get_utxos_for_script(&[1, 2, 3], 6, 4).is_empty());
+        assert_eq!(utxo_set.get_utxos_for_script(&[9], 0, 10).len(), 1);
+    }
+
+    #[test]
+    fn test_distinct_coinbase_outputs_do_not_collide() {
+        let mut utxo_set = UtxoSet::new();
+        let coinbase_a = crate::Transaction::coinbase(1, 5000, vec![1, 2, 3]);
+        let coinbase_b = crate::Transaction::coinbase(2, 7000, vec![4, 5, 6]);
+
+        let utxo_a = Utxo::new(coinbase_a.hash().unwrap(), 0, 5000, vec![1, 2, 3], 1);
+        let utxo_b = Utxo::new(coinbase_b.hash().unwrap(), 0, 7000, vec![4, 5, 6], 2);
+
+        // Cada UTXO deve ter seu próprio OutPoint (chave do mapa)
+        assert_ne!(utxo_a.outpoint(), utxo_b.outpoint());
+
+        utxo_set.add_utxo(utxo_a);
+        utxo_set.add_utxo(utxo_b);
+
+        assert_eq!(utxo_set.len(), 2);
+        assert_eq!(utxo_set.get_balance_for_script(&[1, 2, 3]), 5000);
+        assert_eq!(utxo_set.get_balance_for_script(&[4, 5, 6]), 7000);
+    }
+
+    fn block_at(height: u64, transactions: Vec<crate::transaction::Transaction>) -> Block {
+        let mut all = vec![crate::transaction::Transaction::coinbase(
+            height,
+            5_000,
+            vec![9],
+        )];
+        all.extend(transactions);
+        let merkle_root = crate::block::calculate_merkle_root(&all).unwrap();
+        let header = crate::block::BlockHeader::new(
+            1,
+            Hash256::zero(),
+            merkle_root,
+            chrono::Utc::now(),
+            shared::MAX_TARGET_BITS,
+            0,
+        );
+        Block::new(header, all)
+    }
+
+    #[test]
+    fn test_apply_and_undo_block_restores_set() {
+        use crate::transaction::{Transaction, TxInput, TxOutput};
+
+        let mut utxo_set = UtxoSet::new();
+        let funding = Utxo::new(Hash256::keccak256(b"funding"), 0, 1_000, vec![1], 3);
+        let untouched = Utxo::new(Hash256::keccak256(b"untouched"), 1, 250, vec![1], 3);
+        utxo_set.add_utxo(funding.clone());
+        utxo_set.add_utxo(untouched.clone());
+        let before = utxo_set.clone();
+
+        // A segunda transação gasta um output criado no próprio bloco
+        let spend = Transaction::new(
+            1,
+            vec![TxInput::new(funding.outpoint(), vec![], 0)],
+            vec![TxOutput::new(600, vec![2]), TxOutput::new(400, vec![1])],
+            0,
+        );
+        let chained = Transaction::new(
+            1,
+            vec![TxInput::new(
+                OutPoint::new(spend.hash().unwrap(), 0),
+                vec![],
+                0,
+            )],
+            vec![TxOutput::new(600, vec![3])],
+            0,
+        );
+        let block = block_at(10, vec![spend, chained]);
+
+        let undo = utxo_set.apply_block(&block).unwrap();
+        assert_eq!(undo.spent, vec![funding]);
+        assert_eq!(undo.created.len(), 3);
+        assert_eq!(utxo_set.get_balance_for_script(&[1]), 650);
+        assert_eq!(utxo_set.get_balance_for_script(&[3]), 600);
+        assert_eq!(utxo_set.get_balance_for_script(&[9]), 5_000);
+
+        utxo_set.undo_block(&undo).unwrap();
+        assert_eq!(utxo_set, before);
+        assert_eq!(utxo_set.get_balance_for_script(&[1]), 1_250);
+        assert_eq!(utxo_set.get_balance_for_script(&[3]), 0);
+        assert_eq!(utxo_set.get_balance_for_script(&[9]), 0);
+        assert_eq!(utxo_set.get_utxo(&untouched.outpoint()), Some(&untouched));
+    }
+
+    #[test]
+    fn test_failed_apply_leaves_set_unchanged() {
+        use crate::transaction::{Transaction, TxInput, TxOutput};
+
+        let mut utxo_set = UtxoSet::new();
+        let funding = Utxo::new(Hash256::keccak256(b"funding"), 0, 1_000, vec![1], 3);
+        utxo_set.add_utxo(funding.clone());
+        let before = utxo_set.clone();
+
+        let valid = Transaction::new(
+            1,
+            vec![TxInput::new(funding.outpoint(), vec![], 0)],
+            vec![TxOutput::new(900, vec![2])],
+            0,
+        );
+        let missing = Transaction::new(
+            1,
+            vec![TxInput::new(
+                OutPoint::new(Hash256::keccak256(b"missing"), 0),
+                vec![],
+                0,
+            )],
+            vec![TxOutput::new(100, vec![2])],
+            0,
+        );
+        let block = block_at(10, vec![valid, missing]);
+
+        assert!(matches!(
+            utxo_set.apply_block(&block),
+            Err(BlockchainError::UtxoNotFound)
+        ));
+        assert_eq!(utxo_set, before);
+    }
 }
@@ -0,0 +1,47 @@
+//! Consensus engine abstraction shared by the Bond and Aevum chains
+//!
+//! The P2P node shell only needs to validate, apply and produce blocks and
+//! to know the chain height; everything else (proof of work, validator
+//! schedules, UTXOs or accounts) stays behind this trait.
+
+use crate::Result;
+
+/// Chain rules driven by a node shell
+pub trait ConsensusEngine {
+    /// Block type of the chain
+    type Block;
+    /// Transaction type included in blocks
+    type Transaction;
+    /// Who produces blocks: a miner for proof of work, a validator for `DPoS`
+    type Producer;
+
+    /// Check `block` against the current tip without applying it
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the block is not a valid extension of the chain
+    fn validate_block(&self, block: &Self::Block) -> Result<()>;
+
+    /// Validate and apply `block`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the block is rejected; the chain is left unchanged
+    fn add_block(&mut self, block: Self::Block) -> Result<()>;
+
+    /// Build the next block on top of the tip with `transactions`
+    ///
+    /// The block is returned, not applied; pass it to `add_block`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `producer` cannot produce the next block
+    fn produce_block(
+        &self,
+        producer: &Self::Producer,
+        transactions: Vec<Self::Transaction>,
+    ) -> Result<Self::Block>;
+
+    /// Height of the chain tip
+    fn tip_height(&self) -> u64;
+}
@@ -2,17 +2,84 @@
 
 use crate::{BlockchainError, Hash256, Result};
 use chrono::{DateTime, Utc};
-use pqcrypto_dilithium::dilithium5;
+use pqcrypto_dilithium::{dilithium2, dilithium5};
 use pqcrypto_traits::sign::{
     PublicKey as PQCPublicKeyTrait, SecretKey as PQCSecretKeyTrait, SignedMessage,
 };
 use serde::{Deserialize, Serialize};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
 
 /// Algoritmos de assinatura suportados
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SignatureAlgorithm {
+    /// ML-DSA usado pelo Bond
     MLDSA65,
+    /// ML-DSA-44 (Nível 1) usado pelo Aevum, com chaves e assinaturas menores
+    MLDSA44,
+}
+
+impl SignatureAlgorithm {
+    /// Tamanho da chave pública em bytes
+    #[must_use]
+    pub fn public_key_bytes(self) -> usize {
+        match self {
+            Self::MLDSA65 => dilithium5::public_key_bytes(),
+            Self::MLDSA44 => dilithium2::public_key_bytes(),
+        }
+    }
+
+    /// Tamanho da chave privada em bytes
+    #[must_use]
+    pub fn secret_key_bytes(self) -> usize {
+        match self {
+            Self::MLDSA65 => dilithium5::secret_key_bytes(),
+            Self::MLDSA44 => dilithium2::secret_key_bytes(),
+        }
+    }
+
+    /// One-byte id prefixed to tagged key encodings
+    #[must_use]
+    pub const fn tag(self) -> u8 {
+        match self {
+            Self::MLDSA65 => 0x01,
+            Self::MLDSA44 => 0x02,
+        }
+    }
+
+    /// Algorithm with the given tagged-encoding id
+    #[must_use]
+    pub const fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0x01 => Some(Self::MLDSA65),
+            0x02 => Some(Self::MLDSA44),
+            _ => None,
+        }
+    }
+
+    /// Split a tagged key encoding into its algorithm and key bytes
+    fn split_tagged(bytes: &[u8]) -> Result<(Self, &[u8])> {
+        let (&tag, key) = bytes
+            .split_first()
+            .ok_or_else(|| BlockchainError::CryptographicError("Empty tagged key".to_string()))?;
+        let algorithm = Self::from_tag(tag).ok_or_else(|| {
+            BlockchainError::CryptographicError(format!("Unknown key algorithm tag {tag:#04x}"))
+        })?;
+        Ok((algorithm, key))
+    }
+
+    /// Identifica o algoritmo pelo tamanho de uma chave pública
+    fn from_public_key_len(len: usize) -> Option<Self> {
+        [Self::MLDSA65, Self::MLDSA44]
+            .into_iter()
+            .find(|algorithm| algorithm.public_key_bytes() == len)
+    }
+
+    /// Identifica o algoritmo pelo tamanho de uma chave privada
+    fn from_secret_key_len(len: usize) -> Option<Self> {
+        [Self::MLDSA65, Self::MLDSA44]
+            .into_iter()
+            .find(|algorithm| algorithm.secret_key_bytes() == len)
+    }
 }
 
 /// Chave pública ML-DSA
@@ -48,14 +115,18 @@ pub struct Signature {
 impl PublicKey {
     /// Cria uma chave pública a partir de bytes
     ///
+    /// O algoritmo é identificado pelo tamanho da chave; tamanhos
+    /// desconhecidos são tratados como ML-DSA-65 e falham na verificação.
+    ///
     /// # Errors
     ///
     /// Retorna erro se os bytes não representarem uma chave válida
-    #[allow(clippy::missing_const_for_fn)] // Vec::new() não é const
     pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        let algorithm = SignatureAlgorithm::from_public_key_len(bytes.len())
+            .unwrap_or(SignatureAlgorithm::MLDSA65);
         Ok(Self {
             key_data: bytes,
-            algorithm: SignatureAlgorithm::MLDSA65,
+            algorithm,
         })
     }
 
@@ -71,8 +142,41 @@ impl PublicKey {
         self.algorithm
     }
 
-    fn to_pqc_public_key(&self) -> dilithium5::PublicKey {
-        dilithium5::PublicKey::from_bytes(&self.key_data).expect("Chave pública válida")
+    /// Compact wire encoding: the algorithm tag followed by the key bytes
+    #[must_use]
+    pub fn to_tagged_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + self.key_data.len());
+        bytes.push(self.algorithm.tag());
+        bytes.extend_from_slice(&self.key_data);
+        bytes
+    }
+
+    /// Decodes a key written by `to_tagged_bytes`
+    ///
+    /// # Errors
+    ///
+    /// Returns `CryptographicError` if the input is empty or the tag is
+    /// unknown, and `InvalidKeySize` if the key length does not match the
+    /// tagged algorithm
+    pub fn from_tagged_bytes(bytes: &[u8]) -> Result<Self> {
+        let (algorithm, key) = SignatureAlgorithm::split_tagged(bytes)?;
+        let public_key = Self {
+            key_data: key.to_vec(),
+            algorithm,
+        };
+        public_key.check_size(algorithm)?;
+        Ok(public_key)
+    }
+
+    fn check_size(&self, algorithm: SignatureAlgorithm) -> Result<()> {
+        if self.key_data.len() == algorithm.public_key_bytes() {
+            Ok(())
+        } else {
+            Err(BlockchainError::InvalidKeySize {
+                expected: algorithm.public_key_bytes(),
+                actual: self.key_data.len(),
+            })
+        }
     }
 }
 
@@ -82,11 +186,12 @@ impl PrivateKey {
     /// # Errors
     ///
     /// Returns error if the bytes are invalid for the algorithm
-    #[allow(clippy::missing_const_for_fn)] // Vec operations not const
     pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        let algorithm = SignatureAlgorithm::from_secret_key_len(bytes.len())
+            .unwrap_or(SignatureAlgorithm::MLDSA65);
         Ok(Self {
             key_data: bytes,
-            algorithm: SignatureAlgorithm::MLDSA65,
+            algorithm,
         })
     }
 
@@ -102,22 +207,75 @@ impl PrivateKey {
         self.algorithm
     }
 
-    fn to_pqc_secret_key(&self) -> dilithium5::SecretKey {
-        dilithium5::SecretKey::from_bytes(&self.key_data).expect("Chave privada válida")
+    /// Tagged encoding of the key, for persisting a wallet
+    ///
+    /// Exporting a secret key is refused unless the caller passes
+    /// `allow_export`, so it cannot happen by accident. The returned buffer
+    /// is wiped when dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CryptographicError` if `allow_export` is false
+    pub fn export_tagged_bytes(&self, allow_export: bool) -> Result<Zeroizing<Vec<u8>>> {
+        if !allow_export {
+            return Err(BlockchainError::CryptographicError(
+                "Private key export not allowed".to_string(),
+            ));
+        }
+        let mut bytes = Zeroizing::new(Vec::with_capacity(1 + self.key_data.len()));
+        bytes.push(self.algorithm.tag());
+        bytes.extend_from_slice(&self.key_data);
+        Ok(bytes)
+    }
+
+    /// Decodes a key written by `export_tagged_bytes`
+    ///
+    /// # Errors
+    ///
+    /// Returns `CryptographicError` if the input is empty or the tag is
+    /// unknown, and `InvalidKeySize` if the key length does not match the
+    /// tagged algorithm
+    pub fn from_tagged_bytes(bytes: &[u8]) -> Result<Self> {
+        let (algorithm, key) = SignatureAlgorithm::split_tagged(bytes)?;
+        if key.len() != algorithm.secret_key_bytes() {
+            return Err(BlockchainError::InvalidKeySize {
+                expected: algorithm.secret_key_bytes(),
+                actual: key.len(),
+            });
+        }
+        Ok(Self {
+            key_data: key.to_vec(),
+            algorithm,
+        })
     }
 }
 
 impl KeyPair {
-    /// Generates a new keypair for ML-DSA-65
+    /// Generates a new keypair for the given algorithm
     ///
     /// # Errors
     ///
     /// Returns error if key generation fails
-    pub fn generate() -> Result<Self> {
-        let (public_key_bytes, secret_key_bytes) = dilithium5::keypair();
-
-        let public_key = PublicKey::from_bytes(public_key_bytes.as_bytes().to_vec())?;
-        let private_key = PrivateKey::from_bytes(secret_key_bytes.as_bytes().to_vec())?;
+    pub fn generate(algorithm: SignatureAlgorithm) -> Result<Self> {
+        let (public_key_bytes, secret_key_bytes) = match algorithm {
+            SignatureAlgorithm::MLDSA65 => {
+                let (pk, sk) = dilithium5::keypair();
+                (pk.as_bytes().to_vec(), sk.as_bytes().to_vec())
+            }
+            SignatureAlgorithm::MLDSA44 => {
+                let (pk, sk) = dilithium2::keypair();
+                (pk.as_bytes().to_vec(), sk.as_bytes().to_vec())
+            }
+        };
+
+        let public_key = PublicKey {
+            key_data: public_key_bytes,
+            algorithm,
+        };
+        let private_key = PrivateKey {
+            key_data: secret_key_bytes,
+            algorithm,
+        };
 
         Ok(Self {
             public_key,
@@ -131,32 +289,82 @@ impl KeyPair {
     ///
     /// Returns error if signing fails
     pub fn sign(&self, message: &[u8]) -> Result<Signature> {
-        let secret_key = self.private_key.to_pqc_secret_key();
-        let signed_message = dilithium5::sign(message, &secret_key);
+        let algorithm = self.private_key.algorithm;
+        let invalid_key = |_| BlockchainError::InvalidKeySize {
+            expected: algorithm.secret_key_bytes(),
+            actual: self.private_key.key_data.len(),
+        };
+
+        let data = match algorithm {
+            SignatureAlgorithm::MLDSA65 => {
+                let secret_key = dilithium5::SecretKey::from_bytes(&self.private_key.key_data)
+                    .map_err(invalid_key)?;
+                dilithium5::sign(message, &secret_key).as_bytes().to_vec()
+            }
+            SignatureAlgorithm::MLDSA44 => {
+                let secret_key = dilithium2::SecretKey::from_bytes(&self.private_key.key_data)
+                    .map_err(invalid_key)?;
+                dilithium2::sign(message, &secret_key).as_bytes().to_vec()
+            }
+        };
 
         Ok(Signature {
-            data: signed_message.as_bytes().to_vec(),
+            data,
             public_key: self.public_key.clone(),
-            algorithm: SignatureAlgorithm::MLDSA65,
+            algorithm,
             timestamp: Utc::now(),
         })
     }
+
+    /// Returns the signature algorithm of this keypair
+    #[must_use]
+    pub const fn algorithm(&self) -> SignatureAlgorithm {
+        self.public_key.algorithm
+    }
 }
 
 impl Signature {
     /// Verifies the signature against a message
     ///
+    /// Verification uses the algorithm stored in the signature; a public key
+    /// of a different level is rejected.
+    ///
     /// # Errors
     ///
-    /// Returns error if verification fails
+    /// Returns error if the public key does not match the signature algorithm
+    /// or the signature bytes are malformed
     pub fn verify(&self, message: &[u8]) -> Result<bool> {
-        let public_key = self.public_key.to_pqc_public_key();
-        let signed_message =
-            SignedMessage::from_bytes(&self.data).map_err(|_| BlockchainError::InvalidSignature)?;
+        self.public_key.check_size(self.algorithm)?;
+        let key_data = &self.public_key.key_data;
+        let invalid_key = |_| BlockchainError::InvalidKeySize {
+            expected: self.algorithm.public_key_bytes(),
+            actual: key_data.len(),
+        };
+
+        let opened = match self.algorithm {
+            SignatureAlgorithm::MLDSA65 => {
+                let public_key =
+                    dilithium5::PublicKey::from_bytes(key_data).map_err(invalid_key)?;
+                let signed_message = dilithium5::SignedMessage::from_bytes(&self.data)
+                    .map_err(|_| BlockchainError::InvalidSignature)?;
+                dilithium5::open(&signed_message, &public_key).ok()
+            }
+            SignatureAlgorithm::MLDSA44 => {
+                let public_key =
+                    dilithium2::PublicKey::from_bytes(key_data).map_err(invalid_key)?;
+                let signed_message = dilithium2::SignedMessage::from_bytes(&self.data)
+                    .map_err(|_| BlockchainError::InvalidSignature)?;
+                dilithium2::open(&signed_message, &public_key).ok()
+            }
+        };
+
+        Ok(opened.is_some_and(|verified_message| verified_message == message))
+    }
 
-        dilithium5::open(&signed_message, &public_key).map_or(Ok(false), |verified_message| {
-            Ok(verified_message == message)
-        })
+    /// Returns the raw signature bytes
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
     }
 
     /// Returns the public key used for verification
@@ -214,6 +422,51 @@ pub fn verify_transaction_signature(tx_hash: &Hash256, signature: &Signature) ->
     signature.verify(tx_hash.as_bytes())
 }
 
+/// Verifies many transaction signatures, returning whether all are valid
+///
+/// See [`verify_batch_first_failure`] for the index of the first invalid
+/// signature.
+///
+/// # Errors
+///
+/// Returns the error of the first item (in input order) whose verification
+/// fails with an error rather than an invalid signature
+pub fn verify_batch(items: &[(Hash256, &Signature)]) -> Result<bool> {
+    Ok(verify_batch_first_failure(items)?.is_none())
+}
+
+/// Verifies many transaction signatures, returning the index of the first
+/// invalid one
+///
+/// ML-DSA has no algebraic batch verification, so every signature is still
+/// checked on its own; with the `parallel` feature the checks are spread
+/// across all cores with rayon.
+///
+/// # Errors
+///
+/// Returns the error of the first item (in input order) whose verification
+/// fails with an error rather than an invalid signature
+pub fn verify_batch_first_failure(items: &[(Hash256, &Signature)]) -> Result<Option<usize>> {
+    let verify = |(tx_hash, signature): &(Hash256, &Signature)| {
+        verify_transaction_signature(tx_hash, signature)
+    };
+
+    #[cfg(feature = "parallel")]
+    let results: Vec<Result<bool>> = {
+        use rayon::prelude::*;
+        items.par_iter().map(verify).collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let results: Vec<Result<bool>> = items.iter().map(verify).collect();
+
+    for (index, result) in results.into_iter().enumerate() {
+        if !result? {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
+
 /// Creates a public key from raw bytes
 ///
 /// # Errors
@@ -224,7 +477,8 @@ pub fn public_key_from_bytes(bytes: &[u8]) -> Result<PublicKey> {
 }
 
 /// Creates a signature from raw components
-#[allow(clippy::missing_const_for_fn)] // DateTime operations not const
+///
+/// The algorithm is taken from the public key.
 #[must_use]
 pub const fn signature_from_bytes(
     signature_data: Vec<u8>,
@@ -233,8 +487,8 @@ pub const fn signature_from_bytes(
 ) -> Signature {
     Signature {
         data: signature_data,
+        algorithm: public_key.algorithm,
         public_key,
-        algorithm: SignatureAlgorithm::MLDSA65,
         timestamp,
     }
 }
@@ -245,7 +499,7 @@ mod tests {
 
     #[test]
     fn test_pqc_basic_functionality() {
-        let keypair = KeyPair::generate().unwrap();
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
         let message = b"Aevum & Bond - Post-quantum blockchain";
 
         let signature = keypair.sign(message).unwrap();
@@ -262,4 +516,153 @@ mod tests {
         );
         println!("   Signature:   {} bytes", signature.size());
     }
+
+    #[test]
+    fn test_mldsa44_sign_and_verify() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        assert_eq!(keypair.algorithm(), SignatureAlgorithm::MLDSA44);
+        assert_eq!(
+            keypair.public_key.as_bytes().len(),
+            SignatureAlgorithm::MLDSA44.public_key_bytes()
+        );
+
+        let tx_hash = Hash256::keccak256(b"aevum transfer");
+        let signature = sign_transaction_hash(&tx_hash, &keypair).unwrap();
+        assert_eq!(signature.algorithm(), SignatureAlgorithm::MLDSA44);
+        assert!(verify_transaction_signature(&tx_hash, &signature).unwrap());
+
+        // Chave reconstruída a partir dos bytes mantém o algoritmo
+        let public_key = public_key_from_bytes(keypair.public_key.as_bytes()).unwrap();
+        assert_eq!(public_key.algorithm(), SignatureAlgorithm::MLDSA44);
+        let rebuilt = signature_from_bytes(signature.as_bytes().to_vec(), public_key, Utc::now());
+        assert!(rebuilt.verify(tx_hash.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_public_key_tagged_bytes_round_trip() {
+        for algorithm in [SignatureAlgorithm::MLDSA65, SignatureAlgorithm::MLDSA44] {
+            let keypair = KeyPair::generate(algorithm).unwrap();
+            let tagged = keypair.public_key.to_tagged_bytes();
+            assert_eq!(tagged[0], algorithm.tag());
+            assert_eq!(tagged.len(), 1 + algorithm.public_key_bytes());
+
+            let decoded = PublicKey::from_tagged_bytes(&tagged).unwrap();
+            assert_eq!(decoded, keypair.public_key);
+            assert_eq!(decoded.algorithm(), algorithm);
+        }
+
+        let tagged = KeyPair::generate(SignatureAlgorithm::MLDSA44)
+            .unwrap()
+            .public_key
+            .to_tagged_bytes();
+
+        // Tag desconhecida, entrada vazia e tag que não corresponde ao tamanho
+        let mut unknown = tagged.clone();
+        unknown[0] = 0xff;
+        assert!(matches!(
+            PublicKey::from_tagged_bytes(&unknown),
+            Err(BlockchainError::CryptographicError(_))
+        ));
+        assert!(PublicKey::from_tagged_bytes(&[]).is_err());
+        let mut relabeled = tagged;
+        relabeled[0] = SignatureAlgorithm::MLDSA65.tag();
+        assert!(matches!(
+            PublicKey::from_tagged_bytes(&relabeled),
+            Err(BlockchainError::InvalidKeySize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_private_key_export_requires_explicit_flag() {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        assert!(keypair.private_key.export_tagged_bytes(false).is_err());
+
+        let exported = keypair.private_key.export_tagged_bytes(true).unwrap();
+        assert_eq!(exported[0], SignatureAlgorithm::MLDSA44.tag());
+        let restored = PrivateKey::from_tagged_bytes(&exported).unwrap();
+        assert_eq!(restored.algorithm(), SignatureAlgorithm::MLDSA44);
+        assert_eq!(restored.as_bytes(), keypair.private_key.as_bytes());
+    }
+
+    #[test]
+    fn test_mldsa65_signature_is_not_valid_as_mldsa44() {
+        let bond_keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65).unwrap();
+        let aevum_keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let message = b"cross-level";
+        let signature = bond_keypair.sign(message).unwrap();
+
+        // Assinatura de nível superior declarada como ML-DSA-44
+        let relabeled = Signature {
+            algorithm: SignatureAlgorithm::MLDSA44,
+            ..signature.clone()
+        };
+        assert!(relabeled.verify(message).is_err());
+
+        // Mesmos bytes sob uma chave ML-DSA-44 não verificam
+        let wrong_key = signature_from_bytes(
+            signature.as_bytes().to_vec(),
+            aevum_keypair.public_key.clone(),
+            Utc::now(),
+        );
+        assert!(!wrong_key.verify(message).unwrap_or(false));
+    }
+
+    fn signed_batch(count: usize) -> Vec<(Hash256, Signature)> {
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        (0..count)
+            .map(|i| {
+                let tx_hash = Hash256::keccak256(&i.to_le_bytes());
+                (tx_hash, sign_transaction_hash(&tx_hash, &keypair).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_batch_all_valid() {
+        let batch = signed_batch(8);
+        let items: Vec<(Hash256, &Signature)> = batch.iter().map(|(h, s)| (*h, s)).collect();
+
+        assert!(verify_batch(&items).unwrap());
+        assert_eq!(verify_batch_first_failure(&items).unwrap(), None);
+        assert!(verify_batch(&[]).unwrap());
+    }
+
+    #[test]
+    fn test_verify_batch_reports_corrupt_signature() {
+        let mut batch = signed_batch(8);
+        let mut corrupt = batch[5].1.as_bytes().to_vec();
+        corrupt[10] ^= 0xff;
+        batch[5].1 = signature_from_bytes(corrupt, batch[5].1.public_key().clone(), Utc::now());
+        // Assinatura válida, mas de outra mensagem
+        batch[6].0 = Hash256::keccak256(b"outra");
+        let items: Vec<(Hash256, &Signature)> = batch.iter().map(|(h, s)| (*h, s)).collect();
+
+        assert!(!verify_batch(&items).unwrap());
+        assert_eq!(verify_batch_first_failure(&items).unwrap(), Some(5));
+    }
+
+    /// Compara o lote com a verificação sequencial:
+    /// `cargo test -p shared --release --features parallel -- --ignored bench_verify_batch --nocapture`
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_verify_batch() {
+        let batch = signed_batch(512);
+        let items: Vec<(Hash256, &Signature)> = batch.iter().map(|(h, s)| (*h, s)).collect();
+
+        let start = std::time::Instant::now();
+        for (tx_hash, signature) in &items {
+            assert!(verify_transaction_signature(tx_hash, signature).unwrap());
+        }
+        let sequential = start.elapsed();
+
+        let start = std::time::Instant::now();
+        assert!(verify_batch(&items).unwrap());
+        let batched = start.elapsed();
+
+        println!(
+            "{} assinaturas: sequencial {sequential:?}, lote {batched:?} (parallel: {})",
+            items.len(),
+            cfg!(feature = "parallel")
+        );
+    }
 }
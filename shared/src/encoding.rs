@@ -0,0 +1,38 @@
+//! Deterministic binary encoding used to derive identifiers
+//!
+//! Integers are written little-endian at their full width, variable-length
+//! byte strings and sequences are prefixed with their length as a `u64`,
+//! and fields are written in declaration order. Unlike JSON, the output
+//! does not depend on the serializer, so hashes over it are stable across
+//! serde versions and implementations in other languages.
+
+/// Append a length prefix
+pub fn write_len(out: &mut Vec<u8>, len: usize) {
+    out.extend_from_slice(&(len as u64).to_le_bytes());
+}
+
+/// Append a length-prefixed byte string
+pub fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_len(out, bytes.len());
+    out.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_length_prefix_separates_fields() {
+        // Without prefixes ("ab", "c") and ("a", "bc") would encode identically
+        let mut first = Vec::new();
+        write_bytes(&mut first, b"ab");
+        write_bytes(&mut first, b"c");
+        let mut second = Vec::new();
+        write_bytes(&mut second, b"a");
+        write_bytes(&mut second, b"bc");
+
+        assert_ne!(first, second);
+        assert_eq!(&first[..8], &2u64.to_le_bytes());
+        assert_eq!(first.len(), 8 + 2 + 8 + 1);
+    }
+}
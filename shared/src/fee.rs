@@ -0,0 +1,49 @@
+//! Dynamic admission fee floor for transaction pools
+//!
+//! While a pool is less than half full it accepts anything paying the base
+//! fee. Past that point the floor doubles for every further tenth of the
+//! capacity in use, so under congestion low-value transactions are shed
+//! first. The floor is derived from the current occupancy alone: as the
+//! pool drains, it falls back to the base.
+
+/// Occupancy (percent of capacity) at which the floor starts to rise
+pub const FEE_FLOOR_START_PERCENT: usize = 50;
+
+/// Occupancy (percent of capacity) that doubles the floor again
+pub const FEE_FLOOR_STEP_PERCENT: usize = 10;
+
+/// Minimum fee for a pool holding `len` of `capacity` entries
+///
+/// A base of zero is treated as one once the floor starts to rise, so a
+/// congested pool always charges something. A capacity of zero means the
+/// pool is unbounded and the floor stays at `base`.
+#[must_use]
+pub fn dynamic_fee_floor(base: u128, len: usize, capacity: usize) -> u128 {
+    if capacity == 0 {
+        return base;
+    }
+    let occupancy = len.saturating_mul(100) / capacity;
+    let Some(above) = occupancy.checked_sub(FEE_FLOOR_START_PERCENT) else {
+        return base;
+    };
+    let doublings = (above / FEE_FLOOR_STEP_PERCENT + 1).min(127) as u32;
+    base.max(1).saturating_mul(1u128 << doublings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_floor_rises_with_occupancy_and_returns_to_base() {
+        assert_eq!(dynamic_fee_floor(5, 0, 100), 5);
+        assert_eq!(dynamic_fee_floor(5, 49, 100), 5);
+        assert_eq!(dynamic_fee_floor(5, 50, 100), 10);
+        assert_eq!(dynamic_fee_floor(5, 75, 100), 40);
+        assert_eq!(dynamic_fee_floor(5, 100, 100), 320);
+        assert_eq!(dynamic_fee_floor(0, 100, 100), 64);
+        assert_eq!(dynamic_fee_floor(5, 10, 100), 5);
+        assert_eq!(dynamic_fee_floor(5, 1_000, 0), 5);
+        assert_eq!(dynamic_fee_floor(u128::MAX, 100, 100), u128::MAX);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::{BlockchainError, Result};
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 use std::fmt;
@@ -19,6 +20,20 @@ impl Hash256 {
         Self(bytes)
     }
 
+    /// Parses a hash from its 64-character hex representation
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidHash` if the string is not exactly 64 hex characters.
+    pub fn from_hex(hex_string: &str) -> Result<Self> {
+        if hex_string.len() != 64 {
+            return Err(BlockchainError::InvalidHash);
+        }
+        let mut hash = [0u8; 32];
+        hex::decode_to_slice(hex_string, &mut hash).map_err(|_| BlockchainError::InvalidHash)?;
+        Ok(Self(hash))
+    }
+
     /// Returns the raw bytes of the hash
     #[must_use]
     pub const fn as_bytes(&self) -> &[u8; 32] {
@@ -44,6 +59,12 @@ impl Hash256 {
         leading_zeros >= difficulty
     }
 
+    /// Checks if the hash, read as a big-endian number, is at most `target`
+    #[must_use]
+    pub fn meets_target(&self, target: &[u8; 32]) -> bool {
+        self.0 <= *target
+    }
+
     /// Counts the number of leading zero bits in the hash
     #[must_use]
     pub fn leading_zeros(&self) -> u32 {
@@ -66,12 +87,14 @@ impl fmt::Display for Hash256 {
     }
 }
 
+/// Conversion for hex strings already known to be valid, such as constants.
+/// Untrusted input must go through [`Hash256::from_hex`] instead.
 impl From<String> for Hash256 {
+    /// # Panics
+    ///
+    /// Panics if the string is not exactly 64 hex characters.
     fn from(hex_string: String) -> Self {
-        let bytes = hex::decode(hex_string).expect("Invalid hex string");
-        let mut hash = [0u8; 32];
-        hash.copy_from_slice(&bytes);
-        Self(hash)
+        Self::from_hex(&hex_string).expect("Invalid hex string")
     }
 }
 
@@ -88,6 +111,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_hex() {
+        let hash = Hash256::keccak256(b"hello world");
+        assert_eq!(Hash256::from_hex(&hash.to_string()).unwrap(), hash);
+        assert_eq!(Hash256::from(hash.to_string()), hash);
+
+        let too_short = &hash.to_string()[..62];
+        assert!(matches!(
+            Hash256::from_hex(too_short),
+            Err(BlockchainError::InvalidHash)
+        ));
+
+        let non_hex = "zz".repeat(32);
+        assert!(matches!(
+            Hash256::from_hex(&non_hex),
+            Err(BlockchainError::InvalidHash)
+        ));
+    }
+
     #[test]
     fn test_keccak256_hashing() {
         let data = b"hello world";
@@ -107,6 +149,20 @@ mod tests {
         assert!(!easy_hash.meets_difficulty(40));
     }
 
+    #[test]
+    fn test_target_check() {
+        let hash = Hash256::from_bytes([
+            0, 0, 0x12, 0x34, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0,
+        ]);
+        let mut target = *hash.as_bytes();
+        assert!(hash.meets_target(&target));
+        target[3] = 0x33;
+        assert!(!hash.meets_target(&target));
+        target[2] = 0x13;
+        assert!(hash.meets_target(&target));
+    }
+
     #[test]
     fn test_leading_zeros_count() {
         let hash = Hash256::from_bytes([
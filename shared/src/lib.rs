@@ -1,14 +1,25 @@
+pub mod consensus;
 pub mod crypto;
+pub mod encoding;
 pub mod error;
+pub mod fee;
 pub mod hash;
+pub mod target;
 pub mod types;
 
+pub use consensus::ConsensusEngine;
 pub use crypto::{
-    public_key_from_bytes, sign_transaction_hash, signature_from_bytes,
-    verify_transaction_signature, KeyPair, PrivateKey, PublicKey, Signature, SignatureAlgorithm,
+    public_key_from_bytes, sign_transaction_hash, signature_from_bytes, verify_batch,
+    verify_batch_first_failure, verify_transaction_signature, KeyPair, PrivateKey, PublicKey,
+    Signature, SignatureAlgorithm,
 };
 pub use error::BlockchainError;
+pub use fee::dynamic_fee_floor;
 pub use hash::Hash256;
+pub use target::{
+    compact_from_leading_zeros, compact_to_target, scale_target, target_to_compact, target_work,
+    Target, MAX_TARGET_BITS,
+};
 pub use types::{
     Amount, BlockHeight, BlockId, BlockchainStats, InputIndex, NetworkType, NodeConfig, OutPoint,
     OutputIndex, PeerInfo, PublicKeyHex, SignatureHex, Timestamp, TxId,
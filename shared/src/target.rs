@@ -0,0 +1,180 @@
+//! Proof-of-work targets and their compact (`nBits`) encoding
+//!
+//! A target is a 256-bit big-endian number; a block hash meets it when the
+//! hash, read as a number, is less than or equal to the target. Headers
+//! store targets in Bitcoin's compact form: the high byte is the size of
+//! the number in bytes and the low three bytes are its most significant
+//! digits.
+
+/// A 256-bit big-endian proof-of-work target
+pub type Target = [u8; 32];
+
+/// Easiest target allowed (about one leading zero bit)
+pub const MAX_TARGET_BITS: u32 = 0x207f_ffff;
+
+/// Expand compact bits into a full target
+///
+/// Negative encodings yield a zero target; encodings that overflow 256
+/// bits saturate to the maximum target.
+#[must_use]
+pub fn compact_to_target(bits: u32) -> Target {
+    let mut target = [0u8; 32];
+    if bits & 0x0080_0000 != 0 {
+        return target;
+    }
+
+    let size = (bits >> 24) as usize;
+    let mantissa = (bits & 0x007f_ffff).to_be_bytes();
+    for (offset, &byte) in mantissa[1..].iter().enumerate() {
+        // Byte `offset` of the mantissa sits `size - offset` bytes from the end
+        match (32 + offset).checked_sub(size) {
+            Some(index) if index < 32 => target[index] = byte,
+            Some(_) => {}
+            None if byte != 0 => return [0xff; 32],
+            None => {}
+        }
+    }
+    target
+}
+
+/// Encode a target in compact form, keeping its three most significant bytes
+#[must_use]
+pub fn target_to_compact(target: &Target) -> u32 {
+    let Some(first) = target.iter().position(|&byte| byte != 0) else {
+        return 0;
+    };
+
+    let mut size = 32 - first;
+    let mut digits = [0u8; 4];
+    for (i, byte) in target[first..].iter().take(3).enumerate() {
+        digits[i + 1] = *byte;
+    }
+    let mut mantissa = u32::from_be_bytes(digits);
+    // The top mantissa bit is a sign bit, so shift it out of the way
+    if mantissa & 0x0080_0000 != 0 {
+        mantissa >>= 8;
+        size += 1;
+    }
+    (u32::try_from(size).unwrap_or(u32::MAX) << 24) | mantissa
+}
+
+/// Compact bits for the target requiring `zeros` leading zero bits
+#[must_use]
+pub fn compact_from_leading_zeros(zeros: u32) -> u32 {
+    let mut target = [0xffu8; 32];
+    let zeros = zeros.min(256) as usize;
+    for byte in target.iter_mut().take(zeros / 8) {
+        *byte = 0;
+    }
+    if zeros < 256 {
+        target[zeros / 8] = 0xff >> (zeros % 8);
+    }
+    target_to_compact(&target)
+}
+
+/// Multiply a target by `numerator / denominator`
+///
+/// Results beyond 256 bits saturate to the maximum target.
+#[must_use]
+pub fn scale_target(target: &Target, numerator: u64, denominator: u64) -> Target {
+    if denominator == 0 {
+        return [0xff; 32];
+    }
+
+    // Multiply, least significant byte first
+    let mut product = [0u8; 32];
+    let mut carry: u128 = 0;
+    for i in (0..32).rev() {
+        let value = u128::from(target[i]) * u128::from(numerator) + carry;
+        product[i] = (value & 0xff) as u8;
+        carry = value >> 8;
+    }
+    if carry != 0 {
+        return [0xff; 32];
+    }
+
+    // Long division, most significant byte first
+    let mut quotient = [0u8; 32];
+    let mut remainder: u128 = 0;
+    for i in 0..32 {
+        let value = (remainder << 8) | u128::from(product[i]);
+        quotient[i] = (value / u128::from(denominator)) as u8;
+        remainder = value % u128::from(denominator);
+    }
+    quotient
+}
+
+/// Expected number of hashes needed to meet the target in `bits`
+///
+/// Computed as `2^256 / target` from the target's 64 most significant
+/// bits, saturating at `u128::MAX`. Used to compare the work of chains.
+#[must_use]
+pub fn target_work(bits: u32) -> u128 {
+    let target = compact_to_target(bits);
+    let Some(first) = target.iter().position(|&byte| byte != 0) else {
+        return u128::MAX;
+    };
+
+    let mut window = [0u8; 8];
+    for (i, byte) in target[first..].iter().take(8).enumerate() {
+        window[i] = *byte;
+    }
+    // Normalize so the window's top bit is set
+    let mut top = u64::from_be_bytes(window);
+    let byte_zeros = u32::try_from(first).unwrap_or(32) * 8;
+    let bit_zeros = top.leading_zeros();
+    top <<= bit_zeros;
+    if first + 8 < 32 {
+        top |= u64::from(target[first + 8]) >> (8 - bit_zeros.min(8));
+    }
+
+    // target ~= top * 2^(192 - zeros), so work ~= 2^(zeros + 64) / top
+    let exponent = byte_zeros + bit_zeros + 64;
+    if exponent <= 127 {
+        (1u128 << exponent) / u128::from(top)
+    } else {
+        let base = (1u128 << 127) / u128::from(top);
+        let shift = exponent - 127;
+        if base.leading_zeros() < shift {
+            u128::MAX
+        } else {
+            base << shift
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_roundtrip() {
+        // Bitcoin's genesis target
+        let target = compact_to_target(0x1d00_ffff);
+        assert_eq!(&target[..6], &[0, 0, 0, 0, 0xff, 0xff]);
+        assert!(target[6..].iter().all(|&byte| byte == 0));
+        assert_eq!(target_to_compact(&target), 0x1d00_ffff);
+
+        assert_eq!(compact_from_leading_zeros(1), MAX_TARGET_BITS);
+        assert_eq!(compact_from_leading_zeros(20), 0x1e0f_ffff);
+        assert_eq!(compact_to_target(0x0380_0000), [0; 32]);
+    }
+
+    #[test]
+    fn test_scale_target_and_work() {
+        let bits = compact_from_leading_zeros(20);
+        let target = compact_to_target(bits);
+
+        let halved = target_to_compact(&scale_target(&target, 1, 2));
+        let doubled = target_to_compact(&scale_target(&target, 2, 1));
+        assert_eq!(compact_to_target(halved)[2], 0x07);
+        assert_eq!(compact_to_target(doubled)[2], 0x1f);
+
+        // Within 0.01% of 2^20, 2^21 and 2^19 hashes
+        let close = |work: u128, expected: u128| work.abs_diff(expected) * 10_000 < expected;
+        assert!(close(target_work(bits), 1 << 20));
+        assert!(close(target_work(halved), 1 << 21));
+        assert!(close(target_work(doubled), 1 << 19));
+        assert_eq!(target_work(0), u128::MAX);
+    }
+}
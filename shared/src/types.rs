@@ -60,6 +60,31 @@ pub enum NetworkType {
     Regtest,
 }
 
+impl NetworkType {
+    /// Magic bytes identifying the network on the wire
+    #[must_use]
+    pub const fn magic_bytes(&self) -> [u8; 4] {
+        match self {
+            Self::Mainnet => [0xae, 0xb0, 0x4d, 0x01],
+            Self::Testnet => [0xae, 0xb0, 0x7e, 0x57],
+            Self::Devnet => [0xae, 0xb0, 0xde, 0x70],
+            Self::Regtest => [0xae, 0xb0, 0x4e, 0x67],
+        }
+    }
+
+    /// Identifier exchanged in the P2P handshake; peers on different
+    /// networks refuse each other
+    #[must_use]
+    pub const fn network_id(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "aevum-bond-mainnet",
+            Self::Testnet => "aevum-bond-testnet",
+            Self::Devnet => "aevum-bond-devnet",
+            Self::Regtest => "aevum-bond-regtest",
+        }
+    }
+}
+
 /// Configurações globais do nó
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeConfig {
@@ -0,0 +1,263 @@
+//! Address book ranking known peers by connection quality
+//!
+//! Every address the node has heard of gets a `PeerRecord` tracking how
+//! often dialing it succeeded, how long connections lasted and the latest
+//! ping latency. `dial_order` ranks addresses by the resulting score so the
+//! node dials reliable peers first. The book is saved as JSON on shutdown
+//! and loaded again on startup.
+
+use serde::{Deserialize, Serialize};
+use shared::{BlockchainError, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Uptime beyond this many seconds stops improving a peer's score
+pub const MAX_UPTIME_CREDIT_SECS: u64 = 24 * 60 * 60;
+
+/// Connection history for a single address
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerRecord {
+    pub address: String,
+    /// Successful connections
+    pub successes: u32,
+    /// Failed dial attempts
+    pub failures: u32,
+    /// Smoothed ping latency in milliseconds
+    pub latency_ms: Option<u64>,
+    /// Total seconds spent connected, excluding the current session
+    pub uptime_secs: u64,
+    /// Start of the current session, if connected
+    pub connected_since: Option<u64>,
+    pub last_seen: u64,
+}
+
+impl PeerRecord {
+    fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Quality score; higher is better
+    ///
+    /// Combines the dial success ratio (up to 100 points), uptime (up to 48
+    /// points for a day connected) and a latency penalty of one point per
+    /// 10 ms, capped at 50.
+    #[must_use]
+    pub fn score(&self) -> i64 {
+        let attempts = u64::from(self.successes) + u64::from(self.failures);
+        // Unknown addresses start halfway so they still get a chance
+        let reliability = (u64::from(self.successes) * 100)
+            .checked_div(attempts)
+            .unwrap_or(50);
+        let uptime = self.uptime_secs.min(MAX_UPTIME_CREDIT_SECS) * 48 / MAX_UPTIME_CREDIT_SECS;
+        let latency_penalty = self.latency_ms.map_or(0, |ms| (ms / 10).min(50));
+
+        i64::try_from(reliability + uptime).unwrap_or(i64::MAX)
+            - i64::try_from(latency_penalty).unwrap_or(0)
+    }
+}
+
+/// Known peer addresses ranked by connection quality
+#[derive(Debug, Clone, Default)]
+pub struct AddressBook {
+    records: HashMap<String, PeerRecord>,
+}
+
+impl AddressBook {
+    /// Create an empty address book
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load an address book saved with `save`
+    ///
+    /// A missing file yields an empty book.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or parsed
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        let data = std::fs::read(path).map_err(|e| BlockchainError::IoError(e.to_string()))?;
+        let records: Vec<PeerRecord> = serde_json::from_slice(&data)
+            .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+
+        Ok(Self {
+            records: records
+                .into_iter()
+                .map(|record| (record.address.clone(), record))
+                .collect(),
+        })
+    }
+
+    /// Save the address book to `path`
+    ///
+    /// Open sessions are stored as disconnected so uptime is never counted
+    /// across a restart.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be written
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut records: Vec<PeerRecord> = self.records.values().cloned().collect();
+        records.sort_by(|a, b| a.address.cmp(&b.address));
+        for record in &mut records {
+            record.connected_since = None;
+        }
+
+        let data = serde_json::to_vec_pretty(&records)
+            .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+        std::fs::write(path, data).map_err(|e| BlockchainError::IoError(e.to_string()))
+    }
+
+    /// Learn about an address without changing its history
+    pub fn add_address(&mut self, address: &str) {
+        self.records
+            .entry(address.to_string())
+            .or_insert_with(|| PeerRecord::new(address));
+    }
+
+    /// Record a successful connection
+    pub fn record_connect(&mut self, address: &str, now: u64) {
+        let record = self.record_mut(address);
+        record.successes = record.successes.saturating_add(1);
+        record.connected_since = Some(now);
+        record.last_seen = now;
+    }
+
+    /// Record the end of a connection, crediting its uptime
+    pub fn record_disconnect(&mut self, address: &str, now: u64) {
+        let record = self.record_mut(address);
+        if let Some(since) = record.connected_since.take() {
+            record.uptime_secs = record.uptime_secs.saturating_add(now.saturating_sub(since));
+        }
+        record.last_seen = now;
+    }
+
+    /// Record a failed dial attempt
+    pub fn record_failure(&mut self, address: &str) {
+        let record = self.record_mut(address);
+        record.failures = record.failures.saturating_add(1);
+        record.connected_since = None;
+    }
+
+    /// Record a ping round trip
+    ///
+    /// Latency is smoothed as `(3 * old + new) / 4` so a single slow ping
+    /// does not sink a good peer.
+    pub fn record_latency(&mut self, address: &str, latency_ms: u64) {
+        let record = self.record_mut(address);
+        record.latency_ms = Some(record.latency_ms.map_or(latency_ms, |old| {
+            old.saturating_mul(3).saturating_add(latency_ms) / 4
+        }));
+    }
+
+    /// Addresses to dial, best first
+    ///
+    /// Ties are broken by address so the order is deterministic.
+    #[must_use]
+    pub fn dial_order(&self, limit: usize) -> Vec<String> {
+        let mut records: Vec<&PeerRecord> = self.records.values().collect();
+        records.sort_by(|a, b| {
+            b.score()
+                .cmp(&a.score())
+                .then_with(|| a.address.cmp(&b.address))
+        });
+        records
+            .into_iter()
+            .take(limit)
+            .map(|record| record.address.clone())
+            .collect()
+    }
+
+    /// History for an address, if known
+    #[must_use]
+    pub fn get(&self, address: &str) -> Option<&PeerRecord> {
+        self.records.get(address)
+    }
+
+    /// Number of known addresses
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Check if no address is known
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    fn record_mut(&mut self, address: &str) -> &mut PeerRecord {
+        self.records
+            .entry(address.to_string())
+            .or_insert_with(|| PeerRecord::new(address))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reliable_low_latency_peers_dialed_first() {
+        let mut book = AddressBook::new();
+        book.add_address("10.0.0.1:8333");
+        book.record_connect("10.0.0.2:8333", 0);
+        book.record_disconnect("10.0.0.2:8333", 3_600);
+        book.record_latency("10.0.0.2:8333", 40);
+        book.record_connect("10.0.0.3:8333", 0);
+        book.record_disconnect("10.0.0.3:8333", 3_600);
+        book.record_latency("10.0.0.3:8333", 400);
+
+        assert_eq!(
+            book.dial_order(10),
+            vec!["10.0.0.2:8333", "10.0.0.3:8333", "10.0.0.1:8333"]
+        );
+        assert_eq!(book.dial_order(1), vec!["10.0.0.2:8333"]);
+    }
+
+    #[test]
+    fn test_failed_peers_are_deprioritized() {
+        let mut book = AddressBook::new();
+        book.add_address("10.0.0.1:8333");
+        book.add_address("10.0.0.2:8333");
+        assert_eq!(book.dial_order(10)[0], "10.0.0.1:8333");
+
+        book.record_failure("10.0.0.1:8333");
+        book.record_failure("10.0.0.1:8333");
+        assert_eq!(book.dial_order(10), vec!["10.0.0.2:8333", "10.0.0.1:8333"]);
+
+        // One success after two failures still ranks below a fresh address
+        book.record_connect("10.0.0.1:8333", 10);
+        assert!(book.get("10.0.0.1:8333").unwrap().score() < 50);
+        assert_eq!(book.dial_order(10)[0], "10.0.0.2:8333");
+    }
+
+    #[test]
+    fn test_address_book_survives_restart() {
+        let path =
+            std::env::temp_dir().join(format!("aevum-address-book-{}.json", rand::random::<u64>()));
+        assert!(AddressBook::load(&path).unwrap().is_empty());
+
+        let mut book = AddressBook::new();
+        book.record_connect("10.0.0.1:8333", 100);
+        book.record_latency("10.0.0.1:8333", 80);
+        book.record_failure("10.0.0.2:8333");
+        book.save(&path).unwrap();
+
+        let loaded = AddressBook::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.dial_order(10), book.dial_order(10));
+        let record = loaded.get("10.0.0.1:8333").unwrap();
+        assert_eq!(record.latency_ms, Some(80));
+        assert_eq!(record.connected_since, None);
+    }
+}
@@ -0,0 +1,291 @@
+//! Compact block relay
+//!
+//! Instead of the full block, a `CompactBlock` carries the header, the
+//! coinbase and a short id for every other transaction. The receiver
+//! rebuilds the block from its mempool into a `PartialBlock` and asks the
+//! sender with `GetBlockTxn` only for the transactions it is missing.
+//!
+//! Short ids are salted with the block hash, so colliding ids cannot be
+//! precomputed once for every block. A collision that slips through is
+//! caught by the merkle root check when the block is assembled.
+
+use bond_core::block::calculate_merkle_root;
+use bond_core::{Block, BlockHeader, BondMempool, Transaction};
+use serde::{Deserialize, Serialize};
+use shared::{BlockchainError, Hash256, Result};
+use std::collections::HashMap;
+
+/// Bytes of a transaction id kept in a short id
+pub const SHORT_TXID_LEN: usize = 6;
+
+/// Truncated, block-salted transaction id
+pub type ShortTxId = [u8; SHORT_TXID_LEN];
+
+/// Short id of `txid` within the block `block_hash`
+#[must_use]
+pub fn short_txid(block_hash: &Hash256, txid: &Hash256) -> ShortTxId {
+    let mut data = Vec::with_capacity(64);
+    data.extend_from_slice(block_hash.as_bytes());
+    data.extend_from_slice(txid.as_bytes());
+    let mut short = [0u8; SHORT_TXID_LEN];
+    short.copy_from_slice(&Hash256::keccak256(&data).as_bytes()[..SHORT_TXID_LEN]);
+    short
+}
+
+/// Transaction sent in full inside a compact block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrefilledTransaction {
+    /// Position of the transaction in the block
+    pub index: u32,
+    pub tx: Transaction,
+}
+
+/// Block announcement carrying short ids instead of transactions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactBlock {
+    pub header: BlockHeader,
+    /// Short ids of the transactions not prefilled, in block order
+    pub short_ids: Vec<ShortTxId>,
+    /// Transactions the receiver cannot have in its mempool (the coinbase)
+    pub prefilled: Vec<PrefilledTransaction>,
+}
+
+impl CompactBlock {
+    /// Compact form of `block`, prefilling its coinbase
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a hash cannot be computed
+    pub fn from_block(block: &Block) -> Result<Self> {
+        let block_hash = block.hash()?;
+        let mut short_ids = Vec::new();
+        let mut prefilled = Vec::new();
+        for (index, tx) in block.transactions.iter().enumerate() {
+            if tx.is_coinbase() {
+                prefilled.push(PrefilledTransaction {
+                    index: u32::try_from(index).map_err(|_| {
+                        BlockchainError::InvalidBlock("Too many transactions".to_string())
+                    })?,
+                    tx: tx.clone(),
+                });
+            } else {
+                short_ids.push(short_txid(&block_hash, &tx.hash()?));
+            }
+        }
+
+        Ok(Self {
+            header: block.header.clone(),
+            short_ids,
+            prefilled,
+        })
+    }
+
+    /// Hash of the announced block
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the header cannot be hashed
+    pub fn block_hash(&self) -> Result<Hash256> {
+        self.header.hash()
+    }
+
+    /// Number of transactions in the announced block
+    #[must_use]
+    pub fn tx_count(&self) -> usize {
+        self.short_ids.len() + self.prefilled.len()
+    }
+
+    /// Fill in every transaction found in `mempool`
+    ///
+    /// Mempool transactions sharing a short id are ambiguous and left to be
+    /// fetched from the sender.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidBlock` if a prefilled index is out of range or
+    /// repeated, or error if a hash cannot be computed
+    pub fn reconstruct(&self, mempool: &BondMempool) -> Result<PartialBlock> {
+        let block_hash = self.block_hash()?;
+        let mut slots: Vec<Option<Transaction>> = vec![None; self.tx_count()];
+        for prefilled in &self.prefilled {
+            let slot = slots
+                .get_mut(prefilled.index as usize)
+                .filter(|slot| slot.is_none())
+                .ok_or_else(|| {
+                    BlockchainError::InvalidBlock(format!(
+                        "Invalid prefilled transaction index {}",
+                        prefilled.index
+                    ))
+                })?;
+            *slot = Some(prefilled.tx.clone());
+        }
+
+        let mut candidates: HashMap<ShortTxId, Option<&Transaction>> = HashMap::new();
+        for entry in mempool.sorted_entries() {
+            let short = short_txid(&block_hash, &entry.tx.hash()?);
+            candidates
+                .entry(short)
+                .and_modify(|candidate| *candidate = None)
+                .or_insert(Some(&entry.tx));
+        }
+
+        let empty_slots = slots.iter_mut().filter(|slot| slot.is_none());
+        for (slot, short) in empty_slots.zip(&self.short_ids) {
+            if let Some(Some(tx)) = candidates.get(short) {
+                *slot = Some((*tx).clone());
+            }
+        }
+
+        Ok(PartialBlock {
+            header: self.header.clone(),
+            slots,
+        })
+    }
+}
+
+/// Block being rebuilt from a `CompactBlock`
+#[derive(Debug, Clone)]
+pub struct PartialBlock {
+    header: BlockHeader,
+    slots: Vec<Option<Transaction>>,
+}
+
+impl PartialBlock {
+    /// Positions of the transactions still missing, in block order
+    #[must_use]
+    pub fn missing_indexes(&self) -> Vec<u32> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.is_none())
+            .filter_map(|(index, _)| u32::try_from(index).ok())
+            .collect()
+    }
+
+    /// Check whether every transaction is known
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.slots.iter().all(Option::is_some)
+    }
+
+    /// Fill the missing positions with `transactions`, in block order
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidBlock` if the number of transactions differs from the
+    /// number missing
+    pub fn fill(&mut self, transactions: Vec<Transaction>) -> Result<()> {
+        let missing = self.slots.iter().filter(|slot| slot.is_none()).count();
+        if transactions.len() != missing {
+            return Err(BlockchainError::InvalidBlock(format!(
+                "Expected {missing} missing transactions, got {}",
+                transactions.len()
+            )));
+        }
+
+        let empty_slots = self.slots.iter_mut().filter(|slot| slot.is_none());
+        for (slot, tx) in empty_slots.zip(transactions) {
+            *slot = Some(tx);
+        }
+        Ok(())
+    }
+
+    /// Assemble the full block
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidBlock` if transactions are still missing or if they
+    /// do not match the header's merkle root (a short id collision)
+    pub fn into_block(self) -> Result<Block> {
+        let transactions: Vec<Transaction> = self
+            .slots
+            .into_iter()
+            .collect::<Option<_>>()
+            .ok_or_else(|| {
+                BlockchainError::InvalidBlock("Compact block is incomplete".to_string())
+            })?;
+        if calculate_merkle_root(&transactions)? != self.header.merkle_root {
+            return Err(BlockchainError::InvalidBlock(
+                "Reconstructed transactions do not match the merkle root".to_string(),
+            ));
+        }
+        Ok(Block::new(self.header, transactions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bond_core::{OutPoint, TxInput, TxOutput, Utxo, UtxoSet};
+    use chrono::Utc;
+
+    fn spend(utxo_set: &mut UtxoSet, seed: &[u8]) -> Transaction {
+        let txid = Hash256::keccak256(seed);
+        utxo_set.add_utxo(Utxo::new(txid, 0, 1_000, vec![1], 0));
+        Transaction::new(
+            1,
+            vec![TxInput::new(OutPoint { txid, vout: 0 }, vec![], u32::MAX)],
+            vec![TxOutput::new(900, vec![2])],
+            0,
+        )
+    }
+
+    fn block_with(transactions: Vec<Transaction>) -> Block {
+        let mut all = vec![Transaction::coinbase(1, 5_000, vec![1])];
+        all.extend(transactions);
+        let header = BlockHeader::new(
+            1,
+            Hash256::zero(),
+            calculate_merkle_root(&all).unwrap(),
+            Utc::now(),
+            0,
+            0,
+        );
+        Block::new(header, all)
+    }
+
+    #[test]
+    fn test_reconstruct_requests_only_missing_transactions() {
+        let mut utxo_set = UtxoSet::new();
+        let txs: Vec<Transaction> = [b"a", b"b", b"c"]
+            .iter()
+            .map(|seed| spend(&mut utxo_set, *seed))
+            .collect();
+        let block = block_with(txs.clone());
+
+        let mut mempool = BondMempool::new();
+        for tx in [&txs[0], &txs[2]] {
+            mempool
+                .add_transaction(tx.clone(), &utxo_set, 1, 0)
+                .unwrap();
+        }
+
+        let compact = CompactBlock::from_block(&block).unwrap();
+        assert_eq!(compact.short_ids.len(), 3);
+        assert_eq!(compact.prefilled.len(), 1);
+
+        let mut partial = compact.reconstruct(&mempool).unwrap();
+        assert_eq!(partial.missing_indexes(), vec![2]);
+        assert!(partial.clone().into_block().is_err());
+
+        partial.fill(vec![txs[1].clone()]).unwrap();
+        assert!(partial.is_complete());
+        let rebuilt = partial.into_block().unwrap();
+        assert_eq!(rebuilt.hash().unwrap(), block.hash().unwrap());
+        assert_eq!(rebuilt.transactions, block.transactions);
+    }
+
+    #[test]
+    fn test_wrong_transactions_fail_merkle_check() {
+        let mut utxo_set = UtxoSet::new();
+        let tx = spend(&mut utxo_set, b"a");
+        let other = spend(&mut utxo_set, b"b");
+        let block = block_with(vec![tx]);
+
+        let compact = CompactBlock::from_block(&block).unwrap();
+        let mut partial = compact.reconstruct(&BondMempool::new()).unwrap();
+        assert!(partial.fill(vec![]).is_err());
+        partial.fill(vec![other]).unwrap();
+        assert!(partial.into_block().is_err());
+    }
+}
@@ -1,12 +1,17 @@
-use bond_core::{Block, Blockchain, NetworkParams, Transaction, TxOutput};
+use bond_core::{Block, Blockchain, Miner, MinerConfig, NetworkParams, Transaction, TxOutput};
 use clap::{Parser, Subcommand};
-use shared::Result;
+use shared::{NodeConfig, Result};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tracing::Level;
 use tracing_subscriber::FmtSubscriber;
 
 // Importação do módulo de rede
+pub mod address_book;
+pub mod compact_block;
 pub mod network;
+pub mod rpc;
+pub mod wallet;
 
 /// Aevum-Bond - Blockchain pós-quântica com suporte P2P
 #[derive(Parser, Debug)]
@@ -50,7 +55,7 @@ async fn main() -> Result<()> {
         Commands::DemoPqc => run_demo_pqc(),
         Commands::StartNode => {
             run_p2p_node();
-            Ok(())
+            run_node(NodeConfig::default()).await
         }
         Commands::DemoConsensus => run_consensus_demo(),
     }
@@ -249,7 +254,9 @@ fn run_demo_sprint1() -> Result<()> {
 
 /// Sprint 2: Demonstração de Criptografia Pós-Quântica
 fn run_demo_pqc() -> Result<()> {
-    use shared::{Hash256, KeyPair, sign_transaction_hash, verify_transaction_signature};
+    use shared::{
+        Hash256, KeyPair, SignatureAlgorithm, sign_transaction_hash, verify_transaction_signature,
+    };
 
     println!("🌟 =================================");
     println!("   SPRINT 2: CRIPTOGRAFIA PÓS-QUÂNTICA");
@@ -260,7 +267,7 @@ fn run_demo_pqc() -> Result<()> {
 
     // Gerar par de chaves pós-quânticas
     println!("🔑 1. Gerando par de chaves ML-DSA-65...");
-    let keypair = KeyPair::generate()?;
+    let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA65)?;
 
     println!("   ✅ Chaves geradas com sucesso!");
     println!(
@@ -302,6 +309,54 @@ fn run_demo_pqc() -> Result<()> {
     Ok(())
 }
 
+/// Inicia o nó com o servidor RPC na porta `rpc_port` e aguarda Ctrl+C
+async fn run_node(config: NodeConfig) -> Result<()> {
+    let p2p_config = network::P2PConfig {
+        port: config.p2p_port,
+        listen_addr: config.bind_address.clone(),
+        bootstrap_nodes: config.bootstrap_peers.clone(),
+        ..network::P2PConfig::for_network(config.network.clone())
+    };
+    let mining_threads = match p2p_config.node_mode {
+        network::NodeMode::MiningNode { mining_threads, .. } => Some(mining_threads),
+        _ => None,
+    };
+    let mut node = network::P2PNode::new(p2p_config)?;
+    node.set_blockchain(Blockchain::new(
+        NetworkParams::for_network(config.network.clone()),
+        vec![1, 2, 3],
+    )?);
+    node.start()?;
+    let node = Arc::new(Mutex::new(node));
+
+    if let Some(mining_threads) = mining_threads {
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![1, 2, 3],
+            threads: mining_threads,
+            ..MinerConfig::default()
+        });
+        let mining_node = Arc::clone(&node);
+        tokio::spawn(async move {
+            if let Err(e) = network::run_mining_loop(mining_node, miner).await {
+                eprintln!("❌ Mineração encerrada: {e}");
+            }
+        });
+    }
+
+    let rpc_addr = format!("{}:{}", config.bind_address, config.rpc_port);
+    let server = rpc::RpcServer::bind(&rpc_addr, Arc::clone(&node)).await?;
+    println!("📡 RPC disponível em http://{rpc_addr}");
+
+    tokio::select! {
+        () = server.serve() => {}
+        _ = tokio::signal::ctrl_c() => println!("\n🛑 Encerrando nó..."),
+    }
+
+    node.lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .shutdown()
+}
+
 /// Sprint 3: Demonstração de Nó P2P
 fn run_p2p_node() {
     println!("🌟 =================================");
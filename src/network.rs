@@ -1,10 +1,70 @@
-use bond_core::{Block, Blockchain, Transaction};
+use crate::address_book::AddressBook;
+use crate::compact_block::{CompactBlock, PartialBlock};
+use aevum_core::{AevumChain, SlashingEvidence};
+use bond_core::{
+    Block, BlockHeader, BlockLocator, Blockchain, BondMempool, MempoolError, Miner, Transaction,
+};
 use rand::random;
 use serde::{Deserialize, Serialize};
-use shared::{BlockchainError, Result};
-use std::collections::HashMap;
-use std::time::Duration;
-use tracing::info;
+use shared::{BlockchainError, ConsensusEngine, Hash256, NetworkType, Result};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info, warn};
+
+/// Misbehavior penalty for announcing a block far beyond our tip
+pub const FUTURE_BLOCK_PENALTY: u32 = 20;
+
+/// Misbehavior penalty for a block that fails validation
+pub const INVALID_BLOCK_PENALTY: u32 = 25;
+
+/// Misbehavior penalty for relaying an invalid transaction
+pub const INVALID_TX_PENALTY: u32 = 10;
+
+/// Penalty for gossiping slashing evidence whose signatures do not verify
+pub const INVALID_EVIDENCE_PENALTY: u32 = 25;
+
+/// Misbehavior penalty for a gossip message that cannot be decoded
+pub const MALFORMED_MESSAGE_PENALTY: u32 = 10;
+
+/// Misbehavior penalty for each gossip message dropped by the rate limiter
+pub const RATE_LIMIT_PENALTY: u32 = 1;
+
+/// Peers whose score drops to this value are banned and disconnected
+pub const BAN_SCORE_THRESHOLD: i32 = -100;
+
+/// Maximum number of blocks served in a single sync response
+pub const MAX_BLOCKS_PER_RESPONSE: u64 = 128;
+
+/// Maximum number of headers served per `Headers` response
+pub const MAX_HEADERS_PER_RESPONSE: usize = 2000;
+
+/// Number of recent transaction rejections kept for diagnostics
+pub const MAX_RECENT_REJECTIONS: usize = 256;
+
+/// Largest clock offset accepted from the peer median (70 minutes)
+pub const MAX_TIME_ADJUSTMENT_SECS: i64 = 70 * 60;
+
+/// Peer clock samples needed before the network time is trusted
+pub const MIN_TIME_SAMPLES: usize = 5;
+
+/// Events buffered per subscriber before the slowest one starts lagging
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// How often the mining loop checks whether the miner found a block
+pub const MINING_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Gossip topics every node subscribes to on startup
+const GOSSIP_TOPICS: [&str; 3] = ["blocks", "transactions", "slashing"];
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
 
 /// Different types of nodes in the network
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +91,14 @@ pub struct P2PConfig {
     pub enable_kad_dht: bool,
     pub node_mode: NodeMode,
     pub network_id: String,
+    /// File the address book is loaded from on start and saved to on shutdown
+    pub address_book_path: Option<PathBuf>,
+    /// Gossip messages per second accepted from each peer (zero disables the limit)
+    pub gossip_rate_per_sec: u32,
+    /// Gossip messages a peer may send in a burst before being limited
+    pub gossip_burst: u32,
+    /// Account credited with the bounty of slashing evidence this node applies
+    pub slashing_reporter: Hash256,
 }
 
 impl Default for P2PConfig {
@@ -45,12 +113,26 @@ impl Default for P2PConfig {
             enable_mdns: true,
             enable_kad_dht: true,
             node_mode: NodeMode::FullNode,
-            network_id: "aevum-bond-testnet".to_string(),
+            network_id: NetworkType::default().network_id().to_string(),
+            address_book_path: None,
+            gossip_rate_per_sec: 50,
+            gossip_burst: 100,
+            slashing_reporter: Hash256::zero(),
         }
     }
 }
 
 impl P2PConfig {
+    /// Default config joined to `network`, so it only peers with nodes of
+    /// the same network
+    #[must_use]
+    pub fn for_network(network: NetworkType) -> Self {
+        Self {
+            network_id: network.network_id().to_string(),
+            ..Default::default()
+        }
+    }
+
     /// Create config for mining node
     #[must_use]
     pub fn mining_node(port: u16, bootstrap_nodes: Vec<String>, threads: usize) -> Self {
@@ -104,12 +186,54 @@ pub enum NetworkMessage {
     GetBlock(u64),
     /// Block response
     Block(Block),
+    /// Request a batch of blocks starting at a height
+    GetBlocks { from_height: u64 },
+    /// Batch of consecutive blocks answering `GetBlocks`
+    BlockResponse(Vec<Block>),
     /// Status announcement
     Status {
         chain_height: u64,
         best_hash: String,
         node_id: String,
     },
+    /// Proof that a validator signed two blocks at the same height
+    SlashingEvidence(SlashingEvidence),
+    /// Initial handshake identifying the peer's network
+    Handshake(Handshake),
+    /// Announce a new block by header and short transaction ids
+    CompactBlock(CompactBlock),
+    /// Request the transactions of a compact block missing from our mempool
+    GetBlockTxn {
+        block_hash: Hash256,
+        indexes: Vec<u32>,
+    },
+    /// Transactions answering `GetBlockTxn`, in the requested order
+    BlockTxn {
+        block_hash: Hash256,
+        transactions: Vec<Transaction>,
+    },
+    /// Request the headers following the common ancestor with a locator
+    GetHeaders(BlockLocator),
+    /// Headers answering `GetHeaders`, in ascending height order
+    Headers(Vec<BlockHeader>),
+    /// Request the bodies of blocks by hash, after their headers validated
+    GetBlockData(Vec<Hash256>),
+    /// Blocks answering `GetBlockData`
+    BlockData(Vec<Block>),
+}
+
+/// Handshake sent when connecting to a peer
+///
+/// The genesis hash commits to the network params, so peers running
+/// with different params are refused before any block is exchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handshake {
+    pub network_id: String,
+    pub genesis_hash: Hash256,
+    pub chain_height: u64,
+    /// Sender's clock (seconds since the Unix epoch)
+    #[serde(default)]
+    pub timestamp: u64,
 }
 
 /// Information about a peer
@@ -120,6 +244,63 @@ pub struct PeerInfo {
     pub node_mode: NodeMode,
     pub last_seen: u64,
     pub chain_height: u64,
+    /// Accumulated misbehavior penalties
+    #[serde(default)]
+    pub misbehavior_score: u32,
+}
+
+/// A transaction the node refused to relay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxRejection {
+    pub txid: Hash256,
+    pub reason: MempoolError,
+    pub timestamp: u64,
+}
+
+/// Token bucket refilled at a fixed rate, one token per gossip message
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn full(burst: u32, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(burst),
+            updated: now,
+        }
+    }
+
+    /// Refill for the time elapsed since the last call and take one token
+    fn try_take(&mut self, rate_per_sec: u32, burst: u32, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = elapsed
+            .mul_add(f64::from(rate_per_sec), self.tokens)
+            .min(f64::from(burst));
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Chain and mempool changes published to `P2PNode::subscribe_events` receivers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockchainEvent {
+    /// A block became the new chain tip
+    BlockAdded { height: u64, hash: Hash256 },
+    /// A transaction entered the mempool
+    TransactionAccepted { txid: Hash256 },
+    /// The active chain switched to a heavier branch
+    ChainReorg {
+        fork_height: u64,
+        disconnected: usize,
+        connected: usize,
+    },
 }
 
 /// Network status information
@@ -131,72 +312,177 @@ pub struct NetworkStatus {
     pub listen_addresses: Vec<String>,
     pub node_mode: NodeMode,
     pub chain_height: u64,
+    /// Reputation of every scored peer; connected peers start at zero
+    #[serde(default)]
+    pub peer_scores: HashMap<String, i32>,
 }
 
 /// Mock P2P Node implementation for testing
-pub struct P2PNode {
+///
+/// Generic over the chain's `ConsensusEngine`; the Bond proof-of-work
+/// `Blockchain` is the default and the only engine with mempool, compact
+/// block and sync support.
+pub struct P2PNode<E = Blockchain> {
     config: P2PConfig,
     local_peer_id: String,
     peers: HashMap<String, PeerInfo>,
-    blockchain: Option<Blockchain>,
+    address_book: AddressBook,
+    blockchain: Option<E>,
+    mempool: BondMempool,
+    recent_rejections: VecDeque<TxRejection>,
+    /// Clock offset reported by each peer in its handshake
+    time_offsets: HashMap<String, i64>,
+    /// Reputation of each peer, lowered by every misbehavior penalty
+    peer_score: HashMap<String, i32>,
+    /// Peers refused until the node is recreated
+    banned_peers: HashSet<String>,
+    /// Gossip rate limiter of each peer
+    gossip_buckets: HashMap<String, TokenBucket>,
+    /// Compact blocks waiting for `BlockTxn`, by block hash
+    pending_compact_blocks: HashMap<Hash256, PartialBlock>,
+    /// Validated headers whose bodies were requested with `GetBlockData`
+    pending_headers: Vec<BlockHeader>,
+    /// Slashing evidence already applied and relayed, by validator and height
+    seen_evidence: HashSet<(Hash256, u64)>,
+    /// Sender side of the event channel handed out by `subscribe_events`
+    events: broadcast::Sender<BlockchainEvent>,
+    published: Vec<(String, NetworkMessage)>,
+    subscribed_topics: HashSet<String>,
     is_running: bool,
 }
 
-impl P2PNode {
-    /// Create a new mock P2P node
+impl<E: ConsensusEngine> P2PNode<E> {
+    /// Create a mock P2P node driving `engine`
+    ///
+    /// The shell (peers, gossip topics, scoring) is the same for every
+    /// chain; `P2PNode::new` builds a Bond node whose chain is attached
+    /// later with `set_blockchain`.
     ///
     /// # Errors
     ///
     /// Can return error during node setup
-    pub fn new(config: P2PConfig) -> Result<Self> {
+    pub fn with_engine(config: P2PConfig, engine: E) -> Result<Self> {
+        let mut node = Self::detached(config);
+        node.blockchain = Some(engine);
+        Ok(node)
+    }
+
+    /// Node shell without a consensus engine
+    fn detached(config: P2PConfig) -> Self {
         // Generate a random node ID for this mock implementation
         let local_peer_id = format!("aevum-{}", random::<u32>());
 
         info!("🆔 Created mock P2P node with ID: {}", local_peer_id);
 
-        Ok(Self {
+        Self {
             config,
             local_peer_id,
             peers: HashMap::new(),
+            address_book: AddressBook::new(),
             blockchain: None,
+            mempool: BondMempool::new(),
+            recent_rejections: VecDeque::new(),
+            time_offsets: HashMap::new(),
+            peer_score: HashMap::new(),
+            banned_peers: HashSet::new(),
+            gossip_buckets: HashMap::new(),
+            pending_compact_blocks: HashMap::new(),
+            pending_headers: Vec::new(),
+            seen_evidence: HashSet::new(),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            published: Vec::new(),
+            subscribed_topics: HashSet::new(),
             is_running: false,
-        })
+        }
     }
 
-    /// Start the P2P node
+    /// Consensus engine driven by this node
+    #[must_use]
+    pub const fn engine(&self) -> Option<&E> {
+        self.blockchain.as_ref()
+    }
+
+    /// Apply a block received from a peer through the consensus engine
+    ///
+    /// A block the engine rejects penalizes the peer like an invalid block.
     ///
     /// # Errors
     ///
-    /// Can return error during node startup
+    /// Returns error if the peer is banned, no engine is attached or the
+    /// engine rejects the block
+    pub fn handle_engine_block(&mut self, peer_id: &str, block: E::Block) -> Result<()> {
+        self.ensure_not_banned(peer_id)?;
+        let engine = self
+            .blockchain
+            .as_mut()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?;
+        if let Err(e) = engine.add_block(block) {
+            self.penalize_peer(peer_id, INVALID_BLOCK_PENALTY, "invalid block");
+            return Err(e);
+        }
+        info!(
+            "✅ Block from peer {} accepted at height {}",
+            peer_id,
+            engine.tip_height()
+        );
+        Ok(())
+    }
+
+    /// Start the P2P node
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// May panic if system time is before `UNIX_EPOCH`
+    /// Returns error if subscribing to a gossip topic fails
     pub fn start(&mut self) -> Result<()> {
         info!(
             "🚀 Starting mock P2P node {} on {}:{}",
             self.local_peer_id, self.config.listen_addr, self.config.port
         );
 
-        // Simulate connecting to bootstrap nodes
+        for name in GOSSIP_TOPICS {
+            let topic = format!("{}/{name}", self.config.network_id);
+            if let Err(e) = self.subscribe(&topic) {
+                error!("❌ Failed to subscribe to {}: {}", topic, e);
+                self.subscribed_topics.clear();
+                return Err(e);
+            }
+        }
+
+        if let Some(path) = &self.config.address_book_path {
+            match AddressBook::load(path) {
+                Ok(book) => self.address_book = book,
+                Err(e) => warn!("⚠️ Ignoring unreadable address book {:?}: {}", path, e),
+            }
+        }
         for bootstrap_addr in &self.config.bootstrap_nodes {
-            info!("🌐 [Mock] Connecting to bootstrap node: {}", bootstrap_addr);
+            self.address_book.add_address(bootstrap_addr);
+        }
+
+        // Simulate dialing the best known peers first
+        let now = unix_now();
+        for address in self.address_book.dial_order(self.config.max_peers) {
+            let is_bootstrap = self.config.bootstrap_nodes.contains(&address);
+            info!("🌐 [Mock] Connecting to peer: {}", address);
             // Create a mock peer
-            let peer_id = format!("mock-peer-{bootstrap_addr}");
+            let peer_id = format!("mock-peer-{address}");
             let peer_info = PeerInfo {
                 node_id: peer_id.clone(),
-                address: bootstrap_addr.clone(),
-                node_mode: NodeMode::BootstrapNode,
-                last_seen: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs(),
+                address: address.clone(),
+                node_mode: if is_bootstrap {
+                    NodeMode::BootstrapNode
+                } else {
+                    NodeMode::FullNode
+                },
+                last_seen: now,
                 chain_height: 0,
+                misbehavior_score: 0,
             };
             self.peers.insert(peer_id, peer_info);
+            self.address_book.record_connect(&address, now);
         }
 
-        // Simulate MDNS discovery
+        // Simulate MDNS discovery; with mDNS disabled the node relies on
+        // bootstrap peers only
         if self.config.enable_mdns {
             info!("🔎 [Mock] Discovering local peers via mDNS...");
             // Add some mock local peers
@@ -206,11 +492,9 @@ impl P2PNode {
                     node_id: peer_id.clone(),
                     address: format!("192.168.1.{}", 100 + i),
                     node_mode: NodeMode::FullNode,
-                    last_seen: std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs(),
+                    last_seen: unix_now(),
                     chain_height: 0,
+                    misbehavior_score: 0,
                 };
                 self.peers.insert(peer_id, peer_info);
             }
@@ -221,6 +505,231 @@ impl P2PNode {
         Ok(())
     }
 
+    /// Subscribe to a gossip topic (mock)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the topic name is malformed or already subscribed
+    pub fn subscribe(&mut self, topic: &str) -> Result<()> {
+        let valid = topic
+            .split('/')
+            .all(|part| !part.is_empty() && !part.contains(char::is_whitespace));
+        if !valid {
+            return Err(BlockchainError::NetworkError(format!(
+                "Invalid gossip topic '{topic}'"
+            )));
+        }
+
+        if !self.subscribed_topics.insert(topic.to_string()) {
+            return Err(BlockchainError::NetworkError(format!(
+                "Already subscribed to '{topic}'"
+            )));
+        }
+
+        info!("📡 [Mock] Subscribed to topic {}", topic);
+        Ok(())
+    }
+
+    /// Publish a message on a gossip topic (mock)
+    ///
+    /// `topic` is the short name (e.g. `"blocks"`); it is namespaced with the
+    /// network id like the subscriptions made in `start`. Published messages
+    /// are kept in an outbox until taken with `take_published`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if node is not started
+    pub fn broadcast_message(&mut self, topic: &str, message: NetworkMessage) -> Result<()> {
+        if !self.is_running {
+            return Err(BlockchainError::NetworkError(
+                "Node not started".to_string(),
+            ));
+        }
+
+        let topic = format!("{}/{}", self.config.network_id, topic);
+        for peer_id in self.peers.keys() {
+            info!("  → Sent to peer {} on {}", peer_id, topic);
+        }
+        self.published.push((topic, message));
+
+        Ok(())
+    }
+
+    /// Messages published since the last call, oldest first
+    pub fn take_published(&mut self) -> Vec<(String, NetworkMessage)> {
+        std::mem::take(&mut self.published)
+    }
+
+    /// Lower a peer's score, banning and disconnecting it once the score
+    /// reaches `BAN_SCORE_THRESHOLD`
+    fn penalize_peer(&mut self, peer_id: &str, penalty: u32, reason: &str) {
+        let score = self.peer_score.entry(peer_id.to_string()).or_insert(0);
+        *score = score.saturating_sub(i32::try_from(penalty).unwrap_or(i32::MAX));
+        let score = *score;
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.misbehavior_score = peer.misbehavior_score.saturating_add(penalty);
+        }
+        warn!(
+            "⚠️ Peer {} penalized for {} (score {})",
+            peer_id, reason, score
+        );
+
+        if score <= BAN_SCORE_THRESHOLD && self.banned_peers.insert(peer_id.to_string()) {
+            if let Some(peer) = self.peers.remove(peer_id) {
+                self.address_book.record_failure(&peer.address);
+            }
+            self.time_offsets.remove(peer_id);
+            self.gossip_buckets.remove(peer_id);
+            warn!("🚫 Banned peer {} (score {})", peer_id, score);
+        }
+    }
+
+    fn ensure_not_banned(&self, peer_id: &str) -> Result<()> {
+        if self.is_banned(peer_id) {
+            return Err(BlockchainError::NetworkError(format!(
+                "Peer {peer_id} is banned"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Current score of a peer; peers without penalties score zero
+    #[must_use]
+    pub fn peer_score(&self, peer_id: &str) -> i32 {
+        self.peer_score.get(peer_id).copied().unwrap_or(0)
+    }
+
+    /// Check whether a peer has been banned for misbehavior
+    #[must_use]
+    pub fn is_banned(&self, peer_id: &str) -> bool {
+        self.banned_peers.contains(peer_id)
+    }
+
+    /// Check if node is currently running
+    #[must_use]
+    pub const fn is_running(&self) -> bool {
+        self.is_running
+    }
+}
+
+/// Consensus engines that act on slashing evidence gossiped between nodes
+pub trait SlashingEngine: ConsensusEngine {
+    /// Queue verified `evidence` to punish its validator at a point every
+    /// node agrees on, crediting the bounty to `reporter`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the engine rejects the evidence
+    fn queue_slashing_evidence(
+        &mut self,
+        evidence: SlashingEvidence,
+        reporter: Hash256,
+    ) -> Result<()>;
+}
+
+/// Proof of work has no validators to punish; Bond nodes only relay evidence
+impl SlashingEngine for Blockchain {
+    fn queue_slashing_evidence(
+        &mut self,
+        _evidence: SlashingEvidence,
+        _reporter: Hash256,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Evidence is applied at the end of the epoch, as blocks connect
+impl SlashingEngine for AevumChain {
+    fn queue_slashing_evidence(
+        &mut self,
+        evidence: SlashingEvidence,
+        reporter: Hash256,
+    ) -> Result<()> {
+        Self::queue_slashing_evidence(self, evidence, reporter)
+    }
+}
+
+impl<E: SlashingEngine> P2PNode<E> {
+    /// Broadcast validator slashing evidence to all peers (mock)
+    ///
+    /// Evidence is verified before relaying so forged proofs never
+    /// propagate through this node.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if node is not started or the evidence is invalid
+    pub fn broadcast_slashing_evidence(&mut self, evidence: &SlashingEvidence) -> Result<()> {
+        evidence.verify()?;
+        self.seen_evidence
+            .insert((evidence.validator, evidence.height));
+
+        info!(
+            "📣 [Mock] Broadcasting slashing evidence for validator {} at height {} to {} peers",
+            evidence.validator,
+            evidence.height,
+            self.peers.len()
+        );
+
+        self.broadcast_message(
+            "slashing",
+            NetworkMessage::SlashingEvidence(evidence.clone()),
+        )
+    }
+
+    /// Handle slashing evidence gossiped by a peer
+    ///
+    /// Forged evidence penalizes the peer. Valid evidence is queued by the
+    /// engine, with the bounty going to `P2PConfig::slashing_reporter`, and
+    /// relayed the first time this node sees it. The engine applies queued
+    /// evidence at the epoch boundary, so every node's state changes at the
+    /// same height no matter when the message arrived.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the peer is banned, the evidence is forged or the
+    /// engine rejects it
+    pub fn handle_slashing_evidence(
+        &mut self,
+        peer_id: &str,
+        evidence: SlashingEvidence,
+    ) -> Result<()> {
+        self.ensure_not_banned(peer_id)?;
+        if let Err(e) = evidence.verify() {
+            self.penalize_peer(
+                peer_id,
+                INVALID_EVIDENCE_PENALTY,
+                "forged slashing evidence",
+            );
+            return Err(e);
+        }
+
+        let key = (evidence.validator, evidence.height);
+        if self.seen_evidence.contains(&key) {
+            return Ok(());
+        }
+        if let Some(engine) = self.blockchain.as_mut() {
+            engine.queue_slashing_evidence(evidence.clone(), self.config.slashing_reporter)?;
+        }
+        self.seen_evidence.insert(key);
+
+        info!(
+            "⚔️ Queued slashing evidence for validator {} at height {} from peer {}",
+            evidence.validator, evidence.height, peer_id
+        );
+        self.broadcast_message("slashing", NetworkMessage::SlashingEvidence(evidence))
+    }
+}
+
+impl P2PNode {
+    /// Create a new mock P2P node
+    ///
+    /// # Errors
+    ///
+    /// Can return error during node setup
+    pub fn new(config: P2PConfig) -> Result<Self> {
+        Ok(Self::detached(config))
+    }
+
     /// Run the P2P node - in mock version, just simulate some network activity
     ///
     /// # Errors
@@ -253,6 +762,11 @@ impl P2PNode {
         info!("💸 [Mock] Received transaction broadcast");
         info!("✅ [Mock] Transaction added to mempool");
 
+        let expired = self.mempool.expire(unix_now());
+        if expired > 0 {
+            info!("🧹 Expired {} stale transactions from mempool", expired);
+        }
+
         info!("⏳ [Mock] P2P node would run here. Simulating completion...");
 
         info!("👋 [Mock] P2P node stopping due to simulation end");
@@ -266,13 +780,7 @@ impl P2PNode {
     /// # Errors
     ///
     /// Returns error if node is not started or broadcast fails
-    pub fn broadcast_transaction(&self, tx: &Transaction) -> Result<()> {
-        if !self.is_running {
-            return Err(BlockchainError::NetworkError(
-                "Node not started".to_string(),
-            ));
-        }
-
+    pub fn broadcast_transaction(&mut self, tx: &Transaction) -> Result<()> {
         let tx_hash = tx
             .hash()
             .map_or_else(|_| "error".to_string(), |h| format!("{h:?}"));
@@ -283,11 +791,7 @@ impl P2PNode {
             self.peers.len()
         );
 
-        for peer_id in self.peers.keys() {
-            info!("  → Sent to peer {}", peer_id);
-        }
-
-        Ok(())
+        self.broadcast_message("transactions", NetworkMessage::NewTransaction(tx.clone()))
     }
 
     /// Broadcast a block to all peers (mock)
@@ -295,13 +799,7 @@ impl P2PNode {
     /// # Errors
     ///
     /// Returns error if node is not started or broadcast fails
-    pub fn broadcast_block(&self, block: &Block) -> Result<()> {
-        if !self.is_running {
-            return Err(BlockchainError::NetworkError(
-                "Node not started".to_string(),
-            ));
-        }
-
+    pub fn broadcast_block(&mut self, block: &Block) -> Result<()> {
         let block_hash = block
             .hash()
             .map_or_else(|_| "error".to_string(), |h| format!("{h:?}"));
@@ -312,74 +810,983 @@ impl P2PNode {
             self.peers.len()
         );
 
-        for peer_id in self.peers.keys() {
-            info!("  → Sent to peer {}", peer_id);
-        }
-
-        Ok(())
-    }
-
-    /// Get the local peer ID
-    #[must_use]
-    pub fn node_id(&self) -> String {
-        self.local_peer_id.clone()
-    }
-
-    /// Set blockchain reference
-    pub fn set_blockchain(&mut self, blockchain: Blockchain) {
-        info!("🔗 Setting blockchain reference for P2P node");
-        self.blockchain = Some(blockchain);
+        self.broadcast_message("blocks", NetworkMessage::NewBlock(block.clone()))
     }
 
-    /// Get connected peers count
-    #[must_use]
-    pub fn connected_peers(&self) -> usize {
-        self.peers.len()
-    }
-
-    /// Get network status
-    #[must_use]
-    pub fn network_status(&self) -> NetworkStatus {
-        let chain_height = self.blockchain.as_ref().map_or(0, |bc| bc.stats().height);
+    /// Announce a block to all peers as a `CompactBlock` (mock)
+    ///
+    /// Peers rebuild it from their mempools and fetch only the transactions
+    /// they lack with `GetBlockTxn`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if node is not started or the block cannot be hashed
+    pub fn broadcast_compact_block(&mut self, block: &Block) -> Result<()> {
+        let compact = CompactBlock::from_block(block)?;
+        info!(
+            "📣 [Mock] Broadcasting compact block {} ({} short ids) to {} peers",
+            compact.block_hash()?,
+            compact.short_ids.len(),
+            self.peers.len()
+        );
 
-        NetworkStatus {
-            node_id: self.local_peer_id.clone(),
-            peer_count: self.peers.len(),
-            peers: self.peers.keys().cloned().collect(),
-            listen_addresses: vec![format!("{}:{}", self.config.listen_addr, self.config.port)],
-            node_mode: self.config.node_mode.clone(),
-            chain_height,
-        }
+        self.broadcast_message("blocks", NetworkMessage::CompactBlock(compact))
     }
 
-    /// Shutdown the node
+    /// Handle a block announced by a peer
+    ///
+    /// Blocks claiming a height beyond the chain's acceptance window are
+    /// dropped before validation and the sending peer is penalized, as is a
+    /// peer whose block fails validation. If the
+    /// block makes a side branch the active chain, transactions from the
+    /// disconnected blocks go back into the mempool.
     ///
     /// # Errors
     ///
-    /// Returns error if shutdown process fails
-    pub fn shutdown(&mut self) -> Result<()> {
-        if !self.is_running {
-            return Ok(());
-        }
+    /// Returns error if the peer is banned, no blockchain is attached or the
+    /// block is rejected
+    pub fn handle_new_block(&mut self, peer_id: &str, block: Block) -> Result<()> {
+        self.ensure_not_banned(peer_id)?;
+        let blockchain = self
+            .blockchain
+            .as_mut()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?;
 
-        info!("🛑 Shutting down P2P node {}", self.local_peer_id);
+        if let Err(e) = blockchain.check_height_window(block.height()?) {
+            self.penalize_peer(peer_id, FUTURE_BLOCK_PENALTY, "block outside height window");
+            return Err(e);
+        }
 
-        // Simulate sending goodbye messages to peers
-        for peer_id in self.peers.keys() {
-            info!("👋 [Mock] Sending goodbye to peer {}", peer_id);
+        // Re-announcing a block we already have is not misbehavior
+        let hash = block.hash()?;
+        let height = block.height()?;
+        let already_known = blockchain.get_block_by_hash(&hash).is_some();
+        if let Err(e) = blockchain.add_block(block.clone()) {
+            if !already_known {
+                self.penalize_peer(peer_id, INVALID_BLOCK_PENALTY, "invalid block");
+            }
+            return Err(e);
         }
+        info!("✅ Block from peer {} added to chain", peer_id);
+        // Blocks stored on a side branch do not move the tip
+        let new_tip = !already_known && blockchain.get_latest_block().hash()? == hash;
 
-        self.peers.clear();
-        self.is_running = false;
+        if let Some(reorg) = blockchain.take_last_reorg() {
+            let reinjected = self.mempool.reinject_disconnected(
+                &reorg.disconnected,
+                &reorg.connected,
+                blockchain.utxo_set(),
+                blockchain.height() + 1,
+                unix_now(),
+            );
+            info!(
+                "🔀 Reorganized at height {}: {} blocks disconnected, {} transactions returned to mempool",
+                reorg.fork_height,
+                reorg.disconnected.len(),
+                reinjected.len()
+            );
+            self.emit(BlockchainEvent::ChainReorg {
+                fork_height: reorg.fork_height,
+                disconnected: reorg.disconnected.len(),
+                connected: reorg.connected.len(),
+            });
+        }
 
-        info!("👋 P2P node shutdown complete");
+        if new_tip {
+            self.mempool.remove_transactions(&block.transactions);
+            self.emit(BlockchainEvent::BlockAdded { height, hash });
+        }
+        self.promote_orphans();
         Ok(())
     }
 
-    /// Check if node is currently running
-    #[must_use]
-    pub const fn is_running(&self) -> bool {
-        self.is_running
+    /// Re-admit orphan transactions whose missing outputs are now in the
+    /// UTXO set
+    fn promote_orphans(&mut self) {
+        let Some(blockchain) = self.blockchain.as_ref() else {
+            return;
+        };
+        for tx in self.mempool.take_ready_orphans(blockchain.utxo_set()) {
+            let _ = self.admit_transaction(tx);
+        }
+    }
+
+    /// Rebuild a compact block announced by a peer from the mempool
+    ///
+    /// A block whose transactions are all known is handled like a full
+    /// `NewBlock`. Otherwise it is held until the peer answers the returned
+    /// `GetBlockTxn`, which lists only the missing transactions.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the peer is banned, the compact block is malformed
+    /// or the rebuilt block is rejected
+    pub fn handle_compact_block(
+        &mut self,
+        peer_id: &str,
+        compact: CompactBlock,
+    ) -> Result<Option<NetworkMessage>> {
+        self.ensure_not_banned(peer_id)?;
+        let block_hash = compact.block_hash()?;
+        if self
+            .blockchain
+            .as_ref()
+            .is_some_and(|blockchain| blockchain.get_block_by_hash(&block_hash).is_some())
+        {
+            return Ok(None);
+        }
+
+        let partial = compact.reconstruct(&self.mempool)?;
+        if partial.is_complete() {
+            self.handle_new_block(peer_id, partial.into_block()?)?;
+            return Ok(None);
+        }
+
+        let indexes = partial.missing_indexes();
+        info!(
+            "🧱 Compact block {} needs {} of {} transactions from {}",
+            block_hash,
+            indexes.len(),
+            compact.tx_count(),
+            peer_id
+        );
+        self.pending_compact_blocks.insert(block_hash, partial);
+        Ok(Some(NetworkMessage::GetBlockTxn {
+            block_hash,
+            indexes,
+        }))
+    }
+
+    /// Serve the transactions a peer is missing from one of our blocks
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no blockchain is attached, the block is unknown or
+    /// an index is out of range
+    pub fn handle_get_block_txn(
+        &self,
+        block_hash: Hash256,
+        indexes: &[u32],
+    ) -> Result<NetworkMessage> {
+        let block = self
+            .blockchain
+            .as_ref()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?
+            .get_block_by_hash(&block_hash)
+            .ok_or_else(|| BlockchainError::NetworkError(format!("Unknown block {block_hash}")))?;
+        let transactions = indexes
+            .iter()
+            .map(|&index| {
+                block
+                    .transactions
+                    .get(index as usize)
+                    .cloned()
+                    .ok_or_else(|| {
+                        BlockchainError::NetworkError(format!(
+                            "Block {block_hash} has no transaction {index}"
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(NetworkMessage::BlockTxn {
+            block_hash,
+            transactions,
+        })
+    }
+
+    /// Complete a pending compact block with the transactions from `BlockTxn`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the peer is banned, no compact block is pending
+    /// for `block_hash`, the transactions do not complete it or the
+    /// rebuilt block is rejected
+    pub fn handle_block_txn(
+        &mut self,
+        peer_id: &str,
+        block_hash: Hash256,
+        transactions: Vec<Transaction>,
+    ) -> Result<()> {
+        self.ensure_not_banned(peer_id)?;
+        let mut partial = self
+            .pending_compact_blocks
+            .remove(&block_hash)
+            .ok_or_else(|| {
+                BlockchainError::NetworkError(format!("No pending compact block {block_hash}"))
+            })?;
+        partial.fill(transactions)?;
+        self.handle_new_block(peer_id, partial.into_block()?)
+    }
+
+    /// Add a block mined by this node to the chain and announce it
+    ///
+    /// Its transactions leave the mempool and a `BlockAdded` event is
+    /// published before the block is broadcast.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no blockchain is attached, the block is rejected or
+    /// the node is not started
+    pub fn submit_mined_block(&mut self, block: Block) -> Result<()> {
+        let blockchain = self
+            .blockchain
+            .as_mut()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?;
+        let hash = block.hash()?;
+        let height = block.height()?;
+        blockchain.add_block(block.clone())?;
+        info!("⛏️ Mined block {} at height {}", hash, height);
+
+        self.mempool.remove_transactions(&block.transactions);
+        self.emit(BlockchainEvent::BlockAdded { height, hash });
+        self.broadcast_block(&block)
+    }
+
+    /// Receive every `BlockchainEvent` published from now on
+    ///
+    /// A receiver that falls more than `EVENT_CHANNEL_CAPACITY` events behind
+    /// skips the oldest ones and gets `RecvError::Lagged`.
+    #[must_use]
+    pub fn subscribe_events(&self) -> broadcast::Receiver<BlockchainEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publish an event; having no subscribers is not an error
+    fn emit(&self, event: BlockchainEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Validate a gossiped transaction and add it to the mempool
+    ///
+    /// Duplicates of a transaction already in the pool are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no blockchain is attached or the transaction is
+    /// invalid against the current UTXO set
+    pub fn process_received_transaction(&mut self, tx: Transaction) -> Result<Hash256> {
+        self.admit_transaction(tx)?.map_err(Into::into)
+    }
+
+    /// Mempool admission, keeping the rejection reason apart from node errors
+    fn admit_transaction(
+        &mut self,
+        tx: Transaction,
+    ) -> Result<std::result::Result<Hash256, MempoolError>> {
+        let blockchain = self
+            .blockchain
+            .as_ref()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?;
+
+        let txid = tx.hash()?;
+        if self.mempool.contains(&txid) {
+            return Ok(Ok(txid));
+        }
+
+        // Transactions spending outputs we have not seen yet are held as
+        // orphans until their parents are confirmed
+        let accepted = match blockchain.validate_transaction(&tx) {
+            Ok(()) => self.mempool.add_transaction(
+                tx,
+                blockchain.utxo_set(),
+                blockchain.height() + 1,
+                unix_now(),
+            ),
+            Err(BlockchainError::UtxoNotFound) => {
+                if self
+                    .mempool
+                    .add_orphan(tx, blockchain.utxo_set(), unix_now())
+                {
+                    info!("🧩 Transaction {} held as orphan", txid);
+                }
+                Err(MempoolError::MissingInputs)
+            }
+            Err(e) => Err(e.into()),
+        };
+        if let Err(reason) = accepted {
+            self.record_rejection(txid, reason.clone());
+            return Ok(Err(reason));
+        }
+
+        info!("✅ Transaction {} added to mempool", txid);
+        self.emit(BlockchainEvent::TransactionAccepted { txid });
+        Ok(Ok(txid))
+    }
+
+    /// Validate a transaction gossiped by `peer_id`
+    ///
+    /// Like `process_received_transaction`, but a transaction that is
+    /// invalid (rather than merely conflicting or spending unknown outputs)
+    /// counts against the sending peer.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the peer is banned, no blockchain is attached or the
+    /// transaction is rejected
+    pub fn handle_gossip_transaction(&mut self, peer_id: &str, tx: Transaction) -> Result<Hash256> {
+        self.ensure_not_banned(peer_id)?;
+
+        let admitted = self.admit_transaction(tx)?;
+        if let Err(MempoolError::Invalid(_) | MempoolError::NegativeFee | MempoolError::Coinbase) =
+            &admitted
+        {
+            self.penalize_peer(peer_id, INVALID_TX_PENALTY, "invalid transaction");
+        }
+        admitted.map_err(Into::into)
+    }
+
+    /// Decode and dispatch a raw gossip message from `peer_id`
+    ///
+    /// Messages that fail to decode penalize the sender. Message types the
+    /// node does not act on are accepted and ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the peer is banned or rate-limited, the message is
+    /// malformed or handling the decoded message fails
+    pub fn handle_gossip(&mut self, peer_id: &str, data: &[u8]) -> Result<()> {
+        self.handle_gossip_at(peer_id, data, Instant::now())
+    }
+
+    /// `handle_gossip` with an explicit clock for the rate limiter
+    ///
+    /// Messages beyond the peer's `gossip_rate_per_sec`/`gossip_burst`
+    /// allowance are dropped before being decoded.
+    ///
+    /// # Errors
+    ///
+    /// Same as `handle_gossip`, plus a `NetworkError` for rate-limited messages
+    pub fn handle_gossip_at(&mut self, peer_id: &str, data: &[u8], now: Instant) -> Result<()> {
+        self.ensure_not_banned(peer_id)?;
+
+        let (rate, burst) = (self.config.gossip_rate_per_sec, self.config.gossip_burst);
+        if rate > 0 {
+            let allowed = self
+                .gossip_buckets
+                .entry(peer_id.to_string())
+                .or_insert_with(|| TokenBucket::full(burst, now))
+                .try_take(rate, burst, now);
+            if !allowed {
+                self.penalize_peer(peer_id, RATE_LIMIT_PENALTY, "gossip rate exceeded");
+                return Err(BlockchainError::NetworkError(format!(
+                    "Gossip from {peer_id} exceeds {rate} messages per second"
+                )));
+            }
+        }
+
+        let message: NetworkMessage = match serde_json::from_slice(data) {
+            Ok(message) => message,
+            Err(e) => {
+                self.penalize_peer(peer_id, MALFORMED_MESSAGE_PENALTY, "malformed gossip");
+                return Err(BlockchainError::SerializationError(e.to_string()));
+            }
+        };
+
+        match message {
+            NetworkMessage::NewBlock(block) | NetworkMessage::Block(block) => {
+                self.handle_new_block(peer_id, block)
+            }
+            NetworkMessage::NewTransaction(tx) => {
+                self.handle_gossip_transaction(peer_id, tx).map(drop)
+            }
+            NetworkMessage::GetBlocks { from_height } => {
+                let response = self.handle_block_request(from_height)?;
+                self.broadcast_message("blocks", response)
+            }
+            NetworkMessage::GetBlock(height) => {
+                let block = self
+                    .blockchain
+                    .as_ref()
+                    .and_then(|blockchain| blockchain.get_block_by_height(height))
+                    .cloned();
+                match block {
+                    Some(block) => self.broadcast_message("blocks", NetworkMessage::Block(block)),
+                    None => Ok(()),
+                }
+            }
+            NetworkMessage::BlockResponse(blocks) => {
+                self.process_sync_response(peer_id, blocks).map(drop)
+            }
+            NetworkMessage::CompactBlock(compact) => {
+                match self.handle_compact_block(peer_id, compact)? {
+                    Some(request) => self.broadcast_message("blocks", request),
+                    None => Ok(()),
+                }
+            }
+            NetworkMessage::GetBlockTxn {
+                block_hash,
+                indexes,
+            } => {
+                let response = self.handle_get_block_txn(block_hash, &indexes)?;
+                self.broadcast_message("blocks", response)
+            }
+            NetworkMessage::BlockTxn {
+                block_hash,
+                transactions,
+            } => self.handle_block_txn(peer_id, block_hash, transactions),
+            NetworkMessage::GetHeaders(locator) => {
+                let response = self.handle_get_headers(&locator)?;
+                self.broadcast_message("blocks", response)
+            }
+            NetworkMessage::Headers(headers) => match self.handle_headers(peer_id, headers)? {
+                Some(request) => self.broadcast_message("blocks", request),
+                None => Ok(()),
+            },
+            NetworkMessage::GetBlockData(hashes) => {
+                let response = self.handle_get_block_data(&hashes)?;
+                self.broadcast_message("blocks", response)
+            }
+            NetworkMessage::BlockData(blocks) => self.handle_block_data(peer_id, blocks).map(drop),
+            NetworkMessage::SlashingEvidence(evidence) => {
+                self.handle_slashing_evidence(peer_id, evidence).map(drop)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn record_rejection(&mut self, txid: Hash256, reason: MempoolError) {
+        warn!(%txid, ?reason, "Transaction rejected");
+        if self.recent_rejections.len() == MAX_RECENT_REJECTIONS {
+            self.recent_rejections.pop_front();
+        }
+        self.recent_rejections.push_back(TxRejection {
+            txid,
+            reason,
+            timestamp: unix_now(),
+        });
+    }
+
+    /// Most recent transaction rejections, oldest first
+    #[must_use]
+    pub const fn recent_rejections(&self) -> &VecDeque<TxRejection> {
+        &self.recent_rejections
+    }
+
+    /// Pending transactions received from the network
+    #[must_use]
+    pub const fn mempool(&self) -> &BondMempool {
+        &self.mempool
+    }
+
+    /// Serve a batch of blocks to a syncing peer
+    ///
+    /// Returns consecutive blocks from `from_height` towards the tip, at most
+    /// `MAX_BLOCKS_PER_RESPONSE` of them and never more than the chain's
+    /// `max_block_size` in total. `handle_gossip` publishes the response to a
+    /// gossiped `GetBlocks` on the block topic.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no blockchain is attached
+    pub fn handle_block_request(&self, from_height: u64) -> Result<NetworkMessage> {
+        let blockchain = self
+            .blockchain
+            .as_ref()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?;
+
+        let size_budget = blockchain.network_params().max_block_size;
+        let last_height = blockchain
+            .height()
+            .min(from_height.saturating_add(MAX_BLOCKS_PER_RESPONSE - 1));
+
+        let mut blocks = Vec::new();
+        let mut response_size = 0;
+        for height in from_height..=last_height {
+            let Some(block) = blockchain.get_block_by_height(height) else {
+                break;
+            };
+            response_size += block.size();
+            if response_size > size_budget {
+                break;
+            }
+            blocks.push(block.clone());
+        }
+
+        info!(
+            "📤 [Mock] Publishing {} blocks from height {} on {}/blocks",
+            blocks.len(),
+            from_height,
+            self.config.network_id
+        );
+
+        Ok(NetworkMessage::BlockResponse(blocks))
+    }
+
+    /// Apply a batch of blocks received in answer to `GetBlocks`
+    ///
+    /// Peers may deliver the batch in any order, so blocks are sorted by
+    /// height and connected one at a time. A block whose parent is not the
+    /// current tip is held back and retried after the rest of the batch
+    /// extends the chain; blocks that still do not connect are logged and
+    /// skipped. Returns the number of blocks added to the chain.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no blockchain is attached
+    pub fn process_sync_response(&mut self, peer_id: &str, blocks: Vec<Block>) -> Result<usize> {
+        let mut pending: Vec<Block> = blocks;
+        pending.sort_by_key(|block| block.height().unwrap_or(u64::MAX));
+
+        let mut connected = 0;
+        loop {
+            let tip = self
+                .blockchain
+                .as_ref()
+                .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?
+                .get_latest_block()
+                .hash()?;
+            let Some(next) = pending
+                .iter()
+                .position(|block| block.header.previous_hash == tip)
+            else {
+                break;
+            };
+
+            let block = pending.remove(next);
+            match self.handle_new_block(peer_id, block) {
+                Ok(()) => connected += 1,
+                Err(e) => warn!("⚠️ Skipping synced block from peer {}: {}", peer_id, e),
+            }
+        }
+
+        for block in &pending {
+            warn!(
+                "⚠️ Skipping unconnectable block at height {:?} from peer {}",
+                block.height().ok(),
+                peer_id
+            );
+        }
+        info!(
+            "📥 Synced {} blocks from peer {} ({} skipped)",
+            connected,
+            peer_id,
+            pending.len()
+        );
+
+        Ok(connected)
+    }
+
+    /// `GetHeaders` request with the locator of our active chain, starting a
+    /// headers-first sync
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no blockchain is attached
+    pub fn headers_request(&self) -> Result<NetworkMessage> {
+        let blockchain = self
+            .blockchain
+            .as_ref()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?;
+        Ok(NetworkMessage::GetHeaders(blockchain.block_locator()))
+    }
+
+    /// Serve the headers following the common ancestor with a peer's locator
+    ///
+    /// Returns at most `MAX_HEADERS_PER_RESPONSE` headers; the peer asks
+    /// again with a new locator to continue.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no blockchain is attached
+    pub fn handle_get_headers(&self, locator: &BlockLocator) -> Result<NetworkMessage> {
+        let blockchain = self
+            .blockchain
+            .as_ref()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?;
+        let headers = blockchain.headers_after(locator, MAX_HEADERS_PER_RESPONSE);
+        info!(
+            "📤 [Mock] Publishing {} headers on {}/blocks",
+            headers.len(),
+            self.config.network_id
+        );
+        Ok(NetworkMessage::Headers(headers))
+    }
+
+    /// Validate headers from a peer and request the bodies we lack
+    ///
+    /// Headers that do not connect to a known block, are not continuous or
+    /// miss their target penalize the peer like an invalid block. Returns
+    /// the `GetBlockData` request for the unknown blocks, or `None` if we
+    /// already have them all.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the peer is banned, no blockchain is attached or
+    /// the headers are invalid
+    pub fn handle_headers(
+        &mut self,
+        peer_id: &str,
+        headers: Vec<BlockHeader>,
+    ) -> Result<Option<NetworkMessage>> {
+        self.ensure_not_banned(peer_id)?;
+        let blockchain = self
+            .blockchain
+            .as_ref()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?;
+        if let Err(e) = blockchain.validate_headers(&headers) {
+            self.penalize_peer(peer_id, INVALID_BLOCK_PENALTY, "invalid headers");
+            return Err(e);
+        }
+
+        let mut hashes = Vec::new();
+        let mut wanted = Vec::new();
+        for header in headers {
+            let hash = header.hash()?;
+            if blockchain.get_block_by_hash(&hash).is_none() {
+                hashes.push(hash);
+                wanted.push(header);
+            }
+        }
+        if hashes.is_empty() {
+            return Ok(None);
+        }
+
+        info!(
+            "📋 Requesting {} block bodies from peer {}",
+            hashes.len(),
+            peer_id
+        );
+        self.pending_headers = wanted;
+        Ok(Some(NetworkMessage::GetBlockData(hashes)))
+    }
+
+    /// Serve the blocks a peer requested by hash
+    ///
+    /// Unknown hashes are skipped. Like `handle_block_request`, the response
+    /// holds at most `MAX_BLOCKS_PER_RESPONSE` blocks and never more than
+    /// the chain's `max_block_size` in total.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no blockchain is attached
+    pub fn handle_get_block_data(&self, hashes: &[Hash256]) -> Result<NetworkMessage> {
+        let blockchain = self
+            .blockchain
+            .as_ref()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?;
+
+        let size_budget = blockchain.network_params().max_block_size;
+        let mut blocks = Vec::new();
+        let mut response_size = 0;
+        for hash in hashes.iter().take(MAX_BLOCKS_PER_RESPONSE as usize) {
+            let Some(block) = blockchain.get_block_by_hash(hash) else {
+                continue;
+            };
+            response_size += block.size();
+            if response_size > size_budget {
+                break;
+            }
+            blocks.push(block.clone());
+        }
+        Ok(NetworkMessage::BlockData(blocks))
+    }
+
+    /// Apply block bodies received in answer to `GetBlockData`
+    ///
+    /// Blocks are connected in the order of the pending headers, so a
+    /// branch diverging from our tip is stored and reorganized onto like
+    /// any other block. Blocks matching no pending header are ignored;
+    /// headers whose body did not arrive stay pending. Returns the number
+    /// of blocks accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the peer is banned
+    pub fn handle_block_data(&mut self, peer_id: &str, blocks: Vec<Block>) -> Result<usize> {
+        self.ensure_not_banned(peer_id)?;
+        let mut received: HashMap<Hash256, Block> = HashMap::new();
+        for block in blocks {
+            received.insert(block.hash()?, block);
+        }
+
+        let mut accepted = 0;
+        let mut still_pending = Vec::new();
+        for header in std::mem::take(&mut self.pending_headers) {
+            let Some(block) = received.remove(&header.hash()?) else {
+                still_pending.push(header);
+                continue;
+            };
+            match self.handle_new_block(peer_id, block) {
+                Ok(()) => accepted += 1,
+                Err(e) => warn!("⚠️ Skipping block body from peer {}: {}", peer_id, e),
+            }
+        }
+        self.pending_headers = still_pending;
+
+        info!(
+            "📥 Accepted {} block bodies from peer {} ({} still pending)",
+            accepted,
+            peer_id,
+            self.pending_headers.len()
+        );
+        Ok(accepted)
+    }
+
+    /// Build the handshake announced to new peers
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no blockchain is attached
+    pub fn handshake(&self) -> Result<Handshake> {
+        let blockchain = self
+            .blockchain
+            .as_ref()
+            .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()))?;
+
+        Ok(Handshake {
+            network_id: self.config.network_id.clone(),
+            genesis_hash: blockchain.genesis_hash()?,
+            chain_height: blockchain.height(),
+            timestamp: unix_now(),
+        })
+    }
+
+    /// Accept a connecting peer after checking its handshake
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the peer is banned or on a different network or genesis
+    pub fn accept_peer(&mut self, mut peer: PeerInfo, handshake: &Handshake) -> Result<()> {
+        self.ensure_not_banned(&peer.node_id)?;
+        let local = self.handshake()?;
+
+        if handshake.network_id != local.network_id {
+            self.address_book.record_failure(&peer.address);
+            return Err(BlockchainError::NetworkError(format!(
+                "Peer {} is on network {}",
+                peer.node_id, handshake.network_id
+            )));
+        }
+
+        if handshake.genesis_hash != local.genesis_hash {
+            self.address_book.record_failure(&peer.address);
+            warn!(
+                "⚠️ Refusing peer {}: genesis {} does not match ours",
+                peer.node_id, handshake.genesis_hash
+            );
+            return Err(BlockchainError::NetworkError(format!(
+                "Peer {} has a different genesis block",
+                peer.node_id
+            )));
+        }
+
+        peer.chain_height = handshake.chain_height;
+        self.address_book.record_connect(&peer.address, unix_now());
+        self.record_time_sample(&peer.node_id, handshake.timestamp);
+        info!("🤝 Accepted peer {}", peer.node_id);
+        self.peers.insert(peer.node_id.clone(), peer);
+        Ok(())
+    }
+
+    /// Record a peer's clock and update the chain's network time
+    fn record_time_sample(&mut self, peer_id: &str, peer_time: u64) {
+        let offset = i64::try_from(peer_time)
+            .unwrap_or(i64::MAX)
+            .saturating_sub(i64::try_from(unix_now()).unwrap_or(i64::MAX));
+        self.time_offsets.insert(peer_id.to_string(), offset);
+
+        let network_offset = self.network_time_offset();
+        if let Some(blockchain) = self.blockchain.as_mut() {
+            blockchain.set_time_offset(network_offset);
+        }
+    }
+
+    /// Median clock offset of connected peers, in seconds
+    ///
+    /// Zero until `MIN_TIME_SAMPLES` peers have reported their time, or if
+    /// the median is more than `MAX_TIME_ADJUSTMENT_SECS` away from the
+    /// local clock, in which case the local clock is likely wrong and is
+    /// used as is.
+    #[must_use]
+    pub fn network_time_offset(&self) -> i64 {
+        if self.time_offsets.len() < MIN_TIME_SAMPLES {
+            return 0;
+        }
+
+        let mut offsets: Vec<i64> = self.time_offsets.values().copied().collect();
+        offsets.sort_unstable();
+        let median = offsets[offsets.len() / 2];
+        if median.abs() > MAX_TIME_ADJUSTMENT_SECS {
+            warn!(
+                "⚠️ Peers report a clock offset of {}s; check the local clock",
+                median
+            );
+            return 0;
+        }
+        median
+    }
+
+    /// Record a ping round trip to a connected peer
+    pub fn record_peer_latency(&mut self, peer_id: &str, latency: Duration) {
+        if let Some(peer) = self.peers.get(peer_id) {
+            let latency_ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+            self.address_book.record_latency(&peer.address, latency_ms);
+        }
+    }
+
+    /// Known peer addresses and their connection history
+    #[must_use]
+    pub const fn address_book(&self) -> &AddressBook {
+        &self.address_book
+    }
+
+    /// Get the local peer ID
+    #[must_use]
+    pub fn node_id(&self) -> String {
+        self.local_peer_id.clone()
+    }
+
+    /// Set blockchain reference
+    ///
+    /// The mempool adopts the chain's dust threshold, so it admits the same
+    /// outputs as `Blockchain::validate_transaction`.
+    pub fn set_blockchain(&mut self, blockchain: Blockchain) {
+        info!("🔗 Setting blockchain reference for P2P node");
+        self.mempool = std::mem::take(&mut self.mempool)
+            .with_dust_threshold(blockchain.network_params().dust_threshold);
+        self.blockchain = Some(blockchain);
+    }
+
+    /// Attached blockchain, if any
+    #[must_use]
+    pub const fn blockchain(&self) -> Option<&Blockchain> {
+        self.blockchain.as_ref()
+    }
+
+    /// Get connected peers count
+    #[must_use]
+    pub fn connected_peers(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Get network status
+    #[must_use]
+    pub fn network_status(&self) -> NetworkStatus {
+        let chain_height = self.blockchain.as_ref().map_or(0, |bc| bc.stats().height);
+        let mut peer_scores = self.peer_score.clone();
+        for peer_id in self.peers.keys() {
+            peer_scores.entry(peer_id.clone()).or_insert(0);
+        }
+
+        NetworkStatus {
+            node_id: self.local_peer_id.clone(),
+            peer_count: self.peers.len(),
+            peers: self.peers.keys().cloned().collect(),
+            listen_addresses: vec![format!("{}:{}", self.config.listen_addr, self.config.port)],
+            node_mode: self.config.node_mode.clone(),
+            chain_height,
+            peer_scores,
+        }
+    }
+
+    /// Shutdown the node
+    ///
+    /// # Errors
+    ///
+    /// Returns error if shutdown process fails
+    pub fn shutdown(&mut self) -> Result<()> {
+        if !self.is_running {
+            return Ok(());
+        }
+
+        info!("🛑 Shutting down P2P node {}", self.local_peer_id);
+
+        // Simulate sending goodbye messages to peers
+        let now = unix_now();
+        for (peer_id, peer) in &self.peers {
+            info!("👋 [Mock] Sending goodbye to peer {}", peer_id);
+            self.address_book.record_disconnect(&peer.address, now);
+        }
+        if let Some(path) = &self.config.address_book_path
+            && let Err(e) = self.address_book.save(path)
+        {
+            warn!("⚠️ Failed to save address book {:?}: {}", path, e);
+        }
+
+        self.peers.clear();
+        self.time_offsets.clear();
+        self.published.clear();
+        self.subscribed_topics.clear();
+        self.is_running = false;
+
+        info!("👋 P2P node shutdown complete");
+        Ok(())
+    }
+}
+
+/// Mine blocks on top of `node`'s chain until the node shuts down
+///
+/// Each round takes the best-paying mempool transactions that fit in a
+/// block, mines them on the miner's worker threads and submits the result
+/// with `P2PNode::submit_mined_block`. When a block from the network moves
+/// the tip first, the round is cancelled and mining restarts on the new tip.
+/// The node lock is never held while the workers search for a nonce.
+///
+/// # Errors
+///
+/// Returns error if no blockchain is attached to the node
+pub async fn run_mining_loop(node: Arc<Mutex<P2PNode>>, miner: Miner) -> Result<()> {
+    let lock = || node.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut events = lock().subscribe_events();
+
+    loop {
+        let started = {
+            let node = lock();
+            if !node.is_running() {
+                return Ok(());
+            }
+            let blockchain = node.blockchain().ok_or_else(|| {
+                BlockchainError::NetworkError("No blockchain attached".to_string())
+            })?;
+            let transactions = blockchain.select_mempool_transactions(node.mempool());
+            blockchain.start_mining_next_block(&miner, transactions)
+        };
+        let handle = match started {
+            Ok(handle) => handle,
+            Err(e) => {
+                warn!("⚠️ Could not start mining: {}", e);
+                tokio::time::sleep(MINING_POLL_INTERVAL).await;
+                continue;
+            }
+        };
+
+        let finished = loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Ok(BlockchainEvent::BlockAdded { .. } | BlockchainEvent::ChainReorg { .. })
+                    | Err(RecvError::Lagged(_)) => {
+                        info!("🔁 Chain tip changed, restarting mining");
+                        break false;
+                    }
+                    Ok(BlockchainEvent::TransactionAccepted { .. }) => {}
+                    Err(RecvError::Closed) => break false,
+                },
+                () = tokio::time::sleep(MINING_POLL_INTERVAL) => {
+                    if !handle.is_running() {
+                        break true;
+                    }
+                    let node_running = lock().is_running();
+                    if !node_running {
+                        break false;
+                    }
+                }
+            }
+        };
+
+        if !finished {
+            handle.cancel();
+            let _ = handle.join();
+            continue;
+        }
+
+        if let Some(result) = handle.join() {
+            if let Err(e) = lock().submit_mined_block(result.block) {
+                warn!("⚠️ Mined block rejected: {}", e);
+            }
+            // Our own BlockAdded must not cancel the next round
+            events = events.resubscribe();
+        }
     }
 }
 
@@ -391,3 +1798,1094 @@ pub struct MockPeer {
     pub node_mode: NodeMode,
     pub chain_height: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bond_core::{Miner, MinerConfig, NetworkParams};
+
+    fn started_node() -> (P2PNode, String) {
+        let config = P2PConfig {
+            bootstrap_nodes: vec!["10.0.0.1:8333".to_string()],
+            enable_mdns: false,
+            ..Default::default()
+        };
+        let mut node = P2PNode::new(config).unwrap();
+        node.start().unwrap();
+        node.set_blockchain(Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap());
+        (node, "mock-peer-10.0.0.1:8333".to_string())
+    }
+
+    fn node_with_params(params: NetworkParams) -> P2PNode {
+        let mut node = P2PNode::new(P2PConfig {
+            enable_mdns: false,
+            ..Default::default()
+        })
+        .unwrap();
+        node.set_blockchain(Blockchain::new(params, vec![1, 2, 3]).unwrap());
+        node
+    }
+
+    fn peer_info(node: &P2PNode) -> PeerInfo {
+        PeerInfo {
+            node_id: node.node_id(),
+            address: "10.0.0.2:8333".to_string(),
+            node_mode: NodeMode::FullNode,
+            last_seen: 0,
+            chain_height: 0,
+            misbehavior_score: 0,
+        }
+    }
+
+    #[test]
+    fn test_peers_with_different_params_refuse_to_connect() {
+        let mut node = node_with_params(NetworkParams::default());
+        let same = node_with_params(NetworkParams::default());
+        let different = node_with_params(NetworkParams {
+            initial_reward: 1,
+            ..NetworkParams::default()
+        });
+
+        let same_handshake = same.handshake().unwrap();
+        let different_handshake = different.handshake().unwrap();
+        assert_ne!(
+            same_handshake.genesis_hash,
+            different_handshake.genesis_hash
+        );
+
+        node.accept_peer(peer_info(&same), &same_handshake).unwrap();
+        assert!(
+            node.accept_peer(peer_info(&different), &different_handshake)
+                .is_err()
+        );
+        assert_eq!(node.connected_peers(), 1);
+    }
+
+    #[test]
+    fn test_start_without_mdns() {
+        let mut node = P2PNode::new(P2PConfig {
+            bootstrap_nodes: vec!["10.0.0.1:8333".to_string()],
+            enable_mdns: false,
+            ..Default::default()
+        })
+        .unwrap();
+
+        node.start().unwrap();
+        assert!(node.is_running());
+        // Only the bootstrap peer is known; no local discovery happened
+        assert_eq!(node.connected_peers(), 1);
+    }
+
+    #[test]
+    fn test_subscription_failure_fails_start() {
+        let mut node = P2PNode::new(P2PConfig {
+            network_id: "bad network".to_string(),
+            enable_mdns: false,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(node.start().is_err());
+        assert!(!node.is_running());
+        assert!(node.subscribed_topics.is_empty());
+    }
+
+    #[test]
+    fn test_handle_block_request_serves_range() {
+        let mut blockchain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        for _ in 0..5 {
+            let result = blockchain.mine_next_block(&miner, vec![]).unwrap();
+            blockchain.add_block(result.block).unwrap();
+        }
+
+        let mut node = P2PNode::new(P2PConfig::default()).unwrap();
+        node.set_blockchain(blockchain);
+
+        let NetworkMessage::BlockResponse(blocks) = node.handle_block_request(2).unwrap() else {
+            panic!("expected a block response");
+        };
+        let heights: Vec<u64> = blocks.iter().map(|b| b.height().unwrap()).collect();
+        assert_eq!(heights, vec![2, 3, 4, 5]);
+
+        let NetworkMessage::BlockResponse(blocks) = node.handle_block_request(6).unwrap() else {
+            panic!("expected a block response");
+        };
+        assert!(blocks.is_empty());
+    }
+
+    #[test]
+    fn test_sync_response_applies_blocks_in_height_order() {
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let mut source = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        for _ in 0..5 {
+            let result = source.mine_next_block(&miner, vec![]).unwrap();
+            source.add_block(result.block).unwrap();
+        }
+        let mut blocks: Vec<Block> = (1..=5)
+            .map(|height| source.get_block_by_height(height).unwrap().clone())
+            .collect();
+        blocks.reverse();
+
+        // A block from an unrelated chain never connects
+        let mut other = Blockchain::new(NetworkParams::default(), vec![7, 8, 9]).unwrap();
+        let stray = other.mine_next_block(&miner, vec![]).unwrap().block;
+        other.add_block(stray.clone()).unwrap();
+        let stray_child = other.mine_next_block(&miner, vec![]).unwrap().block;
+        blocks.insert(2, stray_child);
+
+        let (mut node, peer) = started_node();
+        let connected = node.process_sync_response(&peer, blocks).unwrap();
+
+        assert_eq!(connected, 5);
+        let blockchain = node.blockchain().unwrap();
+        assert_eq!(blockchain.height(), 5);
+        assert_eq!(
+            blockchain.get_latest_block().hash().unwrap(),
+            source.get_latest_block().hash().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_gossiped_block_requests_are_answered() {
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let mut chain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        for _ in 0..3 {
+            let block = chain.mine_next_block(&miner, vec![]).unwrap().block;
+            chain.add_block(block).unwrap();
+        }
+        let (mut server, _) = started_node();
+        server.set_blockchain(chain);
+        let (mut requester, _) = started_node();
+        let encode = |message: &NetworkMessage| serde_json::to_vec(message).unwrap();
+
+        // A batch request is answered with the blocks and syncs the requester
+        let request = NetworkMessage::GetBlocks { from_height: 1 };
+        server
+            .handle_gossip("requester", &encode(&request))
+            .unwrap();
+        let published = server.take_published();
+        assert_eq!(published.len(), 1);
+        assert!(published[0].0.ends_with("/blocks"));
+        let NetworkMessage::BlockResponse(blocks) = &published[0].1 else {
+            panic!("expected a BlockResponse");
+        };
+        assert_eq!(blocks.len(), 3);
+        requester
+            .handle_gossip("server", &encode(&published[0].1))
+            .unwrap();
+        assert_eq!(requester.blockchain().unwrap().height(), 3);
+
+        // A single block is answered by height; unknown heights are ignored
+        server
+            .handle_gossip("requester", &encode(&NetworkMessage::GetBlock(2)))
+            .unwrap();
+        let published = server.take_published();
+        assert_eq!(published.len(), 1);
+        let NetworkMessage::Block(block) = &published[0].1 else {
+            panic!("expected a Block");
+        };
+        assert_eq!(
+            block.hash().unwrap(),
+            server
+                .blockchain()
+                .unwrap()
+                .get_block_by_height(2)
+                .unwrap()
+                .hash()
+                .unwrap()
+        );
+        server
+            .handle_gossip("requester", &encode(&NetworkMessage::GetBlock(9)))
+            .unwrap();
+        assert!(server.take_published().is_empty());
+    }
+
+    #[test]
+    fn test_received_transactions_are_validated_and_deduplicated() {
+        // Anyone-can-spend script (OP_1)
+        let open_script = vec![bond_core::OpCode::Op1 as u8];
+        let blockchain = Blockchain::new(NetworkParams::default(), open_script.clone()).unwrap();
+        let tx = blockchain
+            .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+        let mut overspend = tx.clone();
+        overspend.outputs[0].value = 1_000_000;
+
+        let mut node = P2PNode::new(P2PConfig::default()).unwrap();
+        node.set_blockchain(blockchain);
+
+        let txid = node.process_received_transaction(tx.clone()).unwrap();
+        assert_eq!(node.process_received_transaction(tx).unwrap(), txid);
+        assert!(node.process_received_transaction(overspend).is_err());
+
+        assert_eq!(node.mempool().len(), 1);
+        assert!(node.mempool().contains(&txid));
+    }
+
+    #[test]
+    fn test_received_block_removes_its_transactions_from_mempool() {
+        let open_script = vec![bond_core::OpCode::Op1 as u8];
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let mut source = Blockchain::new(NetworkParams::default(), open_script.clone()).unwrap();
+        let tx = source
+            .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+
+        let mut node = P2PNode::new(P2PConfig::default()).unwrap();
+        node.set_blockchain(Blockchain::new(NetworkParams::default(), open_script).unwrap());
+        node.process_received_transaction(tx.clone()).unwrap();
+        assert_eq!(node.mempool().len(), 1);
+
+        let block = source.mine_next_block(&miner, vec![tx]).unwrap().block;
+        source.add_block(block.clone()).unwrap();
+        node.handle_new_block("peer", block).unwrap();
+
+        assert_eq!(node.blockchain().unwrap().height(), 1);
+        assert!(node.mempool().is_empty());
+    }
+
+    #[test]
+    fn test_broadcast_block_publishes_on_blocks_topic() {
+        let (mut node, _) = started_node();
+        let genesis = node
+            .blockchain()
+            .unwrap()
+            .get_block_by_height(0)
+            .unwrap()
+            .clone();
+
+        node.broadcast_block(&genesis).unwrap();
+
+        let published = node.take_published();
+        assert_eq!(published.len(), 1);
+        let (topic, NetworkMessage::NewBlock(block)) = &published[0] else {
+            panic!("expected a NewBlock message");
+        };
+        assert_eq!(topic, &format!("{}/blocks", node.config.network_id));
+        assert_eq!(block.hash().unwrap(), genesis.hash().unwrap());
+        assert!(node.take_published().is_empty());
+
+        node.shutdown().unwrap();
+        assert!(node.broadcast_block(&genesis).is_err());
+    }
+
+    #[test]
+    fn test_rejections_are_recorded_with_reason() {
+        let open_script = vec![bond_core::OpCode::Op1 as u8];
+        let blockchain = Blockchain::new(NetworkParams::default(), open_script.clone()).unwrap();
+        let tx = blockchain
+            .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+        let mut conflicting = tx.clone();
+        conflicting.outputs[0].value = 900;
+        let mut missing_input = tx.clone();
+        missing_input.inputs[0].previous_output.vout = 7;
+
+        let mut node = P2PNode::new(P2PConfig::default()).unwrap();
+        node.set_blockchain(blockchain);
+        node.process_received_transaction(tx).unwrap();
+        assert!(node.recent_rejections().is_empty());
+
+        assert!(
+            node.process_received_transaction(conflicting.clone())
+                .is_err()
+        );
+        assert!(node.process_received_transaction(missing_input).is_err());
+
+        let rejections = node.recent_rejections();
+        assert_eq!(rejections.len(), 2);
+        assert_eq!(rejections[0].txid, conflicting.hash().unwrap());
+        assert_eq!(rejections[0].reason, MempoolError::DoubleSpend);
+        assert_eq!(rejections[1].reason, MempoolError::MissingInputs);
+        assert!(rejections[1].timestamp > 0);
+
+        // Oldest rejections are evicted once the buffer is full
+        for _ in 0..MAX_RECENT_REJECTIONS {
+            node.record_rejection(Hash256::zero(), MempoolError::Coinbase);
+        }
+        assert_eq!(node.recent_rejections().len(), MAX_RECENT_REJECTIONS);
+        assert!(
+            node.recent_rejections()
+                .iter()
+                .all(|rejection| rejection.reason == MempoolError::Coinbase)
+        );
+    }
+
+    #[test]
+    fn test_far_future_block_penalizes_peer() {
+        let (mut node, peer_id) = started_node();
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+
+        let blockchain = node.blockchain.as_ref().unwrap();
+        let tip_hash = blockchain.get_latest_block().hash().unwrap();
+        let far_ahead = miner.mine_block(tip_hash, vec![], 1000, 5000).unwrap();
+        let next = blockchain.mine_next_block(&miner, vec![]).unwrap();
+
+        assert!(node.handle_new_block(&peer_id, far_ahead.block).is_err());
+        assert_eq!(node.peers[&peer_id].misbehavior_score, FUTURE_BLOCK_PENALTY);
+
+        node.handle_new_block(&peer_id, next.block).unwrap();
+        assert_eq!(node.network_status().chain_height, 1);
+        assert_eq!(node.peers[&peer_id].misbehavior_score, FUTURE_BLOCK_PENALTY);
+    }
+
+    #[test]
+    fn test_nodes_on_different_networks_do_not_peer() {
+        // Same chain, so only the network id tells the nodes apart
+        let node_on = |network: NetworkType| {
+            let mut node = P2PNode::new(P2PConfig::for_network(network)).unwrap();
+            node.set_blockchain(Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap());
+            node
+        };
+        let mut testnet = node_on(NetworkType::Testnet);
+        let other_testnet = node_on(NetworkType::Testnet);
+        let mainnet = node_on(NetworkType::Mainnet);
+        assert_eq!(mainnet.config.network_id, "aevum-bond-mainnet");
+
+        let handshake = mainnet.handshake().unwrap();
+        assert!(
+            testnet
+                .accept_peer(peer_info(&mainnet), &handshake)
+                .is_err()
+        );
+        let handshake = other_testnet.handshake().unwrap();
+        testnet
+            .accept_peer(peer_info(&other_testnet), &handshake)
+            .unwrap();
+        assert_eq!(testnet.connected_peers(), 1);
+    }
+
+    #[test]
+    fn test_orphan_transaction_is_promoted_once_parent_confirms() {
+        let open_script = vec![bond_core::OpCode::Op1 as u8];
+        let blockchain = Blockchain::new(NetworkParams::default(), open_script.clone()).unwrap();
+        let parent = blockchain
+            .create_transaction(&open_script, open_script.clone(), 1000, 100, None)
+            .unwrap();
+        let parent_output = bond_core::OutPoint {
+            txid: parent.hash().unwrap(),
+            vout: 0,
+        };
+        let child = Transaction::new(
+            1,
+            vec![bond_core::TxInput::new(parent_output, vec![], u32::MAX)],
+            vec![bond_core::TxOutput::new(900, vec![4, 5, 6])],
+            0,
+        );
+        let child_id = child.hash().unwrap();
+
+        let (mut node, peer_id) = started_node();
+        node.set_blockchain(blockchain);
+        assert!(node.process_received_transaction(child).is_err());
+        assert!(node.mempool().is_orphan(&child_id));
+
+        node.process_received_transaction(parent.clone()).unwrap();
+        assert!(node.mempool().is_orphan(&child_id));
+
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let block = node
+            .blockchain()
+            .unwrap()
+            .mine_next_block(&miner, vec![parent])
+            .unwrap()
+            .block;
+        node.handle_new_block(&peer_id, block).unwrap();
+
+        assert!(node.mempool().contains(&child_id));
+        assert_eq!(node.mempool().orphan_len(), 0);
+    }
+
+    #[test]
+    fn test_compact_block_fetches_only_missing_transaction() {
+        let open_script = vec![bond_core::OpCode::Op1 as u8];
+        let params = NetworkParams::default();
+        let genesis = bond_core::GenesisConfig {
+            allocations: vec![(open_script.clone(), 1_000); 3],
+            timestamp: params.genesis_timestamp,
+            premine: 3_000,
+        };
+        let new_chain = || {
+            Blockchain::with_genesis(
+                params.clone(),
+                &genesis,
+                Box::new(bond_core::MemoryStore::new()),
+            )
+            .unwrap()
+        };
+
+        let mut sender_chain = new_chain();
+        let mut reserved = HashSet::new();
+        let mut txs = Vec::new();
+        for _ in 0..3 {
+            let tx = sender_chain
+                .create_transaction(&open_script, vec![4, 5, 6], 900, 100, Some(&reserved))
+                .unwrap();
+            reserved.extend(tx.inputs.iter().map(|input| input.previous_output));
+            txs.push(tx);
+        }
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let block = sender_chain
+            .mine_next_block(&miner, txs.clone())
+            .unwrap()
+            .block;
+        sender_chain.add_block(block.clone()).unwrap();
+        let mut sender = P2PNode::new(P2PConfig::default()).unwrap();
+        sender.set_blockchain(sender_chain);
+
+        let (mut receiver, peer_id) = started_node();
+        receiver.set_blockchain(new_chain());
+        for tx in [&txs[0], &txs[2]] {
+            receiver.process_received_transaction(tx.clone()).unwrap();
+        }
+
+        let compact = CompactBlock::from_block(&block).unwrap();
+        let request = receiver
+            .handle_compact_block(&peer_id, compact)
+            .unwrap()
+            .unwrap();
+        let NetworkMessage::GetBlockTxn {
+            block_hash,
+            indexes,
+        } = request
+        else {
+            panic!("expected a GetBlockTxn request");
+        };
+        // Coinbase is prefilled, so only the transaction absent from the
+        // receiver's mempool is missing, at its canonical position
+        let missing = block
+            .transactions
+            .iter()
+            .position(|tx| *tx == txs[1])
+            .unwrap();
+        assert_eq!(indexes, vec![u32::try_from(missing).unwrap()]);
+
+        let NetworkMessage::BlockTxn { transactions, .. } =
+            sender.handle_get_block_txn(block_hash, &indexes).unwrap()
+        else {
+            panic!("expected a BlockTxn response");
+        };
+        assert_eq!(transactions, vec![txs[1].clone()]);
+
+        receiver
+            .handle_block_txn(&peer_id, block_hash, transactions)
+            .unwrap();
+        let blockchain = receiver.blockchain().unwrap();
+        assert_eq!(blockchain.height(), 1);
+        assert_eq!(blockchain.get_latest_block().hash().unwrap(), block_hash);
+    }
+
+    #[test]
+    fn test_headers_first_sync_switches_to_heavier_branch() {
+        let mine = |chain: &mut Blockchain, script: Vec<u8>, count: usize| {
+            let miner = Miner::new(MinerConfig {
+                reward_script: script,
+                threads: 1,
+                difficulty: 1,
+                throttle: None,
+            });
+            (0..count)
+                .map(|_| {
+                    let block = chain.mine_next_block(&miner, vec![]).unwrap().block;
+                    chain.add_block(block.clone()).unwrap();
+                    block
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut sender_chain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        let mut receiver_chain = Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap();
+        for block in mine(&mut sender_chain, vec![4, 5, 6], 3) {
+            receiver_chain.add_block(block).unwrap();
+        }
+        mine(&mut receiver_chain, vec![7, 8, 9], 2);
+        mine(&mut sender_chain, vec![4, 5, 6], 4);
+        let (mut receiver, peer_id) = started_node();
+        receiver.set_blockchain(receiver_chain);
+        let mut sender = P2PNode::new(P2PConfig::default()).unwrap();
+        sender.set_blockchain(sender_chain);
+
+        let NetworkMessage::GetHeaders(locator) = receiver.headers_request().unwrap() else {
+            panic!("expected a GetHeaders request");
+        };
+        let NetworkMessage::Headers(headers) = sender.handle_get_headers(&locator).unwrap() else {
+            panic!("expected a Headers response");
+        };
+        // The fork point is height 3, so only the sender's branch is sent
+        assert_eq!(headers.len(), 4);
+
+        let Some(NetworkMessage::GetBlockData(hashes)) =
+            receiver.handle_headers(&peer_id, headers).unwrap()
+        else {
+            panic!("expected a GetBlockData request");
+        };
+        assert_eq!(hashes.len(), 4);
+
+        let NetworkMessage::BlockData(blocks) = sender.handle_get_block_data(&hashes).unwrap()
+        else {
+            panic!("expected a BlockData response");
+        };
+        assert_eq!(receiver.handle_block_data(&peer_id, blocks).unwrap(), 4);
+
+        let receiver_chain = receiver.blockchain().unwrap();
+        let sender_chain = sender.blockchain().unwrap();
+        assert_eq!(receiver_chain.height(), 7);
+        assert_eq!(
+            receiver_chain.get_latest_block().hash().unwrap(),
+            sender_chain.get_latest_block().hash().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_disconnected_headers_penalize_peer() {
+        let (mut receiver, peer_id) = started_node();
+        let sender_chain = Blockchain::new(NetworkParams::default(), vec![9, 9, 9]).unwrap();
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let block = sender_chain.mine_next_block(&miner, vec![]).unwrap().block;
+
+        assert!(
+            receiver
+                .handle_headers(&peer_id, vec![block.header])
+                .is_err()
+        );
+        assert_eq!(
+            receiver.peer_score(&peer_id),
+            -i32::try_from(INVALID_BLOCK_PENALTY).unwrap()
+        );
+    }
+
+    /// Engine accepting consecutive heights, recording every added block
+    #[derive(Default)]
+    struct MockEngine {
+        added: Vec<u64>,
+    }
+
+    impl ConsensusEngine for MockEngine {
+        type Block = u64;
+        type Transaction = ();
+        type Producer = ();
+
+        fn validate_block(&self, block: &u64) -> Result<()> {
+            if *block == self.tip_height() + 1 {
+                Ok(())
+            } else {
+                Err(BlockchainError::InvalidBlock(format!(
+                    "Unexpected height {block}"
+                )))
+            }
+        }
+
+        fn add_block(&mut self, block: u64) -> Result<()> {
+            self.validate_block(&block)?;
+            self.added.push(block);
+            Ok(())
+        }
+
+        fn produce_block(&self, (): &(), _transactions: Vec<()>) -> Result<u64> {
+            Ok(self.tip_height() + 1)
+        }
+
+        fn tip_height(&self) -> u64 {
+            self.added.last().copied().unwrap_or(0)
+        }
+    }
+
+    #[test]
+    fn test_node_shell_drives_engine_add_block() {
+        let config = P2PConfig {
+            bootstrap_nodes: vec!["10.0.0.1:8333".to_string()],
+            enable_mdns: false,
+            ..Default::default()
+        };
+        let mut node = P2PNode::with_engine(config, MockEngine::default()).unwrap();
+        node.start().unwrap();
+        let peer_id = "mock-peer-10.0.0.1:8333";
+
+        let block = node.engine().unwrap().produce_block(&(), vec![]).unwrap();
+        node.handle_engine_block(peer_id, block).unwrap();
+        node.handle_engine_block(peer_id, 2).unwrap();
+        assert_eq!(node.engine().unwrap().added, vec![1, 2]);
+        assert_eq!(node.engine().unwrap().tip_height(), 2);
+
+        // A block the engine rejects penalizes the sender
+        assert!(node.handle_engine_block(peer_id, 7).is_err());
+        assert_eq!(node.engine().unwrap().added, vec![1, 2]);
+        assert_eq!(
+            node.peer_score(peer_id),
+            -i32::try_from(INVALID_BLOCK_PENALTY).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_node_shell_runs_aevum_chain() {
+        use aevum_core::{AevumChain, AevumState, DposConfig, DposEngine, ValidatorInfo, utils};
+        use shared::{KeyPair, SignatureAlgorithm};
+
+        let validator_key = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let validator = utils::address_from_key(&validator_key.public_key);
+        let mut state = AevumState::new();
+        state
+            .validators
+            .insert(validator, ValidatorInfo::new(validator, 1_000));
+        let chain = AevumChain::new(state, DposEngine::new(DposConfig::default()).unwrap());
+        let mut node = P2PNode::with_engine(P2PConfig::default(), chain).unwrap();
+
+        // The leader's block without the producer signature is rejected
+        let block = node
+            .engine()
+            .unwrap()
+            .produce_block(&validator_key, vec![])
+            .unwrap();
+        let mut forged = block.clone();
+        forged.signature = None;
+        assert!(node.handle_engine_block("forger", forged).is_err());
+        assert!(node.peer_score("forger") < 0);
+
+        node.handle_engine_block("peer", block).unwrap();
+        assert_eq!(node.engine().unwrap().tip_height(), 1);
+    }
+
+    /// Equivocation of `keypair`: two different blocks signed at height 7
+    fn equivocation(keypair: &shared::KeyPair) -> SlashingEvidence {
+        let block_hash_a = Hash256::keccak256(b"block a");
+        let block_hash_b = Hash256::keccak256(b"block b");
+        SlashingEvidence {
+            validator: aevum_core::utils::address_from_key(&keypair.public_key),
+            height: 7,
+            block_hash_a,
+            block_hash_b,
+            sig_a: SlashingEvidence::sign_block(keypair, 7, &block_hash_a).unwrap(),
+            sig_b: SlashingEvidence::sign_block(keypair, 7, &block_hash_b).unwrap(),
+        }
+    }
+
+    /// Started Aevum node with two-block epochs and `validators` staked,
+    /// crediting bounties to `reporter`
+    fn aevum_node(validators: &[Hash256], reporter: Hash256) -> P2PNode<AevumChain> {
+        use aevum_core::{AevumState, DposConfig, DposEngine, ValidatorInfo};
+
+        let config = DposConfig {
+            epoch_length: 2,
+            ..DposConfig::default()
+        };
+        let mut state = AevumState::with_config(config.clone());
+        for validator in validators {
+            state
+                .validators
+                .insert(*validator, ValidatorInfo::new(*validator, 10_000));
+        }
+        let chain = AevumChain::new(state, DposEngine::new(config).unwrap());
+        let config = P2PConfig {
+            enable_mdns: false,
+            slashing_reporter: reporter,
+            ..Default::default()
+        };
+        let mut node = P2PNode::with_engine(config, chain).unwrap();
+        node.start().unwrap();
+        node.take_published();
+        node
+    }
+
+    #[test]
+    fn test_gossiped_slashing_evidence_slashes_at_epoch_end() {
+        use shared::{KeyPair, SignatureAlgorithm};
+
+        let offender = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let producer = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let evidence = equivocation(&offender);
+        let reporter = Hash256::keccak256(b"reporter");
+        let mut node = aevum_node(
+            &[
+                evidence.validator,
+                aevum_core::utils::address_from_key(&producer.public_key),
+            ],
+            reporter,
+        );
+        let stake = |node: &P2PNode<AevumChain>| {
+            node.engine().unwrap().state().validators[&evidence.validator].stake_amount
+        };
+
+        // Queued, not applied on arrival
+        node.handle_slashing_evidence("peer", evidence.clone())
+            .unwrap();
+        assert_eq!(stake(&node), 10_000);
+        assert_eq!(node.peer_score("peer"), 0);
+
+        // Queued evidence is relayed to the other peers
+        let published = node.take_published();
+        assert_eq!(published.len(), 1);
+        assert!(published[0].0.ends_with("/slashing"));
+        assert!(matches!(
+            &published[0].1,
+            NetworkMessage::SlashingEvidence(relayed) if relayed.validator == evidence.validator
+        ));
+
+        // The same evidence from another peer is neither queued nor relayed again
+        node.handle_slashing_evidence("other", evidence.clone())
+            .unwrap();
+        assert!(node.take_published().is_empty());
+
+        // Applied once, by the block that closes the epoch
+        for height in 1..=2 {
+            assert_eq!(stake(&node), 10_000);
+            let block = node
+                .engine()
+                .unwrap()
+                .produce_block(&producer, vec![])
+                .unwrap();
+            node.handle_engine_block("peer", block).unwrap();
+            assert_eq!(node.engine().unwrap().tip_height(), height);
+        }
+        assert_eq!(stake(&node), 9_000);
+        let state = node.engine().unwrap().state();
+        assert_eq!(state.get_account(&reporter).unwrap().balance, 100);
+    }
+
+    #[test]
+    fn test_forged_slashing_evidence_penalizes_peer() {
+        use shared::{KeyPair, SignatureAlgorithm};
+
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let impostor = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let mut forged = equivocation(&keypair);
+        forged.sig_b = equivocation(&impostor).sig_b;
+        let reporter = Hash256::keccak256(b"reporter");
+        let mut node = aevum_node(&[forged.validator], reporter);
+
+        assert!(
+            node.handle_slashing_evidence("forger", forged.clone())
+                .is_err()
+        );
+        assert_eq!(
+            node.peer_score("forger"),
+            -i32::try_from(INVALID_EVIDENCE_PENALTY).unwrap()
+        );
+        let state = node.engine().unwrap().state();
+        assert_eq!(state.validators[&forged.validator].stake_amount, 10_000);
+        assert!(state.get_account(&reporter).is_none());
+        assert!(node.take_published().is_empty());
+    }
+
+    #[test]
+    fn test_bond_node_relays_gossiped_slashing_evidence() {
+        use shared::{KeyPair, SignatureAlgorithm};
+
+        let (mut node, peer_id) = started_node();
+        node.take_published();
+        let keypair = KeyPair::generate(SignatureAlgorithm::MLDSA44).unwrap();
+        let evidence = equivocation(&keypair);
+
+        // Forged evidence arriving through gossip penalizes the peer
+        let mut forged = evidence.clone();
+        forged.sig_b = forged.sig_a.clone();
+        let data = serde_json::to_vec(&NetworkMessage::SlashingEvidence(forged)).unwrap();
+        assert!(node.handle_gossip(&peer_id, &data).is_err());
+        assert_eq!(
+            node.peer_score(&peer_id),
+            -i32::try_from(INVALID_EVIDENCE_PENALTY).unwrap()
+        );
+        assert!(node.take_published().is_empty());
+
+        // Valid evidence is relayed only once
+        let data = serde_json::to_vec(&NetworkMessage::SlashingEvidence(evidence)).unwrap();
+        node.handle_gossip(&peer_id, &data).unwrap();
+        let published = node.take_published();
+        assert_eq!(published.len(), 1);
+        assert!(matches!(
+            published[0].1,
+            NetworkMessage::SlashingEvidence(_)
+        ));
+        node.handle_gossip(&peer_id, &data).unwrap();
+        assert!(node.take_published().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mining_loop_produces_and_broadcasts_block() {
+        let (mut node, _) = started_node();
+        node.set_blockchain(
+            Blockchain::new(
+                NetworkParams::for_network(NetworkType::Regtest),
+                vec![1, 2, 3],
+            )
+            .unwrap(),
+        );
+        let node = Arc::new(Mutex::new(node));
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let task = tokio::spawn(run_mining_loop(Arc::clone(&node), miner));
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while node.lock().unwrap().blockchain().unwrap().height() == 0 {
+                tokio::time::sleep(MINING_POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let published = {
+            let mut node = node.lock().unwrap();
+            let published = node.take_published();
+            node.shutdown().unwrap();
+            published
+        };
+        task.await.unwrap().unwrap();
+
+        let mined = published
+            .iter()
+            .find_map(|(topic, message)| match message {
+                NetworkMessage::NewBlock(block) if topic.ends_with("/blocks") => Some(block),
+                _ => None,
+            })
+            .unwrap();
+        let blockchain_has_block = node
+            .lock()
+            .unwrap()
+            .blockchain()
+            .unwrap()
+            .get_block_by_hash(&mined.hash().unwrap())
+            .is_some();
+        assert!(blockchain_has_block);
+        assert_eq!(
+            mined.transactions[0].outputs[0].script_pubkey,
+            vec![4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn test_new_block_publishes_event() {
+        let (mut node, peer_id) = started_node();
+        let mut events = node.subscribe_events();
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let next = node
+            .blockchain
+            .as_ref()
+            .unwrap()
+            .mine_next_block(&miner, vec![])
+            .unwrap()
+            .block;
+        let hash = next.hash().unwrap();
+
+        node.handle_new_block(&peer_id, next.clone()).unwrap();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            BlockchainEvent::BlockAdded { height: 1, hash }
+        );
+
+        // A re-announced block is not reported again
+        let _ = node.handle_new_block(&peer_id, next);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_repeated_invalid_blocks_ban_peer() {
+        let (mut node, peer_id) = started_node();
+        let miner = Miner::new(MinerConfig {
+            reward_script: vec![4, 5, 6],
+            threads: 1,
+            difficulty: 1,
+            throttle: None,
+        });
+        let blockchain = node.blockchain.as_ref().unwrap();
+        let mut invalid = blockchain.mine_next_block(&miner, vec![]).unwrap().block;
+        invalid.header.merkle_root = Hash256::zero();
+
+        let receipts = BAN_SCORE_THRESHOLD.unsigned_abs() / INVALID_BLOCK_PENALTY;
+        for receipt in 1..=receipts {
+            assert!(!node.is_banned(&peer_id));
+            assert!(node.handle_new_block(&peer_id, invalid.clone()).is_err());
+            let expected = -i32::try_from(receipt * INVALID_BLOCK_PENALTY).unwrap();
+            assert_eq!(node.peer_score(&peer_id), expected);
+        }
+
+        assert!(node.is_banned(&peer_id));
+        assert_eq!(node.connected_peers(), 0);
+        let status = node.network_status();
+        assert!(!status.peers.contains(&peer_id));
+        assert_eq!(status.peer_scores[&peer_id], BAN_SCORE_THRESHOLD);
+
+        // Banned peers are ignored without further scoring
+        assert!(node.handle_new_block(&peer_id, invalid).is_err());
+        assert_eq!(node.peer_score(&peer_id), BAN_SCORE_THRESHOLD);
+        assert!(node.handle_gossip(&peer_id, b"{}").is_err());
+        assert_eq!(node.peer_score(&peer_id), BAN_SCORE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_gossip_above_rate_is_dropped() {
+        let mut node = P2PNode::new(P2PConfig {
+            gossip_rate_per_sec: 10,
+            gossip_burst: 5,
+            ..Default::default()
+        })
+        .unwrap();
+        let message = serde_json::to_vec(&NetworkMessage::GetPeers).unwrap();
+        let start = Instant::now();
+
+        let accepted = (0..8)
+            .filter(|_| node.handle_gossip_at("flooder", &message, start).is_ok())
+            .count();
+        assert_eq!(accepted, 5);
+        assert_eq!(
+            node.peer_score("flooder"),
+            -3 * i32::try_from(RATE_LIMIT_PENALTY).unwrap()
+        );
+
+        // Half a second refills five tokens; other peers have their own bucket
+        let later = start + Duration::from_millis(500);
+        let accepted = (0..8)
+            .filter(|_| node.handle_gossip_at("flooder", &message, later).is_ok())
+            .count();
+        assert_eq!(accepted, 5);
+        assert!(node.handle_gossip_at("quiet", &message, later).is_ok());
+        assert_eq!(node.peer_score("quiet"), 0);
+    }
+
+    #[test]
+    fn test_malformed_gossip_and_invalid_transactions_lower_score() {
+        // Anyone-can-spend script (OP_1)
+        let open_script = vec![bond_core::OpCode::Op1 as u8];
+        let blockchain = Blockchain::new(NetworkParams::default(), open_script.clone()).unwrap();
+        let tx = blockchain
+            .create_transaction(&open_script, vec![4, 5, 6], 1000, 100, None)
+            .unwrap();
+        let mut overspend = tx.clone();
+        overspend.outputs[0].value = 1_000_000;
+        let mut node = P2PNode::new(P2PConfig::default()).unwrap();
+        node.set_blockchain(blockchain);
+        let peer_id = "gossip-peer";
+
+        assert!(node.handle_gossip(peer_id, b"not a message").is_err());
+        assert_eq!(
+            node.peer_score(peer_id),
+            -i32::try_from(MALFORMED_MESSAGE_PENALTY).unwrap()
+        );
+
+        // A valid transaction and a duplicate of it are not penalized
+        let message = serde_json::to_vec(&NetworkMessage::NewTransaction(tx.clone())).unwrap();
+        node.handle_gossip(peer_id, &message).unwrap();
+        node.handle_gossip_transaction(peer_id, tx).unwrap();
+        assert!(node.handle_gossip_transaction(peer_id, overspend).is_err());
+        assert_eq!(
+            node.peer_score(peer_id),
+            -i32::try_from(MALFORMED_MESSAGE_PENALTY + INVALID_TX_PENALTY).unwrap()
+        );
+        assert!(!node.is_banned(peer_id));
+        assert_eq!(node.mempool().len(), 1);
+    }
+
+    #[test]
+    fn test_restart_dials_best_known_peers_first() {
+        let path = std::env::temp_dir().join(format!("aevum-peers-{}.json", rand::random::<u64>()));
+        let config = P2PConfig {
+            bootstrap_nodes: vec!["10.0.0.1:8333".to_string(), "10.0.0.2:8333".to_string()],
+            enable_mdns: false,
+            address_book_path: Some(path.clone()),
+            ..Default::default()
+        };
+
+        let mut node = P2PNode::new(config.clone()).unwrap();
+        node.start().unwrap();
+        node.record_peer_latency("mock-peer-10.0.0.1:8333", Duration::from_millis(900));
+        node.shutdown().unwrap();
+
+        // Only one slot: the slow peer loses to the other bootstrap node
+        let mut restarted = P2PNode::new(P2PConfig {
+            max_peers: 1,
+            ..config
+        })
+        .unwrap();
+        restarted.start().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(restarted.address_book().len(), 2);
+        assert_eq!(
+            restarted.network_status().peers,
+            vec!["mock-peer-10.0.0.2:8333"]
+        );
+    }
+
+    #[test]
+    fn test_network_time_follows_peer_median() {
+        let mut node = node_with_params(NetworkParams::default());
+        let mut handshake = node_with_params(NetworkParams::default())
+            .handshake()
+            .unwrap();
+        let ahead = 30 * 60;
+        let now = unix_now();
+
+        for i in 0..MIN_TIME_SAMPLES {
+            // One outlier does not move the median
+            let skew = if i == 0 { 10 * 60 * 60 } else { ahead };
+            handshake.timestamp = now + skew;
+            let mut peer = peer_info(&node);
+            peer.node_id = format!("peer-{i}");
+            node.accept_peer(peer, &handshake).unwrap();
+            if i + 1 < MIN_TIME_SAMPLES {
+                assert_eq!(node.network_time_offset(), 0);
+            }
+        }
+
+        let offset = node.network_time_offset();
+        assert!((ahead as i64 - offset).abs() <= 2);
+        let adjusted = node.blockchain().unwrap().adjusted_time().timestamp();
+        assert!((adjusted - chrono::Utc::now().timestamp() - offset).abs() <= 2);
+
+        // Peers too far ahead are ignored entirely
+        handshake.timestamp = now + 5 * 60 * 60;
+        for i in 0..MIN_TIME_SAMPLES {
+            let mut peer = peer_info(&node);
+            peer.node_id = format!("peer-{i}");
+            node.accept_peer(peer, &handshake).unwrap();
+        }
+        assert_eq!(node.network_time_offset(), 0);
+    }
+}
@@ -0,0 +1,407 @@
+//! JSON-RPC server for querying a running node
+//!
+//! Speaks JSON-RPC 2.0 over plain HTTP `POST` requests. The server shares
+//! the node with the rest of the process through an `Arc<Mutex<P2PNode>>`,
+//! so transactions submitted over RPC go through the same validation and
+//! mempool as those received from peers.
+
+use crate::network::P2PNode;
+use bond_core::Transaction;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use shared::{BlockchainError, Hash256, Result};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{info, warn};
+
+/// Largest request body accepted by the server
+pub const MAX_REQUEST_SIZE: usize = 1024 * 1024;
+
+/// Invalid JSON was received
+pub const PARSE_ERROR: i64 = -32700;
+/// The method does not exist
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameters
+pub const INVALID_PARAMS: i64 = -32602;
+/// The node rejected the request
+pub const SERVER_ERROR: i64 = -32000;
+
+/// A JSON-RPC request
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcRequest {
+    pub method: String,
+    #[serde(default)]
+    pub params: Vec<Value>,
+    #[serde(default)]
+    pub id: Value,
+}
+
+/// A JSON-RPC error object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn invalid_params(message: &str) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<BlockchainError> for RpcError {
+    fn from(error: BlockchainError) -> Self {
+        Self {
+            code: SERVER_ERROR,
+            message: error.to_string(),
+        }
+    }
+}
+
+/// A JSON-RPC response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+    pub id: Value,
+}
+
+impl RpcResponse {
+    fn new(id: Value, outcome: std::result::Result<Value, RpcError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result,
+            error,
+            id,
+        }
+    }
+}
+
+/// JSON-RPC server bound to a local address
+pub struct RpcServer {
+    listener: TcpListener,
+    node: Arc<Mutex<P2PNode>>,
+}
+
+impl RpcServer {
+    /// Bind the server to `addr`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the address cannot be bound
+    pub async fn bind(addr: &str, node: Arc<Mutex<P2PNode>>) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| BlockchainError::NetworkError(format!("RPC bind {addr}: {e}")))?;
+        Ok(Self { listener, node })
+    }
+
+    /// Address the server is listening on
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the socket address cannot be read
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener
+            .local_addr()
+            .map_err(|e| BlockchainError::NetworkError(e.to_string()))
+    }
+
+    /// Accept connections until the task is cancelled
+    pub async fn serve(self) {
+        info!(
+            "🛰️ RPC server listening on {:?}",
+            self.listener.local_addr()
+        );
+        loop {
+            let stream = match self.listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    warn!("RPC accept failed: {}", e);
+                    continue;
+                }
+            };
+
+            let node = Arc::clone(&self.node);
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, &node).await {
+                    warn!("RPC connection error: {}", e);
+                }
+            });
+        }
+    }
+}
+
+/// Read one HTTP request, dispatch it and write the response
+async fn handle_connection(stream: TcpStream, node: &Mutex<P2PNode>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut content_length = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(());
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+
+    let response = if content_length > MAX_REQUEST_SIZE {
+        RpcResponse::new(
+            Value::Null,
+            Err(RpcError::invalid_params("Request body too large")),
+        )
+    } else {
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).await?;
+        handle_body(node, &body)
+    };
+
+    let body = serde_json::to_vec(&response).unwrap_or_default();
+    let mut stream = reader.into_inner();
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await
+}
+
+/// Parse a request body and dispatch it
+#[must_use]
+pub fn handle_body(node: &Mutex<P2PNode>, body: &[u8]) -> RpcResponse {
+    match serde_json::from_slice::<RpcRequest>(body) {
+        Ok(request) => handle_request(node, request),
+        Err(e) => RpcResponse::new(
+            Value::Null,
+            Err(RpcError {
+                code: PARSE_ERROR,
+                message: e.to_string(),
+            }),
+        ),
+    }
+}
+
+/// Execute a single JSON-RPC request against the node
+#[must_use]
+pub fn handle_request(node: &Mutex<P2PNode>, request: RpcRequest) -> RpcResponse {
+    let mut node = node.lock().unwrap_or_else(PoisonError::into_inner);
+    let outcome = dispatch(&mut node, &request.method, &request.params);
+    RpcResponse::new(request.id, outcome)
+}
+
+fn dispatch(
+    node: &mut P2PNode,
+    method: &str,
+    params: &[Value],
+) -> std::result::Result<Value, RpcError> {
+    match method {
+        "getblockcount" => Ok(json!(attached_chain(node)?.height())),
+        "getblock" => {
+            let blockchain = attached_chain(node)?;
+            let block = match params.first() {
+                Some(Value::String(hash)) => {
+                    let hash = Hash256::from_hex(hash).map_err(|_| {
+                        RpcError::invalid_params("Expected a 64-character hex block hash")
+                    })?;
+                    blockchain.get_block_by_hash(&hash)
+                }
+                Some(height) => {
+                    let height = height.as_u64().ok_or_else(|| {
+                        RpcError::invalid_params("Expected a block height or hash")
+                    })?;
+                    blockchain.get_block_by_height(height)
+                }
+                None => return Err(RpcError::invalid_params("Expected a block height or hash")),
+            }
+            .ok_or_else(|| RpcError::invalid_params("Block not found"))?;
+            serde_json::to_value(block)
+                .map_err(|e| BlockchainError::SerializationError(e.to_string()).into())
+        }
+        "getbalance" => {
+            let script = params
+                .first()
+                .and_then(Value::as_str)
+                .and_then(|script| hex::decode(script).ok())
+                .ok_or_else(|| RpcError::invalid_params("Expected a hex-encoded script"))?;
+            Ok(json!(attached_chain(node)?.get_balance(&script)))
+        }
+        "getmempoolinfo" => {
+            let mempool = node.mempool();
+            Ok(json!({
+                "size": mempool.len(),
+                "deferred": mempool.deferred_len(),
+            }))
+        }
+        "getrawmempool" => node
+            .mempool()
+            .sorted_entries()
+            .into_iter()
+            .map(|entry| {
+                Ok(json!({
+                    "txid": entry.tx.hash()?.to_string(),
+                    "fee": entry.fee,
+                    "size": entry.size,
+                    "fee_rate": entry.fee_rate,
+                }))
+            })
+            .collect::<Result<Vec<Value>>>()
+            .map(Value::Array)
+            .map_err(RpcError::from),
+        "getrejections" => serde_json::to_value(node.recent_rejections())
+            .map_err(|e| BlockchainError::SerializationError(e.to_string()).into()),
+        "sendrawtransaction" => {
+            let tx: Transaction = params
+                .first()
+                .cloned()
+                .and_then(|value| serde_json::from_value(value).ok())
+                .ok_or_else(|| RpcError::invalid_params("Expected a transaction"))?;
+            let txid = node.process_received_transaction(tx.clone())?;
+            if node.is_running() {
+                node.broadcast_transaction(&tx)?;
+            }
+            Ok(json!(txid.to_string()))
+        }
+        _ => Err(RpcError {
+            code: METHOD_NOT_FOUND,
+            message: format!("Method not found: {method}"),
+        }),
+    }
+}
+
+fn attached_chain(node: &P2PNode) -> std::result::Result<&bond_core::Blockchain, RpcError> {
+    node.blockchain()
+        .ok_or_else(|| BlockchainError::NetworkError("No blockchain attached".to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::P2PConfig;
+    use bond_core::{Blockchain, NetworkParams};
+
+    async fn start_server() -> (SocketAddr, Arc<Mutex<P2PNode>>) {
+        let mut node = P2PNode::new(P2PConfig::default()).unwrap();
+        node.set_blockchain(Blockchain::new(NetworkParams::default(), vec![1, 2, 3]).unwrap());
+        let node = Arc::new(Mutex::new(node));
+
+        let server = RpcServer::bind("127.0.0.1:0", Arc::clone(&node))
+            .await
+            .unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(server.serve());
+        (addr, node)
+    }
+
+    async fn call(addr: SocketAddr, method: &str, params: Value) -> RpcResponse {
+        let body =
+            json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 1}).to_string();
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST / HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_getblockcount() {
+        let (addr, _node) = start_server().await;
+
+        let response = call(addr, "getblockcount", json!([])).await;
+        assert_eq!(response.result, Some(json!(0)));
+        assert_eq!(response.id, json!(1));
+        assert!(response.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_getblock_by_height_or_hash() {
+        let (addr, node) = start_server().await;
+        let genesis_hash = {
+            let node = node.lock().unwrap();
+            let blockchain = node.blockchain().unwrap();
+            blockchain.get_latest_block().hash().unwrap()
+        };
+
+        let by_height = call(addr, "getblock", json!([0])).await;
+        let by_hash = call(addr, "getblock", json!([genesis_hash.to_string()])).await;
+        assert!(by_height.result.is_some());
+        assert_eq!(by_height.result, by_hash.result);
+
+        let short = call(addr, "getblock", json!(["abcd"])).await;
+        assert_eq!(short.error.unwrap().code, INVALID_PARAMS);
+
+        let non_hex = call(addr, "getblock", json!(["zz".repeat(32)])).await;
+        assert_eq!(non_hex.error.unwrap().code, INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_getbalance() {
+        let (addr, _node) = start_server().await;
+
+        let genesis = call(addr, "getbalance", json!(["010203"])).await;
+        assert_eq!(genesis.result, Some(json!(5_000)));
+
+        let empty = call(addr, "getbalance", json!(["ff"])).await;
+        assert_eq!(empty.result, Some(json!(0)));
+
+        let invalid = call(addr, "getbalance", json!(["not hex"])).await;
+        assert_eq!(invalid.error.unwrap().code, INVALID_PARAMS);
+
+        let unknown = call(addr, "getpeers", json!([])).await;
+        assert_eq!(unknown.error.unwrap().code, METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_getrejections() {
+        let (addr, node) = start_server().await;
+        let missing_input = {
+            let node = node.lock().unwrap();
+            let blockchain = node.blockchain().unwrap();
+            let mut tx = blockchain
+                .create_transaction(&[1, 2, 3], vec![4, 5, 6], 1000, 100, None)
+                .unwrap();
+            tx.inputs[0].previous_output.vout = 7;
+            tx
+        };
+
+        let sent = call(addr, "sendrawtransaction", json!([missing_input])).await;
+        assert_eq!(sent.error.unwrap().code, SERVER_ERROR);
+
+        let response = call(addr, "getrejections", json!([])).await;
+        let rejections = response.result.unwrap();
+        assert_eq!(rejections.as_array().unwrap().len(), 1);
+        assert_eq!(rejections[0]["reason"], json!("MissingInputs"));
+        assert_eq!(
+            rejections[0]["txid"],
+            serde_json::to_value(missing_input.hash().unwrap()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Wallet keystore holding the node's ML-DSA keypairs
+//!
+//! Each keypair backs one Bond P2PKH script and one Aevum account address.
+//! On disk the private key material is encrypted with ChaCha20-Poly1305
+//! under a key derived from the passphrase with Argon2id, so a copied
+//! keystore file is useless without the passphrase. Only the salt, the
+//! nonce and the ciphertext are stored in the clear.
+
+use aevum_core::{AevumTransaction, utils};
+use argon2::Argon2;
+use bond_core::script::{p2pkh_script_pubkey, p2pkh_script_sig};
+use bond_core::{Transaction, UtxoSet};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use shared::{
+    BlockchainError, Hash256, KeyPair, PrivateKey, PublicKey, Result, SignatureAlgorithm,
+    sign_transaction_hash,
+};
+use std::path::Path;
+use zeroize::Zeroize;
+
+/// Current version of the keystore file format
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Public identifiers derived from one keypair in the wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletAddress {
+    /// Bond P2PKH `script_pubkey` paying to the key
+    pub bond_script: Vec<u8>,
+    /// Aevum account address of the key
+    pub aevum_address: Hash256,
+    /// Signature scheme of the key
+    pub algorithm: SignatureAlgorithm,
+}
+
+/// Serialized keystore file
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    /// Argon2id salt, hex encoded
+    salt: String,
+    /// ChaCha20-Poly1305 nonce, hex encoded
+    nonce: String,
+    /// Encrypted `StoredKey` list, hex encoded
+    ciphertext: String,
+}
+
+/// Keypair as stored inside the encrypted payload
+#[derive(Serialize, Deserialize)]
+struct StoredKey {
+    public_key: String,
+    private_key: String,
+}
+
+impl Drop for StoredKey {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+/// Collection of keypairs that can sign Bond and Aevum transactions
+#[derive(Debug, Clone, Default)]
+pub struct WalletKeystore {
+    keys: Vec<KeyPair>,
+}
+
+impl WalletKeystore {
+    /// Create an empty keystore
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generate a new keypair and return its addresses
+    ///
+    /// Aevum only accepts ML-DSA-44 signatures; keys of other algorithms
+    /// can still receive and spend Bond outputs.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if key generation fails
+    pub fn create_address(&mut self, algorithm: SignatureAlgorithm) -> Result<WalletAddress> {
+        let keypair = KeyPair::generate(algorithm)?;
+        let address = wallet_address(&keypair);
+        self.keys.push(keypair);
+        Ok(address)
+    }
+
+    /// Addresses of every key, in creation order
+    #[must_use]
+    pub fn list_addresses(&self) -> Vec<WalletAddress> {
+        self.keys.iter().map(wallet_address).collect()
+    }
+
+    /// Number of keys in the wallet
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether the wallet holds no keys
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Sign every input of a Bond transaction
+    ///
+    /// Each input is signed by the key whose P2PKH script locks the spent
+    /// UTXO and covers that input's `Transaction::sighash`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a spent UTXO is unknown, if the wallet holds no key
+    /// for one of the inputs or if signing fails
+    pub fn sign_bond_tx(&self, tx: &mut Transaction, utxo_set: &UtxoSet) -> Result<()> {
+        let signatures = tx
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| {
+                let utxo = utxo_set
+                    .get_utxo(&input.previous_output)
+                    .ok_or(BlockchainError::UtxoNotFound)?;
+                let keypair = self
+                    .keys
+                    .iter()
+                    .find(|keypair| p2pkh_script_pubkey(&keypair.public_key) == utxo.script)
+                    .ok_or_else(|| {
+                        BlockchainError::InvalidTransaction(format!(
+                            "No wallet key can spend input {index}"
+                        ))
+                    })?;
+                sign_transaction_hash(&tx.sighash(index, &utxo.script), keypair)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (input, signature) in tx.inputs.iter_mut().zip(&signatures) {
+            input.script_sig = p2pkh_script_sig(signature);
+        }
+
+        Ok(())
+    }
+
+    /// Sign an Aevum transaction with the key of its `from` address
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the wallet holds no key for `from` or if signing
+    /// fails
+    pub fn sign_aevum_tx(&self, tx: &mut AevumTransaction) -> Result<()> {
+        let keypair = self
+            .keys
+            .iter()
+            .find(|keypair| utils::address_from_key(&keypair.public_key) == tx.from)
+            .ok_or_else(|| {
+                BlockchainError::InvalidTransaction("No wallet key for sender".to_string())
+            })?;
+        tx.sign(keypair)
+    }
+
+    /// Encrypt the keystore with `passphrase` and write it to `path`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if key derivation, encryption or the write fails
+    pub fn save(&self, path: &Path, passphrase: &str) -> Result<()> {
+        let stored: Vec<StoredKey> = self
+            .keys
+            .iter()
+            .map(|keypair| StoredKey {
+                public_key: hex::encode(keypair.public_key.as_bytes()),
+                private_key: hex::encode(keypair.private_key.as_bytes()),
+            })
+            .collect();
+        let mut plaintext = serde_json::to_vec(&stored)
+            .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+
+        let salt: [u8; SALT_LEN] = rand::random();
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let encrypted = cipher(passphrase, &salt)?
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|e| BlockchainError::CryptographicError(e.to_string()));
+        plaintext.zeroize();
+
+        let file = KeystoreFile {
+            version: KEYSTORE_VERSION,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(encrypted?),
+        };
+        let data = serde_json::to_vec_pretty(&file)
+            .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+        std::fs::write(path, data).map_err(|e| BlockchainError::IoError(e.to_string()))
+    }
+
+    /// Read and decrypt a keystore written by [`WalletKeystore::save`]
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or parsed, if its version
+    /// is unsupported or if `passphrase` does not decrypt it
+    pub fn load(path: &Path, passphrase: &str) -> Result<Self> {
+        let data = std::fs::read(path).map_err(|e| BlockchainError::IoError(e.to_string()))?;
+        let file: KeystoreFile = serde_json::from_slice(&data)
+            .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+        if file.version != KEYSTORE_VERSION {
+            return Err(BlockchainError::SerializationError(format!(
+                "Unsupported keystore version {}",
+                file.version
+            )));
+        }
+
+        let salt = decode_hex(&file.salt)?;
+        let nonce = decode_hex(&file.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(BlockchainError::SerializationError(
+                "Invalid keystore nonce".to_string(),
+            ));
+        }
+        let mut plaintext = cipher(passphrase, &salt)?
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                decode_hex(&file.ciphertext)?.as_slice(),
+            )
+            .map_err(|_| {
+                BlockchainError::CryptographicError(
+                    "Wrong passphrase or corrupted keystore".to_string(),
+                )
+            })?;
+        let stored: std::result::Result<Vec<StoredKey>, _> = serde_json::from_slice(&plaintext);
+        plaintext.zeroize();
+        let stored = stored.map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+
+        let keys = stored
+            .iter()
+            .map(|key| {
+                let mut private_bytes = decode_hex(&key.private_key)?;
+                let private_key = PrivateKey::from_bytes(private_bytes.clone());
+                private_bytes.zeroize();
+                Ok(KeyPair {
+                    public_key: PublicKey::from_bytes(decode_hex(&key.public_key)?)?,
+                    private_key: private_key?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { keys })
+    }
+}
+
+fn wallet_address(keypair: &KeyPair) -> WalletAddress {
+    WalletAddress {
+        bond_script: p2pkh_script_pubkey(&keypair.public_key),
+        aevum_address: utils::address_from_key(&keypair.public_key),
+        algorithm: keypair.public_key.algorithm(),
+    }
+}
+
+/// ChaCha20-Poly1305 keyed by Argon2id over the passphrase
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<ChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| BlockchainError::CryptographicError(e.to_string()))?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+    key.zeroize();
+    Ok(cipher)
+}
+
+fn decode_hex(data: &str) -> Result<Vec<u8>> {
+    hex::decode(data).map_err(|e| BlockchainError::SerializationError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aevum_core::AevumTransactionType;
+    use bond_core::script::{ScriptContext, ScriptVM};
+    use bond_core::{TxInput, TxOutput, Utxo};
+
+    fn temp_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("aevum-keystore-{}.json", rand::random::<u64>()))
+    }
+
+    #[test]
+    fn test_keystore_round_trip_signs_both_chains() {
+        let path = temp_path();
+        let mut wallet = WalletKeystore::new();
+        let bond = wallet.create_address(SignatureAlgorithm::MLDSA65).unwrap();
+        let aevum = wallet.create_address(SignatureAlgorithm::MLDSA44).unwrap();
+        wallet.save(&path, "correct horse").unwrap();
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains(&hex::encode(&wallet.keys[0].private_key.as_bytes()[..32])));
+
+        let loaded = WalletKeystore::load(&path, "correct horse").unwrap();
+        assert_eq!(loaded.list_addresses(), vec![bond.clone(), aevum.clone()]);
+
+        // Spend a P2PKH output owned by the wallet
+        let funding = Hash256::keccak256(b"funding");
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.add_utxo(Utxo::new(funding, 0, 1_000, bond.bond_script.clone(), 0));
+        let mut tx = Transaction::new(
+            1,
+            vec![TxInput::new(
+                bond_core::OutPoint::new(funding, 0),
+                vec![],
+                0,
+            )],
+            vec![TxOutput::new(900, vec![4, 5, 6])],
+            0,
+        );
+        loaded.sign_bond_tx(&mut tx, &utxo_set).unwrap();
+        let context = ScriptContext::new(tx.sighash(0, &bond.bond_script), 0);
+        assert!(ScriptVM::verify(&tx.inputs[0].script_sig, &bond.bond_script, &context).unwrap());
+
+        // Aevum transfer signed by the sender key
+        let mut aevum_tx = AevumTransaction::new(
+            aevum.aevum_address,
+            Hash256::keccak256(b"to"),
+            1,
+            0,
+            aevum_core::transaction::GAS_TRANSFER,
+            1,
+            AevumTransactionType::Transfer,
+        );
+        loaded.sign_aevum_tx(&mut aevum_tx).unwrap();
+        assert!(
+            aevum_tx
+                .verify_signature(&loaded.keys[1].public_key)
+                .unwrap()
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_wrong_passphrase_fails_to_decrypt() {
+        let path = temp_path();
+        let mut wallet = WalletKeystore::new();
+        wallet.create_address(SignatureAlgorithm::MLDSA44).unwrap();
+        wallet.save(&path, "correct horse").unwrap();
+
+        assert!(matches!(
+            WalletKeystore::load(&path, "battery staple"),
+            Err(BlockchainError::CryptographicError(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}